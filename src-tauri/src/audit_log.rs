@@ -0,0 +1,185 @@
+//! Local audit log of operations performed through VaultRaider
+//!
+//! Complements Azure's activity log with a record of what this app did:
+//! which secrets were read, created, updated or deleted, when, and by whom.
+//! Entries are appended as JSON lines to a file in the app data directory.
+//! Secret values are never recorded.
+
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+use crate::azure::auth::service::get_user_info;
+use crate::user_config::constants::APP_NAME;
+
+/// Audit log file name
+const AUDIT_LOG_FILE_NAME: &str = "audit.jsonl";
+
+/// An operation recorded in the audit log
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditOperation {
+    Read,
+    Create,
+    Update,
+    Delete,
+    Recover,
+    Purge,
+    Backup,
+    Restore,
+}
+
+/// A single audit log entry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditEntry {
+    /// Unix timestamp (seconds) of the operation
+    pub timestamp: i64,
+    pub operation: AuditOperation,
+    pub vault_uri: String,
+    pub secret_name: Option<String>,
+    /// Email of the authenticated user, if known
+    pub user: Option<String>,
+}
+
+lazy_static::lazy_static! {
+    /// Serializes writes so concurrent entries don't interleave
+    static ref AUDIT_LOG_LOCK: Arc<Mutex<()>> = Arc::new(Mutex::new(()));
+}
+
+/// Get the audit log file path
+fn get_audit_log_path() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join(APP_NAME).join(AUDIT_LOG_FILE_NAME))
+}
+
+/// Record an operation in the audit log.
+///
+/// The write happens on a background task, and failures are logged rather
+/// than returned, so auditing never slows down or breaks the operation itself.
+pub async fn record_audit_event(
+    operation: AuditOperation,
+    vault_uri: &str,
+    secret_name: Option<&str>,
+) {
+    let entry = AuditEntry {
+        timestamp: chrono::Utc::now().timestamp(),
+        operation,
+        vault_uri: vault_uri.to_string(),
+        secret_name: secret_name.map(str::to_string),
+        user: get_user_info().await.map(|(email, _)| email),
+    };
+
+    tokio::spawn(async move {
+        let Some(path) = get_audit_log_path() else {
+            log::warn!("Could not determine app data directory, audit entry dropped");
+            return;
+        };
+
+        let _guard = AUDIT_LOG_LOCK.lock().await;
+        if let Err(e) = append_entry(&path, &entry) {
+            log::error!("Failed to write audit log entry: {}", e);
+        }
+    });
+}
+
+/// Get the most recent audit log entries, newest first
+pub async fn get_audit_entries(limit: usize) -> Result<Vec<AuditEntry>, String> {
+    let path = get_audit_log_path().ok_or("Could not determine app data directory")?;
+    let _guard = AUDIT_LOG_LOCK.lock().await;
+    read_entries(&path, limit)
+}
+
+/// Delete all audit log entries
+pub async fn clear_audit_entries() -> Result<(), String> {
+    let path = get_audit_log_path().ok_or("Could not determine app data directory")?;
+    let _guard = AUDIT_LOG_LOCK.lock().await;
+
+    match fs::remove_file(&path) {
+        Ok(()) => {
+            log::info!("Cleared local audit log");
+            Ok(())
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(format!("Failed to clear audit log: {}", e)),
+    }
+}
+
+/// Append a single entry to the log file, creating it if needed
+fn append_entry(path: &Path, entry: &AuditEntry) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create audit log directory: {}", e))?;
+    }
+
+    let line = serde_json::to_string(entry)
+        .map_err(|e| format!("Failed to serialize audit entry: {}", e))?;
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| format!("Failed to open audit log: {}", e))?;
+
+    writeln!(file, "{}", line).map_err(|e| format!("Failed to write audit log: {}", e))
+}
+
+/// Read the last `limit` entries from the log file, newest first
+///
+/// Lines that can't be parsed are skipped.
+fn read_entries(path: &Path, limit: usize) -> Result<Vec<AuditEntry>, String> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("Failed to read audit log: {}", e)),
+    };
+
+    Ok(content
+        .lines()
+        .rev()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .take(limit)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(secret_name: &str, operation: AuditOperation) -> AuditEntry {
+        AuditEntry {
+            timestamp: 1_700_000_000,
+            operation,
+            vault_uri: "https://vault.vault.azure.net".to_string(),
+            secret_name: Some(secret_name.to_string()),
+            user: Some("user@example.com".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_append_and_read_newest_first() {
+        let dir = std::env::temp_dir().join(format!("vaultraider-test-{}", uuid::Uuid::new_v4()));
+        let path = dir.join(AUDIT_LOG_FILE_NAME);
+
+        append_entry(&path, &entry("first", AuditOperation::Create)).unwrap();
+        append_entry(&path, &entry("second", AuditOperation::Read)).unwrap();
+        append_entry(&path, &entry("third", AuditOperation::Delete)).unwrap();
+
+        let entries = read_entries(&path, 2).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].secret_name.as_deref(), Some("third"));
+        assert_eq!(entries[0].operation, AuditOperation::Delete);
+        assert_eq!(entries[1].secret_name.as_deref(), Some("second"));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_read_missing_log_is_empty() {
+        let path = std::env::temp_dir()
+            .join(format!("vaultraider-missing-{}.jsonl", uuid::Uuid::new_v4()));
+        assert!(read_entries(&path, 10).unwrap().is_empty());
+    }
+}
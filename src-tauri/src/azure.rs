@@ -6,5 +6,6 @@ pub(crate) mod activity_log;
 pub(crate) mod auth;
 pub(crate) mod http;
 pub(crate) mod keyvault;
+pub(crate) mod rbac;
 pub(crate) mod resource_group;
 pub(crate) mod subscription;
@@ -11,9 +11,7 @@ use std::collections::HashMap;
 
 use crate::azure::auth::token::get_token_for_scope;
 use crate::azure::http::AzureHttpClient;
-
-/// Microsoft Graph API scope
-const GRAPH_SCOPE: &str = "https://graph.microsoft.com/.default";
+use crate::config::{graph_scope, urls};
 
 /// Maximum number of IDs per batch request (Graph API limit is 1000)
 const MAX_IDS_PER_BATCH: usize = 100;
@@ -135,13 +133,12 @@ async fn resolve_caller_identities_internal(
     );
 
     // Get a token for Microsoft Graph
-    let token = get_token_for_scope(GRAPH_SCOPE)
+    let token = get_token_for_scope(graph_scope())
         .await
         .map_err(|e| anyhow::anyhow!(e))
         .context("Failed to get Microsoft Graph token")?;
 
-    let client = AzureHttpClient::new()
-        .with_bearer_token(&token)
+    let client = AzureHttpClient::with_scoped_token(&token, graph_scope())
         .context("Failed to create HTTP client for Graph API")?;
 
     // Process in batches
@@ -157,10 +154,7 @@ async fn resolve_caller_identities_internal(
         };
 
         let response: GetByIdsResponse = match client
-            .post(
-                "https://graph.microsoft.com/v1.0/directoryObjects/getByIds",
-                &request_body,
-            )
+            .post(&urls::graph_directory_objects_by_ids(), &request_body)
             .await
         {
             Ok(resp) => resp,
@@ -1,14 +1,15 @@
 //! Activity Log service - business logic for Azure Monitor Activity Logs
 
 use anyhow::{Context, Result};
-use log::{debug, error, info};
+use log::{debug, error, info, warn};
+use std::collections::HashMap;
 
 use crate::azure::auth::token::get_token_from_state;
-use crate::azure::auth::types::AzureListResponse;
-use crate::azure::http::AzureHttpClient;
-use crate::config::urls;
+use crate::azure::http::{fetch_all_paginated, AzureHttpClient};
+use crate::config::{management_scope, urls};
 
-use super::types::ActivityLogEvent;
+use super::graph::{resolve_caller_identities, ResolvedCaller};
+use super::types::{ActivityLogEvent, ActivityLogFilter, ResolvedActivityLogEvent};
 
 /// Fetch activity log events for a specific Key Vault resource.
 ///
@@ -19,15 +20,17 @@ use super::types::ActivityLogEvent;
 /// * `vault_id` - The full Azure resource ID of the Key Vault
 ///   (e.g., `/subscriptions/{sub}/resourceGroups/{rg}/providers/Microsoft.KeyVault/vaults/{name}`)
 /// * `days` - Number of days of history to fetch (1-90, default 7)
+/// * `filter` - Conditions the returned events must meet
 ///
 /// # Returns
 ///
-/// A vector of activity log events or an error.
+/// A vector of the matching activity log events, newest first, or an error.
 pub async fn get_activity_logs(
     vault_id: &str,
     days: Option<u32>,
+    filter: &ActivityLogFilter,
 ) -> Result<Vec<ActivityLogEvent>, String> {
-    get_activity_logs_internal(vault_id, days)
+    get_activity_logs_internal(vault_id, days, filter)
         .await
         .map_err(|e| {
             error!("Failed to get activity logs: {}", e);
@@ -38,6 +41,7 @@ pub async fn get_activity_logs(
 async fn get_activity_logs_internal(
     vault_id: &str,
     days: Option<u32>,
+    filter: &ActivityLogFilter,
 ) -> Result<Vec<ActivityLogEvent>> {
     let days = days.unwrap_or(7).min(90).max(1);
     info!("Fetching activity logs for vault, last {} days", days);
@@ -49,32 +53,25 @@ async fn get_activity_logs_internal(
 
     debug!("Successfully retrieved authentication token for activity logs");
 
-    let client = AzureHttpClient::new()
-        .with_bearer_token(&token)
+    let client = AzureHttpClient::with_scoped_token(&token, management_scope())
         .context("Failed to create HTTP client with token")?;
 
     let url = urls::activity_logs(vault_id, days);
     debug!("Calling Azure Monitor API: {}", url);
 
-    // The Activity Log API uses the same pagination pattern
-    let mut results = Vec::new();
-    let mut current_url = Some(url);
-
-    while let Some(url) = current_url {
-        debug!("Fetching activity log page: {}", url);
-
-        let response: AzureListResponse<ActivityLogEvent> = client
-            .get(&url)
-            .await
-            .with_context(|| format!("Failed to fetch activity logs for {}", vault_id))?;
-
-        let items_count = response.value.len();
-        debug!("Activity log page fetched: {} events", items_count);
-        results.extend(response.value);
+    let mut results = fetch_all_paginated::<ActivityLogEvent>(&url, &client)
+        .await
+        .with_context(|| format!("Failed to fetch activity logs for {}", vault_id))?;
 
-        current_url = response.next_link;
+    // The API's $filter can't express these conditions, so they're applied here
+    let fetched = results.len();
+    results.retain(|event| filter.matches(event));
+    if results.len() < fetched {
+        debug!("Filtered out {} activity log events", fetched - results.len());
     }
 
+    sort_newest_first(&mut results);
+
     info!(
         "Successfully retrieved {} activity log events",
         results.len()
@@ -82,3 +79,239 @@ async fn get_activity_logs_internal(
 
     Ok(results)
 }
+
+/// Fetch activity log events for a Key Vault with each caller resolved to a
+/// display name.
+///
+/// The distinct callers are resolved in one pass via Microsoft Graph. Callers
+/// that can't be resolved (including when Graph can't be reached) keep their
+/// raw value as display name.
+///
+/// # Arguments
+///
+/// * `vault_id` - The full Azure resource ID of the Key Vault
+/// * `days` - Number of days of history to fetch (1-90, default 7)
+/// * `filter` - Conditions the returned events must meet
+///
+/// # Returns
+///
+/// The matching events newest first, each with its resolved caller, or an error if the
+/// events themselves can't be fetched.
+pub async fn get_activity_logs_resolved(
+    vault_id: &str,
+    days: Option<u32>,
+    filter: &ActivityLogFilter,
+) -> Result<Vec<ResolvedActivityLogEvent>, String> {
+    // Filtered first, so only the callers that are shown get resolved
+    let events = get_activity_logs(vault_id, days, filter).await?;
+
+    let mut callers: Vec<String> = events.iter().filter_map(|e| e.caller.clone()).collect();
+    callers.sort();
+    callers.dedup();
+
+    let resolved = resolve_caller_identities(callers)
+        .await
+        .unwrap_or_else(|e| {
+            warn!("Failed to resolve activity log callers, showing raw values: {}", e);
+            HashMap::new()
+        });
+
+    Ok(attach_callers(events, &resolved))
+}
+
+/// Pair each event with its caller from `resolved`, falling back to the raw caller
+fn attach_callers(
+    events: Vec<ActivityLogEvent>,
+    resolved: &HashMap<String, ResolvedCaller>,
+) -> Vec<ResolvedActivityLogEvent> {
+    events
+        .into_iter()
+        .map(|event| {
+            let resolved_caller = event.caller.as_ref().map(|caller| {
+                resolved
+                    .get(caller)
+                    .cloned()
+                    .unwrap_or_else(|| ResolvedCaller {
+                        id: caller.clone(),
+                        display_name: caller.clone(),
+                        caller_type: "unknown".to_string(),
+                        user_principal_name: None,
+                    })
+            });
+            ResolvedActivityLogEvent {
+                event,
+                resolved_caller,
+            }
+        })
+        .collect()
+}
+
+/// Sort events newest first; events without a parseable timestamp go last
+fn sort_newest_first(events: &mut [ActivityLogEvent]) {
+    events.sort_by(|a, b| b.timestamp.event_time.cmp(&a.timestamp.event_time));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(timestamp: Option<&str>) -> ActivityLogEvent {
+        serde_json::from_value(serde_json::json!({
+            "eventTimestamp": timestamp
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_sort_newest_first_across_formats() {
+        let mut events = vec![
+            event(Some("2024-01-15T10:00:00Z")),
+            event(None),
+            event(Some("2024-01-15T12:30:00.5+02:00")),
+            event(Some("2024-01-15T10:45:00.1234567")),
+        ];
+
+        sort_newest_first(&mut events);
+
+        let order: Vec<Option<&str>> = events
+            .iter()
+            .map(|e| e.timestamp.event_timestamp.as_deref())
+            .collect();
+        assert_eq!(
+            order,
+            vec![
+                Some("2024-01-15T10:45:00.1234567"),
+                Some("2024-01-15T12:30:00.5+02:00"),
+                Some("2024-01-15T10:00:00Z"),
+                None,
+            ]
+        );
+    }
+
+    /// An event shaped like the Activity Log API returns it: the outcome is in `status`
+    fn outcome(operation: &str, status: &str, level: &str) -> ActivityLogEvent {
+        serde_json::from_value(serde_json::json!({
+            "operationName": {
+                "value": format!("Microsoft.KeyVault/vaults/{}", operation),
+                "localizedValue": "Write Secret"
+            },
+            "status": { "value": status, "localizedValue": status },
+            "subStatus": { "value": "", "localizedValue": "" },
+            "level": level
+        }))
+        .unwrap()
+    }
+
+    fn result_types<'a>(
+        events: &'a [ActivityLogEvent],
+        filter: &ActivityLogFilter,
+    ) -> Vec<&'a str> {
+        events
+            .iter()
+            .filter(|e| filter.matches(e))
+            .filter_map(|e| e.outcome())
+            .collect()
+    }
+
+    #[test]
+    fn test_filter_by_result_type_excludes_succeeded() {
+        let events = vec![
+            outcome("secrets/write", "Succeeded", "Informational"),
+            outcome("secrets/write", "Failed", "Error"),
+            outcome("write", "Start", "Informational"),
+        ];
+        let failed = ActivityLogFilter {
+            result_type: Some("failed".to_string()),
+            ..ActivityLogFilter::default()
+        };
+
+        let everything = ActivityLogFilter::default();
+
+        assert_eq!(result_types(&events, &failed), vec!["Failed"]);
+        assert_eq!(result_types(&events, &everything).len(), 3);
+    }
+
+    #[test]
+    fn test_outcome_falls_back_to_result_type() {
+        let legacy: ActivityLogEvent = serde_json::from_value(serde_json::json!({
+            "resultType": "Failed"
+        }))
+        .unwrap();
+        let failed = ActivityLogFilter {
+            result_type: Some("Failed".to_string()),
+            ..ActivityLogFilter::default()
+        };
+
+        assert_eq!(legacy.outcome(), Some("Failed"));
+        assert!(failed.matches(&legacy));
+    }
+
+    #[test]
+    fn test_filter_conditions_combine() {
+        let events = vec![
+            outcome("secrets/write", "Succeeded", "Informational"),
+            outcome("secrets/delete", "Succeeded", "Warning"),
+            outcome("write", "Succeeded", "Informational"),
+        ];
+        let secret_writes = ActivityLogFilter {
+            operation_name_contains: Some("SECRETS/".to_string()),
+            level: Some("informational".to_string()),
+            ..ActivityLogFilter::default()
+        };
+
+        let matched: Vec<_> = events.iter().filter(|e| secret_writes.matches(e)).collect();
+        assert_eq!(matched.len(), 1);
+        assert_eq!(
+            matched[0].operation_name.as_ref().unwrap().value.as_deref(),
+            Some("Microsoft.KeyVault/vaults/secrets/write")
+        );
+    }
+
+    fn called_by(caller: Option<&str>) -> ActivityLogEvent {
+        serde_json::from_value(serde_json::json!({ "caller": caller })).unwrap()
+    }
+
+    #[test]
+    fn test_attach_callers_enriches_and_falls_back() {
+        const RESOLVED_GUID: &str = "550e8400-e29b-41d4-a716-446655440000";
+        const UNRESOLVED_GUID: &str = "6ba7b810-9dad-11d1-80b4-00c04fd430c8";
+
+        let resolved = HashMap::from([(
+            RESOLVED_GUID.to_string(),
+            ResolvedCaller {
+                id: RESOLVED_GUID.to_string(),
+                display_name: "deploy-pipeline".to_string(),
+                caller_type: "servicePrincipal".to_string(),
+                user_principal_name: None,
+            },
+        )]);
+        let events = vec![
+            called_by(Some(RESOLVED_GUID)),
+            called_by(Some(UNRESOLVED_GUID)),
+            called_by(Some("alice@contoso.com")),
+            called_by(None),
+        ];
+
+        let enriched = attach_callers(events, &resolved);
+
+        let callers: Vec<Option<(&str, &str)>> = enriched
+            .iter()
+            .map(|e| {
+                e.resolved_caller
+                    .as_ref()
+                    .map(|c| (c.display_name.as_str(), c.caller_type.as_str()))
+            })
+            .collect();
+        assert_eq!(
+            callers,
+            vec![
+                Some(("deploy-pipeline", "servicePrincipal")),
+                Some((UNRESOLVED_GUID, "unknown")),
+                Some(("alice@contoso.com", "unknown")),
+                None,
+            ]
+        );
+        // The original event stays intact next to the resolved caller
+        assert_eq!(enriched[1].event.caller.as_deref(), Some(UNRESOLVED_GUID));
+    }
+}
@@ -3,7 +3,12 @@
 //! These types match the Azure Monitor Activity Log REST API response format.
 //! API Reference: https://learn.microsoft.com/en-us/rest/api/monitor/activity-logs/list
 
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
+use time::format_description::well_known::Rfc3339;
+use time::macros::format_description;
+use time::{OffsetDateTime, PrimitiveDateTime};
+
+use super::graph::ResolvedCaller;
 
 /// A single activity log event from Azure Monitor.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -33,8 +38,9 @@ pub struct ActivityLogEvent {
     /// A property bag describing the operation's result in detail.
     pub result_signature: Option<String>,
 
-    /// The event timestamp (ISO 8601).
-    pub event_timestamp: Option<String>,
+    /// When the event happened, parsed while the event is deserialized.
+    #[serde(flatten, deserialize_with = "deserialize_event_timestamp")]
+    pub timestamp: EventTimestamp,
 
     /// The submission timestamp (ISO 8601).
     pub submission_timestamp: Option<String>,
@@ -82,6 +88,121 @@ pub struct ActivityLogEvent {
     pub resource_provider_name: Option<LocalizableString>,
 }
 
+impl ActivityLogEvent {
+    /// The outcome of the operation, e.g. `Succeeded` or `Failed`
+    ///
+    /// The Activity Log API reports it in `status.value`; `resultType` is only
+    /// used when that is missing.
+    pub fn outcome(&self) -> Option<&str> {
+        self.status
+            .as_ref()
+            .and_then(|status| status.value.as_deref())
+            .or(self.result_type.as_deref())
+    }
+}
+
+/// The event timestamp as returned by Azure, together with its UTC value.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventTimestamp {
+    /// The event timestamp (ISO 8601), as returned by Azure.
+    pub event_timestamp: Option<String>,
+
+    /// `event_timestamp` parsed and normalized to UTC, if it could be parsed.
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub event_time: Option<OffsetDateTime>,
+}
+
+/// Read the raw `eventTimestamp` and parse it with `parse_azure_timestamp`.
+fn deserialize_event_timestamp<'de, D>(deserializer: D) -> Result<EventTimestamp, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct RawTimestamp {
+        event_timestamp: Option<String>,
+    }
+
+    let raw = RawTimestamp::deserialize(deserializer)?;
+    Ok(EventTimestamp {
+        event_time: raw.event_timestamp.as_deref().and_then(parse_azure_timestamp),
+        event_timestamp: raw.event_timestamp,
+    })
+}
+
+/// Conditions an activity log event must meet to be returned; unset fields match anything.
+///
+/// All comparisons ignore case.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityLogFilter {
+    /// Substring of the operation name, e.g. `secrets/write`.
+    pub operation_name_contains: Option<String>,
+    /// Exact outcome (`status.value`, see `ActivityLogEvent::outcome`), e.g. `Failed`.
+    pub result_type: Option<String>,
+    /// Exact event level, e.g. `Error`.
+    pub level: Option<String>,
+}
+
+impl ActivityLogFilter {
+    /// Whether `event` meets every condition set in the filter
+    pub fn matches(&self, event: &ActivityLogEvent) -> bool {
+        let equals = |wanted: &Option<String>, actual: Option<&str>| match wanted {
+            Some(wanted) => actual.is_some_and(|actual| actual.eq_ignore_ascii_case(wanted)),
+            None => true,
+        };
+
+        let operation_matches = match &self.operation_name_contains {
+            Some(needle) => {
+                let needle = needle.to_lowercase();
+                event.operation_name.as_ref().is_some_and(|name| {
+                    [&name.value, &name.localized_value]
+                        .into_iter()
+                        .flatten()
+                        .any(|text| text.to_lowercase().contains(&needle))
+                })
+            }
+            None => true,
+        };
+
+        operation_matches
+            && equals(&self.result_type, event.outcome())
+            && equals(&self.level, event.level.as_deref())
+    }
+}
+
+/// An activity log event together with the identity of its caller.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolvedActivityLogEvent {
+    /// The event as returned by Azure Monitor.
+    #[serde(flatten)]
+    pub event: ActivityLogEvent,
+
+    /// The caller's identity. Callers that couldn't be resolved keep their raw
+    /// value as display name; `None` if the event has no caller.
+    pub resolved_caller: Option<ResolvedCaller>,
+}
+
+/// Parse an ISO 8601 timestamp as emitted by Azure Monitor, normalized to UTC.
+///
+/// Azure mixes RFC 3339 timestamps with and without fractional seconds (up to
+/// 7 digits) and occasionally omits the offset; those are taken to be UTC.
+pub fn parse_azure_timestamp(raw: &str) -> Option<OffsetDateTime> {
+    let raw = raw.trim();
+    if let Ok(parsed) = OffsetDateTime::parse(raw, &Rfc3339) {
+        return Some(parsed.to_offset(time::UtcOffset::UTC));
+    }
+
+    let without_offset = format_description!(
+        "[year]-[month]-[day]T[hour]:[minute]:[second][optional [.[subsecond]]]"
+    );
+    PrimitiveDateTime::parse(raw, &without_offset)
+        .ok()
+        .map(PrimitiveDateTime::assume_utc)
+}
+
 /// Authorization details for an activity log event.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -117,3 +238,87 @@ pub struct HttpRequestInfo {
     /// The request URI.
     pub uri: Option<String>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::macros::datetime;
+
+    #[test]
+    fn test_parse_timestamp_without_fraction() {
+        assert_eq!(
+            parse_azure_timestamp("2024-01-15T10:30:45Z"),
+            Some(datetime!(2024-01-15 10:30:45 UTC))
+        );
+    }
+
+    #[test]
+    fn test_parse_timestamp_with_fraction() {
+        assert_eq!(
+            parse_azure_timestamp("2024-01-15T10:30:45.1234567Z"),
+            Some(datetime!(2024-01-15 10:30:45.1234567 UTC))
+        );
+        assert_eq!(
+            parse_azure_timestamp("2024-01-15T10:30:45.12Z"),
+            Some(datetime!(2024-01-15 10:30:45.12 UTC))
+        );
+    }
+
+    #[test]
+    fn test_parse_timestamp_with_offset_normalizes_to_utc() {
+        let parsed = parse_azure_timestamp("2024-01-15T12:30:45.5+02:00").unwrap();
+        assert_eq!(parsed, datetime!(2024-01-15 10:30:45.5 UTC));
+        assert_eq!(parsed.offset(), time::UtcOffset::UTC);
+    }
+
+    #[test]
+    fn test_parse_timestamp_without_offset_assumes_utc() {
+        assert_eq!(
+            parse_azure_timestamp("2024-01-15T10:30:45.1234567"),
+            Some(datetime!(2024-01-15 10:30:45.1234567 UTC))
+        );
+        assert_eq!(
+            parse_azure_timestamp("2024-01-15T10:30:45"),
+            Some(datetime!(2024-01-15 10:30:45 UTC))
+        );
+    }
+
+    #[test]
+    fn test_parse_timestamp_rejects_garbage() {
+        assert_eq!(parse_azure_timestamp(""), None);
+        assert_eq!(parse_azure_timestamp("yesterday"), None);
+    }
+
+    #[test]
+    fn test_event_keeps_raw_timestamp() {
+        let event: ActivityLogEvent = serde_json::from_value(serde_json::json!({
+            "eventTimestamp": "2024-01-15T10:30:45.1234567Z"
+        }))
+        .unwrap();
+
+        assert_eq!(
+            event.timestamp.event_timestamp.as_deref(),
+            Some("2024-01-15T10:30:45.1234567Z")
+        );
+        assert_eq!(
+            event.timestamp.event_time,
+            Some(datetime!(2024-01-15 10:30:45.1234567 UTC))
+        );
+
+        // Both are serialized at the top level, as the frontend expects
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["eventTimestamp"], "2024-01-15T10:30:45.1234567Z");
+        assert_eq!(json["eventTime"], "2024-01-15T10:30:45.1234567Z");
+    }
+
+    #[test]
+    fn test_event_without_timestamp() {
+        let event: ActivityLogEvent = serde_json::from_value(serde_json::json!({
+            "caller": "someone@contoso.com"
+        }))
+        .unwrap();
+
+        assert!(event.timestamp.event_timestamp.is_none());
+        assert!(event.timestamp.event_time.is_none());
+    }
+}
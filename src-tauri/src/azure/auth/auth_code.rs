@@ -0,0 +1,551 @@
+//! Authorization code flow with PKCE
+//!
+//! This module implements a real browser sign-in: the system browser is opened
+//! at the Azure AD authorize endpoint and the redirect is captured by a
+//! temporary listener on `localhost`. Unlike the device code flow the user
+//! doesn't have to copy a code, and PKCE means no client secret is needed.
+
+use std::collections::HashMap;
+
+use azure_core::credentials::{AccessToken, Secret};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD as BASE64URL;
+use base64::Engine;
+use log::{error as log_error, info, warn};
+use sha2::{Digest, Sha256};
+use time::OffsetDateTime;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::oneshot;
+
+use crate::azure::auth::interactive::{
+    credential_from_tokens, reset_interactive_login, LOGIN_CANCELLED_ERROR,
+};
+use crate::azure::auth::state::{AUTH_CODE_STATE, LOGIN_CANCEL};
+use crate::azure::auth::token::{decode_token_payload, store_auth_result};
+use crate::azure::auth::types::{
+    AuthCodeLoginInfo, AuthCodeRedirect, AuthCodeState, AuthResult, TokenResponse,
+};
+use crate::config::{management_scope, AUTH_CODE_TIMEOUT_SECONDS};
+use crate::user_config::{get_auth_scopes, get_authority_host, get_client_id, get_tenant_id};
+
+/// Error returned when the browser never redirects back, usually because it was closed
+///
+/// The redirect listener is shut down, so the UI can start a new sign-in straight away.
+pub const LOGIN_BROWSER_CLOSED_ERROR: &str =
+    "Sign-in was not completed in the browser, please start again";
+
+/// Error returned when the redirect's `state` doesn't match the one sent to Azure AD
+///
+/// Means the code wasn't issued for this sign-in (possible CSRF); it is never exchanged.
+pub const LOGIN_STATE_MISMATCH_ERROR: &str =
+    "Security check failed: the sign-in response does not belong to this login attempt";
+
+/// Error returned when the ID token's `nonce` doesn't match the one sent to Azure AD
+pub const LOGIN_NONCE_MISMATCH_ERROR: &str =
+    "Security check failed: the ID token was not issued for this login attempt";
+
+/// Largest request the redirect listener reads before dropping the connection
+const MAX_REDIRECT_REQUEST_BYTES: usize = 16 * 1024;
+
+/// How long a single connection to the listener may take to send its request
+const REDIRECT_READ_TIMEOUT_SECS: u64 = 10;
+
+/// Page shown in the browser once the redirect has been captured
+const REDIRECT_SUCCESS_PAGE: &str = "<!DOCTYPE html><html><head><title>VaultRaider</title></head>\
+    <body><h2>Signed in</h2><p>You can close this window and return to VaultRaider.</p></body></html>";
+
+/// Page shown in the browser when Azure AD redirected back with an error
+const REDIRECT_FAILURE_PAGE: &str = "<!DOCTYPE html><html><head><title>VaultRaider</title></head>\
+    <body><h2>Sign-in failed</h2><p>Return to VaultRaider for details.</p></body></html>";
+
+/// Generate a PKCE code verifier (43 URL-safe characters from two random UUIDs)
+fn generate_code_verifier() -> String {
+    let mut bytes = [0u8; 32];
+    bytes[..16].copy_from_slice(uuid::Uuid::new_v4().as_bytes());
+    bytes[16..].copy_from_slice(uuid::Uuid::new_v4().as_bytes());
+    BASE64URL.encode(bytes)
+}
+
+/// Derive the S256 PKCE code challenge for a code verifier
+fn code_challenge(code_verifier: &str) -> String {
+    BASE64URL.encode(Sha256::digest(code_verifier.as_bytes()))
+}
+
+/// Build the Azure AD authorize URL the browser is sent to for a login attempt
+fn build_authorize_url(auth_state: &AuthCodeState) -> String {
+    format!(
+        "{}/{}/oauth2/v2.0/authorize?client_id={}&response_type=code&redirect_uri={}&response_mode=query&scope={}&state={}&nonce={}&code_challenge={}&code_challenge_method=S256",
+        auth_state.authority_host,
+        auth_state.tenant_id,
+        urlencoding::encode(&auth_state.client_id),
+        urlencoding::encode(&auth_state.redirect_uri),
+        urlencoding::encode(&auth_state.scopes),
+        urlencoding::encode(&auth_state.state),
+        urlencoding::encode(&auth_state.nonce),
+        code_challenge(&auth_state.code_verifier)
+    )
+}
+
+/// Check that the `state` returned with the code is the one generated at start
+fn validate_state(expected: &str, received: &str) -> Result<(), String> {
+    if expected.is_empty() || expected != received {
+        warn!("Sign-in response state does not match the login attempt, rejecting it");
+        return Err(LOGIN_STATE_MISMATCH_ERROR.to_string());
+    }
+    Ok(())
+}
+
+/// Check that the ID token, if Azure AD returned one, carries the nonce generated at start
+fn validate_nonce(expected: &str, id_token: Option<&str>) -> Result<(), String> {
+    let Some(id_token) = id_token else {
+        return Ok(());
+    };
+
+    let nonce = decode_token_payload(id_token)
+        .and_then(|claims| claims.get("nonce")?.as_str().map(str::to_string));
+    if nonce.as_deref() != Some(expected) {
+        warn!("ID token nonce does not match the login attempt, rejecting it");
+        return Err(LOGIN_NONCE_MISMATCH_ERROR.to_string());
+    }
+    Ok(())
+}
+
+/// Decode a form-encoded query value, where `+` stands for a space
+fn decode_query_value(value: &str) -> String {
+    let value = value.replace('+', " ");
+    urlencoding::decode(&value)
+        .map(|decoded| decoded.into_owned())
+        .unwrap_or(value)
+}
+
+/// Parse the request target of a request made to the redirect listener
+///
+/// Returns `None` for requests that aren't the redirect, such as the
+/// browser asking for `/favicon.ico`.
+fn parse_redirect(target: &str) -> Option<Result<AuthCodeRedirect, String>> {
+    let (_, query) = target.split_once('?')?;
+    let params: HashMap<&str, String> = query
+        .split('&')
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (key, decode_query_value(value))
+        })
+        .collect();
+
+    if let Some(error) = params.get("error") {
+        let message = match params.get("error_description") {
+            Some(description) => format!("Sign-in failed: {} - {}", error, description),
+            None => format!("Sign-in failed: {}", error),
+        };
+        return Some(Err(message));
+    }
+
+    let code = params.get("code")?;
+    Some(Ok(AuthCodeRedirect {
+        code: code.clone(),
+        state: params.get("state").cloned().unwrap_or_default(),
+    }))
+}
+
+/// Read the request line of an HTTP request and return its target for `GET` requests
+async fn read_request_target(stream: &mut TcpStream) -> Option<String> {
+    let mut request = Vec::new();
+    let mut chunk = [0u8; 1024];
+
+    while !request.windows(4).any(|window| window == b"\r\n\r\n") {
+        if request.len() > MAX_REDIRECT_REQUEST_BYTES {
+            return None;
+        }
+        let read = stream.read(&mut chunk).await.ok()?;
+        if read == 0 {
+            break;
+        }
+        request.extend_from_slice(&chunk[..read]);
+    }
+
+    let request = String::from_utf8_lossy(&request);
+    let mut request_line = request.lines().next()?.split_whitespace();
+    match (request_line.next(), request_line.next()) {
+        (Some("GET"), Some(target)) => Some(target.to_string()),
+        _ => None,
+    }
+}
+
+/// Write a minimal HTTP response and close the connection
+async fn respond(stream: &mut TcpStream, status: &str, body: &str) {
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+    if let Err(e) = stream.write_all(response.as_bytes()).await {
+        warn!("Failed to answer redirect request: {}", e);
+    }
+}
+
+/// Serve the redirect listener until Azure AD redirects back with a code or an error
+async fn run_redirect_listener(
+    listener: TcpListener,
+    redirect_tx: oneshot::Sender<Result<AuthCodeRedirect, String>>,
+) {
+    loop {
+        let mut stream = match listener.accept().await {
+            Ok((stream, _)) => stream,
+            Err(e) => {
+                log_error!("Redirect listener failed: {}", e);
+                let _ = redirect_tx.send(Err(format!("Redirect listener failed: {}", e)));
+                return;
+            }
+        };
+
+        let read = tokio::time::timeout(
+            std::time::Duration::from_secs(REDIRECT_READ_TIMEOUT_SECS),
+            read_request_target(&mut stream),
+        );
+        let Ok(Some(target)) = read.await else {
+            continue;
+        };
+
+        match parse_redirect(&target) {
+            None => respond(&mut stream, "404 Not Found", "").await,
+            Some(redirect) => {
+                let page = if redirect.is_ok() {
+                    REDIRECT_SUCCESS_PAGE
+                } else {
+                    REDIRECT_FAILURE_PAGE
+                };
+                respond(&mut stream, "200 OK", page).await;
+                info!("Captured sign-in redirect");
+                // Receiver is gone if the flow was reset meanwhile
+                let _ = redirect_tx.send(redirect);
+                return;
+            }
+        }
+    }
+}
+
+/// Start the authorization code login flow
+///
+/// Starts a redirect listener on a random `localhost` port and opens the
+/// system browser at the Azure AD sign-in page. Any login flow that was
+/// still in progress is reset first.
+///
+/// # Returns
+///
+/// Returns the sign-in URL (so the UI can offer it if the browser didn't
+/// open) and the redirect URI the listener is waiting on.
+pub async fn start_auth_code_login() -> Result<AuthCodeLoginInfo, String> {
+    info!("Starting authorization code login flow...");
+
+    reset_interactive_login().await;
+
+    let authority_host = get_authority_host().await;
+    let client_id = get_client_id().await;
+    let tenant_id = get_tenant_id().await;
+    let scopes = get_auth_scopes().await;
+
+    let listener = TcpListener::bind(("127.0.0.1", 0))
+        .await
+        .map_err(|e| format!("Failed to start redirect listener: {}", e))?;
+    let port = listener
+        .local_addr()
+        .map_err(|e| format!("Failed to start redirect listener: {}", e))?
+        .port();
+    let redirect_uri = format!("http://localhost:{}", port);
+
+    let (redirect_tx, redirect_rx) = oneshot::channel();
+    let listener = tokio::spawn(run_redirect_listener(listener, redirect_tx));
+
+    let auth_state = AuthCodeState {
+        authority_host,
+        client_id,
+        tenant_id,
+        scopes,
+        redirect_uri: redirect_uri.clone(),
+        code_verifier: generate_code_verifier(),
+        state: uuid::Uuid::new_v4().to_string(),
+        nonce: uuid::Uuid::new_v4().to_string(),
+        expires_at: tokio::time::Instant::now()
+            + std::time::Duration::from_secs(AUTH_CODE_TIMEOUT_SECONDS),
+        redirect_rx: Some(redirect_rx),
+        listener,
+    };
+    let authorize_url = build_authorize_url(&auth_state);
+
+    if let Err(e) = tauri_plugin_opener::open_url(&authorize_url, None::<&str>) {
+        // The UI can still open the returned URL itself
+        warn!("Failed to open the system browser: {}", e);
+    }
+
+    *AUTH_CODE_STATE.lock().await = Some(auth_state);
+
+    info!("Waiting for sign-in redirect on {}", redirect_uri);
+    Ok(AuthCodeLoginInfo {
+        authorize_url,
+        redirect_uri,
+    })
+}
+
+/// Wait for the redirect listener, the timeout or a cancellation, whichever comes first
+async fn wait_for_redirect(
+    redirect_rx: oneshot::Receiver<Result<AuthCodeRedirect, String>>,
+    expires_at: tokio::time::Instant,
+) -> Result<AuthCodeRedirect, String> {
+    let (cancel_tx, mut cancel_rx) = tokio::sync::watch::channel(false);
+    *LOGIN_CANCEL.lock().await = Some(cancel_tx);
+
+    let outcome = tokio::select! {
+        result = tokio::time::timeout_at(expires_at, redirect_rx) => Some(result),
+        _ = cancel_rx.wait_for(|cancelled| *cancelled) => None,
+    };
+
+    *LOGIN_CANCEL.lock().await = None;
+
+    match outcome {
+        None => {
+            info!("Browser login cancelled");
+            Err(LOGIN_CANCELLED_ERROR.to_string())
+        }
+        Some(Err(_)) => {
+            info!("Browser did not redirect back before the timeout");
+            Err(LOGIN_BROWSER_CLOSED_ERROR.to_string())
+        }
+        // The listener was stopped by a reset
+        Some(Ok(Err(_))) => Err(LOGIN_CANCELLED_ERROR.to_string()),
+        Some(Ok(Ok(redirect))) => redirect,
+    }
+}
+
+/// Exchange an authorization code for tokens, proving possession of the PKCE verifier
+async fn exchange_code(pending: &AuthCodeState, code: &str) -> Result<TokenResponse, String> {
+    let url = format!(
+        "{}/{}/oauth2/v2.0/token",
+        pending.authority_host, pending.tenant_id
+    );
+
+    let response = reqwest::Client::new()
+        .post(&url)
+        .form(&[
+            ("grant_type", "authorization_code"),
+            ("client_id", &pending.client_id),
+            ("code", code),
+            ("redirect_uri", &pending.redirect_uri),
+            ("code_verifier", &pending.code_verifier),
+            ("scope", &pending.scopes),
+        ])
+        .send()
+        .await
+        .map_err(|e| format!("Failed to exchange authorization code: {}", e))?;
+
+    if !response.status().is_success() {
+        let error_text = response.text().await.unwrap_or_default();
+        return Err(format!("Authorization code exchange failed: {}", error_text));
+    }
+
+    response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse token response: {}", e))
+}
+
+/// Complete the authorization code login flow
+///
+/// Waits for the redirect listener to capture the authorization code,
+/// unless `auth_code` is given (for example pasted by the user, together
+/// with the `state` from the same redirect). The code is only exchanged
+/// once its `state` matches the one generated at start.
+///
+/// # Errors
+///
+/// Returns `LOGIN_CANCELLED_ERROR` if the flow was cancelled,
+/// `LOGIN_BROWSER_CLOSED_ERROR` if the browser didn't redirect back in time
+/// and `LOGIN_STATE_MISMATCH_ERROR` or `LOGIN_NONCE_MISMATCH_ERROR` if the
+/// response wasn't issued for this login attempt.
+/// The flow is cleared on every failure.
+pub async fn complete_auth_code_login(
+    auth_code: Option<String>,
+    state: Option<String>,
+) -> Result<AuthResult, String> {
+    info!("Completing authorization code login...");
+
+    let (redirect_rx, expires_at) = {
+        let mut state_guard = AUTH_CODE_STATE.lock().await;
+        let pending = state_guard
+            .as_mut()
+            .ok_or_else(|| "No authentication flow in progress".to_string())?;
+        (pending.redirect_rx.take(), pending.expires_at)
+    };
+
+    let redirect = match (auth_code, redirect_rx) {
+        (Some(code), _) => Ok(AuthCodeRedirect {
+            code,
+            state: state.unwrap_or_default(),
+        }),
+        (None, Some(redirect_rx)) => wait_for_redirect(redirect_rx, expires_at).await,
+        (None, None) => Err("Browser login is already being completed".to_string()),
+    };
+
+    let pending = AUTH_CODE_STATE.lock().await.take();
+    if let Some(pending) = &pending {
+        pending.listener.abort();
+    }
+
+    let redirect = redirect?;
+    let pending = pending.ok_or_else(|| LOGIN_CANCELLED_ERROR.to_string())?;
+
+    validate_state(&pending.state, &redirect.state)?;
+
+    let token_res = exchange_code(&pending, &redirect.code).await.map_err(|e| {
+        log_error!("{}", e);
+        e
+    })?;
+
+    validate_nonce(&pending.nonce, token_res.id_token.as_deref())?;
+
+    let expires_in = token_res.expires_in.unwrap_or(3600);
+    let expires_on = OffsetDateTime::now_utc() + std::time::Duration::from_secs(expires_in);
+    let access_token = AccessToken::new(Secret::new(token_res.access_token.clone()), expires_on);
+
+    let credential = credential_from_tokens(
+        pending.authority_host,
+        pending.client_id,
+        pending.tenant_id,
+        management_scope(),
+        access_token,
+        token_res.refresh_token,
+    )
+    .await;
+
+    store_auth_result(credential, &token_res.access_token, "Browser Sign-In", None).await
+}
+
+/// Whether an authorization code flow is waiting to be completed
+pub async fn is_auth_code_login_pending() -> bool {
+    AUTH_CODE_STATE.lock().await.is_some()
+}
+
+/// Stop a pending authorization code flow and its redirect listener
+///
+/// # Returns
+///
+/// Returns `true` if an authorization code flow was in progress.
+pub async fn reset_auth_code_login() -> bool {
+    match AUTH_CODE_STATE.lock().await.take() {
+        Some(pending) => {
+            pending.listener.abort();
+            info!("Authorization code login flow reset");
+            true
+        }
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_code_challenge_matches_rfc_7636() {
+        // Example from RFC 7636 appendix B
+        assert_eq!(
+            code_challenge("dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk"),
+            "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM"
+        );
+    }
+
+    #[test]
+    fn test_generate_code_verifier() {
+        let verifier = generate_code_verifier();
+        assert_eq!(verifier.len(), 43);
+        assert_ne!(verifier, generate_code_verifier());
+    }
+
+    #[tokio::test]
+    async fn test_build_authorize_url() {
+        let auth_state = AuthCodeState {
+            authority_host: "https://login.microsoftonline.com".to_string(),
+            client_id: "client-1".to_string(),
+            tenant_id: "tenant-1".to_string(),
+            scopes: "openid offline_access".to_string(),
+            redirect_uri: "http://localhost:8400".to_string(),
+            code_verifier: "dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk".to_string(),
+            state: "state-1".to_string(),
+            nonce: "nonce-1".to_string(),
+            expires_at: tokio::time::Instant::now(),
+            redirect_rx: None,
+            listener: tokio::spawn(async {}),
+        };
+
+        let url = build_authorize_url(&auth_state);
+
+        assert!(url.starts_with(
+            "https://login.microsoftonline.com/tenant-1/oauth2/v2.0/authorize?client_id=client-1&"
+        ));
+        assert!(url.contains("&redirect_uri=http%3A%2F%2Flocalhost%3A8400&"));
+        assert!(url.contains("&scope=openid%20offline_access&"));
+        assert!(url.contains("&state=state-1&nonce=nonce-1&"));
+        assert!(url.contains("&code_challenge=E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM&"));
+    }
+
+    #[test]
+    fn test_parse_redirect() {
+        let redirect = parse_redirect("/?code=0.AXo%2Babc&state=state-1&session_state=s")
+            .unwrap()
+            .unwrap();
+        assert_eq!(redirect.code, "0.AXo+abc");
+        assert_eq!(redirect.state, "state-1");
+
+        assert_eq!(parse_redirect("/favicon.ico"), None);
+        assert_eq!(parse_redirect("/?foo=bar"), None);
+    }
+
+    #[test]
+    fn test_validate_state() {
+        assert_eq!(validate_state("state-1", "state-1"), Ok(()));
+    }
+
+    #[test]
+    fn test_validate_state_mismatch() {
+        assert_eq!(
+            validate_state("state-1", "state-2"),
+            Err(LOGIN_STATE_MISMATCH_ERROR.to_string())
+        );
+        assert_eq!(
+            validate_state("state-1", ""),
+            Err(LOGIN_STATE_MISMATCH_ERROR.to_string())
+        );
+        assert_eq!(validate_state("", ""), Err(LOGIN_STATE_MISMATCH_ERROR.to_string()));
+    }
+
+    #[test]
+    fn test_validate_nonce() {
+        let id_token = |claims: &str| format!("header.{}.signature", BASE64URL.encode(claims));
+
+        assert_eq!(validate_nonce("nonce-1", None), Ok(()));
+        assert_eq!(
+            validate_nonce("nonce-1", Some(&id_token(r#"{"nonce":"nonce-1"}"#))),
+            Ok(())
+        );
+        assert_eq!(
+            validate_nonce("nonce-1", Some(&id_token(r#"{"nonce":"nonce-2"}"#))),
+            Err(LOGIN_NONCE_MISMATCH_ERROR.to_string())
+        );
+        assert_eq!(
+            validate_nonce("nonce-1", Some(&id_token(r#"{"sub":"user-1"}"#))),
+            Err(LOGIN_NONCE_MISMATCH_ERROR.to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_redirect_error() {
+        let redirect = parse_redirect(
+            "/?error=access_denied&error_description=AADSTS65004%3A+User+declined+to+consent.&state=state-1",
+        )
+        .unwrap();
+        assert_eq!(
+            redirect,
+            Err("Sign-in failed: access_denied - AADSTS65004: User declined to consent.".to_string())
+        );
+    }
+}
@@ -1,25 +1,34 @@
-use crate::azure::auth::constants::VAULT_SCOPE;
+use crate::azure::auth::state::CredentialFactory;
 use crate::azure::auth::token::store_auth_result;
 use crate::azure::auth::types::AuthResult;
+use crate::config::keyvault_scope;
 use azure_core::credentials::TokenCredential;
 use azure_identity::{AzureCliCredential, AzureCliCredentialOptions};
+use std::sync::Arc;
 
-/// Initiates Azure authentication using Azure CLI
-/// Note: This requires the user to be logged in via Azure CLI (az login)
-pub async fn try_azure_cli_login() -> Result<AuthResult, String> {
+/// Build an Azure CLI credential with an empty token cache
+fn cli_credential() -> Result<Arc<dyn TokenCredential>, String> {
     // Use default options - let Azure CLI use its own context
     // Don't override tenant_id as the CLI already knows which tenant the user logged into
     let options = AzureCliCredentialOptions::default();
 
-    let credential = AzureCliCredential::new(Some(options))
+    let credential: Arc<dyn TokenCredential> = AzureCliCredential::new(Some(options))
         .map_err(|e| format!("Failed to create Azure CLI credential: {}", e))?;
+    Ok(credential)
+}
+
+/// Initiates Azure authentication using Azure CLI
+/// Note: This requires the user to be logged in via Azure CLI (az login)
+pub async fn try_azure_cli_login() -> Result<AuthResult, String> {
+    let credential = cli_credential()?;
 
     // Try to get a token to verify authentication
-    let scopes = &[VAULT_SCOPE];
+    let scopes = &[keyvault_scope()];
     let token = credential
         .get_token(scopes, None)
         .await
         .map_err(|e| format!("Azure CLI authentication failed: {}", e))?;
 
-    store_auth_result(credential, token.token.secret(), "Azure CLI").await
+    let rebuild: CredentialFactory = Arc::new(cli_credential);
+    store_auth_result(credential, token.token.secret(), "Azure CLI", Some(rebuild)).await
 }
@@ -1,10 +0,0 @@
-// ============================================================================
-// Constants
-// ============================================================================
-
-// Note: Azure Client ID and Tenant ID are stored in user_config.rs
-// and can be configured by the user at runtime.
-
-// Token scopes for API calls
-pub const VAULT_SCOPE: &str = "https://vault.azure.net/.default";
-
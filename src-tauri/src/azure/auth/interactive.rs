@@ -13,25 +13,66 @@ use azure_core::Error;
 use log::{error as log_error, info};
 use time::OffsetDateTime;
 
-use crate::azure::auth::state::{AUTH_CREDENTIAL, DEVICE_CODE_STATE};
+use crate::azure::auth::auth_code::reset_auth_code_login;
+use crate::azure::auth::state::{
+    ScopedTokenCache, AUTH_CREDENTIAL, AUTH_SESSION, DEVICE_CODE_STATE, INTERACTIVE_TOKEN_CACHE,
+    LOGIN_CANCEL,
+};
 use crate::azure::auth::token::store_auth_result;
 use crate::azure::auth::types::{
     AuthResult, DeviceCodeInfo, DeviceCodeResponse, DeviceCodeState, TokenResponse,
 };
-use crate::config::{
-    AUTH_SCOPES, DEVICE_CODE_ENDPOINT, MAX_POLL_ATTEMPTS, POLL_SLOWDOWN_SECONDS,
-    TOKEN_ENDPOINT,
-};
-use crate::user_config::{get_client_id, get_tenant_id};
+use crate::cancellation::register_operation;
+use crate::config::{management_scope, MAX_POLL_ATTEMPTS, POLL_SLOWDOWN_SECONDS};
+use crate::user_config::{get_auth_scopes, get_authority_host, get_client_id, get_tenant_id};
+
+/// Error returned when the user cancels a login that is waiting for them
+pub const LOGIN_CANCELLED_ERROR: &str = "Authentication cancelled";
+
+/// Operation id under which device code polling can be stopped with `cancel_operation`
+pub const DEVICE_CODE_LOGIN_OPERATION: &str = "device-code-login";
+
+/// Error returned when the device code expires before the user signs in
+pub const LOGIN_TIMED_OUT_ERROR: &str = "Authentication timed out";
+
+/// Error returned when Azure AD reports the device code as expired
+///
+/// The flow is cleared, so the UI can start a new one straight away.
+pub const LOGIN_CODE_EXPIRED_ERROR: &str = "Your sign-in code expired, please start again";
+
+/// What to do after a failed device code token request
+#[derive(Debug, PartialEq, Eq)]
+enum DeviceCodePollError {
+    /// The user hasn't signed in yet; poll again after the interval
+    Pending,
+    /// Polling too fast; wait longer before the next attempt
+    SlowDown,
+    /// The device code is no longer valid; a new flow has to be started
+    Expired,
+    /// Any other error code, which ends the flow
+    Failed(String),
+}
+
+/// Classify the error body of a device code token request
+fn classify_poll_error(error_json: &serde_json::Value) -> DeviceCodePollError {
+    match error_json["error"].as_str().unwrap_or("") {
+        "authorization_pending" => DeviceCodePollError::Pending,
+        "slow_down" => DeviceCodePollError::SlowDown,
+        "expired_token" | "code_expired" => DeviceCodePollError::Expired,
+        other => DeviceCodePollError::Failed(other.to_string()),
+    }
+}
 
 /// Credential implementation for interactive device code flow
 /// Supports requesting tokens for different scopes using refresh tokens
 #[derive(Debug)]
 struct InteractiveDeviceCodeCredential {
+    /// Azure AD login endpoint the flow was started against
+    authority_host: String,
     client_id: String,
     tenant_id: String,
     /// Cached access tokens keyed by scope
-    cached_tokens: Arc<tokio::sync::RwLock<HashMap<String, AccessToken>>>,
+    cached_tokens: ScopedTokenCache,
     /// Refresh token for obtaining new access tokens for different resources
     refresh_token: Arc<tokio::sync::RwLock<Option<String>>>,
 }
@@ -52,7 +93,7 @@ impl InteractiveDeviceCodeCredential {
         info!("Using refresh token to get access token for scope: {}", scope);
 
         let client = reqwest::Client::new();
-        let url = format!("{}/{}/oauth2/v2.0/token", TOKEN_ENDPOINT, self.tenant_id);
+        let url = format!("{}/{}/oauth2/v2.0/token", self.authority_host, self.tenant_id);
 
         let response = client
             .post(&url)
@@ -146,14 +187,14 @@ impl TokenCredential for InteractiveDeviceCodeCredential {
         };
 
         let client = reqwest::Client::new();
-        let url = format!("{}/{}/oauth2/v2.0/token", TOKEN_ENDPOINT, self.tenant_id);
+        let url = format!("{}/{}/oauth2/v2.0/token", self.authority_host, self.tenant_id);
 
         let mut attempts = 0;
         loop {
             if attempts >= MAX_POLL_ATTEMPTS {
                 return Err(Error::with_message(
                     azure_core::error::ErrorKind::Other,
-                    "Authentication timed out",
+                    LOGIN_TIMED_OUT_ERROR,
                 ));
             }
 
@@ -189,37 +230,86 @@ impl TokenCredential for InteractiveDeviceCodeCredential {
                 let access_token =
                     AccessToken::new(Secret::new(token_res.access_token), expires_on);
 
-                // Cache the token for the AUTH_SCOPES (management.azure.com)
+                // Cache the token for the auth scopes (the cloud's management API)
                 {
                     let mut cache = self.cached_tokens.write().await;
                     // Cache under the management scope since that's what we requested
-                    cache.insert("https://management.azure.com/.default".to_string(), access_token.clone());
+                    cache.insert(management_scope().to_string(), access_token.clone());
                 }
 
                 return Ok(access_token);
             } else {
                 let error_json: serde_json::Value = response.json().await.unwrap_or_default();
-                let error_code = error_json["error"].as_str().unwrap_or("");
-
-                if error_code == "authorization_pending" {
-                    attempts += 1;
-                    tokio::time::sleep(std::time::Duration::from_secs(state.interval)).await;
-                } else if error_code == "slow_down" {
-                    tokio::time::sleep(std::time::Duration::from_secs(
-                        state.interval + POLL_SLOWDOWN_SECONDS,
-                    ))
-                    .await;
-                } else {
-                    return Err(Error::with_message(
-                        azure_core::error::ErrorKind::Other,
-                        format!("Authentication failed: {}", error_code),
-                    ));
+
+                match classify_poll_error(&error_json) {
+                    DeviceCodePollError::Pending => {
+                        attempts += 1;
+                        tokio::time::sleep(std::time::Duration::from_secs(state.interval)).await;
+                    }
+                    DeviceCodePollError::SlowDown => {
+                        tokio::time::sleep(std::time::Duration::from_secs(
+                            state.interval + POLL_SLOWDOWN_SECONDS,
+                        ))
+                        .await;
+                    }
+                    DeviceCodePollError::Expired => {
+                        return Err(Error::with_message(
+                            azure_core::error::ErrorKind::Other,
+                            LOGIN_CODE_EXPIRED_ERROR,
+                        ));
+                    }
+                    DeviceCodePollError::Failed(error_code) => {
+                        return Err(Error::with_message(
+                            azure_core::error::ErrorKind::Other,
+                            format!("Authentication failed: {}", error_code),
+                        ));
+                    }
                 }
             }
         }
     }
 }
 
+/// Drop the interactive credential's cached token for a scope
+///
+/// The next request for that scope goes to Azure AD with the refresh token.
+/// Other credential types manage their own caches and are unaffected.
+pub(crate) async fn evict_cached_token(scope: &str) {
+    let cache = INTERACTIVE_TOKEN_CACHE.lock().await.clone();
+    if let Some(cache) = cache {
+        if cache.write().await.remove(scope).is_some() {
+            info!("Evicted cached token for scope: {}", scope);
+        }
+    }
+}
+
+/// Build an interactive credential from tokens obtained outside the device code flow
+///
+/// The authorization code flow ends with the same access and refresh tokens,
+/// so it reuses this credential for caching and per-scope refreshes.
+pub(crate) async fn credential_from_tokens(
+    authority_host: String,
+    client_id: String,
+    tenant_id: String,
+    scope: &str,
+    access_token: AccessToken,
+    refresh_token: Option<String>,
+) -> Arc<dyn TokenCredential> {
+    let cached_tokens: ScopedTokenCache = Arc::new(tokio::sync::RwLock::new(HashMap::from([(
+        scope.to_string(),
+        access_token,
+    )])));
+    *INTERACTIVE_TOKEN_CACHE.lock().await = Some(cached_tokens.clone());
+
+    Arc::new(InteractiveDeviceCodeCredential {
+        authority_host,
+        client_id,
+        tenant_id,
+        cached_tokens,
+        refresh_token: Arc::new(tokio::sync::RwLock::new(refresh_token)),
+    })
+}
+
 /// Start interactive browser login flow (uses device code flow)
 ///
 /// This initiates the device code authentication flow where the user
@@ -231,18 +321,23 @@ impl TokenCredential for InteractiveDeviceCodeCredential {
 pub async fn start_interactive_browser_login() -> Result<DeviceCodeInfo, String> {
     info!("Starting interactive browser login flow...");
 
+    // A redirect listener left over from an abandoned browser sign-in
+    reset_auth_code_login().await;
+
     // Get dynamic configuration
+    let authority_host = get_authority_host().await;
     let client_id = get_client_id().await;
     let tenant_id = get_tenant_id().await;
+    let scopes = get_auth_scopes().await;
 
     let device_code_url = format!(
         "{}/{}/oauth2/v2.0/devicecode",
-        DEVICE_CODE_ENDPOINT, tenant_id
+        authority_host, tenant_id
     );
 
     let mut params = HashMap::new();
     params.insert("client_id", client_id.as_str());
-    params.insert("scope", AUTH_SCOPES);
+    params.insert("scope", scopes.as_str());
 
     let client = reqwest::Client::new();
     let response = client
@@ -266,16 +361,22 @@ pub async fn start_interactive_browser_login() -> Result<DeviceCodeInfo, String>
     let state = DeviceCodeState {
         device_code: device_response.device_code.clone(),
         interval: device_response.interval,
+        expires_at: tokio::time::Instant::now()
+            + std::time::Duration::from_secs(device_response.expires_in),
     };
 
     let mut state_guard = DEVICE_CODE_STATE.lock().await;
     *state_guard = Some(state);
 
     // Initialize the credential and store it in AUTH_CREDENTIAL
+    let cached_tokens: ScopedTokenCache = Arc::new(tokio::sync::RwLock::new(HashMap::new()));
+    *INTERACTIVE_TOKEN_CACHE.lock().await = Some(cached_tokens.clone());
+
     let credential = InteractiveDeviceCodeCredential {
+        authority_host,
         client_id,
         tenant_id,
-        cached_tokens: Arc::new(tokio::sync::RwLock::new(HashMap::new())),
+        cached_tokens,
         refresh_token: Arc::new(tokio::sync::RwLock::new(None)),
     };
 
@@ -298,9 +399,19 @@ pub async fn start_interactive_browser_login() -> Result<DeviceCodeInfo, String>
 /// This should be called after `start_interactive_browser_login` once
 /// the user has completed authentication in their browser.
 ///
+/// Polling stops when the device code expires, when `cancel_interactive_login`
+/// is called or when the `DEVICE_CODE_LOGIN_OPERATION` operation is cancelled;
+/// all of them clear the in-progress flow.
+///
 /// # Returns
 ///
 /// Returns authentication result with user information.
+///
+/// # Errors
+///
+/// Returns `LOGIN_CANCELLED_ERROR` if the flow was cancelled,
+/// `LOGIN_TIMED_OUT_ERROR` if the device code expired while polling and
+/// `LOGIN_CODE_EXPIRED_ERROR` if Azure AD rejected it as expired.
 pub async fn complete_interactive_browser_login() -> Result<AuthResult, String> {
     info!("Completing interactive browser login...");
 
@@ -312,13 +423,61 @@ pub async fn complete_interactive_browser_login() -> Result<AuthResult, String>
         })?
     };
 
-    let token_response = credential
-        .get_token(&["https://management.azure.com/.default"], None)
+    let expires_at = DEVICE_CODE_STATE
+        .lock()
         .await
-        .map_err(|e| {
-            log_error!("Failed to complete authentication: {}", e);
-            format!("{}", e)
-        })?;
+        .as_ref()
+        .map(|state| state.expires_at);
+
+    let (cancel_tx, mut cancel_rx) = tokio::sync::watch::channel(false);
+    *LOGIN_CANCEL.lock().await = Some(cancel_tx);
+    let operation = register_operation(DEVICE_CODE_LOGIN_OPERATION);
+
+    let poll = async {
+        let get_token = credential.get_token(&[management_scope()], None);
+        match expires_at {
+            Some(deadline) => tokio::time::timeout_at(deadline, get_token).await.ok(),
+            None => Some(get_token.await),
+        }
+    };
+
+    let outcome = tokio::select! {
+        result = poll => Some(result),
+        _ = cancel_rx.wait_for(|cancelled| *cancelled) => None,
+        _ = operation.token().cancelled() => None,
+    };
+
+    *LOGIN_CANCEL.lock().await = None;
+    let cancelled_by_operation = operation.token().is_cancelled();
+    drop(operation);
+
+    let token_response = match outcome {
+        None => {
+            info!("Interactive login cancelled");
+            if cancelled_by_operation {
+                // cancel_interactive_login resets the flow itself; cancel_operation doesn't
+                reset_interactive_login().await;
+            }
+            return Err(LOGIN_CANCELLED_ERROR.to_string());
+        }
+        Some(None) => {
+            info!("Device code expired before login completed");
+            reset_interactive_login().await;
+            return Err(LOGIN_TIMED_OUT_ERROR.to_string());
+        }
+        Some(Some(result)) => match result {
+            Ok(token) => token,
+            Err(e) => {
+                log_error!("Failed to complete authentication: {}", e);
+                let message = format!("{}", e);
+                if message == LOGIN_CODE_EXPIRED_ERROR {
+                    // The stale device code would only fail again
+                    reset_interactive_login().await;
+                }
+                return Err(message);
+            }
+        },
+    };
 
     // Clear device code state after successful authentication
     {
@@ -330,6 +489,94 @@ pub async fn complete_interactive_browser_login() -> Result<AuthResult, String>
         credential,
         token_response.token.secret(),
         "Interactive Browser Flow",
+        None,
     )
     .await
 }
+
+/// Cancel a login that is waiting for the user to sign in
+///
+/// Stops the polling in `complete_interactive_browser_login`, which then
+/// returns `LOGIN_CANCELLED_ERROR`, and clears the in-progress flow.
+///
+/// # Returns
+///
+/// Returns `true` if a login flow was in progress.
+pub async fn cancel_interactive_login() -> bool {
+    if let Some(cancel_tx) = LOGIN_CANCEL.lock().await.take() {
+        // Receiver may already be gone if polling just finished
+        let _ = cancel_tx.send(true);
+    }
+    reset_interactive_login().await
+}
+
+/// Reset a wedged or abandoned interactive login flow
+///
+/// Clears the stored device code state and, if no login has completed yet,
+/// the half-built credential created by `start_interactive_browser_login`.
+/// A pending authorization code flow and its redirect listener are stopped too.
+/// A fully authenticated credential is left untouched.
+///
+/// # Returns
+///
+/// Returns `true` if a device code or authorization code flow was in progress.
+pub async fn reset_interactive_login() -> bool {
+    let auth_code_in_progress = reset_auth_code_login().await;
+
+    let was_in_progress = {
+        let mut state_guard = DEVICE_CODE_STATE.lock().await;
+        state_guard.take().is_some()
+    };
+
+    if was_in_progress {
+        let has_session = AUTH_SESSION.lock().await.is_some();
+        if !has_session {
+            let mut auth_lock = AUTH_CREDENTIAL.lock().await;
+            *auth_lock = None;
+            info!("Cleared in-progress credential from AUTH_CREDENTIAL");
+        }
+    }
+
+    info!(
+        "Interactive login flow reset (flow in progress: {})",
+        was_in_progress || auth_code_in_progress
+    );
+    was_in_progress || auth_code_in_progress
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_poll_error() {
+        let pending = serde_json::json!({"error": "authorization_pending"});
+        assert_eq!(classify_poll_error(&pending), DeviceCodePollError::Pending);
+
+        let slow_down = serde_json::json!({"error": "slow_down"});
+        assert_eq!(classify_poll_error(&slow_down), DeviceCodePollError::SlowDown);
+
+        let denied = serde_json::json!({"error": "authorization_declined"});
+        assert_eq!(
+            classify_poll_error(&denied),
+            DeviceCodePollError::Failed("authorization_declined".to_string())
+        );
+    }
+
+    #[test]
+    fn test_classify_poll_error_expired_token() {
+        let expired: serde_json::Value = serde_json::from_str(
+            r#"{
+                "error": "expired_token",
+                "error_description": "AADSTS70020: This device code has expired.",
+                "error_codes": [70020],
+                "timestamp": "2024-05-02 09:14:11Z"
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(classify_poll_error(&expired), DeviceCodePollError::Expired);
+
+        let code_expired = serde_json::json!({"error": "code_expired"});
+        assert_eq!(classify_poll_error(&code_expired), DeviceCodePollError::Expired);
+    }
+}
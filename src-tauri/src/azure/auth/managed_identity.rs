@@ -0,0 +1,60 @@
+use crate::azure::auth::state::CredentialFactory;
+use crate::azure::auth::token::store_auth_result;
+use crate::azure::auth::types::AuthResult;
+use crate::config::{keyvault_scope, MANAGED_IDENTITY_TIMEOUT_SECONDS};
+use crate::user_config::get_config;
+use azure_core::credentials::TokenCredential;
+use azure_identity::{ManagedIdentityCredential, ManagedIdentityCredentialOptions, UserAssignedId};
+use log::info;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Initiates Azure authentication using the managed identity of the host (VM, container, App Service)
+/// Uses the user-assigned identity from `managed_identity_client_id` in the user config if set,
+/// otherwise the system-assigned identity.
+/// Note: Managed identity tokens carry no user claims, so no email or name is stored
+pub async fn try_managed_identity_login() -> Result<AuthResult, String> {
+    let client_id = get_config().await.managed_identity_client_id;
+
+    match &client_id {
+        Some(client_id) => info!("Trying user-assigned managed identity {}", client_id),
+        None => info!("Trying system-assigned managed identity"),
+    }
+
+    let credential = managed_identity_credential(client_id.clone())?;
+
+    // Outside Azure the metadata endpoint doesn't answer, so don't let it stall the login chain
+    let scopes = &[keyvault_scope()];
+    let token = tokio::time::timeout(
+        Duration::from_secs(MANAGED_IDENTITY_TIMEOUT_SECONDS),
+        credential.get_token(scopes, None),
+    )
+    .await
+    .map_err(|_| "No managed identity endpoint responded".to_string())?
+    .map_err(|e| format!("Managed identity authentication failed: {}", e))?;
+
+    let rebuild: CredentialFactory =
+        Arc::new(move || managed_identity_credential(client_id.clone()));
+    store_auth_result(
+        credential,
+        token.token.secret(),
+        "Managed Identity",
+        Some(rebuild),
+    )
+    .await
+}
+
+/// Build a managed identity credential with an empty token cache; user-assigned
+/// if `client_id` is set, system-assigned otherwise
+fn managed_identity_credential(
+    client_id: Option<String>,
+) -> Result<Arc<dyn TokenCredential>, String> {
+    let options = ManagedIdentityCredentialOptions {
+        user_assigned_id: client_id.map(UserAssignedId::ClientId),
+        ..Default::default()
+    };
+
+    let credential: Arc<dyn TokenCredential> = ManagedIdentityCredential::new(Some(options))
+        .map_err(|e| format!("Failed to create managed identity credential: {}", e))?;
+    Ok(credential)
+}
@@ -3,17 +3,21 @@
 //! This module provides authentication functionality for Azure services,
 //! supporting multiple authentication methods:
 //! - Azure CLI credentials
+//! - Managed Identity (system- or user-assigned)
 //! - Service Principal via environment variables  
 //! - Device Code Flow
 //! - Interactive Browser Flow
+//! - Authorization Code Flow with PKCE (browser redirect to localhost)
+pub mod auth_code;
 pub mod interactive;
+pub mod openid;
 pub mod provider;
 pub mod service;
 pub mod token;
 pub mod types;
 
 pub(crate) mod cli;
-pub(crate) mod constants;
+pub(crate) mod managed_identity;
 pub(crate) mod service_principal;
 pub(crate) mod state;
 pub(crate) mod user_info;
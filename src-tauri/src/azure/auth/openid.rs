@@ -0,0 +1,151 @@
+//! OpenID Connect discovery - fetch the configured tenant's endpoints
+//!
+//! Used to check that the tenant exists and its endpoints resolve before a
+//! login is attempted.
+
+use log::{error, info};
+use serde::Deserialize;
+
+use super::types::OpenIdConfiguration;
+use crate::cache::azure_cache;
+use crate::user_config::{get_authority_host, get_tenant_id};
+
+/// Error body returned by Azure AD for a failed discovery request
+#[derive(Debug, Deserialize)]
+struct DiscoveryError {
+    #[serde(default)]
+    error: String,
+    #[serde(default)]
+    error_description: String,
+}
+
+/// Build the discovery document URL for a tenant
+fn discovery_url(authority_host: &str, tenant_id: &str) -> String {
+    format!(
+        "{}/{}/v2.0/.well-known/openid-configuration",
+        authority_host, tenant_id
+    )
+}
+
+/// Fetch the OpenID configuration for the effective authority host and tenant.
+///
+/// Results are cached briefly, so repeated diagnostics don't hit Azure AD.
+///
+/// # Errors
+///
+/// This function will return an error if:
+/// - The tenant doesn't exist
+/// - The authority host can't be reached
+/// - The response isn't a discovery document
+pub async fn get_openid_config() -> Result<OpenIdConfiguration, String> {
+    let authority_host = get_authority_host().await;
+    let tenant_id = get_tenant_id().await;
+    let url = discovery_url(&authority_host, &tenant_id);
+
+    let request_url = url.clone();
+    azure_cache()
+        .get_openid_config_or_load(&url, || async move {
+            fetch_openid_config(&request_url, &tenant_id).await
+        })
+        .await
+        .map_err(|e| {
+            error!("Failed to get OpenID configuration: {}", e);
+            e
+        })
+}
+
+async fn fetch_openid_config(url: &str, tenant_id: &str) -> Result<OpenIdConfiguration, String> {
+    info!("Fetching OpenID configuration for tenant '{}'", tenant_id);
+
+    let response = reqwest::Client::new()
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach the authority host: {}", e))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let error_text = response.text().await.unwrap_or_default();
+        return Err(describe_discovery_error(
+            tenant_id,
+            status.as_u16(),
+            &error_text,
+        ));
+    }
+
+    response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse OpenID configuration: {}", e))
+}
+
+/// Turn a failed discovery response into a message, calling out unknown tenants
+fn describe_discovery_error(tenant_id: &str, status: u16, body: &str) -> String {
+    let parsed: Option<DiscoveryError> = serde_json::from_str(body).ok();
+
+    match parsed {
+        Some(e) if e.error == "invalid_tenant" => format!(
+            "Tenant '{}' was not found. Check the tenant ID in the settings",
+            tenant_id
+        ),
+        Some(e) if !e.error_description.is_empty() => format!(
+            "OpenID configuration request failed ({}): {}",
+            status, e.error_description
+        ),
+        _ => format!("OpenID configuration request failed ({}): {}", status, body),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_discovery_url() {
+        assert_eq!(
+            discovery_url("https://login.microsoftonline.com", "organizations"),
+            "https://login.microsoftonline.com/organizations/v2.0/.well-known/openid-configuration"
+        );
+    }
+
+    #[test]
+    fn test_unknown_tenant_error() {
+        let body = r#"{"error":"invalid_tenant","error_description":"AADSTS90002: Tenant 'contoso-typo' not found."}"#;
+        let message = describe_discovery_error("contoso-typo", 400, body);
+        assert!(message.contains("Tenant 'contoso-typo' was not found"));
+    }
+
+    #[test]
+    fn test_other_discovery_errors() {
+        let body = r#"{"error":"temporarily_unavailable","error_description":"Try again later."}"#;
+        assert_eq!(
+            describe_discovery_error("organizations", 503, body),
+            "OpenID configuration request failed (503): Try again later."
+        );
+        assert_eq!(
+            describe_discovery_error("organizations", 502, "Bad Gateway"),
+            "OpenID configuration request failed (502): Bad Gateway"
+        );
+    }
+
+    #[test]
+    fn test_parse_discovery_document() {
+        let config: OpenIdConfiguration = serde_json::from_str(
+            r#"{
+                "issuer": "https://login.microsoftonline.com/{tenantid}/v2.0",
+                "authorization_endpoint": "https://login.microsoftonline.com/organizations/oauth2/v2.0/authorize",
+                "token_endpoint": "https://login.microsoftonline.com/organizations/oauth2/v2.0/token",
+                "device_authorization_endpoint": "https://login.microsoftonline.com/organizations/oauth2/v2.0/devicecode",
+                "scopes_supported": ["openid", "profile", "email", "offline_access"],
+                "response_types_supported": ["code", "id_token"]
+            }"#,
+        )
+        .unwrap();
+
+        assert!(config.token_endpoint.ends_with("/oauth2/v2.0/token"));
+        assert_eq!(config.scopes_supported.len(), 4);
+
+        let serialized = serde_json::to_value(&config).unwrap();
+        assert!(serialized.get("authorizationEndpoint").is_some());
+    }
+}
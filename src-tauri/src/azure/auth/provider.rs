@@ -4,16 +4,11 @@
 //! for different authentication methods and easier testing.
 
 use async_trait::async_trait;
-use log::{debug, error, info};
+use log::{debug, error, info, warn};
+use std::sync::atomic::Ordering;
 
 use crate::azure::http::AzureHttpError;
-
-// ============================================================================
-// Token Scopes
-// ============================================================================
-
-/// Azure Resource Management API scope
-pub const MANAGEMENT_SCOPE: &str = "https://management.azure.com/.default";
+use crate::config::management_scope;
 
 // ============================================================================
 // TokenProvider Trait
@@ -49,13 +44,56 @@ pub trait TokenProvider: Send + Sync {
     ///
     /// * `scope` - The OAuth2 scope to request (e.g., "https://graph.microsoft.com/.default")
     async fn get_token_for_scope(&self, scope: &str) -> Result<String, AzureHttpError>;
+
+    /// Get a new token for a specific scope, bypassing any cached token.
+    ///
+    /// Used when Azure rejects a token that still looks valid. Providers
+    /// without their own cache can rely on the default.
+    async fn refresh_token_for_scope(&self, scope: &str) -> Result<String, AzureHttpError> {
+        self.get_token_for_scope(scope).await
+    }
 }
 
 // ============================================================================
 // Global Token Provider (backed by AUTH_CREDENTIAL state)
 // ============================================================================
 
-use crate::azure::auth::state::AUTH_CREDENTIAL;
+use crate::azure::auth::interactive::evict_cached_token;
+use crate::azure::auth::state::{AUTH_CREDENTIAL, CREDENTIAL_FACTORY, REAUTH_REQUIRED};
+
+/// Markers in a credential error that mean the refresh token is expired, revoked
+/// or otherwise unusable, so only a new login helps
+const REAUTH_MARKERS: &[&str] = &[
+    // Refresh token expired due to inactivity
+    "AADSTS700082",
+    // Refresh token for a single-page app expired
+    "AADSTS700084",
+    // Refresh token expired or was revoked by conditional access
+    "AADSTS70043",
+    // Refresh token revoked, e.g. after a password change
+    "AADSTS50173",
+    "invalid_grant",
+    "interaction_required",
+    "No refresh token available",
+];
+
+/// Whether a credential error means the user has to sign in again
+fn requires_reauth(message: &str) -> bool {
+    REAUTH_MARKERS.iter().any(|marker| message.contains(marker))
+}
+
+/// Map a credential error to `ReauthRequired` for expired sign-ins,
+/// `TokenError` for everything else
+fn map_credential_error(scope: &str, message: &str) -> AzureHttpError {
+    if requires_reauth(message) {
+        AzureHttpError::ReauthRequired(message.to_string())
+    } else {
+        AzureHttpError::TokenError(format!(
+            "Failed to get token for scope {}: {}",
+            scope, message
+        ))
+    }
+}
 
 /// A token provider that uses the global AUTH_CREDENTIAL state.
 ///
@@ -79,7 +117,7 @@ impl Default for GlobalTokenProvider {
 #[async_trait]
 impl TokenProvider for GlobalTokenProvider {
     async fn get_management_token(&self) -> Result<String, AzureHttpError> {
-        self.get_token_for_scope(MANAGEMENT_SCOPE).await
+        self.get_token_for_scope(management_scope()).await
     }
 
     async fn get_token_for_scope(&self, scope: &str) -> Result<String, AzureHttpError> {
@@ -95,12 +133,40 @@ impl TokenProvider for GlobalTokenProvider {
 
         let token_response = credential.get_token(&[scope], None).await.map_err(|e| {
             error!("Failed to get token: {}", e);
-            AzureHttpError::TokenError(format!("Failed to get token for scope {}: {}", scope, e))
+            let err = map_credential_error(scope, &e.to_string());
+            if matches!(err, AzureHttpError::ReauthRequired(_)) {
+                warn!("Sign-in expired, the user has to log in again");
+                REAUTH_REQUIRED.store(true, Ordering::Relaxed);
+            }
+            err
         })?;
 
+        REAUTH_REQUIRED.store(false, Ordering::Relaxed);
         info!("Successfully obtained token");
         Ok(token_response.token.secret().to_string())
     }
+
+    async fn refresh_token_for_scope(&self, scope: &str) -> Result<String, AzureHttpError> {
+        evict_cached_token(scope).await;
+
+        // Other credentials keep their token cache to themselves, so swap in a fresh one
+        let rebuild = CREDENTIAL_FACTORY.lock().await.clone();
+        if let Some(rebuild) = rebuild {
+            match rebuild() {
+                Ok(credential) => {
+                    let mut cred_lock = AUTH_CREDENTIAL.lock().await;
+                    // Don't bring back a credential the user just logged out of
+                    if cred_lock.is_some() {
+                        info!("Rebuilt the credential to refresh its token");
+                        *cred_lock = Some(credential);
+                    }
+                }
+                Err(e) => warn!("Failed to rebuild the credential, reusing it: {}", e),
+            }
+        }
+
+        self.get_token_for_scope(scope).await
+    }
 }
 
 // ============================================================================
@@ -110,6 +176,7 @@ impl TokenProvider for GlobalTokenProvider {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::CloudEnvironment;
 
     #[test]
     fn test_global_provider_creation() {
@@ -117,8 +184,82 @@ mod tests {
         let _provider_default = GlobalTokenProvider::default();
     }
 
+    #[test]
+    fn test_expired_refresh_token_requires_reauth() {
+        let messages = [
+            "AADSTS700082: The refresh token has expired due to inactivity.",
+            r#"{"error":"invalid_grant","error_description":"AADSTS50173: The provided grant has expired"}"#,
+            "interaction_required: AADSTS50076: MFA is required",
+            "No refresh token available - please re-authenticate",
+        ];
+
+        for message in messages {
+            assert!(
+                matches!(
+                    map_credential_error("https://vault.azure.net/.default", message),
+                    AzureHttpError::ReauthRequired(m) if m == message
+                ),
+                "{}",
+                message
+            );
+        }
+    }
+
+    #[test]
+    fn test_other_credential_errors_stay_token_errors() {
+        let err = map_credential_error(
+            "https://vault.azure.net/.default",
+            "error sending request: connection refused",
+        );
+        assert_eq!(
+            err.to_string(),
+            "Token error: Failed to get token for scope https://vault.azure.net/.default: \
+             error sending request: connection refused"
+        );
+    }
+
+    #[derive(Debug)]
+    struct StaticCredential(&'static str);
+
+    #[async_trait]
+    impl azure_core::credentials::TokenCredential for StaticCredential {
+        async fn get_token(
+            &self,
+            _scopes: &[&str],
+            _options: Option<azure_core::credentials::TokenRequestOptions<'_>>,
+        ) -> azure_core::Result<azure_core::credentials::AccessToken> {
+            Ok(azure_core::credentials::AccessToken::new(
+                azure_core::credentials::Secret::new(self.0.to_string()),
+                time::OffsetDateTime::now_utc() + std::time::Duration::from_secs(3600),
+            ))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_refresh_bypasses_the_credential_cache() {
+        *AUTH_CREDENTIAL.lock().await = Some(std::sync::Arc::new(StaticCredential("cached")));
+        *CREDENTIAL_FACTORY.lock().await = Some(std::sync::Arc::new(|| {
+            let credential: std::sync::Arc<dyn azure_core::credentials::TokenCredential> =
+                std::sync::Arc::new(StaticCredential("fresh"));
+            Ok(credential)
+        }));
+
+        let provider = GlobalTokenProvider::new();
+        let scope = "https://vault.azure.net/.default";
+        let cached = provider.get_token_for_scope(scope).await.unwrap();
+        let refreshed = provider.refresh_token_for_scope(scope).await.unwrap();
+
+        *AUTH_CREDENTIAL.lock().await = None;
+        *CREDENTIAL_FACTORY.lock().await = None;
+        assert_eq!(cached, "cached");
+        assert_eq!(refreshed, "fresh");
+    }
+
     #[test]
     fn test_scopes_are_correct() {
-        assert_eq!(MANAGEMENT_SCOPE, "https://management.azure.com/.default");
+        assert_eq!(
+            CloudEnvironment::Public.management_scope(),
+            "https://management.azure.com/.default"
+        );
     }
 }
@@ -4,17 +4,27 @@
 //! between different authentication methods (CLI, Service Principal, etc.)
 
 use crate::azure::auth::cli::try_azure_cli_login;
+use crate::azure::auth::managed_identity::try_managed_identity_login;
+use crate::azure::auth::provider::{GlobalTokenProvider, TokenProvider};
 use crate::azure::auth::service_principal::try_environment_credential;
-use crate::azure::auth::state::AUTH_CREDENTIAL;
-use crate::azure::auth::types::AuthResult;
+use crate::azure::auth::state::{
+    AUTH_CREDENTIAL, AUTH_SESSION, CREDENTIAL_FACTORY, INTERACTIVE_TOKEN_CACHE, REAUTH_REQUIRED,
+};
+use crate::azure::auth::token::{decode_token_payload, get_token_for_scope};
+use crate::azure::auth::types::{AuthResult, AuthStatus, AuthStatusDetail};
 use crate::azure::auth::user_info::USER_INFO;
-use log::{error, info};
+use crate::azure::http::AzureHttpError;
+use crate::cache::forget_snapshot_owner;
+use crate::config::management_scope;
+use log::{error, info, warn};
+use std::sync::atomic::Ordering;
 
 /// Try to authenticate with the best available method.
 ///
 /// This function attempts authentication in the following order:
 /// 1. Azure CLI credentials (if `az login` has been run)
-/// 2. Service Principal via environment variables
+/// 2. Managed Identity (when running on Azure)
+/// 3. Service Principal via environment variables
 ///
 /// # Returns
 ///
@@ -24,31 +34,46 @@ pub async fn login() -> Result<AuthResult, String> {
     info!("Starting generic login flow...");
 
     // First, try Azure CLI authentication
-    match try_azure_cli_login().await {
+    let cli_error = match try_azure_cli_login().await {
         Ok(result) => {
             info!("Successfully authenticated with Azure CLI");
             return Ok(result);
         }
-        Err(cli_error) => {
-            info!("Azure CLI authentication failed: {}", cli_error);
-            info!("Falling back to Service Principal authentication...");
-
-            // Fall back to Service Principal authentication via environment variables
-            match try_environment_credential().await {
-                Ok(result) => Ok(result),
-                Err(env_error) => {
-                    error!("Service Principal authentication failed: {}", env_error);
-                    Err(format!(
-                        "All authentication methods failed.\n\n\
-                        Azure CLI: {}\n\n\
-                        Service Principal: {}\n\n\
-                        Please either:\n\
-                        1. Run 'az login' in your terminal, or\n\
-                        2. Set AZURE_CLIENT_SECRET environment variable for Service Principal auth",
-                        cli_error, env_error
-                    ))
-                }
-            }
+        Err(cli_error) => cli_error,
+    };
+    info!("Azure CLI authentication failed: {}", cli_error);
+    info!("Falling back to Managed Identity authentication...");
+
+    // Then the managed identity of the host, when running on Azure
+    let managed_identity_error = match try_managed_identity_login().await {
+        Ok(result) => {
+            info!("Successfully authenticated with Managed Identity");
+            return Ok(result);
+        }
+        Err(managed_identity_error) => managed_identity_error,
+    };
+    info!(
+        "Managed Identity authentication failed: {}",
+        managed_identity_error
+    );
+    info!("Falling back to Service Principal authentication...");
+
+    // Fall back to Service Principal authentication via environment variables
+    match try_environment_credential().await {
+        Ok(result) => Ok(result),
+        Err(env_error) => {
+            error!("Service Principal authentication failed: {}", env_error);
+            Err(format!(
+                "All authentication methods failed.\n\n\
+                Azure CLI: {}\n\n\
+                Managed Identity: {}\n\n\
+                Service Principal: {}\n\n\
+                Please either:\n\
+                1. Run 'az login' in your terminal,\n\
+                2. Run VaultRaider on an Azure host with a managed identity, or\n\
+                3. Set AZURE_CLIENT_SECRET environment variable for Service Principal auth",
+                cli_error, managed_identity_error, env_error
+            ))
         }
     }
 }
@@ -65,15 +90,26 @@ pub async fn is_authenticated() -> bool {
 
 /// Logout and clear all stored credentials.
 ///
-/// This clears both the authentication credential and any cached user info.
+/// This clears the authentication credential, its cached tokens and any cached
+/// user info, and stops saving the cache snapshot for the signed-out account.
 pub async fn logout() {
     info!("Logging out, clearing AUTH_CREDENTIAL");
 
+    forget_snapshot_owner();
+
     let mut cred = AUTH_CREDENTIAL.lock().await;
     *cred = None;
 
+    *CREDENTIAL_FACTORY.lock().await = None;
+    *INTERACTIVE_TOKEN_CACHE.lock().await = None;
+
+    let mut session = AUTH_SESSION.lock().await;
+    *session = None;
+
     let mut user_info = USER_INFO.lock().await;
     *user_info = None;
+
+    REAUTH_REQUIRED.store(false, Ordering::Relaxed);
 }
 
 /// Get the current user's information.
@@ -85,3 +121,97 @@ pub async fn get_user_info() -> Option<(String, Option<String>)> {
     let user_info = USER_INFO.lock().await;
     user_info.clone()
 }
+
+/// Get the claims of the current management token, for troubleshooting.
+///
+/// Useful for diagnosing RBAC and tenant mismatches (`oid`, `tid`, `roles`,
+/// `aud`, ...). Only the decoded payload is returned; the raw token and its
+/// signature never leave the backend.
+///
+/// # Returns
+///
+/// The token's claims as a JSON object, or an error if not authenticated.
+pub async fn get_token_claims() -> Result<serde_json::Map<String, serde_json::Value>, String> {
+    let token = get_token_for_scope(management_scope()).await.map_err(|e| {
+        error!("Failed to get token for claims: {}", e);
+        e.to_string()
+    })?;
+
+    decode_token_payload(&token).ok_or_else(|| "Current token is not a decodable JWT".to_string())
+}
+
+/// Get the combined authentication status.
+///
+/// A user only counts as authenticated once a login has completed; a credential
+/// stored mid-way through the device code flow is not enough. The token expiry
+/// is read from the credential's (cached) management token when available.
+///
+/// # Returns
+///
+/// The authentication status including user, tenant, method and token expiry.
+pub async fn get_auth_status() -> AuthStatus {
+    let session = AUTH_SESSION.lock().await.clone();
+    let credential = AUTH_CREDENTIAL.lock().await.clone();
+
+    let (session, credential) = match (session, credential) {
+        (Some(session), Some(credential)) => (session, credential),
+        _ => {
+            return AuthStatus {
+                authenticated: false,
+                email: None,
+                name: None,
+                tenant_id: None,
+                auth_method: None,
+                token_expires_at: None,
+            };
+        }
+    };
+
+    let token_expires_at = match credential.get_token(&[management_scope()], None).await {
+        Ok(token) => Some(token.expires_on.unix_timestamp()),
+        Err(e) => {
+            warn!("Failed to read token expiry: {}", e);
+            None
+        }
+    };
+
+    let (email, name) = match get_user_info().await {
+        Some((email, name)) => (Some(email), name),
+        None => (None, None),
+    };
+
+    AuthStatus {
+        authenticated: true,
+        email,
+        name,
+        tenant_id: session.tenant_id,
+        auth_method: Some(session.auth_method),
+        token_expires_at,
+    }
+}
+
+/// Check whether the stored sign-in can still get tokens.
+///
+/// Requests a management token, so an expired refresh token shows up as
+/// `Expired` instead of failing the next Azure call. If the check fails for
+/// another reason (e.g. no network), the state is `Unknown`, unless an earlier
+/// token request already found the sign-in expired.
+pub async fn get_auth_status_detail() -> AuthStatusDetail {
+    if !is_authenticated().await {
+        return AuthStatusDetail::NotAuthenticated;
+    }
+
+    match GlobalTokenProvider::new().get_management_token().await {
+        Ok(_) => AuthStatusDetail::Authenticated,
+        Err(AzureHttpError::ReauthRequired(_)) => AuthStatusDetail::Expired,
+        Err(AzureHttpError::NotAuthenticated) => AuthStatusDetail::NotAuthenticated,
+        Err(e) => {
+            warn!("Failed to check the sign-in: {}", e);
+            if REAUTH_REQUIRED.load(Ordering::Relaxed) {
+                AuthStatusDetail::Expired
+            } else {
+                AuthStatusDetail::Unknown
+            }
+        }
+    }
+}
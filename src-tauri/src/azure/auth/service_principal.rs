@@ -1,15 +1,121 @@
-use crate::azure::auth::constants::VAULT_SCOPE;
-use crate::azure::auth::token::store_auth_result;
-use crate::azure::auth::types::AuthResult;
-use crate::user_config::{get_client_id, get_tenant_id};
-use azure_core::credentials::{Secret, TokenCredential};
+use crate::azure::auth::state::{CredentialFactory, ScopedTokenCache};
+use crate::azure::auth::token::{decode_token_claims, store_auth_result};
+use crate::azure::auth::types::{AuthResult, ServicePrincipalTestResult, TokenResponse};
+use crate::config::{keyvault_scope, management_scope, CloudEnvironment};
+use crate::user_config::{get_authority_host, get_client_id, get_tenant_id};
+use async_trait::async_trait;
+use azure_core::credentials::{AccessToken, Secret, TokenCredential, TokenRequestOptions};
+use azure_core::error::ErrorKind;
+use azure_core::Error;
 use azure_identity::{ClientSecretCredential, ClientSecretCredentialOptions};
-use log::info;
+use log::{info, warn};
+use std::collections::HashMap;
 use std::env;
+use std::sync::Arc;
+use time::OffsetDateTime;
+
+/// Client credentials flow against an overridden authority host
+///
+/// Used for sovereign clouds, where the token request has to go to the
+/// configured login endpoint instead of the public cloud.
+#[derive(Debug)]
+struct AuthorityClientSecretCredential {
+    authority_host: String,
+    tenant_id: String,
+    client_id: String,
+    client_secret: Secret,
+    /// Cached access tokens keyed by scope
+    cached_tokens: ScopedTokenCache,
+}
+
+#[async_trait]
+impl TokenCredential for AuthorityClientSecretCredential {
+    async fn get_token(
+        &self,
+        scopes: &[&str],
+        _options: Option<TokenRequestOptions<'_>>,
+    ) -> azure_core::Result<AccessToken> {
+        let scope = scopes.join(" ");
+
+        if let Some(token) = self.cached_tokens.read().await.get(&scope) {
+            if token.expires_on > OffsetDateTime::now_utc() {
+                return Ok(token.clone());
+            }
+        }
+
+        let url = format!("{}/{}/oauth2/v2.0/token", self.authority_host, self.tenant_id);
+        let response = reqwest::Client::new()
+            .post(&url)
+            .form(&[
+                ("grant_type", "client_credentials"),
+                ("client_id", &self.client_id),
+                ("client_secret", self.client_secret.secret()),
+                ("scope", &scope),
+            ])
+            .send()
+            .await
+            .map_err(|e| Error::with_message(ErrorKind::Io, e.to_string()))?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(Error::with_message(
+                ErrorKind::Credential,
+                format!("Failed to get token for scope {}: {}", scope, error_text),
+            ));
+        }
+
+        let token_res: TokenResponse = response
+            .json()
+            .await
+            .map_err(|e| Error::with_message(ErrorKind::DataConversion, e.to_string()))?;
+
+        let expires_in = token_res.expires_in.unwrap_or(3600);
+        let expires_on = OffsetDateTime::now_utc() + std::time::Duration::from_secs(expires_in);
+        let access_token = AccessToken::new(Secret::new(token_res.access_token), expires_on);
+
+        self.cached_tokens
+            .write()
+            .await
+            .insert(scope, access_token.clone());
+        Ok(access_token)
+    }
+}
+
+/// Build a client secret credential for the given authority host
+///
+/// The public cloud keeps using `azure_identity`'s credential; any other
+/// authority host gets a credential that sends its token requests there.
+fn client_secret_credential(
+    authority_host: &str,
+    client_id: &str,
+    tenant_id: String,
+    client_secret: String,
+) -> Result<Arc<dyn TokenCredential>, String> {
+    if authority_host == CloudEnvironment::Public.authority_host() {
+        let credential: Arc<dyn TokenCredential> = ClientSecretCredential::new(
+            client_id,
+            tenant_id,
+            Secret::new(client_secret),
+            Some(ClientSecretCredentialOptions::default()),
+        )
+        .map_err(|e| format!("Failed to create client secret credential: {}", e))?;
+        return Ok(credential);
+    }
+
+    info!("Using authority host {} for service principal", authority_host);
+    Ok(Arc::new(AuthorityClientSecretCredential {
+        authority_host: authority_host.to_string(),
+        tenant_id,
+        client_id: client_id.to_string(),
+        client_secret: Secret::new(client_secret),
+        cached_tokens: Arc::new(tokio::sync::RwLock::new(HashMap::new())),
+    }))
+}
 
 /// Initiates Azure authentication using environment variables
 /// This tries to authenticate using AZURE_CLIENT_ID, AZURE_CLIENT_SECRET, and AZURE_TENANT_ID
-/// environment variables (Service Principal authentication)
+/// environment variables (Service Principal authentication), against the
+/// authority host resolved by `get_authority_host` (honours AZURE_AUTHORITY_HOST)
 pub async fn try_environment_credential() -> Result<AuthResult, String> {
     info!("try_environment_credential...");
 
@@ -27,22 +133,81 @@ pub async fn try_environment_credential() -> Result<AuthResult, String> {
     let tenant_id = env::var("AZURE_TENANT_ID")
         .unwrap_or(default_tenant_id);
 
-    let options = ClientSecretCredentialOptions::default();
-
-    let credential = ClientSecretCredential::new(
+    let authority_host = get_authority_host().await;
+    let credential = client_secret_credential(
+        &authority_host,
         &client_id,
-        tenant_id,
-        Secret::new(client_secret),
-        Some(options),
-    )
-    .map_err(|e| format!("Failed to create client secret credential: {}", e))?;
+        tenant_id.clone(),
+        client_secret.clone(),
+    )?;
 
     // Try to get a token to verify authentication
-    let scopes = &[VAULT_SCOPE];
+    let scopes = &[keyvault_scope()];
     let token = credential
         .get_token(scopes, None)
         .await
         .map_err(|e| format!("Service Principal authentication failed: {}", e))?;
 
-    store_auth_result(credential, token.token.secret(), "Service Principal").await
+    let rebuild: CredentialFactory = Arc::new(move || {
+        client_secret_credential(
+            &authority_host,
+            &client_id,
+            tenant_id.clone(),
+            client_secret.clone(),
+        )
+    });
+    store_auth_result(
+        credential,
+        token.token.secret(),
+        "Service Principal",
+        Some(rebuild),
+    )
+    .await
+}
+
+/// Check that a service principal can acquire a management token
+///
+/// Purely diagnostic: the credential is dropped afterwards, and neither the
+/// global auth state nor the user config is touched.
+pub async fn test_service_principal(
+    client_id: &str,
+    tenant_id: &str,
+    client_secret: &str,
+) -> ServicePrincipalTestResult {
+    info!("Testing service principal credentials for client {}", client_id);
+
+    let failure = |message: String| {
+        warn!("{}", message);
+        ServicePrincipalTestResult {
+            success: false,
+            message,
+            tenant_id: None,
+            token_expires_at: None,
+        }
+    };
+
+    let authority_host = get_authority_host().await;
+    let credential = match client_secret_credential(
+        &authority_host,
+        client_id.trim(),
+        tenant_id.trim().to_string(),
+        client_secret.to_string(),
+    ) {
+        Ok(credential) => credential,
+        Err(message) => return failure(message),
+    };
+
+    let token = match credential.get_token(&[management_scope()], None).await {
+        Ok(token) => token,
+        Err(e) => return failure(format!("Service Principal authentication failed: {}", e)),
+    };
+
+    let token_tenant_id = decode_token_claims(token.token.secret()).and_then(|claims| claims.tid);
+
+    ServicePrincipalTestResult {
+        success: true,
+        message: "Service Principal credentials are valid".to_string(),
+        tenant_id: token_tenant_id,
+        token_expires_at: Some(token.expires_on.unix_timestamp()),
+    }
 }
@@ -2,17 +2,52 @@
 // Global State
 // ============================================================================
 
-use crate::azure::auth::types::DeviceCodeState;
-use azure_core::credentials::TokenCredential;
+use crate::azure::auth::types::{AuthCodeState, AuthSession, DeviceCodeState};
+use azure_core::credentials::{AccessToken, TokenCredential};
+use std::collections::HashMap;
+use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
-use tokio::sync::Mutex;
+use tokio::sync::{watch, Mutex, RwLock};
+
+/// Access tokens keyed by scope
+pub type ScopedTokenCache = Arc<RwLock<HashMap<String, AccessToken>>>;
+
+/// Builds a new credential for the signed-in identity, with empty token caches
+pub type CredentialFactory =
+    Arc<dyn Fn() -> Result<Arc<dyn TokenCredential>, String> + Send + Sync>;
 
 lazy_static::lazy_static! {
     /// Stores the authenticated credential for making Azure API calls
     pub static ref AUTH_CREDENTIAL: Arc<Mutex<Option<Arc<dyn TokenCredential>>>> =
         Arc::new(Mutex::new(None));
 
+    /// Stores details about the completed authentication (method, tenant)
+    pub static ref AUTH_SESSION: Arc<Mutex<Option<AuthSession>>> =
+        Arc::new(Mutex::new(None));
+
     /// Stores device code state during authentication flow
     pub static ref DEVICE_CODE_STATE: Arc<Mutex<Option<DeviceCodeState>>> =
         Arc::new(Mutex::new(None));
+
+    /// Stores the pending authorization code flow and its redirect listener
+    pub static ref AUTH_CODE_STATE: Arc<Mutex<Option<AuthCodeState>>> =
+        Arc::new(Mutex::new(None));
+
+    /// Token cache of the interactive credential, so a rejected token can be evicted
+    pub static ref INTERACTIVE_TOKEN_CACHE: Arc<Mutex<Option<ScopedTokenCache>>> =
+        Arc::new(Mutex::new(None));
+
+    /// Rebuilds the stored credential when a rejected token has to be refreshed;
+    /// set for credentials whose token cache can't be evicted (Azure CLI,
+    /// service principal, managed identity)
+    pub static ref CREDENTIAL_FACTORY: Arc<Mutex<Option<CredentialFactory>>> =
+        Arc::new(Mutex::new(None));
+
+    /// Signals cancellation to a login flow that is waiting for the user
+    pub static ref LOGIN_CANCEL: Arc<Mutex<Option<watch::Sender<bool>>>> =
+        Arc::new(Mutex::new(None));
 }
+
+/// Set when a token request failed because the sign-in expired, cleared again
+/// by the next successful token request, login or logout
+pub static REAUTH_REQUIRED: AtomicBool = AtomicBool::new(false);
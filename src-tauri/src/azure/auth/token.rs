@@ -1,46 +1,47 @@
 use crate::azure::auth::provider::{GlobalTokenProvider, TokenProvider};
-use crate::azure::auth::state::AUTH_CREDENTIAL;
-use crate::azure::auth::types::{AuthResult, TokenClaims};
+use crate::azure::auth::state::{
+    CredentialFactory, AUTH_CREDENTIAL, AUTH_SESSION, CREDENTIAL_FACTORY, REAUTH_REQUIRED,
+};
+use crate::azure::auth::types::{AuthResult, AuthSession, TokenClaims};
 use crate::azure::auth::user_info::store_user_info;
+use crate::azure::http::AzureHttpError;
+use crate::cache::{azure_cache, forget_snapshot_owner, SnapshotOwner};
+use crate::config::cloud_environment;
 use azure_core::credentials::TokenCredential;
 use base64::engine::general_purpose::URL_SAFE_NO_PAD as BASE64URL;
 use base64::Engine;
 use log::{info, warn};
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
 
 /// Get a token for Azure Resource Management API.
 ///
 /// This is a backwards-compatible wrapper around `GlobalTokenProvider`.
-pub async fn get_token_from_state() -> Result<String, String> {
-    let provider = GlobalTokenProvider::new();
-    provider
-        .get_management_token()
-        .await
-        .map_err(|e| e.to_string())
+/// The typed error is kept so callers can tell `ReauthRequired` apart.
+pub async fn get_token_from_state() -> Result<String, AzureHttpError> {
+    GlobalTokenProvider::new().get_management_token().await
 }
 
 /// Get a token for a specific scope.
 ///
 /// This is a backwards-compatible wrapper around `GlobalTokenProvider`.
-pub async fn get_token_for_scope(scope: &str) -> Result<String, String> {
-    let provider = GlobalTokenProvider::new();
-    provider
-        .get_token_for_scope(scope)
-        .await
-        .map_err(|e| e.to_string())
+pub async fn get_token_for_scope(scope: &str) -> Result<String, AzureHttpError> {
+    GlobalTokenProvider::new().get_token_for_scope(scope).await
 }
 
-/// Decode JWT token without verification to extract user info.
-pub fn extract_user_info_from_token(
-    token: &str,
-) -> Result<(Option<String>, Option<String>), String> {
+/// Decode the payload of a JWT without verification.
+///
+/// Only the payload segment is decoded; the header and signature are never
+/// returned. Returns `None` if the token is not a JWT or its payload is not
+/// a JSON object.
+pub fn decode_token_payload(token: &str) -> Option<serde_json::Map<String, serde_json::Value>> {
     // Check if this looks like a JWT (has 3 dot-separated parts)
     let parts: Vec<&str> = token.split('.').collect();
 
     if parts.len() != 3 {
         // Not a standard JWT format
         info!("Token is not a JWT format - user info will be fetched separately");
-        return Ok((None, None));
+        return None;
     }
 
     // Decode the payload (second part)
@@ -54,18 +55,43 @@ pub fn extract_user_info_from_token(
                 Ok(d) => d,
                 Err(_) => {
                     warn!("Failed to decode token payload: {}", e);
-                    return Ok((None, None));
+                    return None;
                 }
             }
         }
     };
 
-    let claims: TokenClaims = match serde_json::from_slice(&decoded) {
-        Ok(c) => c,
+    match serde_json::from_slice(&decoded) {
+        Ok(c) => Some(c),
+        Err(e) => {
+            warn!("Failed to parse token claims: {}", e);
+            None
+        }
+    }
+}
+
+/// Decode JWT token claims without verification.
+///
+/// Returns `None` if the token is not a JWT or its payload cannot be parsed.
+pub fn decode_token_claims(token: &str) -> Option<TokenClaims> {
+    let payload = decode_token_payload(token)?;
+
+    match serde_json::from_value(serde_json::Value::Object(payload)) {
+        Ok(c) => Some(c),
         Err(e) => {
             warn!("Failed to parse token claims: {}", e);
-            return Ok((None, None));
+            None
         }
+    }
+}
+
+/// Decode JWT token without verification to extract user info.
+pub fn extract_user_info_from_token(
+    token: &str,
+) -> Result<(Option<String>, Option<String>), String> {
+    let claims = match decode_token_claims(token) {
+        Some(c) => c,
+        None => return Ok((None, None)),
     };
 
     // Try to get email from various possible fields (ordered by preference)
@@ -79,10 +105,15 @@ pub fn extract_user_info_from_token(
 }
 
 /// Extracts user info from token and stores credential
+///
+/// `rebuild` creates a fresh credential when a token refresh has to bypass
+/// the credential's own cache; `None` for credentials whose cache is evicted
+/// through `INTERACTIVE_TOKEN_CACHE`.
 pub async fn store_auth_result(
     credential: Arc<dyn TokenCredential>,
     token_secret: &str,
     auth_method: &str,
+    rebuild: Option<CredentialFactory>,
 ) -> Result<AuthResult, String> {
     info!("Storing authentication result for method: {}", auth_method);
     let (user_email, user_name) = match extract_user_info_from_token(token_secret) {
@@ -100,6 +131,8 @@ pub async fn store_auth_result(
     {
         let mut cred = AUTH_CREDENTIAL.lock().await;
         *cred = Some(credential);
+        *CREDENTIAL_FACTORY.lock().await = rebuild;
+        REAUTH_REQUIRED.store(false, Ordering::Relaxed);
         info!(
             "Credential stored in global AUTH_CREDENTIAL. Type: {}. Is Some: {}",
             auth_method,
@@ -107,6 +140,33 @@ pub async fn store_auth_result(
         );
     }
 
+    // Store session details
+    let claims = decode_token_claims(token_secret);
+    {
+        let tenant_id = claims.as_ref().and_then(|c| c.tid.clone());
+        let mut session = AUTH_SESSION.lock().await;
+        *session = Some(AuthSession {
+            auth_method: auth_method.to_string(),
+            tenant_id,
+        });
+    }
+
+    // Restore the lists this account saw last time; other accounts' snapshots stay untouched
+    match claims.and_then(|c| c.tid.zip(c.oid)) {
+        Some((tenant_id, object_id)) => {
+            let owner = SnapshotOwner {
+                cloud: cloud_environment(),
+                tenant_id,
+                object_id,
+            };
+            azure_cache().load_from_disk(&owner).await;
+        }
+        None => {
+            info!("Token has no tenant or object id, cache snapshot disabled for this session");
+            forget_snapshot_owner();
+        }
+    }
+
     // Store user info
     store_user_info(user_email.clone(), user_name.clone()).await;
 
@@ -117,3 +177,38 @@ pub async fn store_auth_result(
         user_name,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_token_payload() {
+        let payload = BASE64URL.encode(r#"{"oid":"user-1","tid":"tenant-1","roles":["Reader"]}"#);
+        let token = format!("header.{}.signature", payload);
+
+        let claims = decode_token_payload(&token).unwrap();
+        assert_eq!(claims["oid"], "user-1");
+        assert_eq!(claims["roles"][0], "Reader");
+        assert!(!claims.values().any(|v| *v == "signature"));
+
+        let typed = decode_token_claims(&token).unwrap();
+        assert_eq!(typed.tid.as_deref(), Some("tenant-1"));
+    }
+
+    #[test]
+    fn test_managed_identity_token_has_no_user_info() {
+        // Managed identity tokens identify an app, not a user
+        let payload = BASE64URL.encode(
+            r#"{"oid":"mi-1","tid":"tenant-1","appid":"client-1","idtyp":"app","xms_mirid":"/subscriptions/sub/resourcegroups/rg/providers/Microsoft.Compute/virtualMachines/vm"}"#,
+        );
+        let token = format!("header.{}.signature", payload);
+
+        assert_eq!(extract_user_info_from_token(&token), Ok((None, None)));
+    }
+
+    #[test]
+    fn test_decode_token_payload_rejects_non_jwt() {
+        assert!(decode_token_payload("not-a-jwt").is_none());
+    }
+}
@@ -28,6 +28,26 @@ pub struct DeviceCodeInfo {
     pub message: String,
 }
 
+/// Information returned when initiating the authorization code (redirect) flow
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthCodeLoginInfo {
+    /// Azure AD sign-in page, already opened in the system browser
+    pub authorize_url: String,
+    /// Local address the browser is redirected to after sign-in
+    pub redirect_uri: String,
+}
+
+/// Information returned when starting browser authentication, depending on the flow
+///
+/// Untagged, so the device code variant serializes exactly like `DeviceCodeInfo`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum BrowserLoginInfo {
+    DeviceCode(DeviceCodeInfo),
+    Redirect(AuthCodeLoginInfo),
+}
+
 /// Result of an authentication attempt
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AuthResult {
@@ -37,6 +57,58 @@ pub struct AuthResult {
     pub user_name: Option<String>,
 }
 
+/// Combined authentication status for the frontend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthStatus {
+    pub authenticated: bool,
+    pub email: Option<String>,
+    pub name: Option<String>,
+    pub tenant_id: Option<String>,
+    pub auth_method: Option<String>,
+    /// Unix timestamp (seconds) when the current management token expires
+    pub token_expires_at: Option<i64>,
+}
+
+/// Whether the stored sign-in can still be used
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AuthStatusDetail {
+    /// Tokens can be obtained
+    Authenticated,
+    /// Logged in, but the refresh token expired or was revoked
+    Expired,
+    /// Never logged in, or logged out
+    NotAuthenticated,
+    /// Logged in, but the check failed for another reason, e.g. no network
+    Unknown,
+}
+
+/// Outcome of checking a service principal's credentials
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServicePrincipalTestResult {
+    pub success: bool,
+    pub message: String,
+    /// Tenant the issued token belongs to
+    pub tenant_id: Option<String>,
+    /// Unix timestamp (seconds) when the issued token expires
+    pub token_expires_at: Option<i64>,
+}
+
+/// The parts of a tenant's OpenID Connect discovery document used for diagnostics
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct OpenIdConfiguration {
+    pub issuer: String,
+    pub authorization_endpoint: String,
+    pub token_endpoint: String,
+    #[serde(default)]
+    pub device_authorization_endpoint: Option<String>,
+    #[serde(default)]
+    pub scopes_supported: Vec<String>,
+}
+
 // ============================================================================
 // Internal Data Structures
 // ============================================================================
@@ -54,6 +126,17 @@ pub struct TokenClaims {
     pub name: Option<String>,
     #[serde(default)]
     pub preferred_username: Option<String>,
+    #[serde(default)]
+    pub tid: Option<String>, // Tenant ID
+    #[serde(default)]
+    pub oid: Option<String>, // Object ID of the signed-in principal
+}
+
+/// Details about the currently authenticated session
+#[derive(Debug, Clone)]
+pub struct AuthSession {
+    pub auth_method: String,
+    pub tenant_id: Option<String>,
 }
 
 /// State for device code authentication flow
@@ -61,6 +144,37 @@ pub struct TokenClaims {
 pub struct DeviceCodeState {
     pub device_code: String,
     pub interval: u64,
+    /// When the device code stops being accepted by Azure AD
+    pub expires_at: tokio::time::Instant,
+}
+
+/// Query parameters Azure AD sends to the redirect listener after sign-in
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuthCodeRedirect {
+    pub code: String,
+    pub state: String,
+}
+
+/// State for the authorization code flow while waiting for the redirect
+#[derive(Debug)]
+pub struct AuthCodeState {
+    pub authority_host: String,
+    pub client_id: String,
+    pub tenant_id: String,
+    pub scopes: String,
+    pub redirect_uri: String,
+    /// PKCE secret whose hash was sent with the authorize request
+    pub code_verifier: String,
+    /// Random value the redirect has to echo back, guarding against CSRF
+    pub state: String,
+    /// Random value the ID token has to carry, guarding against token replay
+    pub nonce: String,
+    /// When to stop waiting for the redirect
+    pub expires_at: tokio::time::Instant,
+    /// Receives the redirect captured by the listener; taken by whoever waits for it
+    pub redirect_rx: Option<tokio::sync::oneshot::Receiver<Result<AuthCodeRedirect, String>>>,
+    /// Temporary localhost server capturing the redirect
+    pub listener: tokio::task::JoinHandle<()>,
 }
 
 /// Response from Azure token endpoint
@@ -72,6 +186,9 @@ pub struct TokenResponse {
     pub expires_in: Option<u64>,
     #[serde(default)]
     pub refresh_token: Option<String>,
+    /// Only returned when the `openid` scope was requested
+    #[serde(default)]
+    pub id_token: Option<String>,
 }
 
 /// Response from Azure device code endpoint
@@ -80,8 +197,7 @@ pub struct DeviceCodeResponse {
     pub device_code: String,
     pub user_code: String,
     pub verification_uri: String,
-    #[allow(dead_code)]
-    expires_in: u64,
+    pub expires_in: u64,
     pub interval: u64,
     pub message: String,
 }
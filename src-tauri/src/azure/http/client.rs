@@ -6,6 +6,7 @@
 //! - Request/response logging with tracing
 //! - Error handling with detailed context
 //! - Automatic JSON serialization/deserialization
+//! - Retrying throttled (429) and failed (5xx) requests with backoff
 //!
 //! # Example
 //!
@@ -18,13 +19,49 @@
 //! let vaults: Vec<KeyVault> = client.get(&url).await?;
 //! ```
 
-use log::{debug, error, info};
+use log::{debug, error, info, warn};
 use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION, CONTENT_TYPE};
 use reqwest::{Client, Method, Response};
 use serde::de::DeserializeOwned;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
 
 use super::error::AzureHttpError;
+use super::retry::{parse_retry_after, retry_once_on_unauthorized, RetryPolicy};
+use super::throttle::REQUEST_LIMITER;
+use crate::azure::auth::provider::{GlobalTokenProvider, TokenProvider};
+use crate::config::DEFAULT_HTTP_TIMEOUT_SECS;
+
+/// Timeout for clients created with `new()`, in milliseconds
+static DEFAULT_TIMEOUT_MS: AtomicU64 = AtomicU64::new(DEFAULT_HTTP_TIMEOUT_SECS * 1_000);
+
+/// Set the timeout for clients created from now on, e.g. from `UserConfig`
+pub fn set_default_timeout(timeout: Duration) {
+    DEFAULT_TIMEOUT_MS.store(timeout.as_millis() as u64, Ordering::Relaxed);
+}
+
+/// Error envelope returned by Azure APIs: `{"error":{"code":"...","message":"..."}}`
+#[derive(Debug, Deserialize)]
+struct AzureErrorEnvelope {
+    error: AzureErrorDetail,
+}
+
+#[derive(Debug, Deserialize)]
+struct AzureErrorDetail {
+    #[serde(default)]
+    code: Option<String>,
+    #[serde(default)]
+    message: Option<String>,
+}
+
+/// Build a reqwest client whose requests fail after `timeout`
+fn build_client(timeout: Duration) -> Client {
+    Client::builder().timeout(timeout).build().unwrap_or_else(|e| {
+        error!("Failed to build HTTP client with a timeout, using defaults: {}", e);
+        Client::new()
+    })
+}
 
 /// A reusable HTTP client for making authenticated requests to Azure APIs.
 ///
@@ -33,7 +70,14 @@ use super::error::AzureHttpError;
 #[derive(Clone)]
 pub struct AzureHttpClient {
     client: Client,
+    /// Timeout `client` was built with, reported in `AzureHttpError::Timeout`
+    timeout: Duration,
     base_headers: HeaderMap,
+    /// Scope the bearer token was issued for, when it came from the global credential.
+    /// Requests rejected with 401 are then retried once with a refreshed token.
+    token_scope: Option<String>,
+    /// Retries for throttled (429) and failed (5xx) responses
+    retry_policy: RetryPolicy,
 }
 
 impl Default for AzureHttpClient {
@@ -45,15 +89,22 @@ impl Default for AzureHttpClient {
 impl AzureHttpClient {
     /// Creates a new Azure HTTP client with default settings.
     ///
+    /// Requests time out after the configured HTTP timeout
+    /// (`DEFAULT_HTTP_TIMEOUT_SECS` unless changed in the settings).
+    ///
     /// # Example
     ///
     /// ```rust,ignore
     /// let client = AzureHttpClient::new();
     /// ```
     pub fn new() -> Self {
+        let timeout = Duration::from_millis(DEFAULT_TIMEOUT_MS.load(Ordering::Relaxed));
         Self {
-            client: Client::new(),
+            client: build_client(timeout),
+            timeout,
             base_headers: HeaderMap::new(),
+            token_scope: None,
+            retry_policy: RetryPolicy::default(),
         }
     }
 
@@ -106,6 +157,51 @@ impl AzureHttpClient {
         Ok(self)
     }
 
+    /// Sets how long a request may take, including reading the response,
+    /// before it fails with `AzureHttpError::Timeout`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let client = AzureHttpClient::new().with_timeout(Duration::from_secs(120));
+    /// ```
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.client = build_client(timeout);
+        self.timeout = timeout;
+        self
+    }
+
+    /// Sets how throttled (429) and failed (5xx) requests are retried.
+    ///
+    /// Retry `n` waits `base_delay * 2^n`, unless the response carries a
+    /// `Retry-After` header, which is honored instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_retries` - Retries after the first attempt; `0` disables retrying
+    /// * `base_delay` - Delay before the first retry
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let client = AzureHttpClient::with_token(&token)?
+    ///     .with_retry_policy(5, Duration::from_secs(1));
+    /// ```
+    pub fn with_retry_policy(mut self, max_retries: u32, base_delay: Duration) -> Self {
+        self.retry_policy.max_retries = max_retries;
+        self.retry_policy.base_delay = base_delay;
+        self
+    }
+
+    /// Also retries POST, PUT and PATCH requests.
+    ///
+    /// These aren't retried by default since repeating them may not be safe.
+    /// Only opt in when the request has the same effect when sent twice.
+    pub fn with_non_idempotent_retries(mut self) -> Self {
+        self.retry_policy.retry_non_idempotent = true;
+        self
+    }
+
     /// Returns a reference to the underlying headers.
     ///
     /// Useful for debugging or when you need to inspect the current headers.
@@ -202,8 +298,7 @@ impl AzureHttpClient {
     ///
     /// * `url` - The URL to request
     pub async fn delete_no_content(&self, url: &str) -> Result<(), AzureHttpError> {
-        let response = self.send_request::<()>(Method::DELETE, url, None).await?;
-        self.check_status(response).await?;
+        self.send_checked::<()>(Method::DELETE, url, None).await?;
         Ok(())
     }
 
@@ -266,29 +361,119 @@ impl AzureHttpClient {
     where
         B: Serialize,
     {
-        let response = self.send_request(method, url, body).await?;
-        let response = self.check_status(response).await?;
+        let response = self.send_checked(method, url, body).await?;
 
         response.text().await.map_err(|e| {
             error!("Failed to read response body: {}", e);
-            AzureHttpError::ResponseBodyError(e.to_string())
+            if e.is_timeout() {
+                AzureHttpError::Timeout {
+                    timeout: self.timeout,
+                }
+            } else {
+                AzureHttpError::ResponseBodyError(e.to_string())
+            }
         })
     }
 
+    /// Internal method to send the request and check its status.
+    ///
+    /// For clients created with `for_scope`, a 401 response is retried once
+    /// with a freshly acquired token.
+    async fn send_checked<B>(
+        &self,
+        method: Method,
+        url: &str,
+        body: Option<&B>,
+    ) -> Result<Response, AzureHttpError>
+    where
+        B: Serialize,
+    {
+        let Some(scope) = &self.token_scope else {
+            let response = self.send_with_retries(method, url, body, None).await?;
+            return self.check_status(response).await;
+        };
+
+        retry_once_on_unauthorized(
+            |token| {
+                let method = method.clone();
+                async move {
+                    let response = self
+                        .send_with_retries(method, url, body, token.as_deref())
+                        .await?;
+                    self.check_status(response).await
+                }
+            },
+            || async { GlobalTokenProvider::new().refresh_token_for_scope(scope).await },
+        )
+        .await
+    }
+
+    /// Internal method to send the request, retrying per the client's retry policy.
+    ///
+    /// Returns the last response once it succeeds, fails permanently or retries run out.
+    async fn send_with_retries<B>(
+        &self,
+        method: Method,
+        url: &str,
+        body: Option<&B>,
+        token: Option<&str>,
+    ) -> Result<Response, AzureHttpError>
+    where
+        B: Serialize,
+    {
+        let mut attempt = 0;
+        loop {
+            let response = self.send_request(method.clone(), url, body, token).await?;
+            let status = response.status().as_u16();
+
+            if !self.retry_policy.should_retry(&method, status, attempt) {
+                return Ok(response);
+            }
+
+            let delay = self
+                .retry_policy
+                .delay(attempt, parse_retry_after(response.headers()));
+            warn!(
+                "Request {} {} failed with {}, retrying in {:?} (retry {} of {})",
+                method,
+                url,
+                status,
+                delay,
+                attempt + 1,
+                self.retry_policy.max_retries
+            );
+            drop(response);
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+
     /// Internal method to send the HTTP request.
+    ///
+    /// `token` replaces the client's bearer token for this request only.
     async fn send_request<B>(
         &self,
         method: Method,
         url: &str,
         body: Option<&B>,
+        token: Option<&str>,
     ) -> Result<Response, AzureHttpError>
     where
         B: Serialize,
     {
         debug!("Sending HTTP request: {} {}", method, url);
 
+        let mut headers = self.base_headers.clone();
+        if let Some(token) = token {
+            headers.insert(
+                AUTHORIZATION,
+                HeaderValue::from_str(&format!("Bearer {}", token))
+                    .map_err(|e| AzureHttpError::InvalidHeader(e.to_string()))?,
+            );
+        }
+
         let mut request = self.client.request(method.clone(), url);
-        request = request.headers(self.base_headers.clone());
+        request = request.headers(headers);
 
         // Add JSON content type and body for methods that typically have a body
         if let Some(body) = body {
@@ -301,10 +486,21 @@ impl AzureHttpClient {
             request = request.body(body_json);
         }
 
-        request.send().await.map_err(|e| {
+        // Held until the response headers arrive
+        let _permit = REQUEST_LIMITER.acquire().await;
+        let response = request.send().await.map_err(|e| {
             error!("HTTP request failed: {} \n {}", url, e);
-            AzureHttpError::NetworkError(e.to_string())
-        })
+            if e.is_timeout() {
+                AzureHttpError::Timeout {
+                    timeout: self.timeout,
+                }
+            } else {
+                AzureHttpError::NetworkError(e.to_string())
+            }
+        })?;
+        REQUEST_LIMITER.record_response(response.status().as_u16());
+
+        Ok(response)
     }
 
     /// Internal method to check response status and return error for non-success codes.
@@ -321,12 +517,13 @@ impl AzureHttpClient {
                 .unwrap_or_else(|_| "Unknown error".to_string());
             error!("API request failed: {} \n {}", status.as_u16(), error_text);
 
-            // Try to parse Azure error response format
-            let error_message = Self::parse_azure_error(&error_text);
+            let (code, message) = Self::parse_azure_error(&error_text);
 
             return Err(AzureHttpError::ApiError {
                 status: status.as_u16(),
-                message: error_message,
+                code,
+                message,
+                body: error_text,
             });
         }
 
@@ -334,29 +531,22 @@ impl AzureHttpClient {
         Ok(response)
     }
 
-    /// Parse Azure error response to extract meaningful error message
-    fn parse_azure_error(error_text: &str) -> String {
-        // Try to parse JSON error response
-        if let Ok(json) = serde_json::from_str::<serde_json::Value>(error_text) {
-            // Azure error format: {"error":{"code":"...", "message":"..."}}
-            if let Some(error_obj) = json.get("error") {
-                if let Some(message) = error_obj.get("message").and_then(|m| m.as_str()) {
-                    return message.to_string();
-                }
-                if let Some(code) = error_obj.get("code").and_then(|c| c.as_str()) {
-                    return format!(
-                        "{}: {}",
-                        code,
-                        error_obj
-                            .get("message")
-                            .and_then(|m| m.as_str())
-                            .unwrap_or("Unknown error")
-                    );
-                }
+    /// Parse the Azure error envelope into its code and message.
+    ///
+    /// Bodies that aren't an envelope (HTML from a proxy, plain text) are
+    /// returned as the message, without a code.
+    fn parse_azure_error(error_text: &str) -> (Option<String>, String) {
+        match serde_json::from_str::<AzureErrorEnvelope>(error_text) {
+            Ok(AzureErrorEnvelope { error }) => {
+                let code = error.code.filter(|code| !code.is_empty());
+                let message = error
+                    .message
+                    .filter(|message| !message.is_empty())
+                    .unwrap_or_else(|| "Unknown error".to_string());
+                (code, message)
             }
+            Err(_) => (None, error_text.to_string()),
         }
-        // If parsing fails, return the original error text
-        error_text.to_string()
     }
 }
 
@@ -380,11 +570,58 @@ impl AzureHttpClient {
     pub fn with_token(token: &str) -> Result<Self, AzureHttpError> {
         Self::new().with_bearer_token(token)
     }
+
+    /// Creates a new client with a token for `scope` from the signed-in credential.
+    ///
+    /// Unlike `with_token`, requests rejected with 401 are retried once with a
+    /// refreshed token for `scope`, covering tokens revoked or expired early due
+    /// to clock drift.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let token = get_token_for_scope(keyvault_scope()).await?;
+    /// let client = AzureHttpClient::with_scoped_token(&token, keyvault_scope())?;
+    /// ```
+    pub fn with_scoped_token(token: &str, scope: &str) -> Result<Self, AzureHttpError> {
+        let mut client = Self::with_token(token)?;
+        client.token_scope = Some(scope.to_string());
+        Ok(client)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Instant;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    #[test]
+    fn test_parse_azure_error_envelope() {
+        let body = r#"{"error":{"code":"Forbidden","message":"Caller is not authorized to perform action on resource."}}"#;
+        let (code, message) = AzureHttpClient::parse_azure_error(body);
+        assert_eq!(code.as_deref(), Some("Forbidden"));
+        assert_eq!(message, "Caller is not authorized to perform action on resource.");
+
+        let (code, message) = AzureHttpClient::parse_azure_error(r#"{"error":{"code":"Conflict"}}"#);
+        assert_eq!(code.as_deref(), Some("Conflict"));
+        assert_eq!(message, "Unknown error");
+    }
+
+    #[test]
+    fn test_parse_azure_error_falls_back_to_body() {
+        let body = "<html><body>502 Bad Gateway</body></html>";
+        assert_eq!(AzureHttpClient::parse_azure_error(body), (None, body.to_string()));
+
+        let other_json = r#"{"message":"not an envelope"}"#;
+        assert_eq!(
+            AzureHttpClient::parse_azure_error(other_json),
+            (None, other_json.to_string())
+        );
+    }
 
     #[test]
     fn test_client_creation() {
@@ -406,6 +643,123 @@ mod tests {
         assert!(client.headers().contains_key(CONTENT_TYPE));
     }
 
+    #[test]
+    fn test_client_retry_policy() {
+        let client = AzureHttpClient::new().with_retry_policy(5, Duration::from_millis(10));
+        assert_eq!(client.retry_policy.max_retries, 5);
+        assert_eq!(client.retry_policy.base_delay, Duration::from_millis(10));
+        assert!(!client.retry_policy.retry_non_idempotent);
+
+        let client = client.with_non_idempotent_retries();
+        assert!(client.retry_policy.retry_non_idempotent);
+    }
+
+    #[tokio::test]
+    async fn test_retries_throttled_request_after_retry_after() {
+        let server = MockServer::start(vec![
+            ("429 Too Many Requests\r\nRetry-After: 1", ""),
+            ("200 OK\r\nContent-Type: application/json", r#"{"ok":true}"#),
+        ])
+        .await;
+        let client = AzureHttpClient::new().with_retry_policy(3, Duration::from_millis(1));
+
+        let started = Instant::now();
+        let body: serde_json::Value = client.get(&server.url).await.unwrap();
+
+        assert_eq!(body["ok"], true);
+        assert_eq!(server.hits(), 2);
+        assert!(started.elapsed() >= Duration::from_secs(1));
+    }
+
+    #[tokio::test]
+    async fn test_does_not_retry_non_idempotent_request() {
+        let server =
+            MockServer::start(vec![("503 Service Unavailable", ""), ("200 OK", "{}")]).await;
+        let client = AzureHttpClient::new().with_retry_policy(3, Duration::from_millis(1));
+
+        let result: Result<serde_json::Value, _> = client.post(&server.url, &()).await;
+
+        assert!(matches!(
+            result,
+            Err(AzureHttpError::ApiError { status: 503, .. })
+        ));
+        assert_eq!(server.hits(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_after_max_retries() {
+        let server = MockServer::start(vec![("500 Internal Server Error", ""); 4]).await;
+        let client = AzureHttpClient::new().with_retry_policy(2, Duration::from_millis(1));
+
+        let result = client.delete_no_content(&server.url).await;
+
+        assert!(matches!(
+            result,
+            Err(AzureHttpError::ApiError { status: 500, .. })
+        ));
+        assert_eq!(server.hits(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_request_times_out() {
+        // Accepts the connection but never responds
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let (_stream, _) = listener.accept().await.unwrap();
+            tokio::time::sleep(Duration::from_secs(30)).await;
+        });
+        let client = AzureHttpClient::new().with_timeout(Duration::from_millis(1));
+
+        let result: Result<serde_json::Value, _> = client.get(&url).await;
+
+        assert!(matches!(result, Err(AzureHttpError::Timeout { .. })));
+    }
+
+    /// Serves canned responses in order, one per connection.
+    ///
+    /// Each response is a status line (without the HTTP version) followed by
+    /// optional extra headers, and a body.
+    struct MockServer {
+        url: String,
+        hits: Arc<AtomicUsize>,
+    }
+
+    impl MockServer {
+        async fn start(responses: Vec<(&'static str, &'static str)>) -> Self {
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let url = format!("http://{}/", listener.local_addr().unwrap());
+            let hits = Arc::new(AtomicUsize::new(0));
+
+            let counter = hits.clone();
+            tokio::spawn(async move {
+                for (head, body) in responses {
+                    let Ok((mut stream, _)) = listener.accept().await else {
+                        return;
+                    };
+                    let mut request = vec![0u8; 8192];
+                    let _ = stream.read(&mut request).await;
+                    counter.fetch_add(1, Ordering::SeqCst);
+
+                    let response = format!(
+                        "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        head,
+                        body.len(),
+                        body
+                    );
+                    let _ = stream.write_all(response.as_bytes()).await;
+                    let _ = stream.shutdown().await;
+                }
+            });
+
+            Self { url, hits }
+        }
+
+        fn hits(&self) -> usize {
+            self.hits.load(Ordering::SeqCst)
+        }
+    }
+
     #[test]
     fn test_client_with_custom_header() {
         let client = AzureHttpClient::new()
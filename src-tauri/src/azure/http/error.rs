@@ -1,6 +1,7 @@
 //! Error types for Azure HTTP operations
 
 use std::fmt;
+use std::time::Duration;
 
 /// Errors that can occur during Azure HTTP operations
 #[derive(Debug)]
@@ -11,8 +12,19 @@ pub enum AzureHttpError {
     /// Network or connection error
     NetworkError(String),
 
+    /// The request didn't complete within the client's timeout
+    Timeout { timeout: Duration },
+
     /// Azure API returned an error response
-    ApiError { status: u16, message: String },
+    ApiError {
+        status: u16,
+        /// Error code from the Azure error envelope, e.g. `Forbidden` or `SecretNotFound`
+        code: Option<String>,
+        /// Message from the error envelope, or the raw body if it wasn't one
+        message: String,
+        /// Raw response body, for debugging
+        body: String,
+    },
 
     /// Failed to parse response body
     ParseError {
@@ -31,6 +43,18 @@ pub enum AzureHttpError {
 
     /// User is not authenticated
     NotAuthenticated,
+
+    /// The sign-in expired or was revoked (e.g. an expired refresh token),
+    /// so the user has to log in again
+    ReauthRequired(String),
+
+    /// A paginated listing returned more pages than allowed,
+    /// usually because the server keeps returning a `nextLink`
+    PaginationLimitExceeded { max_pages: usize },
+
+    /// A paginated listing returned a `nextLink` that was already followed,
+    /// which would loop forever
+    PaginationCycle { page: usize },
 }
 
 impl fmt::Display for AzureHttpError {
@@ -42,7 +66,22 @@ impl fmt::Display for AzureHttpError {
             AzureHttpError::NetworkError(msg) => {
                 write!(f, "Network error: {}", msg)
             }
-            AzureHttpError::ApiError { status, message } => {
+            AzureHttpError::Timeout { timeout } => {
+                write!(
+                    f,
+                    "Request timed out after {:?}. On a slow network, raise the HTTP timeout in the settings",
+                    timeout
+                )
+            }
+            AzureHttpError::ApiError {
+                status,
+                code: Some(code),
+                message,
+                ..
+            } => {
+                write!(f, "API request failed with status {}: {}: {}", status, code, message)
+            }
+            AzureHttpError::ApiError { status, message, .. } => {
                 write!(f, "API request failed with status {}: {}", status, message)
             }
             AzureHttpError::ParseError { message, body } => {
@@ -64,6 +103,39 @@ impl fmt::Display for AzureHttpError {
             AzureHttpError::NotAuthenticated => {
                 write!(f, "Not authenticated. Please login first.")
             }
+            AzureHttpError::ReauthRequired(msg) => {
+                write!(f, "Your session has expired. Please log in again ({})", msg)
+            }
+            AzureHttpError::PaginationLimitExceeded { max_pages } => {
+                write!(
+                    f,
+                    "Pagination stopped after {} pages; the server kept returning a nextLink",
+                    max_pages
+                )
+            }
+            AzureHttpError::PaginationCycle { page } => {
+                write!(
+                    f,
+                    "Pagination stopped at page {}; the server repeated an earlier nextLink",
+                    page
+                )
+            }
+        }
+    }
+}
+
+impl AzureHttpError {
+    /// Message for the user: `code: message` for API errors (e.g.
+    /// `Forbidden: Caller is not authorized`), the full description otherwise
+    pub fn user_message(&self) -> String {
+        match self {
+            AzureHttpError::ApiError {
+                code: Some(code),
+                message,
+                ..
+            } => format!("{}: {}", code, message),
+            AzureHttpError::ApiError { message, .. } => message.clone(),
+            other => other.to_string(),
         }
     }
 }
@@ -92,7 +164,9 @@ mod tests {
     fn test_error_display() {
         let err = AzureHttpError::ApiError {
             status: 404,
+            code: None,
             message: "Not found".to_string(),
+            body: "Not found".to_string(),
         };
         assert_eq!(
             err.to_string(),
@@ -100,6 +174,22 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_error_display_with_code() {
+        let err = AzureHttpError::ApiError {
+            status: 403,
+            code: Some("Forbidden".to_string()),
+            message: "Caller is not authorized".to_string(),
+            body: r#"{"error":{"code":"Forbidden","message":"Caller is not authorized"}}"#
+                .to_string(),
+        };
+        assert_eq!(
+            err.to_string(),
+            "API request failed with status 403: Forbidden: Caller is not authorized"
+        );
+        assert_eq!(err.user_message(), "Forbidden: Caller is not authorized");
+    }
+
     #[test]
     fn test_error_into_string() {
         let err = AzureHttpError::NetworkError("Connection refused".to_string());
@@ -1,12 +1,16 @@
 ﻿//! Azure HTTP client utilities
 //!
 //! This module provides a reusable HTTP client wrapper for making
-//! authenticated requests to Azure APIs, including pagination support.
+//! authenticated requests to Azure APIs, including pagination support and an
+//! adaptive concurrency limit.
 
 mod client;
 mod error;
 mod pagination;
+mod retry;
+mod throttle;
 
-pub use client::AzureHttpClient;
+pub use client::{set_default_timeout, AzureHttpClient};
 pub use error::AzureHttpError;
-pub use pagination::fetch_all_paginated;
+pub use pagination::{fetch_all_paginated, fetch_all_paginated_limited};
+pub use throttle::{request_metrics, set_concurrency_bounds, RequestMetrics};
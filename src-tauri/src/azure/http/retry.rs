@@ -0,0 +1,247 @@
+//! Retry policies for failed requests
+//!
+//! A cached token can be rejected even though it hasn't expired by our clock
+//! (clock drift, revoked sessions). Such requests are retried exactly once
+//! with a freshly acquired token.
+//!
+//! Throttled (429) and server error (5xx) responses are transient and are
+//! retried with exponential backoff, honoring `Retry-After` when Azure sends it.
+//! Only idempotent methods are retried unless the caller opts in.
+
+use log::warn;
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::Method;
+use std::future::Future;
+use std::time::Duration;
+
+use super::error::AzureHttpError;
+use crate::config::{DEFAULT_MAX_RETRIES, DEFAULT_RETRY_BASE_DELAY_MS, MAX_RETRY_DELAY_SECS};
+
+/// Millisecond variants of `Retry-After` sent by Azure services, preferred for their precision
+const RETRY_AFTER_MS_HEADERS: [&str; 2] = ["retry-after-ms", "x-ms-retry-after-ms"];
+
+/// When and how long to wait before retrying a throttled or failed request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct RetryPolicy {
+    pub max_retries: u32,
+    pub base_delay: Duration,
+    /// Also retry methods that aren't idempotent (POST, PUT, PATCH)
+    pub retry_non_idempotent: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: DEFAULT_MAX_RETRIES,
+            base_delay: Duration::from_millis(DEFAULT_RETRY_BASE_DELAY_MS),
+            retry_non_idempotent: false,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Whether a response with `status` should be retried after `attempt` earlier retries
+    pub fn should_retry(&self, method: &Method, status: u16, attempt: u32) -> bool {
+        let transient = status == 429 || (500..600).contains(&status);
+        let idempotent = matches!(*method, Method::GET | Method::HEAD | Method::DELETE);
+        transient && attempt < self.max_retries && (idempotent || self.retry_non_idempotent)
+    }
+
+    /// Delay before retry number `attempt` (starting at 0)
+    ///
+    /// A server-provided `Retry-After` wins over the exponential backoff;
+    /// either way the delay is capped at `MAX_RETRY_DELAY_SECS`.
+    pub fn delay(&self, attempt: u32, retry_after: Option<Duration>) -> Duration {
+        let backoff = self.base_delay.saturating_mul(2u32.saturating_pow(attempt));
+        retry_after
+            .unwrap_or(backoff)
+            .min(Duration::from_secs(MAX_RETRY_DELAY_SECS))
+    }
+}
+
+/// Read the delay the server asked for, from `Retry-After` (seconds) or its millisecond variants
+pub(crate) fn parse_retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let header = |name: &str| headers.get(name)?.to_str().ok()?.trim().parse::<u64>().ok();
+
+    RETRY_AFTER_MS_HEADERS
+        .iter()
+        .find_map(|name| header(name))
+        .map(Duration::from_millis)
+        .or_else(|| header(RETRY_AFTER.as_str()).map(Duration::from_secs))
+}
+
+/// Runs `attempt`, and if it fails with 401 runs it once more with a token from `refresh`.
+///
+/// `attempt` receives `None` on the first try (use the current token) and
+/// `Some(token)` on the retry. A failure to refresh, or a second 401, is returned as-is.
+pub(crate) async fn retry_once_on_unauthorized<T, A, AFut, R, RFut>(
+    mut attempt: A,
+    refresh: R,
+) -> Result<T, AzureHttpError>
+where
+    A: FnMut(Option<String>) -> AFut,
+    AFut: Future<Output = Result<T, AzureHttpError>>,
+    R: FnOnce() -> RFut,
+    RFut: Future<Output = Result<String, AzureHttpError>>,
+{
+    match attempt(None).await {
+        Err(AzureHttpError::ApiError { status: 401, .. }) => {
+            warn!("Request rejected with 401, retrying once with a refreshed token");
+            let token = refresh().await?;
+            attempt(Some(token)).await
+        }
+        result => result,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+    use std::cell::Cell;
+
+    #[test]
+    fn test_should_retry_transient_statuses_on_idempotent_methods() {
+        let policy = RetryPolicy::default();
+
+        assert!(policy.should_retry(&Method::GET, 429, 0));
+        assert!(policy.should_retry(&Method::DELETE, 503, 0));
+        assert!(!policy.should_retry(&Method::GET, 404, 0));
+        assert!(!policy.should_retry(&Method::GET, 429, policy.max_retries));
+        assert!(!policy.should_retry(&Method::PUT, 429, 0));
+        assert!(!policy.should_retry(&Method::POST, 503, 0));
+
+        let opted_in = RetryPolicy {
+            retry_non_idempotent: true,
+            ..policy
+        };
+        assert!(opted_in.should_retry(&Method::POST, 503, 0));
+    }
+
+    #[test]
+    fn test_delay_backs_off_and_honors_retry_after() {
+        let policy = RetryPolicy {
+            base_delay: Duration::from_millis(100),
+            ..RetryPolicy::default()
+        };
+
+        assert_eq!(policy.delay(0, None), Duration::from_millis(100));
+        assert_eq!(policy.delay(2, None), Duration::from_millis(400));
+        assert_eq!(
+            policy.delay(2, Some(Duration::from_secs(7))),
+            Duration::from_secs(7)
+        );
+        assert_eq!(
+            policy.delay(0, Some(Duration::from_secs(3_600))),
+            Duration::from_secs(MAX_RETRY_DELAY_SECS)
+        );
+        assert_eq!(
+            policy.delay(40, None),
+            Duration::from_secs(MAX_RETRY_DELAY_SECS)
+        );
+    }
+
+    #[test]
+    fn test_parse_retry_after() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(parse_retry_after(&headers), None);
+
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("2"));
+        assert_eq!(parse_retry_after(&headers), Some(Duration::from_secs(2)));
+
+        headers.insert("x-ms-retry-after-ms", HeaderValue::from_static("250"));
+        assert_eq!(
+            parse_retry_after(&headers),
+            Some(Duration::from_millis(250))
+        );
+
+        let mut dated = HeaderMap::new();
+        dated.insert(
+            RETRY_AFTER,
+            HeaderValue::from_static("Wed, 21 Oct 2015 07:28:00 GMT"),
+        );
+        assert_eq!(parse_retry_after(&dated), None);
+    }
+
+    fn unauthorized() -> AzureHttpError {
+        AzureHttpError::ApiError {
+            status: 401,
+            code: None,
+            message: "Token expired".to_string(),
+            body: String::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retries_once_after_401() {
+        let attempts = Cell::new(0);
+        let refreshes = Cell::new(0);
+
+        let result = retry_once_on_unauthorized(
+            |token| {
+                attempts.set(attempts.get() + 1);
+                async move {
+                    match token {
+                        None => Err(unauthorized()),
+                        Some(token) => Ok(token),
+                    }
+                }
+            },
+            || {
+                refreshes.set(refreshes.get() + 1);
+                async { Ok("fresh-token".to_string()) }
+            },
+        )
+        .await;
+
+        assert_eq!(result.unwrap(), "fresh-token");
+        assert_eq!(attempts.get(), 2);
+        assert_eq!(refreshes.get(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_second_401_is_returned() {
+        let attempts = Cell::new(0);
+
+        let result: Result<(), _> = retry_once_on_unauthorized(
+            |_| {
+                attempts.set(attempts.get() + 1);
+                async { Err(unauthorized()) }
+            },
+            || async { Ok("fresh-token".to_string()) },
+        )
+        .await;
+
+        assert!(matches!(result, Err(AzureHttpError::ApiError { status: 401, .. })));
+        assert_eq!(attempts.get(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_other_errors_are_not_retried() {
+        let attempts = Cell::new(0);
+        let refreshes = Cell::new(0);
+
+        let result: Result<(), _> = retry_once_on_unauthorized(
+            |_| {
+                attempts.set(attempts.get() + 1);
+                async {
+                    Err(AzureHttpError::ApiError {
+                        status: 403,
+                        code: None,
+                        message: "Forbidden".to_string(),
+                        body: String::new(),
+                    })
+                }
+            },
+            || {
+                refreshes.set(refreshes.get() + 1);
+                async { Ok("fresh-token".to_string()) }
+            },
+        )
+        .await;
+
+        assert!(matches!(result, Err(AzureHttpError::ApiError { status: 403, .. })));
+        assert_eq!(attempts.get(), 1);
+        assert_eq!(refreshes.get(), 0);
+    }
+}
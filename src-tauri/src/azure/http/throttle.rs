@@ -0,0 +1,288 @@
+//! Adaptive concurrency limit for outgoing requests
+//!
+//! Azure throttles busy tenants and vaults with 429 responses. Instead of a
+//! fixed concurrency that users have to tune by hand, every request passes
+//! through a shared limiter: the number of concurrent requests is halved when
+//! 429s come back, and grows by one after each full window of unthrottled
+//! responses, always staying within the configured bounds (`UserConfig`'s
+//! `adaptive_concurrency_min` and `adaptive_concurrency_max`).
+
+use lazy_static::lazy_static;
+use log::{info, warn};
+use serde::Serialize;
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+use tokio::sync::Notify;
+
+use crate::config::{
+    ADAPTIVE_CONCURRENCY_MAX, ADAPTIVE_CONCURRENCY_MIN, THROTTLE_DECREASE_COOLDOWN_MS,
+};
+
+lazy_static! {
+    /// Limiter shared by every `AzureHttpClient`, with default bounds until the config is applied
+    pub(crate) static ref REQUEST_LIMITER: AdaptiveLimiter =
+        AdaptiveLimiter::new(ADAPTIVE_CONCURRENCY_MIN, ADAPTIVE_CONCURRENCY_MAX);
+}
+
+/// Snapshot of the request limiter, for diagnostics
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RequestMetrics {
+    /// Requests currently allowed to run at once
+    pub effective_concurrency: usize,
+    pub min_concurrency: usize,
+    pub max_concurrency: usize,
+    pub in_flight: usize,
+    /// 429 responses seen since startup
+    pub throttled_responses: u64,
+    /// Requests sent since startup
+    pub requests_sent: u64,
+}
+
+/// Current state of the shared request limiter
+pub fn request_metrics() -> RequestMetrics {
+    REQUEST_LIMITER.metrics()
+}
+
+/// Set the bounds of the shared request limiter, e.g. from `UserConfig`
+pub fn set_concurrency_bounds(min: usize, max: usize) {
+    REQUEST_LIMITER.set_bounds(min, max);
+}
+
+struct LimiterState {
+    min: usize,
+    max: usize,
+    limit: usize,
+    in_flight: usize,
+    /// Unthrottled responses since the limit last changed
+    successes: usize,
+    last_decrease: Option<Instant>,
+    throttled_responses: u64,
+    requests_sent: u64,
+}
+
+/// Concurrency limit that adapts to 429 responses (additive increase, multiplicative decrease)
+pub(crate) struct AdaptiveLimiter {
+    state: Mutex<LimiterState>,
+    notify: Notify,
+}
+
+/// A slot in the limiter, released on drop
+pub(crate) struct RequestPermit<'a> {
+    limiter: &'a AdaptiveLimiter,
+}
+
+impl Drop for RequestPermit<'_> {
+    fn drop(&mut self) {
+        self.limiter.lock().in_flight -= 1;
+        self.limiter.notify.notify_waiters();
+    }
+}
+
+impl AdaptiveLimiter {
+    /// Creates a limiter starting at `max` concurrent requests
+    pub(crate) fn new(min: usize, max: usize) -> Self {
+        Self {
+            state: Mutex::new(LimiterState {
+                min,
+                max,
+                limit: max,
+                in_flight: 0,
+                successes: 0,
+                last_decrease: None,
+                throttled_responses: 0,
+                requests_sent: 0,
+            }),
+            notify: Notify::new(),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, LimiterState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Replaces the bounds, moving the current limit into them
+    ///
+    /// Requests already in flight keep their slots.
+    pub(crate) fn set_bounds(&self, min: usize, max: usize) {
+        {
+            let mut state = self.lock();
+            if (state.min, state.max) == (min, max) {
+                return;
+            }
+            state.min = min;
+            state.max = max;
+            state.limit = state.limit.clamp(min, max);
+            state.successes = 0;
+        }
+        info!("Request concurrency bounds set to {}-{}", min, max);
+        self.notify.notify_waiters();
+    }
+
+    /// Waits until fewer than the effective limit of requests are in flight
+    pub(crate) async fn acquire(&self) -> RequestPermit<'_> {
+        loop {
+            // Registered before checking, so a release in between isn't missed
+            let notified = self.notify.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+
+            {
+                let mut state = self.lock();
+                if state.in_flight < state.limit {
+                    state.in_flight += 1;
+                    state.requests_sent += 1;
+                    return RequestPermit { limiter: self };
+                }
+            }
+
+            notified.await;
+        }
+    }
+
+    /// Adjusts the limit based on a response status
+    pub(crate) fn record_response(&self, status: u16) {
+        if status == 429 {
+            self.record_throttled(Instant::now());
+        } else {
+            self.record_success();
+        }
+    }
+
+    /// Halves the limit, at most once per cooldown so a burst of 429s
+    /// from the same window only counts once
+    fn record_throttled(&self, now: Instant) {
+        let mut state = self.lock();
+        state.throttled_responses += 1;
+        state.successes = 0;
+
+        let cooldown = Duration::from_millis(THROTTLE_DECREASE_COOLDOWN_MS);
+        if state
+            .last_decrease
+            .is_some_and(|last| now.duration_since(last) < cooldown)
+        {
+            return;
+        }
+
+        let limit = (state.limit / 2).max(state.min);
+        if limit != state.limit {
+            warn!(
+                "Throttled by Azure, reducing request concurrency from {} to {}",
+                state.limit, limit
+            );
+            state.limit = limit;
+        }
+        state.last_decrease = Some(now);
+    }
+
+    /// Grows the limit by one after a full window (the current limit) of unthrottled responses
+    fn record_success(&self) {
+        let grown = {
+            let mut state = self.lock();
+            state.successes += 1;
+            if state.successes < state.limit {
+                return;
+            }
+
+            state.successes = 0;
+            if state.limit >= state.max {
+                return;
+            }
+            state.limit += 1;
+            state.limit
+        };
+
+        info!("Raising request concurrency to {}", grown);
+        self.notify.notify_waiters();
+    }
+
+    fn metrics(&self) -> RequestMetrics {
+        let state = self.lock();
+        RequestMetrics {
+            effective_concurrency: state.limit,
+            min_concurrency: state.min,
+            max_concurrency: state.max,
+            in_flight: state.in_flight,
+            throttled_responses: state.throttled_responses,
+            requests_sent: state.requests_sent,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_throttling_halves_limit_within_bounds() {
+        let limiter = AdaptiveLimiter::new(2, 16);
+        let start = Instant::now();
+        let cooldown = Duration::from_millis(THROTTLE_DECREASE_COOLDOWN_MS);
+
+        limiter.record_throttled(start);
+        assert_eq!(limiter.metrics().effective_concurrency, 8);
+
+        // Same burst: counted, but the limit isn't reduced again
+        limiter.record_throttled(start + Duration::from_millis(1));
+        assert_eq!(limiter.metrics().effective_concurrency, 8);
+
+        limiter.record_throttled(start + cooldown);
+        limiter.record_throttled(start + cooldown * 2);
+        limiter.record_throttled(start + cooldown * 3);
+        let metrics = limiter.metrics();
+        assert_eq!(metrics.effective_concurrency, 2);
+        assert_eq!(metrics.throttled_responses, 5);
+    }
+
+    #[test]
+    fn test_successes_grow_limit_up_to_max() {
+        let limiter = AdaptiveLimiter::new(1, 5);
+        limiter.record_throttled(Instant::now());
+        assert_eq!(limiter.metrics().effective_concurrency, 2);
+
+        limiter.record_success();
+        assert_eq!(limiter.metrics().effective_concurrency, 2);
+        limiter.record_success();
+        assert_eq!(limiter.metrics().effective_concurrency, 3);
+
+        for _ in 0..100 {
+            limiter.record_success();
+        }
+        assert_eq!(limiter.metrics().effective_concurrency, 5);
+    }
+
+    #[test]
+    fn test_new_bounds_move_the_limit_into_them() {
+        let limiter = AdaptiveLimiter::new(2, 16);
+
+        limiter.set_bounds(1, 4);
+        assert_eq!(limiter.metrics().effective_concurrency, 4);
+        assert_eq!(limiter.metrics().min_concurrency, 1);
+
+        limiter.set_bounds(8, 32);
+        let metrics = limiter.metrics();
+        assert_eq!(metrics.effective_concurrency, 8);
+        assert_eq!(metrics.max_concurrency, 32);
+
+        for _ in 0..100 {
+            limiter.record_success();
+        }
+        assert!(limiter.metrics().effective_concurrency > 8);
+    }
+
+    #[tokio::test]
+    async fn test_acquire_waits_for_a_free_slot() {
+        let limiter = AdaptiveLimiter::new(1, 2);
+        let first = limiter.acquire().await;
+        let _second = limiter.acquire().await;
+        assert_eq!(limiter.metrics().in_flight, 2);
+        assert_eq!(limiter.metrics().requests_sent, 2);
+
+        let blocked = tokio::time::timeout(Duration::from_millis(20), limiter.acquire()).await;
+        assert!(blocked.is_err());
+
+        drop(first);
+        let third = tokio::time::timeout(Duration::from_millis(20), limiter.acquire()).await;
+        assert!(third.is_ok());
+    }
+}
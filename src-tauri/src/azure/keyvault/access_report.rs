@@ -0,0 +1,295 @@
+//! Vault access report - access policies and RBAC assignments in one list
+
+use anyhow::{Context, Result};
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::azure::activity_log::graph::{resolve_caller_identities, ResolvedCaller};
+use crate::azure::auth::token::get_token_for_scope;
+use crate::azure::http::AzureHttpClient;
+use crate::azure::rbac::service::{get_vault_role_assignments, role_definition_guid};
+use crate::azure::rbac::types::VaultRoleAssignment;
+use crate::config::{management_scope, urls};
+
+use super::types::{AccessPolicy, KeyVault};
+
+/// Where a principal's access to a vault comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AccessSource {
+    AccessPolicy,
+    Rbac,
+}
+
+/// One principal's access to a vault, from a single access policy or role assignment
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultAccessEntry {
+    pub principal_id: String,
+    pub principal_name: Option<String>,
+    /// 'User', 'Group', 'ServicePrincipal', ... when known
+    pub principal_type: Option<String>,
+    /// Access policy permissions per object type (e.g. `secrets: get, list`),
+    /// or the role name (the role definition GUID if it couldn't be resolved)
+    pub permissions_or_role: Vec<String>,
+    pub source: AccessSource,
+    /// The scope a role was assigned at, which may be a parent of the vault
+    pub scope: Option<String>,
+    /// False for access policies on vaults that use RBAC authorization,
+    /// as Azure ignores them there
+    pub active: bool,
+}
+
+/// List every identity with access to a vault, through access policies or RBAC.
+///
+/// Principal IDs are resolved to display names via Microsoft Graph where possible.
+///
+/// # Arguments
+///
+/// * `subscription_id` - The Azure subscription ID
+/// * `resource_group` - The resource group name
+/// * `vault_name` - The name of the Key Vault
+/// * `vault_resource_id` - The full ARM resource ID of the Key Vault
+///
+/// # Returns
+///
+/// Access policy entries followed by role assignments, each sorted by principal.
+///
+/// # Errors
+///
+/// This function will return an error if:
+/// - The user is not authenticated
+/// - The vault can't be fetched
+/// - The vault's role assignments can't be read
+pub async fn get_vault_access_report(
+    subscription_id: &str,
+    resource_group: &str,
+    vault_name: &str,
+    vault_resource_id: &str,
+) -> Result<Vec<VaultAccessEntry>, String> {
+    get_vault_access_report_internal(
+        subscription_id,
+        resource_group,
+        vault_name,
+        vault_resource_id,
+    )
+    .await
+    .map_err(|e| {
+        error!("Failed to get vault access report: {}", e);
+        e.to_string()
+    })
+}
+
+async fn get_vault_access_report_internal(
+    subscription_id: &str,
+    resource_group: &str,
+    vault_name: &str,
+    vault_resource_id: &str,
+) -> Result<Vec<VaultAccessEntry>> {
+    info!("Building access report for vault: {}", vault_name);
+
+    let token = get_token_for_scope(management_scope())
+        .await
+        .map_err(|e| anyhow::anyhow!(e))
+        .context("Failed to retrieve management token")?;
+
+    let client = AzureHttpClient::with_scoped_token(&token, management_scope())
+        .context("Failed to create HTTP client with token")?;
+
+    let url = urls::keyvault(subscription_id, resource_group, vault_name);
+    let vault: KeyVault = client
+        .get(&url)
+        .await
+        .with_context(|| format!("Failed to fetch keyvault '{}'", vault_name))?;
+
+    // Role assignments come back with their principals already resolved
+    let assignments = get_vault_role_assignments(vault_resource_id)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))
+        .context("Failed to read role assignments")?;
+
+    let policy_principals: Vec<String> = vault
+        .properties
+        .access_policies
+        .iter()
+        .map(|policy| policy.object_id.clone())
+        .collect();
+    let principals = resolve_caller_identities(policy_principals)
+        .await
+        .unwrap_or_else(|e| {
+            warn!("Failed to resolve access policy principals: {}", e);
+            HashMap::new()
+        });
+
+    let entries = merge_access_entries(
+        &vault.properties.access_policies,
+        vault.properties.enable_rbac_authorization,
+        assignments,
+        &principals,
+    );
+
+    info!("Vault access report has {} entries", entries.len());
+    Ok(entries)
+}
+
+/// Combine access policies and role assignments into a single list
+fn merge_access_entries(
+    policies: &[AccessPolicy],
+    rbac_enabled: bool,
+    assignments: Vec<VaultRoleAssignment>,
+    principals: &HashMap<String, ResolvedCaller>,
+) -> Vec<VaultAccessEntry> {
+    let mut policy_entries: Vec<VaultAccessEntry> = policies
+        .iter()
+        .map(|policy| {
+            let principal = principals.get(&policy.object_id);
+            VaultAccessEntry {
+                principal_id: policy.object_id.clone(),
+                principal_name: principal.map(|p| p.display_name.clone()),
+                principal_type: principal.and_then(|p| principal_type(&p.caller_type)),
+                permissions_or_role: policy_permissions(policy),
+                source: AccessSource::AccessPolicy,
+                scope: None,
+                active: !rbac_enabled,
+            }
+        })
+        .collect();
+
+    let mut role_entries: Vec<VaultAccessEntry> = assignments
+        .into_iter()
+        .map(|assignment| VaultAccessEntry {
+            principal_id: assignment.principal_id,
+            principal_name: assignment.principal_display_name,
+            principal_type: assignment.principal_type,
+            permissions_or_role: vec![assignment.role_name.unwrap_or_else(|| {
+                role_definition_guid(&assignment.role_definition_id).to_string()
+            })],
+            source: AccessSource::Rbac,
+            scope: Some(assignment.scope),
+            active: true,
+        })
+        .collect();
+
+    for entries in [&mut policy_entries, &mut role_entries] {
+        entries.sort_by_cached_key(|entry| {
+            entry
+                .principal_name
+                .as_deref()
+                .unwrap_or(&entry.principal_id)
+                .to_lowercase()
+        });
+    }
+
+    policy_entries.extend(role_entries);
+    policy_entries
+}
+
+/// An access policy's permissions, one line per object type that has any
+fn policy_permissions(policy: &AccessPolicy) -> Vec<String> {
+    let permissions = &policy.permissions;
+    [
+        ("secrets", Some(&permissions.secrets)),
+        ("keys", permissions.keys.as_ref()),
+        ("certificates", permissions.certificates.as_ref()),
+        ("storage", permissions.storage.as_ref()),
+    ]
+    .into_iter()
+    .filter_map(|(object_type, granted)| {
+        granted
+            .filter(|granted| !granted.is_empty())
+            .map(|granted| format!("{}: {}", object_type, granted.join(", ")))
+    })
+    .collect()
+}
+
+/// The RBAC principal type for a Graph caller type, so both sources use the same names
+fn principal_type(caller_type: &str) -> Option<String> {
+    match caller_type {
+        "user" => Some("User".to_string()),
+        "servicePrincipal" => Some("ServicePrincipal".to_string()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::azure::keyvault::types::Permissions;
+
+    fn policy(object_id: &str, secrets: &[&str], keys: Option<&[&str]>) -> AccessPolicy {
+        AccessPolicy {
+            tenant_id: "tenant".to_string(),
+            object_id: object_id.to_string(),
+            permissions: Permissions {
+                secrets: secrets.iter().map(|p| p.to_string()).collect(),
+                keys: keys.map(|keys| keys.iter().map(|p| p.to_string()).collect()),
+                certificates: Some(Vec::new()),
+                storage: None,
+            },
+        }
+    }
+
+    fn assignment(principal_id: &str, name: &str, role_name: Option<&str>) -> VaultRoleAssignment {
+        VaultRoleAssignment {
+            id: format!("assignment-{}", principal_id),
+            principal_id: principal_id.to_string(),
+            principal_type: Some("Group".to_string()),
+            principal_display_name: Some(name.to_string()),
+            role_definition_id: "/providers/roleDefinitions/4633458b".to_string(),
+            role_name: role_name.map(str::to_string),
+            scope: "/subscriptions/s/resourceGroups/rg".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_policy_permissions() {
+        let lines = policy_permissions(&policy("p", &["get", "list"], Some(&["get"])));
+        assert_eq!(lines, vec!["secrets: get, list", "keys: get"]);
+
+        assert!(policy_permissions(&policy("p", &[], None)).is_empty());
+    }
+
+    #[test]
+    fn test_merge_access_entries() {
+        let principals = HashMap::from([(
+            "user-1".to_string(),
+            ResolvedCaller {
+                id: "user-1".to_string(),
+                display_name: "Zoe".to_string(),
+                caller_type: "user".to_string(),
+                user_principal_name: None,
+            },
+        )]);
+
+        let entries = merge_access_entries(
+            &[policy("user-1", &["get"], None), policy("app-1", &["list"], None)],
+            true,
+            vec![
+                assignment("group-2", "Ops", None),
+                assignment("group-1", "Admins", Some("Key Vault Secrets User")),
+            ],
+            &principals,
+        );
+
+        let summary: Vec<(&str, AccessSource, bool)> = entries
+            .iter()
+            .map(|e| (e.principal_id.as_str(), e.source, e.active))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("app-1", AccessSource::AccessPolicy, false),
+                ("user-1", AccessSource::AccessPolicy, false),
+                ("group-1", AccessSource::Rbac, true),
+                ("group-2", AccessSource::Rbac, true),
+            ]
+        );
+
+        assert_eq!(entries[1].principal_name.as_deref(), Some("Zoe"));
+        assert_eq!(entries[1].principal_type.as_deref(), Some("User"));
+        assert_eq!(entries[0].principal_name, None);
+        assert_eq!(entries[2].permissions_or_role, vec!["Key Vault Secrets User"]);
+        assert_eq!(entries[3].permissions_or_role, vec!["4633458b"]);
+    }
+}
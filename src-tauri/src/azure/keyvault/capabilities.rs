@@ -0,0 +1,167 @@
+//! Vault capabilities - which operations a vault supports and the user may perform
+//!
+//! Property flags come from the management-plane vault. Access flags come from
+//! lightweight probes that never modify the vault: a data-plane list call for
+//! read access, and the caller's RBAC permissions or access policy entry for
+//! write and delete.
+
+use anyhow::{Context, Result};
+use log::{error, info, warn};
+
+use crate::azure::auth::token::{decode_token_claims, get_token_for_scope};
+use crate::azure::http::AzureHttpClient;
+use crate::azure::rbac::service::{get_caller_permissions, permits_data_action};
+use crate::config::{keyvault_scope, management_scope, urls};
+
+use super::service::check_keyvault_access;
+use super::types::{AccessPolicy, KeyVault, VaultCapabilities};
+
+/// RBAC data action required to create or update secrets
+const SET_SECRET_DATA_ACTION: &str = "Microsoft.KeyVault/vaults/secrets/setSecret/action";
+
+/// RBAC data action required to delete secrets
+const DELETE_SECRET_DATA_ACTION: &str = "Microsoft.KeyVault/vaults/secrets/delete";
+
+/// Determine what a vault supports and what the signed-in user can do in it.
+///
+/// # Arguments
+///
+/// * `subscription_id` - The Azure subscription ID
+/// * `resource_group` - The resource group name
+/// * `vault_name` - The name of the Key Vault
+/// * `vault_uri` - The Key Vault URI
+///
+/// # Returns
+///
+/// The vault's capabilities or an error.
+///
+/// # Errors
+///
+/// This function will return an error if the vault itself can't be fetched.
+/// Failed access probes are reported as `false` rather than as errors.
+pub async fn get_vault_capabilities(
+    subscription_id: &str,
+    resource_group: &str,
+    vault_name: &str,
+    vault_uri: &str,
+) -> Result<VaultCapabilities, String> {
+    get_vault_capabilities_internal(subscription_id, resource_group, vault_name, vault_uri)
+        .await
+        .map_err(|e| {
+            error!("Failed to get vault capabilities: {}", e);
+            e.to_string()
+        })
+}
+
+async fn get_vault_capabilities_internal(
+    subscription_id: &str,
+    resource_group: &str,
+    vault_name: &str,
+    vault_uri: &str,
+) -> Result<VaultCapabilities> {
+    info!("Probing capabilities for vault: {}", vault_name);
+
+    let token = get_token_for_scope(management_scope())
+        .await
+        .map_err(|e| anyhow::anyhow!(e))
+        .context("Failed to retrieve management token")?;
+
+    let client = AzureHttpClient::with_scoped_token(&token, management_scope())
+        .context("Failed to create HTTP client with token")?;
+
+    let url = urls::keyvault(subscription_id, resource_group, vault_name);
+    let vault: KeyVault = client
+        .get(&url)
+        .await
+        .with_context(|| format!("Failed to fetch keyvault '{}'", vault_name))?;
+
+    let rbac_enabled = vault.properties.enable_rbac_authorization;
+
+    let can_read = check_keyvault_access(vault_uri)
+        .await
+        .map(|check| check.can_list_secrets)
+        .unwrap_or(false);
+
+    let (can_write, can_delete) = if rbac_enabled {
+        match get_caller_permissions(&vault.id).await {
+            Ok(permissions) => (
+                permits_data_action(&permissions, SET_SECRET_DATA_ACTION),
+                permits_data_action(&permissions, DELETE_SECRET_DATA_ACTION),
+            ),
+            Err(e) => {
+                warn!("Failed to read caller permissions: {}", e);
+                (false, false)
+            }
+        }
+    } else {
+        match caller_object_id().await {
+            Some(object_id) => (
+                access_policy_grants(&vault.properties.access_policies, &object_id, "set"),
+                access_policy_grants(&vault.properties.access_policies, &object_id, "delete"),
+            ),
+            None => (false, false),
+        }
+    };
+
+    Ok(VaultCapabilities {
+        soft_delete_enabled: vault.properties.enable_soft_delete,
+        purge_protection_enabled: vault.properties.enable_purge_protection.unwrap_or(false),
+        rbac_enabled,
+        can_read,
+        can_write,
+        can_delete,
+    })
+}
+
+/// Get the object ID of the signed-in principal from its Key Vault token
+pub(crate) async fn caller_object_id() -> Option<String> {
+    let token = get_token_for_scope(keyvault_scope())
+        .await
+        .map_err(|e| warn!("Failed to retrieve Key Vault token: {}", e))
+        .ok()?;
+    decode_token_claims(&token)?.oid
+}
+
+/// Check whether the access policy for an object ID grants a secret permission.
+///
+/// Only policies assigned directly to the object ID are considered; permissions
+/// granted through group membership aren't visible here and report `false`.
+fn access_policy_grants(policies: &[AccessPolicy], object_id: &str, permission: &str) -> bool {
+    policies
+        .iter()
+        .filter(|policy| policy.object_id.eq_ignore_ascii_case(object_id))
+        .flat_map(|policy| policy.permissions.secrets.iter())
+        .any(|granted| {
+            granted.eq_ignore_ascii_case(permission) || granted.eq_ignore_ascii_case("all")
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::azure::keyvault::types::Permissions;
+
+    fn policy(object_id: &str, secrets: &[&str]) -> AccessPolicy {
+        AccessPolicy {
+            tenant_id: "tenant".to_string(),
+            object_id: object_id.to_string(),
+            permissions: Permissions {
+                secrets: secrets.iter().map(|s| s.to_string()).collect(),
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn test_access_policy_grants() {
+        let policies = vec![
+            policy("user-1", &["Get", "List", "Set"]),
+            policy("user-2", &["all"]),
+        ];
+
+        assert!(access_policy_grants(&policies, "user-1", "set"));
+        assert!(!access_policy_grants(&policies, "user-1", "delete"));
+        assert!(access_policy_grants(&policies, "USER-2", "delete"));
+        assert!(!access_policy_grants(&policies, "user-3", "set"));
+    }
+}
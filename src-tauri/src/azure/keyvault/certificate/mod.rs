@@ -0,0 +1,6 @@
+//! Key Vault Certificates module
+//!
+//! This module lists Key Vault certificates and finds the ones nearing expiry.
+
+pub mod service;
+pub mod types;
@@ -0,0 +1,281 @@
+//! Certificate service - list certificates and find the ones nearing expiry
+
+use anyhow::{Context, Result};
+use futures::stream::{self, StreamExt};
+use log::{error, info, warn};
+
+use crate::azure::auth::token::get_token_for_scope;
+use crate::azure::http::{fetch_all_paginated, AzureHttpClient};
+use crate::config::{keyvault_scope, urls, MAX_CONCURRENT_VAULTS};
+
+use super::types::{
+    Certificate, ExpiringCertificate, ExpiringCertificates, InaccessibleCertificateVault,
+};
+
+const SECONDS_PER_DAY: u64 = 86_400;
+
+/// Fetch all certificates from a Key Vault.
+///
+/// # Arguments
+///
+/// * `keyvault_uri` - The Key Vault URI
+///
+/// # Errors
+///
+/// This function will return an error if:
+/// - The user is not authenticated
+/// - Listing certificates is denied
+pub async fn get_certificates(keyvault_uri: &str) -> Result<Vec<Certificate>, String> {
+    get_certificates_internal(keyvault_uri).await.map_err(|e| {
+        error!("Failed to get certificates: {}", e);
+        e.to_string()
+    })
+}
+
+async fn get_certificates_internal(keyvault_uri: &str) -> Result<Vec<Certificate>> {
+    info!("Fetching certificates");
+
+    let url = urls::certificates(keyvault_uri);
+    let token = get_token_for_scope(keyvault_scope())
+        .await
+        .map_err(|e| anyhow::anyhow!(e))
+        .context("Failed to retrieve Key Vault token")?;
+
+    let client = AzureHttpClient::with_scoped_token(&token, keyvault_scope())
+        .context("Failed to create HTTP client with token")?;
+
+    let certificates = fetch_all_paginated::<Certificate>(&url, &client)
+        .await
+        .with_context(|| format!("Failed to fetch certificates from {}", keyvault_uri))?;
+
+    info!("Successfully fetched {} certificates", certificates.len());
+    Ok(certificates)
+}
+
+/// Find the certificates in a vault that expired or expire within `days` from now.
+///
+/// Certificates without an expiry date are left out.
+pub async fn certificates_expiring_within(
+    keyvault_uri: &str,
+    days: u32,
+) -> Result<Vec<ExpiringCertificate>, String> {
+    let certificates = get_certificates(keyvault_uri).await?;
+    let now = chrono::Utc::now().timestamp().max(0) as u64;
+
+    Ok(expiring_within(keyvault_uri, certificates, now, days))
+}
+
+/// Find certificates nearing expiry across several vaults.
+///
+/// Vaults are queried concurrently (bounded by `MAX_CONCURRENT_VAULTS`). Vaults
+/// whose certificates can't be listed are returned in `inaccessible_vaults`.
+///
+/// # Returns
+///
+/// A flat list across all vaults, soonest expiry first.
+pub async fn get_expiring_certificates(vault_uris: Vec<String>, days: u32) -> ExpiringCertificates {
+    info!(
+        "Checking {} vaults for certificates expiring within {} days",
+        vault_uris.len(),
+        days
+    );
+
+    let per_vault: Vec<_> = stream::iter(vault_uris)
+        .map(|vault_uri| async move {
+            certificates_expiring_within(&vault_uri, days)
+                .await
+                .map_err(|error| {
+                    warn!("Skipping vault {} for the expiry check: {}", vault_uri, error);
+                    InaccessibleCertificateVault { vault_uri, error }
+                })
+        })
+        .buffer_unordered(MAX_CONCURRENT_VAULTS)
+        .collect()
+        .await;
+
+    let mut certificates = Vec::new();
+    let mut inaccessible_vaults = Vec::new();
+    for outcome in per_vault {
+        match outcome {
+            Ok(expiring) => certificates.extend(expiring),
+            Err(vault) => inaccessible_vaults.push(vault),
+        }
+    }
+    sort_by_expiry(&mut certificates);
+
+    info!(
+        "Found {} certificates nearing expiry, {} vaults inaccessible",
+        certificates.len(),
+        inaccessible_vaults.len()
+    );
+    ExpiringCertificates {
+        certificates,
+        inaccessible_vaults,
+    }
+}
+
+/// Keep the certificates whose expiry is at most `now + days`, including expired ones
+fn expiring_within(
+    vault_uri: &str,
+    certificates: Vec<Certificate>,
+    now: u64,
+    days: u32,
+) -> Vec<ExpiringCertificate> {
+    let window_end = now.saturating_add(u64::from(days) * SECONDS_PER_DAY);
+
+    certificates
+        .into_iter()
+        .filter_map(|certificate| {
+            let expires = certificate.attributes.exp?;
+            if expires > window_end {
+                return None;
+            }
+            let seconds_left = expires as i64 - now as i64;
+            Some(ExpiringCertificate {
+                vault_uri: vault_uri.to_string(),
+                name: certificate.name().to_string(),
+                id: certificate.id,
+                x5t: certificate.x5t,
+                enabled: certificate.attributes.enabled,
+                expires,
+                expired: expires < now,
+                days_until_expiry: seconds_left.div_euclid(SECONDS_PER_DAY as i64),
+            })
+        })
+        .collect()
+}
+
+/// Soonest expiry first; ties ordered by vault and name so the list is stable
+fn sort_by_expiry(certificates: &mut [ExpiringCertificate]) {
+    certificates.sort_by(|a, b| {
+        a.expires
+            .cmp(&b.expires)
+            .then_with(|| a.vault_uri.cmp(&b.vault_uri))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::azure::keyvault::secret::types::{RecoveryLevel, SecretAttributes};
+    use std::collections::HashMap;
+
+    const NOW: u64 = 1_700_000_000;
+
+    fn certificate(name: &str, exp: Option<u64>) -> Certificate {
+        Certificate {
+            id: format!("https://vault.vault.azure.net/certificates/{}", name),
+            attributes: SecretAttributes {
+                enabled: true,
+                created: 0,
+                updated: 0,
+                recovery_level: RecoveryLevel::Recoverable,
+                recoverable_days: 90,
+                exp,
+                nbf: None,
+            },
+            x5t: None,
+            tags: HashMap::new(),
+        }
+    }
+
+    fn names(certificates: &[ExpiringCertificate]) -> Vec<&str> {
+        certificates.iter().map(|c| c.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_expiry_window_boundaries() {
+        let window = 30 * SECONDS_PER_DAY;
+        let expiring = expiring_within(
+            "https://vault.vault.azure.net",
+            vec![
+                certificate("expires-now", Some(NOW)),
+                certificate("window-end", Some(NOW + window)),
+                certificate("after-window", Some(NOW + window + 1)),
+                certificate("no-expiry", None),
+            ],
+            NOW,
+            30,
+        );
+
+        assert_eq!(names(&expiring), vec!["expires-now", "window-end"]);
+        assert_eq!(expiring[0].days_until_expiry, 0);
+        assert!(!expiring[0].expired);
+        assert_eq!(expiring[1].days_until_expiry, 30);
+    }
+
+    #[test]
+    fn test_expired_certificates_are_flagged() {
+        let expiring = expiring_within(
+            "https://vault.vault.azure.net",
+            vec![
+                certificate("just-expired", Some(NOW - 1)),
+                certificate("long-expired", Some(NOW - 400 * SECONDS_PER_DAY)),
+            ],
+            NOW,
+            30,
+        );
+
+        assert_eq!(names(&expiring), vec!["just-expired", "long-expired"]);
+        assert!(expiring.iter().all(|c| c.expired));
+        assert_eq!(expiring[0].days_until_expiry, -1);
+        assert_eq!(expiring[1].days_until_expiry, -400);
+    }
+
+    #[test]
+    fn test_zero_day_window() {
+        let expiring = expiring_within(
+            "https://vault.vault.azure.net",
+            vec![certificate("now", Some(NOW)), certificate("later", Some(NOW + 1))],
+            NOW,
+            0,
+        );
+
+        assert_eq!(names(&expiring), vec!["now"]);
+    }
+
+    #[test]
+    fn test_days_until_expiry_rounds_down() {
+        let expiring = expiring_within(
+            "https://vault.vault.azure.net",
+            vec![certificate("almost-two-days", Some(NOW + 2 * SECONDS_PER_DAY - 1))],
+            NOW,
+            7,
+        );
+
+        assert_eq!(expiring[0].days_until_expiry, 1);
+    }
+
+    #[test]
+    fn test_sort_by_expiry() {
+        let mut expiring = expiring_within(
+            "https://b.vault.azure.net",
+            vec![certificate("late", Some(NOW + 500)), certificate("tie", Some(NOW + 100))],
+            NOW,
+            1,
+        );
+        expiring.extend(expiring_within(
+            "https://a.vault.azure.net",
+            vec![certificate("tie", Some(NOW + 100)), certificate("soon", Some(NOW + 10))],
+            NOW,
+            1,
+        ));
+
+        sort_by_expiry(&mut expiring);
+
+        let order: Vec<(&str, &str)> = expiring
+            .iter()
+            .map(|c| (c.vault_uri.as_str(), c.name.as_str()))
+            .collect();
+        assert_eq!(
+            order,
+            vec![
+                ("https://a.vault.azure.net", "soon"),
+                ("https://a.vault.azure.net", "tie"),
+                ("https://b.vault.azure.net", "tie"),
+                ("https://b.vault.azure.net", "late"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,91 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::azure::keyvault::secret::types::SecretAttributes;
+
+/// A certificate as returned by the list certificates API
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Certificate {
+    pub id: String,
+    /// Certificates carry the same attributes as secrets
+    pub attributes: SecretAttributes,
+    /// Base64url SHA-1 thumbprint of the certificate
+    #[serde(default)]
+    pub x5t: Option<String>,
+    #[serde(default)]
+    pub tags: HashMap<String, String>,
+}
+
+impl Certificate {
+    /// The certificate name, i.e. the segment after `/certificates/` in its ID
+    pub fn name(&self) -> &str {
+        match self.id.split_once("/certificates/") {
+            Some((_, rest)) => rest.split('/').next().unwrap_or(rest),
+            None => self.id.rsplit('/').next().unwrap_or(&self.id),
+        }
+    }
+}
+
+/// A certificate that expired or expires within the requested window
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExpiringCertificate {
+    pub vault_uri: String,
+    pub name: String,
+    pub id: String,
+    pub x5t: Option<String>,
+    pub enabled: bool,
+    /// Expiry time (unix seconds)
+    pub expires: u64,
+    /// The expiry time has already passed
+    pub expired: bool,
+    /// Whole days left until expiry, rounded down; negative once expired
+    pub days_until_expiry: i64,
+}
+
+/// A vault whose certificates couldn't be listed during an expiry check
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InaccessibleCertificateVault {
+    pub vault_uri: String,
+    pub error: String,
+}
+
+/// Expiry check matches plus the vaults that couldn't be checked
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExpiringCertificates {
+    pub certificates: Vec<ExpiringCertificate>,
+    pub inaccessible_vaults: Vec<InaccessibleCertificateVault>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_certificate_list_item_payload() {
+        // A list item as returned by GET {vault}/certificates
+        let certificate: Certificate = serde_json::from_str(
+            r#"{
+                "id": "https://vault.vault.azure.net/certificates/api-tls",
+                "x5t": "fLi3U52HunIVNXubkEnf8tP6Wbo",
+                "attributes": {
+                    "enabled": true,
+                    "nbf": 1690000000,
+                    "exp": 1721536000,
+                    "created": 1690000100,
+                    "updated": 1690000100
+                },
+                "tags": {}
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(certificate.name(), "api-tls");
+        assert_eq!(certificate.x5t.as_deref(), Some("fLi3U52HunIVNXubkEnf8tP6Wbo"));
+        assert_eq!(certificate.attributes.exp, Some(1_721_536_000));
+        assert!(certificate.attributes.enabled);
+    }
+}
@@ -0,0 +1,262 @@
+//! Vault cloning - create a new Key Vault and copy every secret into it
+
+use anyhow::{Context, Result};
+use futures::stream::{self, StreamExt};
+use log::{error, info, warn};
+use serde::Serialize;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::azure::keyvault::secret::service::{
+    create_secret_with_attributes, get_secret, get_secrets,
+};
+use crate::azure::keyvault::secret::types::{SecretBundle, SecretWriteOptions};
+use crate::cache::azure_cache;
+use crate::config::MAX_CONCURRENT_SECRET_FETCHES;
+
+use super::capabilities::caller_object_id;
+use super::service::{check_keyvault_name_availability, create_keyvault, validate_keyvault_name};
+use super::types::{CreateKeyVaultOptions, KeyVault};
+
+/// Progress update emitted after each secret is copied
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CloneVaultProgress {
+    pub secret_name: String,
+    pub success: bool,
+    pub completed: usize,
+    pub total: usize,
+}
+
+/// A secret that could not be copied to the target vault
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SecretCopyFailure {
+    pub secret_name: String,
+    pub error: String,
+}
+
+/// Summary of a vault clone operation
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CloneVaultSummary {
+    pub target_vault: KeyVault,
+    pub total: usize,
+    pub copied: usize,
+    pub failed: Vec<SecretCopyFailure>,
+}
+
+/// Clone all secrets from a source vault into a newly created vault.
+///
+/// The target name is validated and checked for availability before anything
+/// is created. Once the vault exists, secrets are copied concurrently and
+/// individual copy failures are collected rather than aborting the clone.
+///
+/// # Arguments
+///
+/// * `source_uri` - The URI of the vault to copy secrets from
+/// * `target_subscription` - Subscription to create the new vault in
+/// * `target_resource_group` - Resource group to create the new vault in
+/// * `target_vault_name` - Name of the new vault
+/// * `on_progress` - Called after each secret copy attempt
+///
+/// # Returns
+///
+/// A summary with the created vault and per-secret failures.
+pub async fn clone_vault<F>(
+    source_uri: &str,
+    target_subscription: &str,
+    target_resource_group: &str,
+    target_vault_name: &str,
+    on_progress: F,
+) -> Result<CloneVaultSummary, String>
+where
+    F: Fn(CloneVaultProgress) + Send + Sync,
+{
+    clone_vault_internal(
+        source_uri,
+        target_subscription,
+        target_resource_group,
+        target_vault_name,
+        on_progress,
+    )
+    .await
+    .map_err(|e| {
+        error!("Failed to clone vault: {}", e);
+        e.to_string()
+    })
+}
+
+async fn clone_vault_internal<F>(
+    source_uri: &str,
+    target_subscription: &str,
+    target_resource_group: &str,
+    target_vault_name: &str,
+    on_progress: F,
+) -> Result<CloneVaultSummary>
+where
+    F: Fn(CloneVaultProgress) + Send + Sync,
+{
+    info!("Cloning vault into '{}'", target_vault_name);
+
+    // Fail early on invalid or taken names, before anything is created
+    validate_keyvault_name(target_vault_name).map_err(|e| anyhow::anyhow!(e))?;
+
+    let availability = check_keyvault_name_availability(target_subscription, target_vault_name)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    if !availability.name_available {
+        return Err(anyhow::anyhow!(
+            "Key Vault name '{}' is not available: {}",
+            target_vault_name,
+            availability
+                .message
+                .or(availability.reason)
+                .unwrap_or_else(|| "name is taken".to_string())
+        ));
+    }
+
+    // Read the source secrets list before creating the target
+    let uri = source_uri.to_string();
+    let secrets = azure_cache()
+        .get_secrets_list_or_load(source_uri, || async move { get_secrets(&uri).await })
+        .await
+        .map_err(|e| anyhow::anyhow!(e))
+        .context("Failed to list secrets in source vault")?;
+
+    // The new vault uses access policies, so the caller needs one to write the copies
+    let caller = caller_object_id()
+        .await
+        .context("Failed to determine the object ID of the signed-in account")?;
+    let options = CreateKeyVaultOptions {
+        secret_access_object_id: Some(caller),
+        ..CreateKeyVaultOptions::default()
+    };
+
+    let target_vault = create_keyvault(
+        target_subscription,
+        target_resource_group,
+        target_vault_name,
+        &options,
+    )
+    .await
+    .map_err(|e| anyhow::anyhow!(e))
+    .with_context(|| format!("Failed to create target vault '{}'", target_vault_name))?;
+    azure_cache()
+        .invalidate_keyvaults(target_subscription)
+        .await;
+
+    let target_uri = target_vault.properties.vault_uri.clone();
+    let total = secrets.len();
+    let completed = AtomicUsize::new(0);
+
+    let results: Vec<(String, Result<(), String>)> = stream::iter(secrets)
+        .map(|secret| {
+            let target_uri = target_uri.clone();
+            let on_progress = &on_progress;
+            let completed = &completed;
+            async move {
+                let name = secret.id.split('/').last().unwrap_or("").to_string();
+                let result = copy_secret(source_uri, &target_uri, &name).await;
+
+                let done = completed.fetch_add(1, Ordering::SeqCst) + 1;
+                on_progress(CloneVaultProgress {
+                    secret_name: name.clone(),
+                    success: result.is_ok(),
+                    completed: done,
+                    total,
+                });
+
+                (name, result)
+            }
+        })
+        .buffer_unordered(MAX_CONCURRENT_SECRET_FETCHES)
+        .collect()
+        .await;
+
+    let mut failed = Vec::new();
+    for (secret_name, result) in results {
+        if let Err(error) = result {
+            warn!("Failed to copy secret '{}': {}", secret_name, error);
+            failed.push(SecretCopyFailure { secret_name, error });
+        }
+    }
+
+    let copied = total - failed.len();
+    info!(
+        "Vault clone complete: {} of {} secrets copied",
+        copied, total
+    );
+
+    Ok(CloneVaultSummary {
+        target_vault,
+        total,
+        copied,
+        failed,
+    })
+}
+
+/// Copy the latest version of a single secret, with its attributes, tags and
+/// content type, from one vault to another
+async fn copy_secret(source_uri: &str, target_uri: &str, secret_name: &str) -> Result<(), String> {
+    let uri = source_uri.to_string();
+    let name = secret_name.to_string();
+    let bundle = azure_cache()
+        .get_secret_value_or_load(source_uri, secret_name, || async move {
+            get_secret(&uri, &name, None).await
+        })
+        .await?;
+
+    let options = copy_options(&bundle);
+    create_secret_with_attributes(target_uri, secret_name, &bundle.value, options).await?;
+    Ok(())
+}
+
+/// Write options that reproduce a secret's attributes, tags and content type
+fn copy_options(bundle: &SecretBundle) -> SecretWriteOptions {
+    SecretWriteOptions {
+        enabled: Some(bundle.attributes.enabled),
+        exp: bundle.attributes.exp,
+        nbf: bundle.attributes.nbf,
+        tags: (!bundle.tags.is_empty()).then(|| bundle.tags.clone()),
+        content_type: bundle.content_type.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::azure::keyvault::secret::types::fixtures::{secret_id, secret_json};
+
+    #[test]
+    fn test_copy_options_carry_attributes_tags_and_content_type() {
+        let mut json = secret_json(&secret_id("db-pass", Some("v1")), "Recoverable");
+        json["value"] = "hunter2".into();
+        json["contentType"] = "text/plain".into();
+        json["tags"] = serde_json::json!({ "env": "prod" });
+        json["attributes"]["enabled"] = false.into();
+        json["attributes"]["exp"] = 1800000000.into();
+        json["attributes"]["nbf"] = 1700000000.into();
+        let bundle: SecretBundle = serde_json::from_value(json).unwrap();
+
+        let options = copy_options(&bundle);
+
+        assert_eq!(options.enabled, Some(false));
+        assert_eq!(options.exp, Some(1800000000));
+        assert_eq!(options.nbf, Some(1700000000));
+        assert_eq!(options.content_type.as_deref(), Some("text/plain"));
+        assert_eq!(options.tags.unwrap()["env"], "prod");
+    }
+
+    #[test]
+    fn test_copy_options_leave_out_empty_tags() {
+        let mut json = secret_json(&secret_id("db-pass", Some("v1")), "Recoverable");
+        json["value"] = "hunter2".into();
+        let bundle: SecretBundle = serde_json::from_value(json).unwrap();
+
+        let options = copy_options(&bundle);
+
+        assert_eq!(options.enabled, Some(true));
+        assert!(options.tags.is_none());
+        assert!(options.content_type.is_none());
+    }
+}
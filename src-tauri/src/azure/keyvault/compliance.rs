@@ -0,0 +1,109 @@
+//! Compliance report - vaults that could lose secrets permanently
+
+use log::info;
+use serde::Serialize;
+
+use crate::cache::azure_cache;
+
+use super::service::get_keyvaults;
+use super::types::KeyVault;
+
+/// A vault missing soft-delete and/or purge protection
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnprotectedVault {
+    pub name: String,
+    pub resource_group: Option<String>,
+    pub vault_uri: String,
+    pub soft_delete_missing: bool,
+    pub purge_protection_missing: bool,
+}
+
+/// Find vaults in a subscription without soft-delete or purge protection.
+///
+/// Without soft-delete a deleted secret is gone immediately; without purge
+/// protection it can still be purged before the retention period ends.
+/// Uses the keyvault cache, loading on a miss.
+///
+/// # Errors
+///
+/// This function will return an error if the vaults can't be listed.
+pub async fn find_vaults_without_soft_delete(
+    subscription_id: &str,
+) -> Result<Vec<UnprotectedVault>, String> {
+    let sub_id = subscription_id.to_string();
+    let vaults = azure_cache()
+        .get_keyvaults_or_load(subscription_id, || async move { get_keyvaults(&sub_id).await })
+        .await?;
+
+    let unprotected = unprotected_vaults(&vaults);
+    info!(
+        "{} of {} vaults lack soft-delete or purge protection",
+        unprotected.len(),
+        vaults.len()
+    );
+    Ok(unprotected)
+}
+
+fn unprotected_vaults(vaults: &[KeyVault]) -> Vec<UnprotectedVault> {
+    vaults
+        .iter()
+        .filter_map(|vault| {
+            let soft_delete_missing = !vault.properties.enable_soft_delete;
+            let purge_protection_missing = vault.properties.enable_purge_protection != Some(true);
+
+            (soft_delete_missing || purge_protection_missing).then(|| UnprotectedVault {
+                name: vault.name.clone(),
+                resource_group: vault.resource_group().map(str::to_string),
+                vault_uri: vault.properties.vault_uri.clone(),
+                soft_delete_missing,
+                purge_protection_missing,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vault(name: &str, soft_delete: bool, purge_protection: Option<bool>) -> KeyVault {
+        let mut vault = KeyVault {
+            id: format!(
+                "/subscriptions/sub/resourceGroups/rg/providers/Microsoft.KeyVault/vaults/{}",
+                name
+            ),
+            name: name.to_string(),
+            ..Default::default()
+        };
+        vault.properties.enable_soft_delete = soft_delete;
+        vault.properties.enable_purge_protection = purge_protection;
+        vault
+    }
+
+    #[test]
+    fn test_unprotected_vaults() {
+        let vaults = vec![
+            vault("protected", true, Some(true)),
+            vault("no-purge-protection", true, None),
+            vault("purge-protection-off", true, Some(false)),
+            vault("nothing", false, None),
+        ];
+
+        let result = unprotected_vaults(&vaults);
+        let summary: Vec<(&str, bool, bool)> = result
+            .iter()
+            .map(|v| (v.name.as_str(), v.soft_delete_missing, v.purge_protection_missing))
+            .collect();
+
+        assert_eq!(
+            summary,
+            vec![
+                ("no-purge-protection", false, true),
+                ("purge-protection-off", false, true),
+                ("nothing", true, true),
+            ]
+        );
+        assert_eq!(result[0].resource_group.as_deref(), Some("rg"));
+    }
+}
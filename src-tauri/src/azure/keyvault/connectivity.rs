@@ -0,0 +1,238 @@
+//! Vault connectivity check - tells "can't reach the vault" apart from "not allowed"
+//!
+//! Private endpoints and firewalls cause failures that otherwise surface as
+//! generic network or auth errors. This sends a cheap authenticated request
+//! with its own client, so the underlying connection error is still available
+//! to classify.
+
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::time::Duration;
+
+use crate::azure::auth::token::get_token_for_scope;
+use crate::config::{keyvault_scope, urls};
+
+/// How long to wait for the vault before treating it as unreachable
+const PING_TIMEOUT_SECS: u64 = 10;
+
+/// Why a vault could or couldn't be reached
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ConnectivityCategory {
+    /// The vault answered and the request was allowed
+    Reachable,
+    /// The vault's hostname didn't resolve
+    Dns,
+    /// The connection was refused or timed out, typically a firewall or private endpoint
+    Unreachable,
+    /// The TLS handshake failed, typically an intercepting proxy
+    Tls,
+    /// The vault answered but its network rules reject this client's address
+    Firewall,
+    /// The vault answered but rejected the credentials or permissions
+    Auth,
+    Unknown,
+}
+
+/// Outcome of `ping_vault`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultConnectivity {
+    pub vault_uri: String,
+    pub category: ConnectivityCategory,
+    pub message: String,
+}
+
+/// Check whether a vault can be reached and classify the failure if not.
+///
+/// Lists at most one secret, which needs a network path to the vault, a
+/// valid token and list permission.
+///
+/// # Arguments
+///
+/// * `keyvault_uri` - The Key Vault URI
+///
+/// # Returns
+///
+/// The category and a message explaining it. Failures are reported in the
+/// result rather than as errors.
+pub async fn ping_vault(keyvault_uri: &str) -> VaultConnectivity {
+    let (category, message) = ping_vault_internal(keyvault_uri).await;
+    info!("Vault connectivity check: {:?}", category);
+
+    VaultConnectivity {
+        vault_uri: keyvault_uri.to_string(),
+        category,
+        message,
+    }
+}
+
+async fn ping_vault_internal(keyvault_uri: &str) -> (ConnectivityCategory, String) {
+    let token = match get_token_for_scope(keyvault_scope()).await {
+        Ok(token) => token,
+        Err(e) => {
+            return (
+                ConnectivityCategory::Auth,
+                format!("Failed to get a Key Vault token: {}", e),
+            )
+        }
+    };
+
+    let client = match reqwest::Client::builder()
+        .timeout(Duration::from_secs(PING_TIMEOUT_SECS))
+        .build()
+    {
+        Ok(client) => client,
+        Err(e) => return (ConnectivityCategory::Unknown, e.to_string()),
+    };
+
+    let url = format!("{}&maxresults=1", urls::secrets(keyvault_uri));
+    let response = match client.get(&url).bearer_auth(token).send().await {
+        Ok(response) => response,
+        Err(e) => {
+            warn!("Vault connectivity check failed: {}", e);
+            return classify_network_error(e.is_timeout(), &error_chain(&e));
+        }
+    };
+
+    let status = response.status().as_u16();
+    let body = response.text().await.unwrap_or_default();
+    classify_status(status, &body)
+}
+
+/// The error and all of its sources, joined, as the useful detail is usually nested
+fn error_chain(e: &reqwest::Error) -> String {
+    let mut chain = e.to_string();
+    let mut source = e.source();
+    while let Some(inner) = source {
+        chain.push_str(": ");
+        chain.push_str(&inner.to_string());
+        source = inner.source();
+    }
+    chain
+}
+
+/// Classify a request that got no HTTP response
+fn classify_network_error(is_timeout: bool, chain: &str) -> (ConnectivityCategory, String) {
+    let lower = chain.to_lowercase();
+
+    let (category, hint) = if lower.contains("dns error")
+        || lower.contains("failed to lookup address")
+        || lower.contains("no such host")
+        || lower.contains("name or service not known")
+    {
+        (
+            ConnectivityCategory::Dns,
+            "The vault's hostname could not be resolved. Check the vault name, \
+             and the private DNS zone if the vault uses a private endpoint.",
+        )
+    } else if lower.contains("certificate") || lower.contains("tls") || lower.contains("ssl") {
+        (
+            ConnectivityCategory::Tls,
+            "The TLS handshake failed. A proxy may be intercepting traffic to the vault.",
+        )
+    } else if is_timeout || lower.contains("timed out") || lower.contains("connection refused") {
+        (
+            ConnectivityCategory::Unreachable,
+            "The vault could not be reached. A firewall or private endpoint may be \
+             blocking this network.",
+        )
+    } else {
+        (
+            ConnectivityCategory::Unknown,
+            "The request to the vault failed.",
+        )
+    };
+
+    (category, format!("{} ({})", hint, chain))
+}
+
+/// Classify the HTTP response from the vault
+fn classify_status(status: u16, body: &str) -> (ConnectivityCategory, String) {
+    let detail = azure_error_message(body).unwrap_or_else(|| format!("HTTP {}", status));
+
+    match status {
+        200..=299 => (ConnectivityCategory::Reachable, "The vault is reachable.".to_string()),
+        403 if is_firewall_rejection(body) => (
+            ConnectivityCategory::Firewall,
+            format!(
+                "The vault is reachable but its firewall rejects this address: {}",
+                detail
+            ),
+        ),
+        401 | 403 => (
+            ConnectivityCategory::Auth,
+            format!("The vault is reachable but denied access: {}", detail),
+        ),
+        _ => (
+            ConnectivityCategory::Unknown,
+            format!("The vault returned an error: {}", detail),
+        ),
+    }
+}
+
+/// Whether a 403 comes from the vault's network rules rather than from permissions
+fn is_firewall_rejection(body: &str) -> bool {
+    let lower = body.to_lowercase();
+    lower.contains("forbiddenbyfirewall") || lower.contains("client address is not authorized")
+}
+
+/// The `error.message` of an Azure error body
+fn azure_error_message(body: &str) -> Option<String> {
+    let json: serde_json::Value = serde_json::from_str(body).ok()?;
+    json.get("error")?
+        .get("message")?
+        .as_str()
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_network_error() {
+        let dns = "error sending request: client error (Connect): dns error: \
+                   failed to lookup address information: Name or service not known";
+        assert_eq!(classify_network_error(false, dns).0, ConnectivityCategory::Dns);
+
+        let refused = "error sending request: client error (Connect): tcp connect error: \
+                       Connection refused (os error 111)";
+        assert_eq!(
+            classify_network_error(false, refused).0,
+            ConnectivityCategory::Unreachable
+        );
+        assert_eq!(
+            classify_network_error(true, "operation timed out").0,
+            ConnectivityCategory::Unreachable
+        );
+
+        let tls = "error sending request: client error (Connect): invalid peer certificate: \
+                   UnknownIssuer";
+        assert_eq!(classify_network_error(false, tls).0, ConnectivityCategory::Tls);
+    }
+
+    #[test]
+    fn test_classify_status() {
+        assert_eq!(classify_status(200, "{}").0, ConnectivityCategory::Reachable);
+
+        let firewall = r#"{"error": {
+            "code": "Forbidden",
+            "message": "Client address is not authorized and caller is not a trusted service.",
+            "innererror": {"code": "ForbiddenByFirewall"}
+        }}"#;
+        let (category, message) = classify_status(403, firewall);
+        assert_eq!(category, ConnectivityCategory::Firewall);
+        assert!(message.contains("Client address is not authorized"));
+
+        let denied = r#"{"error": {
+            "code": "Forbidden",
+            "message": "Caller is not authorized to perform action on resource.",
+            "innererror": {"code": "ForbiddenByRbac"}
+        }}"#;
+        assert_eq!(classify_status(403, denied).0, ConnectivityCategory::Auth);
+        assert_eq!(classify_status(401, "").0, ConnectivityCategory::Auth);
+        assert_eq!(classify_status(500, "").0, ConnectivityCategory::Unknown);
+    }
+}
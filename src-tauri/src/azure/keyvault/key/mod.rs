@@ -0,0 +1,7 @@
+//! Key Vault Keys module
+//!
+//! This module provides read-only access to Key Vault keys. Only public key
+//! material is ever returned.
+
+pub mod service;
+pub mod types;
@@ -0,0 +1,107 @@
+//! Key service - read-only access to Key Vault keys
+
+use anyhow::{Context, Result};
+use log::{error, info};
+
+use crate::azure::auth::token::get_token_for_scope;
+use crate::azure::http::{fetch_all_paginated, AzureHttpClient};
+use crate::config::{keyvault_scope, urls};
+
+use super::types::{Key, KeyBundle};
+
+/// Fetch all keys from a Key Vault.
+///
+/// # Arguments
+///
+/// * `keyvault_uri` - The Key Vault URI
+///
+/// # Returns
+///
+/// A vector of Key metadata, without key material.
+///
+/// # Errors
+///
+/// This function will return an error if:
+/// - The user is not authenticated
+/// - Listing keys is denied
+pub async fn get_keys(keyvault_uri: &str) -> Result<Vec<Key>, String> {
+    get_keys_internal(keyvault_uri).await.map_err(|e| {
+        error!("Failed to get keys: {}", e);
+        e.to_string()
+    })
+}
+
+async fn get_keys_internal(keyvault_uri: &str) -> Result<Vec<Key>> {
+    info!("Fetching keys");
+
+    let url = urls::keys(keyvault_uri);
+    let token = get_token_for_scope(keyvault_scope())
+        .await
+        .map_err(|e| anyhow::anyhow!(e))
+        .context("Failed to retrieve Key Vault token")?;
+
+    let client = AzureHttpClient::with_scoped_token(&token, keyvault_scope())
+        .context("Failed to create HTTP client with token")?;
+
+    let keys = fetch_all_paginated::<Key>(&url, &client)
+        .await
+        .with_context(|| format!("Failed to fetch keys from {}", keyvault_uri))?;
+
+    info!("Successfully fetched {} keys", keys.len());
+    Ok(keys)
+}
+
+/// Fetch a key with its public key material.
+///
+/// Private key material is dropped while parsing the response, so it is never
+/// returned, even for exportable keys.
+///
+/// # Arguments
+///
+/// * `keyvault_uri` - The Key Vault URI
+/// * `key_name` - The name of the key
+/// * `key_version` - Optional specific version (defaults to latest)
+///
+/// # Errors
+///
+/// This function will return an error if:
+/// - The user is not authenticated
+/// - The key doesn't exist
+/// - Access is denied
+pub async fn get_key(
+    keyvault_uri: &str,
+    key_name: &str,
+    key_version: Option<&str>,
+) -> Result<KeyBundle, String> {
+    get_key_internal(keyvault_uri, key_name, key_version)
+        .await
+        .map_err(|e| {
+            error!("Failed to get key: {}", e);
+            e.to_string()
+        })
+}
+
+async fn get_key_internal(
+    keyvault_uri: &str,
+    key_name: &str,
+    key_version: Option<&str>,
+) -> Result<KeyBundle> {
+    info!("Fetching key");
+
+    let url = urls::key(keyvault_uri, key_name, key_version);
+    let token = get_token_for_scope(keyvault_scope())
+        .await
+        .map_err(|e| anyhow::anyhow!(e))
+        .context("Failed to retrieve Key Vault token")?;
+
+    let client = AzureHttpClient::with_scoped_token(&token, keyvault_scope())
+        .context("Failed to create HTTP client with token")?;
+
+    let key: KeyBundle = client
+        .get(&url)
+        .await
+        .with_context(|| format!("Failed to fetch key '{}' from {}", key_name, keyvault_uri))?;
+
+    info!("Key fetched successfully");
+    Ok(key)
+}
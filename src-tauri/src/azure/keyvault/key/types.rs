@@ -0,0 +1,198 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::azure::keyvault::secret::types::RecoveryLevel;
+
+/// A key as returned by the list keys API, without key material
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Key {
+    pub kid: String,
+    pub attributes: KeyAttributes,
+    /// Operations the key may be used for; the list API leaves them out
+    #[serde(default)]
+    pub key_ops: Vec<String>,
+    #[serde(default)]
+    pub tags: HashMap<String, String>,
+    /// Set for keys backing a certificate, which are managed by Key Vault
+    #[serde(default)]
+    pub managed: bool,
+}
+
+impl Key {
+    /// The key name, i.e. the segment after `/keys/` in its ID
+    pub fn name(&self) -> &str {
+        match self.kid.split_once("/keys/") {
+            Some((_, rest)) => rest.split('/').next().unwrap_or(rest),
+            None => self.kid.rsplit('/').next().unwrap_or(&self.kid),
+        }
+    }
+}
+
+/// Key attributes as returned by Key Vault
+///
+/// Timestamps are unix seconds. Fields Azure leaves out when unset have a default.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyAttributes {
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// Creation time (unix seconds)
+    pub created: u64,
+    /// Last update time (unix seconds)
+    pub updated: u64,
+    #[serde(default)]
+    pub recovery_level: RecoveryLevel,
+    #[serde(default)]
+    pub recoverable_days: u8,
+    /// Expiry time (unix seconds), if set
+    #[serde(default)]
+    pub exp: Option<u64>,
+    /// Not-before time (unix seconds), if set
+    #[serde(default)]
+    pub nbf: Option<u64>,
+    /// Whether the private key can be exported by a release policy
+    #[serde(default)]
+    pub exportable: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// The public part of a JSON Web Key
+///
+/// Private fields (`d`, `p`, `q`, `dp`, `dq`, `qi`) and symmetric key bytes
+/// (`k`) have no field here, so they are dropped when the response is parsed
+/// and can never be passed on.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PublicJsonWebKey {
+    pub kid: String,
+    /// Key type, e.g. `RSA`, `RSA-HSM`, `EC` or `oct-HSM`
+    pub kty: String,
+    /// Azure spells this one `key_ops`, as in the JWK spec
+    #[serde(default, alias = "key_ops")]
+    pub key_ops: Vec<String>,
+    /// RSA modulus (base64url)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub n: Option<String>,
+    /// RSA public exponent (base64url)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub e: Option<String>,
+    /// Elliptic curve name, e.g. `P-256`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub crv: Option<String>,
+    /// EC public point x coordinate (base64url)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub x: Option<String>,
+    /// EC public point y coordinate (base64url)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub y: Option<String>,
+}
+
+/// A single key version with its public key material
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyBundle {
+    pub key: PublicJsonWebKey,
+    pub attributes: KeyAttributes,
+    #[serde(default)]
+    pub tags: HashMap<String, String>,
+    #[serde(default)]
+    pub managed: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_list_item_payload() {
+        // A list item as returned by GET {vault}/keys
+        let key: Key = serde_json::from_str(
+            r#"{
+                "kid": "https://vault.vault.azure.net/keys/signing-key",
+                "attributes": {
+                    "enabled": true,
+                    "created": 1700000000,
+                    "updated": 1700000100,
+                    "recoveryLevel": "Recoverable+Purgeable",
+                    "recoverableDays": 90,
+                    "exportable": false
+                },
+                "tags": { "env": "prod" }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(key.name(), "signing-key");
+        assert!(key.key_ops.is_empty());
+        assert!(!key.managed);
+        assert_eq!(key.attributes.recovery_level, RecoveryLevel::RecoverablePurgeable);
+    }
+
+    #[test]
+    fn test_key_bundle_drops_private_material() {
+        // A key released with its private half; none of it may survive parsing
+        let bundle: KeyBundle = serde_json::from_str(
+            r#"{
+                "key": {
+                    "kid": "https://vault.vault.azure.net/keys/signing-key/4387e9f3d6e1",
+                    "kty": "RSA",
+                    "key_ops": ["sign", "verify"],
+                    "n": "0vx7agoebGcQSuuPiLJXZpt",
+                    "e": "AQAB",
+                    "d": "X4cTteJY_gn4FYPsXB8rdXix",
+                    "p": "83i-7IvMGXoMXCskv73TKr8",
+                    "q": "3dfOR9cuYq-0S-mkFLzgItg",
+                    "dp": "G4sPXkc6Ya9y8oJW9_ILj4",
+                    "dq": "s9lAH9fggBsoFR8Oac2R_E",
+                    "qi": "GyM_p6JrXySiz1toFgKbWV4",
+                    "k": "c3ltbWV0cmljLWtleQ"
+                },
+                "attributes": {
+                    "enabled": true,
+                    "created": 1700000000,
+                    "updated": 1700000000,
+                    "recoveryLevel": "Recoverable",
+                    "exportable": true
+                }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(bundle.key.key_ops, vec!["sign", "verify"]);
+        assert_eq!(bundle.key.e.as_deref(), Some("AQAB"));
+        assert!(bundle.attributes.exportable);
+
+        let json = serde_json::to_value(&bundle).unwrap();
+        let key = json["key"].as_object().unwrap();
+        for private in ["d", "p", "q", "dp", "dq", "qi", "k"] {
+            assert!(!key.contains_key(private), "{} was serialized", private);
+        }
+        assert_eq!(key["keyOps"], serde_json::json!(["sign", "verify"]));
+    }
+
+    #[test]
+    fn test_ec_key_bundle() {
+        let bundle: KeyBundle = serde_json::from_str(
+            r#"{
+                "key": {
+                    "kid": "https://vault.vault.azure.net/keys/ec-key/1",
+                    "kty": "EC-HSM",
+                    "key_ops": ["sign"],
+                    "crv": "P-256",
+                    "x": "f83OJ3D2xF1Bg8vub9tLe1gHMzV76e8Tus9uPHvRVEU",
+                    "y": "x_FEzRu9m36HLN_tue659LNpXW6pCyStikYjKIWI5a0"
+                },
+                "attributes": { "created": 1700000000, "updated": 1700000000 }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(bundle.key.crv.as_deref(), Some("P-256"));
+        assert!(bundle.key.n.is_none());
+        assert!(bundle.attributes.enabled);
+    }
+}
@@ -1,8 +1,17 @@
 //! Azure Key Vault module
 //!
 //! This module provides functionality for working with Azure Key Vault,
-//! including vault management and secret operations.
+//! including vault management, secret operations and read-only key and certificate access.
 
+pub mod access_report;
+pub mod capabilities;
+pub mod certificate;
+pub mod clone;
+pub mod compliance;
+pub mod connectivity;
+pub mod key;
+pub mod restore;
 pub mod secret;
 pub mod service;
+pub mod tree;
 pub mod types;
\ No newline at end of file
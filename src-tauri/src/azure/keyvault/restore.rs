@@ -0,0 +1,279 @@
+//! Full vault restore - restore a whole-vault backup from a storage blob
+//!
+//! Full backups are a Managed HSM data-plane feature; standard vaults reject
+//! the request, and that error is passed through to the caller.
+//!
+//! The restore is a long-running operation: the initial POST returns a job,
+//! whose status is polled until it succeeds or fails.
+//!
+//! API Reference: https://learn.microsoft.com/en-us/rest/api/keyvault/keyvault/full-restore-operation
+
+use anyhow::{Context, Result};
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use crate::azure::auth::token::get_token_for_scope;
+use crate::azure::http::{AzureHttpClient, AzureHttpError};
+use crate::config::{
+    managed_hsm_scope, urls, MAX_RESTORE_POLL_ATTEMPTS, RESTORE_POLL_INTERVAL_SECONDS,
+};
+
+/// Error codes Azure returns when the backup's tenant differs from the target's
+const TENANT_MISMATCH_ERROR_CODES: &[&str] = &["TenantMismatch", "InvalidTenant"];
+
+/// Request body for starting a full restore
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RestoreRequest {
+    sas_token_parameters: SasTokenParameters,
+    folder_to_restore: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SasTokenParameters {
+    storage_resource_uri: String,
+    token: String,
+}
+
+/// Status of a full restore operation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreOperation {
+    pub status: String, // 'InProgress', 'Succeeded' or 'Failed'
+    pub status_details: Option<String>,
+    pub error: Option<RestoreOperationError>,
+    pub job_id: Option<String>,
+    pub start_time: Option<u64>,
+    pub end_time: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreOperationError {
+    pub code: Option<String>,
+    pub message: Option<String>,
+}
+
+/// Progress update emitted after each status check
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreProgress {
+    pub status: String,
+    pub status_details: Option<String>,
+    pub attempt: u32,
+}
+
+/// Restore a full vault backup from a storage blob and wait for it to finish.
+///
+/// # Arguments
+///
+/// * `keyvault_uri` - The URI of the vault to restore into
+/// * `sas_url` - SAS URL of the backup folder
+///   (`https://{account}.blob.core.windows.net/{container}/{folder}?{sas}`)
+/// * `on_progress` - Called after each status check
+///
+/// # Returns
+///
+/// The final state of the restore operation.
+///
+/// # Errors
+///
+/// This function will return an error if:
+/// - The SAS URL is malformed
+/// - The restore can't be started or fails (including a tenant mismatch)
+/// - The restore doesn't finish within the polling window
+pub async fn restore_vault_backup<F>(
+    keyvault_uri: &str,
+    sas_url: &str,
+    on_progress: F,
+) -> Result<RestoreOperation, String>
+where
+    F: Fn(RestoreProgress),
+{
+    restore_vault_backup_internal(keyvault_uri, sas_url, on_progress)
+        .await
+        .map_err(|e| {
+            error!("Failed to restore vault backup: {}", e);
+            if let Some(root_cause) = e.root_cause().downcast_ref::<AzureHttpError>() {
+                match root_cause {
+                    AzureHttpError::ApiError { code, message, .. } => {
+                        explain_restore_error(code.as_deref(), message)
+                    }
+                    other => other.to_string(),
+                }
+            } else {
+                e.to_string()
+            }
+        })
+}
+
+async fn restore_vault_backup_internal<F>(
+    keyvault_uri: &str,
+    sas_url: &str,
+    on_progress: F,
+) -> Result<RestoreOperation>
+where
+    F: Fn(RestoreProgress),
+{
+    info!("Starting full vault restore");
+
+    let body = parse_sas_url(sas_url)?;
+
+    // Full backup and restore are Managed HSM operations, which need an HSM token
+    let token = get_token_for_scope(managed_hsm_scope())
+        .await
+        .map_err(|e| anyhow::anyhow!(e))
+        .context("Failed to retrieve Managed HSM token")?;
+
+    let client = AzureHttpClient::with_scoped_token(&token, managed_hsm_scope())
+        .context("Failed to create HTTP client with token")?;
+
+    let mut operation: RestoreOperation = client
+        .post(&urls::vault_restore(keyvault_uri), &body)
+        .await
+        .context("Failed to start vault restore")?;
+
+    let job_id = operation
+        .job_id
+        .clone()
+        .ok_or_else(|| anyhow::anyhow!("Restore started but no job ID was returned"))?;
+    info!("Vault restore started, job ID: {}", job_id);
+
+    let status_url = urls::vault_restore_status(keyvault_uri, &job_id);
+
+    for attempt in 1..=MAX_RESTORE_POLL_ATTEMPTS {
+        on_progress(RestoreProgress {
+            status: operation.status.clone(),
+            status_details: operation.status_details.clone(),
+            attempt,
+        });
+
+        match operation.status.as_str() {
+            "Succeeded" => {
+                info!("Vault restore completed");
+                return Ok(operation);
+            }
+            "Failed" => {
+                let code = operation.error.as_ref().and_then(|e| e.code.as_deref());
+                let message = operation
+                    .error
+                    .as_ref()
+                    .and_then(|e| e.message.clone())
+                    .or(operation.status_details.clone())
+                    .unwrap_or_else(|| "Restore failed".to_string());
+                return Err(anyhow::anyhow!(explain_restore_error(code, &message)));
+            }
+            _ => {}
+        }
+
+        tokio::time::sleep(Duration::from_secs(RESTORE_POLL_INTERVAL_SECONDS)).await;
+
+        operation = client
+            .get(&status_url)
+            .await
+            .context("Failed to check vault restore status")?;
+    }
+
+    Err(anyhow::anyhow!(
+        "Vault restore did not finish within {} seconds; it may still be running in Azure",
+        RESTORE_POLL_INTERVAL_SECONDS * MAX_RESTORE_POLL_ATTEMPTS as u64
+    ))
+}
+
+/// Split a backup folder SAS URL into the storage container URI, folder name and SAS token
+fn parse_sas_url(sas_url: &str) -> Result<RestoreRequest> {
+    let (location, token) = sas_url
+        .split_once('?')
+        .filter(|(_, token)| !token.is_empty())
+        .ok_or_else(|| anyhow::anyhow!("SAS URL is missing its SAS token"))?;
+
+    let path = location
+        .strip_prefix("https://")
+        .ok_or_else(|| anyhow::anyhow!("SAS URL must use https"))?;
+
+    let mut segments = path.trim_end_matches('/').splitn(3, '/');
+    let (Some(account), Some(container), Some(folder)) =
+        (segments.next(), segments.next(), segments.next())
+    else {
+        return Err(anyhow::anyhow!(
+            "SAS URL must point to a backup folder inside a container"
+        ));
+    };
+
+    Ok(RestoreRequest {
+        sas_token_parameters: SasTokenParameters {
+            storage_resource_uri: format!("https://{}/{}", account, container),
+            token: token.to_string(),
+        },
+        folder_to_restore: folder.to_string(),
+    })
+}
+
+/// Turn a restore failure into something actionable
+///
+/// Backups can only be restored into a vault in the same tenant as the one
+/// they were taken from, which Azure reports rather cryptically. The error
+/// code identifies that case; the message is passed through otherwise.
+fn explain_restore_error(code: Option<&str>, message: &str) -> String {
+    let tenant_mismatch = code.is_some_and(|code| {
+        TENANT_MISMATCH_ERROR_CODES
+            .iter()
+            .any(|known| known.eq_ignore_ascii_case(code))
+    });
+
+    if tenant_mismatch {
+        format!(
+            "The backup was created in a different Azure AD tenant than the target vault. \
+             Backups can only be restored into a vault in the same tenant. ({})",
+            message
+        )
+    } else {
+        message.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_sas_url() {
+        let request = parse_sas_url(
+            "https://account.blob.core.windows.net/backups/mhsm-vault-2024010112?sv=2022&sig=abc",
+        )
+        .unwrap();
+
+        assert_eq!(
+            request.sas_token_parameters.storage_resource_uri,
+            "https://account.blob.core.windows.net/backups"
+        );
+        assert_eq!(request.sas_token_parameters.token, "sv=2022&sig=abc");
+        assert_eq!(request.folder_to_restore, "mhsm-vault-2024010112");
+    }
+
+    #[test]
+    fn test_parse_sas_url_rejects_invalid() {
+        assert!(parse_sas_url("https://account.blob.core.windows.net/backups/folder").is_err());
+        assert!(parse_sas_url("https://account.blob.core.windows.net/backups?sig=abc").is_err());
+        assert!(
+            parse_sas_url("http://account.blob.core.windows.net/backups/folder?sig=abc").is_err()
+        );
+    }
+
+    #[test]
+    fn test_explain_restore_error_tenant_mismatch() {
+        let explained =
+            explain_restore_error(Some("TenantMismatch"), "Backup tenant does not match");
+        assert!(explained.starts_with("The backup was created in a different Azure AD tenant"));
+        assert_eq!(explain_restore_error(Some("Forbidden"), "Forbidden"), "Forbidden");
+    }
+
+    #[test]
+    fn test_explain_restore_error_ignores_tenant_in_message() {
+        let message = "Caller is not authorized to access tenant resources";
+        assert_eq!(explain_restore_error(Some("Forbidden"), message), message);
+        assert_eq!(explain_restore_error(None, message), message);
+    }
+}
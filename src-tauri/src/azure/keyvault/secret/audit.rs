@@ -0,0 +1,165 @@
+//! Secret value auditing - classify secret values for data-quality reports
+//!
+//! Only secret names and classifications are returned; values never leave
+//! this module.
+
+use super::service::{get_secret, get_secrets};
+use crate::cache::azure_cache;
+use crate::config::MAX_CONCURRENT_SECRET_FETCHES;
+use anyhow::Result;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use futures::stream::{self, StreamExt};
+use log::{error, info, warn};
+use serde::Serialize;
+
+/// Shortest value considered for base64 detection, so short words aren't misread as binary
+const MIN_BASE64_LENGTH: usize = 16;
+
+/// What a secret value looks like
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SecretValueClass {
+    /// Plain text
+    Utf8Ok,
+    /// A JSON object or array
+    ValidJson,
+    /// Empty or whitespace only
+    Empty,
+    /// Base64 that decodes to non-text bytes (certificates, keys, ...)
+    BinaryBase64,
+    /// The value could not be read
+    Unreadable,
+}
+
+/// Audit result for a single secret
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SecretValueAudit {
+    pub name: String,
+    pub classification: SecretValueClass,
+}
+
+/// Classify every secret value in a vault.
+///
+/// Values are fetched concurrently (bounded by `MAX_CONCURRENT_SECRET_FETCHES`)
+/// through the value cache.
+///
+/// # Arguments
+///
+/// * `keyvault_uri` - The Key Vault URI
+///
+/// # Returns
+///
+/// One entry per secret, sorted by name.
+pub async fn audit_secret_values(keyvault_uri: &str) -> Result<Vec<SecretValueAudit>, String> {
+    audit_secret_values_internal(keyvault_uri)
+        .await
+        .map_err(|e| {
+            error!("Failed to audit secret values: {}", e);
+            e.to_string()
+        })
+}
+
+async fn audit_secret_values_internal(keyvault_uri: &str) -> Result<Vec<SecretValueAudit>> {
+    info!("Auditing secret values");
+
+    let uri = keyvault_uri.to_string();
+    let secrets = azure_cache()
+        .get_secrets_list_or_load(keyvault_uri, || async move { get_secrets(&uri).await })
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+
+    let mut results: Vec<SecretValueAudit> = stream::iter(secrets)
+        .map(|secret| {
+            let vault_uri = keyvault_uri.to_string();
+            async move {
+                let name = secret.id.split('/').last().unwrap_or("").to_string();
+                let uri = vault_uri.clone();
+                let secret_name = name.clone();
+                let classification = match azure_cache()
+                    .get_secret_value_or_load(&vault_uri, &name, || async move {
+                        get_secret(&uri, &secret_name, None).await
+                    })
+                    .await
+                {
+                    Ok(bundle) => classify_secret_value(&bundle.value),
+                    Err(e) => {
+                        warn!("Failed to fetch value for secret '{}': {}", name, e);
+                        SecretValueClass::Unreadable
+                    }
+                };
+                SecretValueAudit {
+                    name,
+                    classification,
+                }
+            }
+        })
+        .buffer_unordered(MAX_CONCURRENT_SECRET_FETCHES)
+        .collect()
+        .await;
+
+    results.sort_by(|a, b| a.name.cmp(&b.name));
+
+    info!("Audited {} secret values", results.len());
+    Ok(results)
+}
+
+/// Classify a single secret value
+fn classify_secret_value(value: &str) -> SecretValueClass {
+    let trimmed = value.trim();
+
+    if trimmed.is_empty() {
+        return SecretValueClass::Empty;
+    }
+
+    if serde_json::from_str::<serde_json::Value>(trimmed)
+        .is_ok_and(|parsed| parsed.is_object() || parsed.is_array())
+    {
+        return SecretValueClass::ValidJson;
+    }
+
+    if trimmed.len() >= MIN_BASE64_LENGTH
+        && BASE64
+            .decode(trimmed)
+            .is_ok_and(|bytes| std::str::from_utf8(&bytes).is_err())
+    {
+        return SecretValueClass::BinaryBase64;
+    }
+
+    SecretValueClass::Utf8Ok
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_empty() {
+        assert_eq!(classify_secret_value(""), SecretValueClass::Empty);
+        assert_eq!(classify_secret_value("  \n"), SecretValueClass::Empty);
+    }
+
+    #[test]
+    fn test_classify_json() {
+        assert_eq!(classify_secret_value(r#"{"a":1}"#), SecretValueClass::ValidJson);
+        assert_eq!(classify_secret_value("[1,2]"), SecretValueClass::ValidJson);
+        assert_eq!(classify_secret_value("42"), SecretValueClass::Utf8Ok);
+    }
+
+    #[test]
+    fn test_classify_binary_base64() {
+        let encoded = BASE64.encode([
+            0xff, 0xfe, 0x00, 0x81, 0x90, 0xaa, 0xbb, 0xcc, 0xdd, 0xee, 0xf0, 0x01,
+        ]);
+        assert_eq!(classify_secret_value(&encoded), SecretValueClass::BinaryBase64);
+    }
+
+    #[test]
+    fn test_classify_text() {
+        assert_eq!(classify_secret_value("hunter2"), SecretValueClass::Utf8Ok);
+        // Base64 of plain text is still text
+        let encoded = BASE64.encode("a perfectly normal sentence");
+        assert_eq!(classify_secret_value(&encoded), SecretValueClass::Utf8Ok);
+    }
+}
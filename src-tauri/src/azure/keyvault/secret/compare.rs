@@ -0,0 +1,484 @@
+//! Secret comparison functionality - compare a local .env file against a Key Vault,
+//! or two versions of the same secret
+//!
+//! Values are never returned to the caller. Both sides are reduced to a
+//! SHA-256 fingerprint and only the fingerprints are compared and reported.
+
+use super::import::{parse_dotenv_format, ImportedSecret};
+use super::service::{get_secret, get_secret_versions, get_secrets};
+use super::types::{Secret, SecretBundle, SecretVersionDiff};
+use crate::cache::azure_cache;
+use crate::config::MAX_CONCURRENT_SECRET_FETCHES;
+use anyhow::Result;
+use futures::stream::{self, StreamExt};
+use log::{error, info, warn};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+
+/// Outcome of comparing a single secret name between a local file and a vault
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EnvComparisonStatus {
+    /// Present on both sides with the same value
+    Matches,
+    /// Present on both sides with a different value
+    Differs,
+    /// Only present in the local .env file
+    OnlyLocal,
+    /// Only present in the vault
+    OnlyVault,
+}
+
+/// Comparison result for a single secret name
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvComparisonEntry {
+    pub name: String,
+    pub status: EnvComparisonStatus,
+    pub local_fingerprint: Option<String>,
+    pub vault_fingerprint: Option<String>,
+}
+
+/// Result of comparing two versions of a secret
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SecretVersionComparison {
+    pub secret_name: String,
+    pub version_a: String,
+    pub version_b: String,
+    pub fingerprint_a: String,
+    pub fingerprint_b: String,
+    pub values_equal: bool,
+}
+
+/// Compute a short, non-reversible fingerprint of a secret value.
+///
+/// The first 16 hex characters of the SHA-256 digest are enough to tell
+/// values apart without exposing anything about the plaintext.
+pub(crate) fn value_fingerprint(value: &str) -> String {
+    let digest = format!("{:x}", Sha256::digest(value.as_bytes()));
+    digest[..16].to_string()
+}
+
+/// Compare the contents of a local .env file against the secrets in a Key Vault.
+///
+/// Env variable names are converted to secret names using the same rules as
+/// the dotenv importer, so the comparison lines up with what an import would write.
+///
+/// # Arguments
+///
+/// * `keyvault_uri` - The Key Vault URI
+/// * `env_content` - The raw .env file content
+///
+/// # Returns
+///
+/// One entry per secret name found on either side, sorted by name.
+pub async fn compare_env_with_vault(
+    keyvault_uri: &str,
+    env_content: &str,
+) -> Result<Vec<EnvComparisonEntry>, String> {
+    compare_env_with_vault_internal(keyvault_uri, env_content)
+        .await
+        .map_err(|e| {
+            error!("Failed to compare .env with vault: {}", e);
+            e.to_string()
+        })
+}
+
+async fn compare_env_with_vault_internal(
+    keyvault_uri: &str,
+    env_content: &str,
+) -> Result<Vec<EnvComparisonEntry>> {
+    info!("Comparing .env file against vault");
+
+    let local_secrets = parse_dotenv_format(env_content.trim())?;
+
+    let uri = keyvault_uri.to_string();
+    let secrets = azure_cache()
+        .get_secrets_list_or_load(keyvault_uri, || async move { get_secrets(&uri).await })
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+
+    // Fetch vault values concurrently and keep only their fingerprints
+    let vault_fingerprints: Vec<(String, Option<String>)> = stream::iter(secrets)
+        .map(|secret| {
+            let vault_uri = keyvault_uri.to_string();
+            async move {
+                let name = secret.id.split('/').last().unwrap_or("").to_string();
+                let uri = vault_uri.clone();
+                let secret_name = name.clone();
+                let fingerprint = match azure_cache()
+                    .get_secret_value_or_load(&vault_uri, &name, || async move {
+                        get_secret(&uri, &secret_name, None).await
+                    })
+                    .await
+                {
+                    Ok(bundle) => Some(value_fingerprint(&bundle.value)),
+                    Err(e) => {
+                        warn!("Failed to fetch value for secret '{}': {}", name, e);
+                        None
+                    }
+                };
+                (name, fingerprint)
+            }
+        })
+        .buffer_unordered(MAX_CONCURRENT_SECRET_FETCHES)
+        .collect()
+        .await;
+
+    let entries = compare_fingerprints(&local_secrets, vault_fingerprints);
+
+    info!("Compared {} secret names", entries.len());
+    Ok(entries)
+}
+
+/// Compare the values of two versions of a secret.
+///
+/// Both versions are fetched (and cached per version) and reduced to
+/// fingerprints; the plaintext values are never returned.
+///
+/// # Arguments
+///
+/// * `keyvault_uri` - The Key Vault URI
+/// * `secret_name` - The name of the secret
+/// * `version_a` - The first version ID
+/// * `version_b` - The second version ID
+///
+/// # Errors
+///
+/// This function will return an error if:
+/// - Either version doesn't exist
+/// - Either version is disabled, as Key Vault refuses to return its value
+/// - A value can't be fetched
+pub async fn compare_secret_versions(
+    keyvault_uri: &str,
+    secret_name: &str,
+    version_a: &str,
+    version_b: &str,
+) -> Result<SecretVersionComparison, String> {
+    info!("Comparing two versions of secret '{}'", secret_name);
+
+    let versions = get_secret_versions(keyvault_uri, secret_name).await?;
+    check_version_readable(&versions, secret_name, version_a)?;
+    check_version_readable(&versions, secret_name, version_b)?;
+
+    let fingerprint_a = version_fingerprint(keyvault_uri, secret_name, version_a).await?;
+    let fingerprint_b = version_fingerprint(keyvault_uri, secret_name, version_b).await?;
+    let values_equal = fingerprint_a == fingerprint_b;
+
+    info!(
+        "Versions of secret '{}' {}",
+        secret_name,
+        if values_equal { "match" } else { "differ" }
+    );
+    Ok(SecretVersionComparison {
+        secret_name: secret_name.to_string(),
+        version_a: version_a.to_string(),
+        version_b: version_b.to_string(),
+        fingerprint_a,
+        fingerprint_b,
+        values_equal,
+    })
+}
+
+/// Report what changed between two versions of a secret.
+///
+/// Both versions are checked and fetched the same way as in
+/// `compare_secret_versions`; values are compared, never returned.
+///
+/// # Arguments
+///
+/// * `keyvault_uri` - The Key Vault URI
+/// * `secret_name` - The name of the secret
+/// * `version_a` - The first version ID
+/// * `version_b` - The second version ID
+///
+/// # Errors
+///
+/// This function will return an error if either version doesn't exist, is
+/// disabled, or can't be fetched.
+pub async fn diff_secret_versions(
+    keyvault_uri: &str,
+    secret_name: &str,
+    version_a: &str,
+    version_b: &str,
+) -> Result<SecretVersionDiff, String> {
+    info!("Diffing two versions of secret '{}'", secret_name);
+
+    let versions = get_secret_versions(keyvault_uri, secret_name).await?;
+    check_version_readable(&versions, secret_name, version_a)?;
+    check_version_readable(&versions, secret_name, version_b)?;
+
+    let (a, b) = futures::try_join!(
+        fetch_version(keyvault_uri, secret_name, version_a),
+        fetch_version(keyvault_uri, secret_name, version_b)
+    )?;
+    let diff = diff_bundles(secret_name, version_a, version_b, &a, &b);
+
+    info!(
+        "Versions of secret '{}': value {}, {} attributes changed",
+        secret_name,
+        if diff.value_changed { "changed" } else { "unchanged" },
+        diff.attributes_changed.len()
+    );
+    Ok(diff)
+}
+
+/// Build the diff of two fetched versions
+fn diff_bundles(
+    secret_name: &str,
+    version_a: &str,
+    version_b: &str,
+    a: &SecretBundle,
+    b: &SecretBundle,
+) -> SecretVersionDiff {
+    let mut attributes_changed = Vec::new();
+    let mut check = |name: &str, changed: bool| {
+        if changed {
+            attributes_changed.push(name.to_string());
+        }
+    };
+    check("enabled", a.attributes.enabled != b.attributes.enabled);
+    check("exp", a.attributes.exp != b.attributes.exp);
+    check("nbf", a.attributes.nbf != b.attributes.nbf);
+    check("contentType", a.content_type != b.content_type);
+    check("tags", a.tags != b.tags);
+
+    SecretVersionDiff {
+        secret_name: secret_name.to_string(),
+        version_a: version_a.to_string(),
+        version_b: version_b.to_string(),
+        value_changed: a.value != b.value,
+        attributes_changed,
+        a_created: a.attributes.created,
+        b_created: b.attributes.created,
+    }
+}
+
+/// Ensure a version exists and is enabled before asking for its value.
+///
+/// Key Vault answers a read of a disabled version with a bare 403, which
+/// looks like a permissions problem, so this is checked up front.
+fn check_version_readable(
+    versions: &[Secret],
+    secret_name: &str,
+    version: &str,
+) -> Result<(), String> {
+    let found = versions
+        .iter()
+        .find(|v| v.id.rsplit('/').next() == Some(version));
+
+    match found {
+        None => Err(format!(
+            "Version '{}' of secret '{}' was not found",
+            version, secret_name
+        )),
+        Some(v) if !v.attributes.enabled => Err(format!(
+            "Version '{}' of secret '{}' is disabled; enable it to compare its value",
+            version, secret_name
+        )),
+        Some(_) => Ok(()),
+    }
+}
+
+/// Fetch a single secret version through the cache
+async fn fetch_version(
+    keyvault_uri: &str,
+    secret_name: &str,
+    version: &str,
+) -> Result<SecretBundle, String> {
+    let uri = keyvault_uri.to_string();
+    let name = secret_name.to_string();
+    let version_id = version.to_string();
+    azure_cache()
+        .get_secret_version_or_load(keyvault_uri, secret_name, version, || async move {
+            get_secret(&uri, &name, Some(&version_id)).await
+        })
+        .await
+}
+
+/// Fetch a single secret version through the cache and fingerprint its value
+async fn version_fingerprint(
+    keyvault_uri: &str,
+    secret_name: &str,
+    version: &str,
+) -> Result<String, String> {
+    let bundle = fetch_version(keyvault_uri, secret_name, version).await?;
+    Ok(value_fingerprint(&bundle.value))
+}
+
+/// Build comparison entries from parsed local secrets and vault fingerprints.
+///
+/// Names are matched case-insensitively, as Key Vault secret names are.
+/// A vault secret whose value could not be read is reported as `Differs`.
+fn compare_fingerprints(
+    local_secrets: &[ImportedSecret],
+    vault_fingerprints: Vec<(String, Option<String>)>,
+) -> Vec<EnvComparisonEntry> {
+    let mut local: BTreeMap<String, (String, String)> = BTreeMap::new();
+    for secret in local_secrets {
+        // Later lines win, mirroring how sequential writes would behave
+        local.insert(
+            secret.name.to_lowercase(),
+            (secret.name.clone(), value_fingerprint(&secret.value)),
+        );
+    }
+
+    let mut entries: BTreeMap<String, EnvComparisonEntry> = BTreeMap::new();
+
+    for (name, vault_fingerprint) in vault_fingerprints {
+        let key = name.to_lowercase();
+        let entry = match local.remove(&key) {
+            Some((_, local_fingerprint)) => {
+                let status = if vault_fingerprint.as_deref() == Some(local_fingerprint.as_str()) {
+                    EnvComparisonStatus::Matches
+                } else {
+                    EnvComparisonStatus::Differs
+                };
+                EnvComparisonEntry {
+                    name,
+                    status,
+                    local_fingerprint: Some(local_fingerprint),
+                    vault_fingerprint,
+                }
+            }
+            None => EnvComparisonEntry {
+                name,
+                status: EnvComparisonStatus::OnlyVault,
+                local_fingerprint: None,
+                vault_fingerprint,
+            },
+        };
+        entries.insert(key, entry);
+    }
+
+    for (key, (name, local_fingerprint)) in local {
+        entries.insert(
+            key,
+            EnvComparisonEntry {
+                name,
+                status: EnvComparisonStatus::OnlyLocal,
+                local_fingerprint: Some(local_fingerprint),
+                vault_fingerprint: None,
+            },
+        );
+    }
+
+    entries.into_values().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn local(name: &str, value: &str) -> ImportedSecret {
+        ImportedSecret {
+            name: name.to_string(),
+            value: value.to_string(),
+            binary: false,
+        }
+    }
+
+    fn version(id: &str, enabled: bool) -> Secret {
+        serde_json::from_value(serde_json::json!({
+            "id": format!("https://vault.vault.azure.net/secrets/db-password/{}", id),
+            "attributes": { "enabled": enabled, "created": 0, "updated": 0 }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_check_version_readable() {
+        let versions = vec![version("abc", true), version("def", false)];
+
+        assert!(check_version_readable(&versions, "db-password", "abc").is_ok());
+
+        let disabled = check_version_readable(&versions, "db-password", "def").unwrap_err();
+        assert!(disabled.contains("disabled"));
+
+        let missing = check_version_readable(&versions, "db-password", "xyz").unwrap_err();
+        assert!(missing.contains("not found"));
+    }
+
+    fn bundle(version: &str, value: &str, enabled: bool) -> SecretBundle {
+        serde_json::from_value(serde_json::json!({
+            "id": format!("https://vault.vault.azure.net/secrets/db-password/{}", version),
+            "value": value,
+            "attributes": {
+                "enabled": enabled,
+                "created": if version == "a" { 1_700_000_000 } else { 1_700_100_000 },
+                "updated": 1_700_100_000
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_diff_only_value_changed() {
+        let a = bundle("a", "hunter2", true);
+        let b = bundle("b", "hunter3", true);
+        let diff = diff_bundles("db-password", "a", "b", &a, &b);
+
+        assert!(diff.value_changed);
+        assert!(diff.attributes_changed.is_empty());
+        assert_eq!((diff.a_created, diff.b_created), (1_700_000_000, 1_700_100_000));
+
+        // Values are compared, never carried in the result
+        let json = serde_json::to_string(&diff).unwrap();
+        assert!(!json.contains("hunter"));
+    }
+
+    #[test]
+    fn test_diff_only_enabled_changed() {
+        let a = bundle("a", "hunter2", true);
+        let b = bundle("b", "hunter2", false);
+        let diff = diff_bundles("db-password", "a", "b", &a, &b);
+
+        assert!(!diff.value_changed);
+        assert_eq!(diff.attributes_changed, vec!["enabled"]);
+    }
+
+    #[test]
+    fn test_value_fingerprint_is_stable_and_opaque() {
+        let a = value_fingerprint("hunter2");
+        assert_eq!(a, value_fingerprint("hunter2"));
+        assert_ne!(a, value_fingerprint("hunter3"));
+        assert_eq!(a.len(), 16);
+        assert!(!a.contains("hunter2"));
+    }
+
+    #[test]
+    fn test_compare_fingerprints_statuses() {
+        let local_secrets = vec![
+            local("same", "v1"),
+            local("changed", "new"),
+            local("local-only", "x"),
+        ];
+        let vault = vec![
+            ("Same".to_string(), Some(value_fingerprint("v1"))),
+            ("changed".to_string(), Some(value_fingerprint("old"))),
+            ("vault-only".to_string(), Some(value_fingerprint("y"))),
+        ];
+
+        let entries = compare_fingerprints(&local_secrets, vault);
+        let status_of = |name: &str| {
+            entries
+                .iter()
+                .find(|e| e.name.eq_ignore_ascii_case(name))
+                .map(|e| e.status)
+        };
+
+        assert_eq!(entries.len(), 4);
+        assert_eq!(status_of("same"), Some(EnvComparisonStatus::Matches));
+        assert_eq!(status_of("changed"), Some(EnvComparisonStatus::Differs));
+        assert_eq!(status_of("local-only"), Some(EnvComparisonStatus::OnlyLocal));
+        assert_eq!(status_of("vault-only"), Some(EnvComparisonStatus::OnlyVault));
+    }
+
+    #[test]
+    fn test_compare_unreadable_vault_value_differs() {
+        let entries = compare_fingerprints(&[local("a", "1")], vec![("a".to_string(), None)]);
+        assert_eq!(entries[0].status, EnvComparisonStatus::Differs);
+    }
+}
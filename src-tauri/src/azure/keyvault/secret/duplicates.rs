@@ -0,0 +1,147 @@
+//! Duplicate value detection - find secrets in a vault that share a value
+//!
+//! Each value is reduced to a fingerprint as soon as it is fetched; only
+//! secret names are returned, never values or fingerprints.
+
+use super::compare::value_fingerprint;
+use super::service::{get_secret, get_secrets};
+use crate::cache::azure_cache;
+use crate::config::MAX_CONCURRENT_SECRET_FETCHES;
+use anyhow::Result;
+use futures::stream::{self, StreamExt};
+use log::{error, info, warn};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// A set of secrets that all hold the same value
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateValueGroup {
+    /// Names of the secrets sharing the value, sorted
+    pub secret_names: Vec<String>,
+}
+
+/// Find secrets in a vault that share the same value.
+///
+/// Values are fetched concurrently (bounded by `MAX_CONCURRENT_SECRET_FETCHES`)
+/// through the value cache. Secrets whose value can't be read, e.g. disabled
+/// ones, are skipped.
+///
+/// # Arguments
+///
+/// * `keyvault_uri` - The Key Vault URI
+///
+/// # Returns
+///
+/// Groups of two or more secret names, largest group first.
+pub async fn find_duplicate_values(keyvault_uri: &str) -> Result<Vec<DuplicateValueGroup>, String> {
+    find_duplicate_values_internal(keyvault_uri)
+        .await
+        .map_err(|e| {
+            error!("Failed to find duplicate secret values: {}", e);
+            e.to_string()
+        })
+}
+
+async fn find_duplicate_values_internal(keyvault_uri: &str) -> Result<Vec<DuplicateValueGroup>> {
+    info!("Searching for duplicate secret values");
+
+    let uri = keyvault_uri.to_string();
+    let secrets = azure_cache()
+        .get_secrets_list_or_load(keyvault_uri, || async move { get_secrets(&uri).await })
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+
+    let fingerprints: Vec<(String, String)> = stream::iter(secrets)
+        .map(|secret| {
+            let vault_uri = keyvault_uri.to_string();
+            async move {
+                let name = secret.name().to_string();
+                let uri = vault_uri.clone();
+                let secret_name = name.clone();
+                match azure_cache()
+                    .get_secret_value_or_load(&vault_uri, &name, || async move {
+                        get_secret(&uri, &secret_name, None).await
+                    })
+                    .await
+                {
+                    Ok(bundle) => Some((name, value_fingerprint(&bundle.value))),
+                    Err(e) => {
+                        warn!(
+                            "Skipping secret '{}' whose value can't be read: {}",
+                            name, e
+                        );
+                        None
+                    }
+                }
+            }
+        })
+        .buffer_unordered(MAX_CONCURRENT_SECRET_FETCHES)
+        .filter_map(|fingerprint| async move { fingerprint })
+        .collect()
+        .await;
+
+    let groups = group_duplicates(fingerprints);
+
+    info!("Found {} groups of duplicate secret values", groups.len());
+    Ok(groups)
+}
+
+/// Group secret names by fingerprint, keeping only groups with two or more names
+fn group_duplicates(fingerprints: Vec<(String, String)>) -> Vec<DuplicateValueGroup> {
+    let mut by_fingerprint: HashMap<String, Vec<String>> = HashMap::new();
+    for (name, fingerprint) in fingerprints {
+        by_fingerprint.entry(fingerprint).or_default().push(name);
+    }
+
+    let mut groups: Vec<DuplicateValueGroup> = by_fingerprint
+        .into_values()
+        .filter(|names| names.len() >= 2)
+        .map(|mut secret_names| {
+            secret_names.sort();
+            DuplicateValueGroup { secret_names }
+        })
+        .collect();
+
+    groups.sort_by(|a, b| {
+        b.secret_names
+            .len()
+            .cmp(&a.secret_names.len())
+            .then_with(|| a.secret_names.cmp(&b.secret_names))
+    });
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(name: &str, value: &str) -> (String, String) {
+        (name.to_string(), value_fingerprint(value))
+    }
+
+    #[test]
+    fn test_group_duplicates() {
+        let groups = group_duplicates(vec![
+            entry("db-password", "hunter2"),
+            entry("unique", "only-once"),
+            entry("api-key", "shared"),
+            entry("backup-password", "hunter2"),
+            entry("legacy-password", "hunter2"),
+            entry("other-key", "shared"),
+        ]);
+
+        assert_eq!(groups.len(), 2);
+        assert_eq!(
+            groups[0].secret_names,
+            vec!["backup-password", "db-password", "legacy-password"]
+        );
+        assert_eq!(groups[1].secret_names, vec!["api-key", "other-key"]);
+    }
+
+    #[test]
+    fn test_group_duplicates_without_duplicates() {
+        let groups = group_duplicates(vec![entry("a", "1"), entry("b", "2")]);
+        assert!(groups.is_empty());
+    }
+}
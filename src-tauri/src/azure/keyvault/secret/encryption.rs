@@ -0,0 +1,170 @@
+//! Passphrase encryption of exports - AES-256-GCM with an Argon2id-derived key
+
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Nonce};
+use anyhow::{Context, Result};
+use argon2::Argon2;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use rand::rngs::OsRng;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+
+/// Envelope format version; version 1 is Argon2id (default parameters) + AES-256-GCM
+const ENVELOPE_VERSION: u32 = 1;
+
+/// Marks a JSON document as an encrypted export
+const ENVELOPE_KIND: &str = "vaultraider-encrypted-export";
+
+const SALT_LENGTH: usize = 16;
+const NONCE_LENGTH: usize = 12;
+const KEY_LENGTH: usize = 32;
+
+/// Error returned when decryption fails, i.e. the passphrase is wrong or the
+/// file was modified
+pub const WRONG_PASSPHRASE_ERROR: &str =
+    "Wrong passphrase, or the encrypted export has been modified";
+
+/// Encrypted export as written to disk; binary fields are base64
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EncryptedEnvelope {
+    kind: String,
+    version: u32,
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+/// Derive the AES key from the passphrase
+fn derive_key(passphrase: &str, salt: &[u8]) -> Result<[u8; KEY_LENGTH]> {
+    let mut key = [0u8; KEY_LENGTH];
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| anyhow::anyhow!("Failed to derive key from passphrase: {}", e))?;
+    Ok(key)
+}
+
+/// Encrypt `plaintext` with a key derived from `passphrase`.
+///
+/// A fresh salt and nonce are drawn for every call.
+///
+/// # Returns
+///
+/// The pretty-printed JSON envelope.
+///
+/// # Errors
+///
+/// Returns an error if the passphrase is empty.
+pub fn encrypt_export(plaintext: &str, passphrase: &str) -> Result<String> {
+    if passphrase.is_empty() {
+        return Err(anyhow::anyhow!("A passphrase is required for an encrypted export"));
+    }
+
+    let mut salt = [0u8; SALT_LENGTH];
+    let mut nonce = [0u8; NONCE_LENGTH];
+    OsRng.fill_bytes(&mut salt);
+    OsRng.fill_bytes(&mut nonce);
+
+    let key = derive_key(passphrase, &salt)?;
+    let cipher = Aes256Gcm::new_from_slice(&key)
+        .map_err(|e| anyhow::anyhow!("Failed to create cipher: {}", e))?;
+    let ciphertext = cipher
+        .encrypt(Nonce::from_slice(&nonce), plaintext.as_bytes())
+        .map_err(|e| anyhow::anyhow!("Failed to encrypt export: {}", e))?;
+
+    let envelope = EncryptedEnvelope {
+        kind: ENVELOPE_KIND.to_string(),
+        version: ENVELOPE_VERSION,
+        salt: BASE64.encode(salt),
+        nonce: BASE64.encode(nonce),
+        ciphertext: BASE64.encode(ciphertext),
+    };
+    serde_json::to_string_pretty(&envelope).context("Failed to serialize encrypted export")
+}
+
+/// Decrypt an envelope written by `encrypt_export`.
+///
+/// # Errors
+///
+/// Returns `WRONG_PASSPHRASE_ERROR` if the passphrase doesn't match, and a
+/// different error if the content isn't a supported encrypted export.
+pub fn decrypt_export(content: &str, passphrase: &str) -> Result<String> {
+    let envelope: EncryptedEnvelope =
+        serde_json::from_str(content.trim()).context("Not an encrypted export")?;
+
+    if envelope.kind != ENVELOPE_KIND {
+        return Err(anyhow::anyhow!("Not an encrypted export"));
+    }
+    if envelope.version != ENVELOPE_VERSION {
+        return Err(anyhow::anyhow!(
+            "Unsupported encrypted export version {}",
+            envelope.version
+        ));
+    }
+
+    let salt = BASE64
+        .decode(&envelope.salt)
+        .context("Encrypted export has an invalid salt")?;
+    let nonce = BASE64
+        .decode(&envelope.nonce)
+        .context("Encrypted export has an invalid nonce")?;
+    let ciphertext = BASE64
+        .decode(&envelope.ciphertext)
+        .context("Encrypted export has invalid ciphertext")?;
+    if nonce.len() != NONCE_LENGTH {
+        return Err(anyhow::anyhow!("Encrypted export has an invalid nonce"));
+    }
+
+    let key = derive_key(passphrase, &salt)?;
+    let cipher = Aes256Gcm::new_from_slice(&key)
+        .map_err(|e| anyhow::anyhow!("Failed to create cipher: {}", e))?;
+    // The GCM tag check fails for a wrong key, so this is where a bad passphrase shows up
+    let plaintext = cipher
+        .decrypt(Nonce::from_slice(&nonce), ciphertext.as_slice())
+        .map_err(|_| anyhow::anyhow!(WRONG_PASSPHRASE_ERROR))?;
+
+    String::from_utf8(plaintext).context("Decrypted export is not valid UTF-8")
+}
+
+/// Check if content is an encrypted export envelope
+pub fn looks_like_encrypted_export(content: &str) -> bool {
+    serde_json::from_str::<EncryptedEnvelope>(content.trim())
+        .is_ok_and(|envelope| envelope.kind == ENVELOPE_KIND)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PLAINTEXT: &str = r#"{"secrets":[{"name":"db-password","value":"hunter2"}]}"#;
+
+    #[test]
+    fn test_round_trip() {
+        let encrypted = encrypt_export(PLAINTEXT, "correct horse").unwrap();
+
+        assert!(!encrypted.contains("hunter2"));
+        assert!(looks_like_encrypted_export(&encrypted));
+        assert_eq!(decrypt_export(&encrypted, "correct horse").unwrap(), PLAINTEXT);
+    }
+
+    #[test]
+    fn test_wrong_passphrase() {
+        let encrypted = encrypt_export(PLAINTEXT, "correct horse").unwrap();
+
+        let error = decrypt_export(&encrypted, "battery staple").unwrap_err();
+        assert_eq!(error.to_string(), WRONG_PASSPHRASE_ERROR);
+    }
+
+    #[test]
+    fn test_salt_and_nonce_are_fresh() {
+        let first = encrypt_export(PLAINTEXT, "correct horse").unwrap();
+        let second = encrypt_export(PLAINTEXT, "correct horse").unwrap();
+        assert_ne!(first, second);
+    }
+
+    #[test]
+    fn test_empty_passphrase_rejected() {
+        assert!(encrypt_export(PLAINTEXT, "").is_err());
+    }
+}
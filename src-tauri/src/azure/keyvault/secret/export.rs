@@ -1,15 +1,21 @@
 //! Secret export functionality - business logic for exporting secrets in various formats
 
+use super::encryption::encrypt_export;
 use super::service::{get_secret, get_secrets};
-use super::types::Secret;
-use crate::cache::AZURE_CACHE;
+use super::types::{Secret, SecretBundle};
+use crate::cache::azure_cache;
+use crate::config::MAX_CONCURRENT_SECRET_FETCHES;
 use anyhow::{Context, Result};
-use log::{error, info};
+use futures::stream::{self, StreamExt};
+use log::{error, info, warn};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
 
 /// Export format options
-#[derive(Debug, Clone, Deserialize)]
+///
+/// `Debug` is implemented by hand so the passphrase never ends up in a log.
+#[derive(Clone, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ExportOptions {
     pub format: String,
@@ -18,6 +24,87 @@ pub struct ExportOptions {
     pub include_created: bool,
     pub include_updated: bool,
     pub include_recovery_level: bool,
+    /// Include the full secret identifier (with version) in the JSON formats
+    #[serde(default)]
+    pub include_id: bool,
+    /// Include the secret's tags in the full JSON format
+    #[serde(default)]
+    pub include_tags: bool,
+    /// Passphrase for the `encrypted` format, which encrypts the full format
+    #[serde(default)]
+    pub passphrase: Option<String>,
+}
+
+impl fmt::Debug for ExportOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ExportOptions")
+            .field("format", &self.format)
+            .field("include_value", &self.include_value)
+            .field("include_enabled", &self.include_enabled)
+            .field("include_created", &self.include_created)
+            .field("include_updated", &self.include_updated)
+            .field("include_recovery_level", &self.include_recovery_level)
+            .field("include_id", &self.include_id)
+            .field("include_tags", &self.include_tags)
+            .field("passphrase", &self.passphrase.as_ref().map(|_| "<redacted>"))
+            .finish()
+    }
+}
+
+/// Progress of fetching secret values during an export
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportProgress {
+    pub completed: usize,
+    pub total: usize,
+}
+
+/// Exported content plus a summary of secrets whose values couldn't be read
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportResult {
+    pub content: String,
+    pub exported: usize,
+    /// Secrets exported with an empty value because fetching it failed
+    pub failed_secrets: Vec<String>,
+}
+
+/// Progress of a subscription export, reported after each vault
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubscriptionExportProgress {
+    pub completed: usize,
+    pub total: usize,
+    pub vault_name: String,
+}
+
+/// A vault left out of a subscription export
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkippedVault {
+    pub vault_name: String,
+    pub error: String,
+}
+
+/// Combined export of every vault in a subscription
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubscriptionExportResult {
+    /// JSON document with each vault's export keyed by vault name
+    pub content: String,
+    pub exported_vaults: usize,
+    pub skipped_vaults: Vec<SkippedVault>,
+    /// Secrets exported with an empty value, as `vault/secret`
+    pub failed_secrets: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SubscriptionExport {
+    subscription_id: String,
+    exported_at: String,
+    /// JSON formats are embedded as objects, dotenv and YAML as a string
+    vaults: BTreeMap<String, serde_json::Value>,
 }
 
 /// Exported secret data
@@ -26,6 +113,8 @@ pub struct ExportOptions {
 struct ExportedSecret {
     name: String,
     #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     value: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     attributes: Option<ExportedAttributes>,
@@ -42,6 +131,8 @@ struct ExportedAttributes {
     updated: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     recovery_level: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tags: Option<BTreeMap<String, String>>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -61,6 +152,8 @@ struct SimpleExport {
 #[derive(Debug, Clone, Serialize)]
 struct SimpleSecret {
     name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<String>,
     value: String,
 }
 
@@ -71,10 +164,12 @@ struct SimpleSecret {
 /// * `vault_name` - The name of the Key Vault
 /// * `vault_uri` - The Key Vault URI
 /// * `options` - Export options including format and fields to include
+/// * `on_progress` - Called each time a secret value has been fetched
 ///
 /// # Returns
 ///
-/// A formatted string containing the exported secrets.
+/// The formatted export, and the names of secrets whose values couldn't be
+/// fetched (these are exported with an empty value rather than failing the export).
 ///
 /// # Errors
 ///
@@ -83,12 +178,16 @@ struct SimpleSecret {
 /// - Access to the Key Vault is denied
 /// - The API request fails
 /// - The format is invalid
-pub async fn export_secrets(
+pub async fn export_secrets<F>(
     vault_name: &str,
     vault_uri: &str,
     options: ExportOptions,
-) -> Result<String, String> {
-    export_secrets_internal(vault_name, vault_uri, options)
+    on_progress: F,
+) -> Result<ExportResult, String>
+where
+    F: Fn(ExportProgress),
+{
+    export_secrets_internal(vault_name, vault_uri, options, on_progress)
         .await
         .map_err(|e| {
             error!("Failed to export secrets: {}", e);
@@ -96,58 +195,254 @@ pub async fn export_secrets(
         })
 }
 
-async fn export_secrets_internal(
+async fn export_secrets_internal<F>(
     vault_name: &str,
     vault_uri: &str,
     options: ExportOptions,
-) -> Result<String> {
+    on_progress: F,
+) -> Result<ExportResult>
+where
+    F: Fn(ExportProgress),
+{
     info!("Exporting secrets in '{}' format", options.format);
 
     // Get all secrets metadata from cache or load
     let uri = vault_uri.to_string();
-    let secrets = AZURE_CACHE
+    let secrets = azure_cache()
         .get_secrets_list_or_load(vault_uri, || async move { get_secrets(&uri).await })
         .await
         .map_err(|e| anyhow::anyhow!(e))?;
 
-    // Fetch secret values if needed
-    let mut secrets_with_values: Vec<(String, Option<String>, Secret)> = Vec::new();
+    // Values and versioned IDs both come from the current versions
+    let bundles = if options.include_value || options.include_id {
+        fetch_secret_bundles(vault_uri, &secrets, &on_progress).await
+    } else {
+        HashMap::new()
+    };
 
-    for secret in secrets {
-        let name = extract_secret_name(&secret.id);
-        let value = if options.include_value {
-            // Try to get from cache first, then load if not cached
-            let uri = vault_uri.to_string();
-            let secret_name = name.clone();
-            match AZURE_CACHE
-                .get_secret_value_or_load(vault_uri, &name, || async move {
-                    get_secret(&uri, &secret_name, None).await
-                })
-                .await
-            {
-                Ok(bundle) => Some(bundle.value),
-                Err(_) => Some(String::new()),
-            }
-        } else {
-            None
-        };
-        secrets_with_values.push((name, value, secret));
-    }
+    let (secrets_with_values, failed_secrets) =
+        combine_with_bundles(secrets, bundles, options.include_value);
 
     // Generate output based on format
     let output = match options.format.as_str() {
         "full" => export_full_format(vault_name, vault_uri, &secrets_with_values, &options)?,
-        "simple" => export_simple_format(&secrets_with_values)?,
+        "simple" => export_simple_format(&secrets_with_values, &options)?,
         "keyValue" => export_key_value_format(&secrets_with_values)?,
         "dotenv" => export_dotenv_format(&secrets_with_values),
+        "yaml" => export_yaml_format(vault_name, vault_uri, &secrets_with_values, &options)?,
+        "csv" => export_csv_format(&secrets_with_values, &options)?,
+        "encrypted" => {
+            export_encrypted_format(vault_name, vault_uri, &secrets_with_values, &options)?
+        }
         _ => return Err(anyhow::anyhow!("Unknown export format: {}", options.format)),
     };
 
     info!(
-        "Successfully exported {} secrets",
-        secrets_with_values.len()
+        "Successfully exported {} secrets ({} without values)",
+        secrets_with_values.len(),
+        failed_secrets.len()
     );
-    Ok(output)
+    Ok(ExportResult {
+        content: output,
+        exported: secrets_with_values.len(),
+        failed_secrets,
+    })
+}
+
+/// Pair each secret with its name and, if `include_value`, its value
+///
+/// Secrets whose bundle was fetched take the bundle's versioned ID. Returns the
+/// names of secrets exported without their value because the fetch failed.
+fn combine_with_bundles(
+    secrets: Vec<Secret>,
+    mut bundles: HashMap<usize, Result<SecretBundle, String>>,
+    include_value: bool,
+) -> (Vec<(String, Option<String>, Secret)>, Vec<String>) {
+    let mut failed_secrets = Vec::new();
+    let secrets_with_values = secrets
+        .into_iter()
+        .enumerate()
+        .map(|(index, mut secret)| {
+            let name = extract_secret_name(&secret.id);
+            let bundle = match bundles.remove(&index) {
+                Some(Ok(bundle)) => Some(bundle),
+                Some(Err(e)) => {
+                    warn!("Exporting '{}' without its value and version: {}", name, e);
+                    if include_value {
+                        failed_secrets.push(name.clone());
+                    }
+                    None
+                }
+                None => None,
+            };
+            // List IDs have no version, the bundle's ID does
+            if let Some(bundle) = &bundle {
+                secret.id = bundle.id.clone();
+            }
+            let value =
+                include_value.then(|| bundle.map(|bundle| bundle.value).unwrap_or_default());
+            (name, value, secret)
+        })
+        .collect();
+
+    (secrets_with_values, failed_secrets)
+}
+
+/// Fetch the current version of all secrets concurrently, keyed by their position in `secrets`
+///
+/// Bundles come from the cache when possible. A failed fetch is kept as an
+/// error so one forbidden secret doesn't abort the whole export.
+async fn fetch_secret_bundles<F>(
+    vault_uri: &str,
+    secrets: &[Secret],
+    on_progress: &F,
+) -> HashMap<usize, Result<SecretBundle, String>>
+where
+    F: Fn(ExportProgress),
+{
+    let total = secrets.len();
+    let mut completed = 0;
+
+    stream::iter(secrets.iter().enumerate())
+        .map(|(index, secret)| {
+            let name = extract_secret_name(&secret.id);
+            let uri = vault_uri.to_string();
+            async move {
+                let secret_name = name.clone();
+                let result = azure_cache()
+                    .get_secret_value_or_load(&uri, &name, || {
+                        let uri = uri.clone();
+                        async move { get_secret(&uri, &secret_name, None).await }
+                    })
+                    .await;
+                (index, result)
+            }
+        })
+        .buffer_unordered(MAX_CONCURRENT_SECRET_FETCHES)
+        .map(|entry| {
+            completed += 1;
+            on_progress(ExportProgress { completed, total });
+            entry
+        })
+        .collect()
+        .await
+}
+
+/// Export every vault in a subscription into a single JSON document.
+///
+/// Vaults are exported one at a time (each fetching its values concurrently)
+/// using the same options as a single-vault export. Vaults that can't be
+/// exported are skipped and reported instead of failing the whole export.
+///
+/// # Arguments
+///
+/// * `subscription_id` - The subscription whose vaults to export
+/// * `options` - Export options applied to every vault
+/// * `on_progress` - Called after each vault, whether it was exported or skipped
+///
+/// # Errors
+///
+/// This function will return an error if:
+/// - The format is invalid
+/// - The subscription's vaults can't be listed
+pub async fn export_subscription<F>(
+    subscription_id: &str,
+    options: ExportOptions,
+    on_progress: F,
+) -> Result<SubscriptionExportResult, String>
+where
+    F: Fn(SubscriptionExportProgress),
+{
+    export_subscription_internal(subscription_id, options, on_progress)
+        .await
+        .map_err(|e| {
+            error!("Failed to export subscription: {}", e);
+            e.to_string()
+        })
+}
+
+async fn export_subscription_internal<F>(
+    subscription_id: &str,
+    options: ExportOptions,
+    on_progress: F,
+) -> Result<SubscriptionExportResult>
+where
+    F: Fn(SubscriptionExportProgress),
+{
+    if !matches!(
+        options.format.as_str(),
+        "full" | "simple" | "keyValue" | "dotenv" | "yaml" | "csv"
+    ) {
+        return Err(anyhow::anyhow!("Unknown export format: {}", options.format));
+    }
+
+    let sub_id = subscription_id.to_string();
+    let vaults = azure_cache()
+        .get_keyvaults_or_load(subscription_id, || async move {
+            crate::azure::keyvault::service::get_keyvaults(&sub_id).await
+        })
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+
+    info!("Exporting {} vaults in subscription", vaults.len());
+
+    let total = vaults.len();
+    let mut exports = BTreeMap::new();
+    let mut skipped_vaults = Vec::new();
+    let mut failed_secrets = Vec::new();
+
+    for (index, vault) in vaults.into_iter().enumerate() {
+        let vault_uri = &vault.properties.vault_uri;
+        let result = export_secrets_internal(&vault.name, vault_uri, options.clone(), |_| {}).await;
+
+        match result {
+            Ok(export) => {
+                let content = serde_json::from_str(&export.content)
+                    .unwrap_or(serde_json::Value::String(export.content));
+                failed_secrets.extend(
+                    export
+                        .failed_secrets
+                        .into_iter()
+                        .map(|secret| format!("{}/{}", vault.name, secret)),
+                );
+                exports.insert(vault.name.clone(), content);
+            }
+            Err(e) => {
+                warn!("Skipping vault {} in subscription export: {}", vault.name, e);
+                skipped_vaults.push(SkippedVault {
+                    vault_name: vault.name.clone(),
+                    error: e.to_string(),
+                });
+            }
+        }
+
+        on_progress(SubscriptionExportProgress {
+            completed: index + 1,
+            total,
+            vault_name: vault.name,
+        });
+    }
+
+    let exported_vaults = exports.len();
+    let export = SubscriptionExport {
+        subscription_id: subscription_id.to_string(),
+        exported_at: chrono::Utc::now().to_rfc3339(),
+        vaults: exports,
+    };
+    let content =
+        serde_json::to_string_pretty(&export).context("Failed to serialize subscription export")?;
+
+    info!(
+        "Subscription export complete: {} vaults exported, {} skipped",
+        exported_vaults,
+        skipped_vaults.len()
+    );
+    Ok(SubscriptionExportResult {
+        content,
+        exported_vaults,
+        skipped_vaults,
+        failed_secrets,
+    })
 }
 
 /// Extract secret name from ID (last segment of the path)
@@ -162,6 +457,17 @@ fn export_full_format(
     secrets_with_values: &[(String, Option<String>, Secret)],
     options: &ExportOptions,
 ) -> Result<String> {
+    let export = build_full_export(vault_name, vault_uri, secrets_with_values, options);
+    serde_json::to_string_pretty(&export).context("Failed to serialize full export")
+}
+
+/// Build the full format document, shared by the JSON and YAML exports
+fn build_full_export(
+    vault_name: &str,
+    vault_uri: &str,
+    secrets_with_values: &[(String, Option<String>, Secret)],
+    options: &ExportOptions,
+) -> FullExport {
     let exported_secrets: Vec<ExportedSecret> = secrets_with_values
         .iter()
         .map(|(name, value, secret)| {
@@ -169,6 +475,7 @@ fn export_full_format(
                 || options.include_created
                 || options.include_updated
                 || options.include_recovery_level
+                || options.include_tags
             {
                 Some(ExportedAttributes {
                     enabled: if options.include_enabled {
@@ -187,10 +494,14 @@ fn export_full_format(
                         None
                     },
                     recovery_level: if options.include_recovery_level {
-                        Some(secret.attributes.recovery_level.clone())
+                        Some(secret.attributes.recovery_level.as_str().to_string())
                     } else {
                         None
                     },
+                    // Sorted, so repeated exports of the same vault diff cleanly
+                    tags: options
+                        .include_tags
+                        .then(|| secret.tags.clone().into_iter().collect()),
                 })
             } else {
                 None
@@ -198,30 +509,31 @@ fn export_full_format(
 
             ExportedSecret {
                 name: name.clone(),
+                id: options.include_id.then(|| secret.id.clone()),
                 value: value.clone(),
                 attributes: attrs,
             }
         })
         .collect();
 
-    let export = FullExport {
+    FullExport {
         vault_name: vault_name.to_string(),
         vault_uri: vault_uri.to_string(),
         exported_at: chrono::Utc::now().to_rfc3339(),
         secrets: exported_secrets,
-    };
-
-    serde_json::to_string_pretty(&export).context("Failed to serialize full export")
+    }
 }
 
 /// Export secrets in simple format (JSON with name and value)
 fn export_simple_format(
     secrets_with_values: &[(String, Option<String>, Secret)],
+    options: &ExportOptions,
 ) -> Result<String> {
     let simple_secrets: Vec<SimpleSecret> = secrets_with_values
         .iter()
-        .map(|(name, value, _)| SimpleSecret {
+        .map(|(name, value, secret)| SimpleSecret {
             name: name.clone(),
+            id: options.include_id.then(|| secret.id.clone()),
             value: value.clone().unwrap_or_default(),
         })
         .collect();
@@ -258,6 +570,134 @@ fn export_dotenv_format(secrets_with_values: &[(String, Option<String>, Secret)]
         .join("\n")
 }
 
+/// Export secrets in YAML format
+///
+/// A flat `name: value` mapping like the key-value format, or the full format's
+/// document when any metadata (ID, attributes or tags) is requested.
+fn export_yaml_format(
+    vault_name: &str,
+    vault_uri: &str,
+    secrets_with_values: &[(String, Option<String>, Secret)],
+    options: &ExportOptions,
+) -> Result<String> {
+    let with_metadata = options.include_id
+        || options.include_enabled
+        || options.include_created
+        || options.include_updated
+        || options.include_recovery_level
+        || options.include_tags;
+
+    if with_metadata {
+        let export = build_full_export(vault_name, vault_uri, secrets_with_values, options);
+        return serde_yaml::to_string(&export).context("Failed to serialize YAML export");
+    }
+
+    // Sorted, so repeated exports of the same vault diff cleanly
+    let kv: BTreeMap<String, String> = secrets_with_values
+        .iter()
+        .map(|(name, value, _)| (name.clone(), value.clone().unwrap_or_default()))
+        .collect();
+
+    serde_yaml::to_string(&kv).context("Failed to serialize YAML export")
+}
+
+/// First characters that make spreadsheet apps evaluate a CSV cell as a formula
+const CSV_FORMULA_TRIGGERS: &[char] = &['=', '+', '-', '@', '\t', '\r'];
+
+/// Keep a spreadsheet app from running a CSV cell as a formula
+///
+/// Cells starting with a formula trigger get a leading `'`, which spreadsheets
+/// hide. Cells that already start with `'` get one too, so the CSV importer can
+/// strip exactly one again (see `unguard_csv_cell`) and values round-trip.
+pub(crate) fn guard_csv_cell(cell: String) -> String {
+    if cell.starts_with(CSV_FORMULA_TRIGGERS) || cell.starts_with('\'') {
+        format!("'{}", cell)
+    } else {
+        cell
+    }
+}
+
+/// Undo `guard_csv_cell`
+pub(crate) fn unguard_csv_cell(cell: &str) -> &str {
+    match cell.strip_prefix('\'') {
+        Some(rest) if rest.starts_with(CSV_FORMULA_TRIGGERS) || rest.starts_with('\'') => rest,
+        _ => cell,
+    }
+}
+
+/// Export secrets in CSV format
+///
+/// A `name,value` header followed by one row per secret. The attribute options
+/// add columns; tags are written as a JSON object so they survive in one cell.
+/// Every cell passes through `guard_csv_cell`, so opening the file in a
+/// spreadsheet never evaluates a secret value as a formula.
+fn export_csv_format(
+    secrets_with_values: &[(String, Option<String>, Secret)],
+    options: &ExportOptions,
+) -> Result<String> {
+    let mut header = vec!["name", "value"];
+    let columns = [
+        (options.include_id, "id"),
+        (options.include_enabled, "enabled"),
+        (options.include_created, "created"),
+        (options.include_updated, "updated"),
+        (options.include_recovery_level, "recoveryLevel"),
+        (options.include_tags, "tags"),
+    ];
+    header.extend(columns.iter().filter(|(include, _)| *include).map(|(_, name)| *name));
+
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(&header).context("Failed to write CSV header")?;
+
+    for (name, value, secret) in secrets_with_values {
+        let mut row = vec![name.clone(), value.clone().unwrap_or_default()];
+        if options.include_id {
+            row.push(secret.id.clone());
+        }
+        if options.include_enabled {
+            row.push(secret.attributes.enabled.to_string());
+        }
+        if options.include_created {
+            row.push(format_timestamp(secret.attributes.created));
+        }
+        if options.include_updated {
+            row.push(format_timestamp(secret.attributes.updated));
+        }
+        if options.include_recovery_level {
+            row.push(secret.attributes.recovery_level.as_str().to_string());
+        }
+        if options.include_tags {
+            // Sorted, so repeated exports of the same vault diff cleanly
+            let tags: BTreeMap<&String, &String> = secret.tags.iter().collect();
+            row.push(serde_json::to_string(&tags).context("Failed to serialize tags")?);
+        }
+        let row: Vec<String> = row.into_iter().map(guard_csv_cell).collect();
+        writer
+            .write_record(&row)
+            .with_context(|| format!("Failed to write CSV row for '{}'", name))?;
+    }
+
+    let bytes = writer
+        .into_inner()
+        .map_err(|e| anyhow::anyhow!("Failed to finish CSV export: {}", e))?;
+    String::from_utf8(bytes).context("CSV export is not valid UTF-8")
+}
+
+/// Export secrets in full format, encrypted with the passphrase from the options
+fn export_encrypted_format(
+    vault_name: &str,
+    vault_uri: &str,
+    secrets_with_values: &[(String, Option<String>, Secret)],
+    options: &ExportOptions,
+) -> Result<String> {
+    let passphrase = options
+        .passphrase
+        .as_deref()
+        .ok_or_else(|| anyhow::anyhow!("A passphrase is required for an encrypted export"))?;
+    let full = export_full_format(vault_name, vault_uri, secrets_with_values, options)?;
+    encrypt_export(&full, passphrase)
+}
+
 /// Format a Unix timestamp as an RFC3339 string
 fn format_timestamp(timestamp: u64) -> String {
     use chrono::{DateTime, Utc};
@@ -265,3 +705,317 @@ fn format_timestamp(timestamp: u64) -> String {
         .map(|dt| dt.to_rfc3339())
         .unwrap_or_else(|| timestamp.to_string())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::super::import::{parse_encrypted_import, parse_import_file, NameTransform};
+    use super::super::types::fixtures;
+    use super::*;
+
+    const SECRET_ID: &str = "https://vault.vault.azure.net/secrets/db-password/0123456789abcdef";
+
+    fn secrets_with_values() -> Vec<(String, Option<String>, Secret)> {
+        let mut secret = fixtures::secret("db-password");
+        secret.id = SECRET_ID.to_string();
+        secret.tags.insert("owner".to_string(), "team-a".to_string());
+
+        vec![("db-password".to_string(), Some("hunter2".to_string()), secret)]
+    }
+
+    fn options(include_id: bool) -> ExportOptions {
+        ExportOptions {
+            format: "full".to_string(),
+            include_value: true,
+            include_enabled: false,
+            include_created: false,
+            include_updated: false,
+            include_recovery_level: false,
+            include_id,
+            include_tags: false,
+            passphrase: None,
+        }
+    }
+
+    #[test]
+    fn test_ids_only_exported_when_requested() {
+        let secrets = secrets_with_values();
+
+        for include_id in [false, true] {
+            let options = options(include_id);
+            let full = export_full_format("vault", "https://vault", &secrets, &options).unwrap();
+            let simple = export_simple_format(&secrets, &options).unwrap();
+
+            assert_eq!(full.contains(SECRET_ID), include_id);
+            assert_eq!(simple.contains(SECRET_ID), include_id);
+        }
+    }
+
+    #[test]
+    fn test_exported_ids_are_versioned() {
+        let secrets = vec![fixtures::secret("db-password"), fixtures::secret("api-key")];
+        let mut bundle = fixtures::secret_json(SECRET_ID, "Recoverable");
+        bundle["value"] = serde_json::json!("hunter2");
+        let bundles = HashMap::from([
+            (0, Ok(serde_json::from_value(bundle).unwrap())),
+            (1, Err("Forbidden".to_string())),
+        ]);
+
+        let (secrets_with_values, failed) = combine_with_bundles(secrets, bundles, true);
+
+        assert_eq!(secrets_with_values[0].1.as_deref(), Some("hunter2"));
+        assert_eq!(secrets_with_values[0].2.id, SECRET_ID);
+        assert_eq!(secrets_with_values[1].1.as_deref(), Some(""));
+        assert_eq!(secrets_with_values[1].2.id, fixtures::secret_id("api-key", None));
+        assert_eq!(failed, vec!["api-key".to_string()]);
+    }
+
+    #[test]
+    fn test_exported_ids_ignored_on_import() {
+        let secrets = secrets_with_values();
+
+        let full = export_full_format("vault", "https://vault", &secrets, &options(true)).unwrap();
+        let simple = export_simple_format(&secrets, &options(true)).unwrap();
+
+        for content in [full, simple] {
+            let imported = parse_import_file(&content, None, None, NameTransform::AsIs).unwrap();
+            assert_eq!(imported.len(), 1);
+            assert_eq!(imported[0].name, "db-password");
+            assert_eq!(imported[0].value, "hunter2");
+        }
+    }
+
+    #[test]
+    fn test_tags_only_exported_when_requested() {
+        let secrets = secrets_with_values();
+
+        let without =
+            export_full_format("vault", "https://vault", &secrets, &options(false)).unwrap();
+        assert!(!without.contains("team-a"));
+
+        let with_tags = ExportOptions {
+            include_tags: true,
+            ..options(false)
+        };
+        let full = export_full_format("vault", "https://vault", &secrets, &with_tags).unwrap();
+        let json: serde_json::Value = serde_json::from_str(&full).unwrap();
+        assert_eq!(json["secrets"][0]["attributes"]["tags"]["owner"], "team-a");
+    }
+
+    fn yaml_round_trip_secrets() -> Vec<(String, Option<String>, Secret)> {
+        let (_, _, secret) = secrets_with_values().remove(0);
+        [
+            ("db-password", "hunter2"),
+            ("connection-string", "Server=db;Password=p@ss: word;"),
+            ("port", "5432"),
+            ("enabled-flag", "true"),
+            ("multi-line", "line one\nline two"),
+        ]
+        .into_iter()
+        .map(|(name, value)| (name.to_string(), Some(value.to_string()), secret.clone()))
+        .collect()
+    }
+
+    fn imported_set(content: &str, format: Option<&str>) -> BTreeMap<String, String> {
+        parse_import_file(content, format, None, NameTransform::AsIs)
+            .unwrap()
+            .into_iter()
+            .map(|secret| (secret.name, secret.value))
+            .collect()
+    }
+
+    #[test]
+    fn test_yaml_round_trip() {
+        let secrets = yaml_round_trip_secrets();
+        let expected: BTreeMap<String, String> = secrets
+            .iter()
+            .map(|(name, value, _)| (name.clone(), value.clone().unwrap()))
+            .collect();
+
+        let flat = ExportOptions {
+            format: "yaml".to_string(),
+            ..options(false)
+        };
+        let with_metadata = ExportOptions {
+            include_enabled: true,
+            include_tags: true,
+            ..flat.clone()
+        };
+
+        for options in [flat, with_metadata] {
+            let yaml = export_yaml_format("vault", "https://vault", &secrets, &options).unwrap();
+            assert_eq!(imported_set(&yaml, Some("yaml")), expected);
+            assert_eq!(imported_set(&yaml, None), expected);
+        }
+    }
+
+    #[test]
+    fn test_yaml_export_shape() {
+        let secrets = secrets_with_values();
+
+        let flat = export_yaml_format("vault", "https://vault", &secrets, &options(false)).unwrap();
+        assert_eq!(flat.trim(), "db-password: hunter2");
+
+        let full = export_yaml_format("vault", "https://vault", &secrets, &options(true)).unwrap();
+        let yaml: serde_yaml::Value = serde_yaml::from_str(&full).unwrap();
+        assert_eq!(yaml["vaultName"], "vault");
+        assert_eq!(yaml["secrets"][0]["id"], SECRET_ID);
+    }
+
+    #[test]
+    fn test_debug_redacts_passphrase() {
+        let options = ExportOptions {
+            passphrase: Some("correct horse".to_string()),
+            ..options(false)
+        };
+
+        let debug = format!("{:?}", options);
+        assert!(!debug.contains("correct horse"), "{}", debug);
+        assert!(debug.contains("<redacted>"));
+    }
+
+    #[test]
+    fn test_encrypted_round_trip() {
+        let secrets = secrets_with_values();
+        let options = ExportOptions {
+            format: "encrypted".to_string(),
+            passphrase: Some("correct horse".to_string()),
+            ..options(false)
+        };
+
+        let encrypted =
+            export_encrypted_format("vault", "https://vault", &secrets, &options).unwrap();
+        assert!(!encrypted.contains("hunter2"));
+
+        let imported = parse_encrypted_import(
+            &encrypted,
+            "correct horse",
+            None,
+            NameTransform::AsIs,
+        )
+        .unwrap();
+        assert_eq!(imported.len(), 1);
+        assert_eq!(imported[0].name, "db-password");
+        assert_eq!(imported[0].value, "hunter2");
+
+        // Names go through the same prefix, transform and validation as other formats
+        let prefixed = parse_encrypted_import(
+            &encrypted,
+            "correct horse",
+            Some("app_"),
+            NameTransform::Kebab,
+        )
+        .unwrap();
+        assert_eq!(prefixed[0].name, "app-db-password");
+
+        let error = parse_encrypted_import(&encrypted, "wrong", None, NameTransform::AsIs)
+            .unwrap_err();
+        assert_eq!(error, super::super::encryption::WRONG_PASSPHRASE_ERROR);
+
+        // Without the passphrase, import explains why it can't read the file
+        let error = parse_import_file(&encrypted, None, None, NameTransform::AsIs).unwrap_err();
+        assert!(error.contains("encrypted"), "{}", error);
+    }
+
+    #[test]
+    fn test_csv_round_trip() {
+        let mut secrets = yaml_round_trip_secrets();
+        let (_, _, secret) = secrets[0].clone();
+        secrets.push((
+            "quoted".to_string(),
+            Some(r#"a "quoted", comma-separated value"#.to_string()),
+            secret,
+        ));
+        let expected: BTreeMap<String, String> = secrets
+            .iter()
+            .map(|(name, value, _)| (name.clone(), value.clone().unwrap()))
+            .collect();
+
+        let plain = ExportOptions {
+            format: "csv".to_string(),
+            ..options(false)
+        };
+        let with_columns = ExportOptions {
+            include_id: true,
+            include_enabled: true,
+            include_tags: true,
+            ..plain.clone()
+        };
+
+        for options in [plain, with_columns] {
+            let csv = export_csv_format(&secrets, &options).unwrap();
+            assert_eq!(imported_set(&csv, Some("csv")), expected);
+            assert_eq!(imported_set(&csv, None), expected);
+        }
+    }
+
+    #[test]
+    fn test_csv_round_trip_guarded_name() {
+        let (_, _, secret) = secrets_with_values().remove(0);
+        let secrets = vec![("-db-pass".to_string(), Some("hunter2".to_string()), secret)];
+
+        let csv = export_csv_format(&secrets, &options(false)).unwrap();
+        assert!(csv.contains("'-db-pass,hunter2"), "{}", csv);
+
+        let imported = imported_set(&csv, Some("csv"));
+        assert_eq!(imported.get("-db-pass").map(String::as_str), Some("hunter2"));
+        assert_eq!(imported.len(), 1);
+    }
+
+    #[test]
+    fn test_csv_export_shape() {
+        let secrets = secrets_with_values();
+        let options = ExportOptions {
+            include_enabled: true,
+            include_tags: true,
+            ..options(false)
+        };
+
+        let csv = export_csv_format(&secrets, &options).unwrap();
+        let mut lines = csv.lines();
+        assert_eq!(lines.next(), Some("name,value,enabled,tags"));
+        assert_eq!(lines.next(), Some(r#"db-password,hunter2,true,"{""owner"":""team-a""}""#));
+    }
+
+    #[test]
+    fn test_csv_export_guards_formulas() {
+        let (_, _, secret) = secrets_with_values()[0].clone();
+        let values = [
+            "=HYPERLINK(\"x\")",
+            "+1",
+            "-1",
+            "@SUM(A1)",
+            "'=quoted",
+            "'plain",
+            "ok",
+        ];
+        let secrets: Vec<_> = values
+            .iter()
+            .enumerate()
+            .map(|(i, value)| (format!("s{}", i), Some(value.to_string()), secret.clone()))
+            .collect();
+
+        let csv = export_csv_format(&secrets, &options(false)).unwrap();
+        let cells: Vec<&str> = csv
+            .lines()
+            .skip(1)
+            .map(|line| line.split_once(',').unwrap().1)
+            .collect();
+        assert_eq!(
+            cells,
+            vec![
+                r#""'=HYPERLINK(""x"")""#,
+                "'+1",
+                "'-1",
+                "'@SUM(A1)",
+                "''=quoted",
+                "''plain",
+                "ok"
+            ]
+        );
+
+        let imported = imported_set(&csv, Some("csv"));
+        for (i, value) in values.iter().enumerate() {
+            assert_eq!(imported[&format!("s{}", i)], *value);
+        }
+    }
+}
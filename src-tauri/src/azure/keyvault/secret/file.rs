@@ -0,0 +1,308 @@
+//! Save secret values to disk and load them from it - e.g. certificates stored as base64
+
+use super::service::{get_secret, update_secret};
+use super::types::SecretBundle;
+use crate::cache::azure_cache;
+use anyhow::{Context, Result};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use log::{error, info};
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::Path;
+
+/// Largest secret value Key Vault accepts, in bytes
+const MAX_SECRET_VALUE_BYTES: u64 = 25 * 1024;
+
+/// Fetch a secret and write its value to a file.
+///
+/// # Arguments
+///
+/// * `keyvault_uri` - The Key Vault URI
+/// * `secret_name` - The name of the secret
+/// * `file_path` - Where to write the value
+/// * `decode_base64` - Write the base64-decoded bytes instead of the raw value
+/// * `overwrite` - Replace the file if it already exists
+///
+/// # Returns
+///
+/// The number of bytes written.
+///
+/// # Errors
+///
+/// This function will return an error if:
+/// - The secret can't be fetched
+/// - The value isn't valid base64 when `decode_base64` is set
+/// - The file exists and `overwrite` isn't set
+/// - The file can't be written (missing directory, permissions, ...)
+pub async fn save_secret_to_file(
+    keyvault_uri: &str,
+    secret_name: &str,
+    file_path: &str,
+    decode_base64: bool,
+    overwrite: bool,
+) -> Result<usize, String> {
+    let uri = keyvault_uri.to_string();
+    let name = secret_name.to_string();
+    let bundle = azure_cache()
+        .get_secret_value_or_load(keyvault_uri, secret_name, || async move {
+            get_secret(&uri, &name, None).await
+        })
+        .await?;
+
+    let bytes = secret_bytes(&bundle.value, decode_base64).map_err(|e| e.to_string())?;
+
+    write_new_file(Path::new(file_path), &bytes, overwrite).map_err(|e| {
+        error!("Failed to save secret to file: {}", e);
+        e.to_string()
+    })?;
+
+    info!("Saved secret to file ({} bytes)", bytes.len());
+    Ok(bytes.len())
+}
+
+/// The bytes to write for a secret value, base64-decoding it if requested
+///
+/// Whitespace is ignored when decoding, so wrapped base64 (as in PEM bodies)
+/// is accepted.
+fn secret_bytes(value: &str, decode_base64: bool) -> Result<Vec<u8>> {
+    if !decode_base64 {
+        return Ok(value.as_bytes().to_vec());
+    }
+
+    let compact: String = value.chars().filter(|c| !c.is_whitespace()).collect();
+    BASE64
+        .decode(compact)
+        .context("Secret value is not valid base64")
+}
+
+/// Write `bytes` to `path`, refusing to replace an existing file unless `overwrite` is set
+///
+/// On unix the file is only readable by the user, as it holds a secret value.
+fn write_new_file(path: &Path, bytes: &[u8], overwrite: bool) -> Result<()> {
+    let mut options = OpenOptions::new();
+    options.write(true);
+    if overwrite {
+        options.create(true).truncate(true);
+    } else {
+        // Checked atomically by the OS, unlike a separate exists() check
+        options.create_new(true);
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+
+    let mut file = options
+        .open(path)
+        .map_err(|e| anyhow::anyhow!(describe_write_error(path, &e)))?;
+    // The mode above only applies to new files; tighten a file being overwritten too
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(fs::Permissions::from_mode(0o600))
+            .map_err(|e| anyhow::anyhow!(describe_write_error(path, &e)))?;
+    }
+    file.write_all(bytes)
+        .map_err(|e| anyhow::anyhow!(describe_write_error(path, &e)))?;
+
+    Ok(())
+}
+
+/// Set a secret's value from a file, creating the secret if it doesn't exist.
+///
+/// # Arguments
+///
+/// * `keyvault_uri` - The Key Vault URI
+/// * `secret_name` - The name of the secret
+/// * `file_path` - The file to read
+/// * `encode_base64` - Store the file base64-encoded, required for binary files
+///
+/// # Returns
+///
+/// The created or updated secret bundle.
+///
+/// # Errors
+///
+/// This function will return an error if:
+/// - The file can't be read
+/// - The file (or its base64 encoding) exceeds Key Vault's 25 KB value limit
+/// - The file isn't UTF-8 text and `encode_base64` isn't set
+/// - The secret can't be written
+pub async fn set_secret_from_file(
+    keyvault_uri: &str,
+    secret_name: &str,
+    file_path: &str,
+    encode_base64: bool,
+) -> Result<SecretBundle, String> {
+    let value = read_secret_value(Path::new(file_path), encode_base64).map_err(|e| {
+        error!("Failed to read secret file: {}", e);
+        e.to_string()
+    })?;
+
+    update_secret(keyvault_uri, secret_name, &value).await
+}
+
+/// Read a file as a secret value, base64-encoding it if requested
+///
+/// The size is checked before the file is read, so oversized files are
+/// rejected without loading them.
+fn read_secret_value(path: &Path, encode_base64: bool) -> Result<String> {
+    let size = fs::metadata(path)
+        .map_err(|e| anyhow::anyhow!(describe_read_error(path, &e)))?
+        .len();
+    if size > MAX_SECRET_VALUE_BYTES {
+        return Err(anyhow::anyhow!(
+            "File {} is {} bytes; Key Vault secrets are limited to {} bytes",
+            path.display(),
+            size,
+            MAX_SECRET_VALUE_BYTES
+        ));
+    }
+
+    let bytes = fs::read(path).map_err(|e| anyhow::anyhow!(describe_read_error(path, &e)))?;
+
+    if !encode_base64 {
+        return String::from_utf8(bytes).map_err(|_| {
+            anyhow::anyhow!(
+                "File {} is not UTF-8 text; enable base64 encoding to store binary files",
+                path.display()
+            )
+        });
+    }
+
+    let encoded = BASE64.encode(bytes);
+    if encoded.len() as u64 > MAX_SECRET_VALUE_BYTES {
+        return Err(anyhow::anyhow!(
+            "File {} is {} bytes once base64-encoded; Key Vault secrets are limited to {} bytes",
+            path.display(),
+            encoded.len(),
+            MAX_SECRET_VALUE_BYTES
+        ));
+    }
+
+    Ok(encoded)
+}
+
+/// A user-facing message for a failed file read
+fn describe_read_error(path: &Path, e: &std::io::Error) -> String {
+    let path = path.display();
+    match e.kind() {
+        ErrorKind::PermissionDenied => format!("Permission denied reading {}", path),
+        ErrorKind::NotFound => format!("File {} does not exist", path),
+        _ => format!("Failed to read {}: {}", path, e),
+    }
+}
+
+/// A user-facing message for a failed file write
+fn describe_write_error(path: &Path, e: &std::io::Error) -> String {
+    let path = path.display();
+    match e.kind() {
+        ErrorKind::AlreadyExists => {
+            format!("File {} already exists; enable overwrite to replace it", path)
+        }
+        ErrorKind::PermissionDenied => format!("Permission denied writing to {}", path),
+        ErrorKind::NotFound => format!("The folder for {} does not exist", path),
+        _ => format!("Failed to write {}: {}", path, e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_secret_bytes_decodes_wrapped_base64() {
+        let bytes = secret_bytes("aGVs\nbG8g\r\nd29y bGQ=", true).unwrap();
+        assert_eq!(bytes, b"hello world");
+
+        assert_eq!(secret_bytes("aGVsbG8=", false).unwrap(), b"aGVsbG8=");
+        assert!(secret_bytes("not base64!", true).is_err());
+    }
+
+    #[test]
+    fn test_write_new_file_refuses_to_overwrite() {
+        let dir = std::env::temp_dir().join(format!("vaultraider-test-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("cert.pfx");
+
+        write_new_file(&path, b"first", false).unwrap();
+        let err = write_new_file(&path, b"second", false).unwrap_err();
+        assert!(err.to_string().contains("already exists"));
+        assert_eq!(fs::read(&path).unwrap(), b"first");
+
+        write_new_file(&path, b"2nd", true).unwrap();
+        assert_eq!(fs::read(&path).unwrap(), b"2nd");
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_write_new_file_is_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = std::env::temp_dir().join(format!("vaultraider-test-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let created = dir.join("created.pem");
+        let overwritten = dir.join("overwritten.pem");
+        fs::write(&overwritten, b"old").unwrap();
+        fs::set_permissions(&overwritten, fs::Permissions::from_mode(0o644)).unwrap();
+
+        write_new_file(&created, b"secret", false).unwrap();
+        write_new_file(&overwritten, b"secret", true).unwrap();
+
+        for path in [&created, &overwritten] {
+            let mode = fs::metadata(path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600, "{:?}", path);
+        }
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_read_secret_value() {
+        let dir = std::env::temp_dir().join(format!("vaultraider-test-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let text = dir.join("key.pem");
+        let binary = dir.join("cert.pfx");
+        fs::write(&text, "-----BEGIN KEY-----").unwrap();
+        fs::write(&binary, [0xff, 0xfe, 0x00]).unwrap();
+
+        assert_eq!(read_secret_value(&text, false).unwrap(), "-----BEGIN KEY-----");
+        assert_eq!(read_secret_value(&binary, true).unwrap(), "//4A");
+        let err = read_secret_value(&binary, false).unwrap_err();
+        assert!(err.to_string().contains("not UTF-8"));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_read_secret_value_size_limit() {
+        let dir = std::env::temp_dir().join(format!("vaultraider-test-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("big.bin");
+
+        // Fits as-is, but not once base64-encoded
+        fs::write(&path, vec![b'a'; MAX_SECRET_VALUE_BYTES as usize]).unwrap();
+        assert!(read_secret_value(&path, false).is_ok());
+        assert!(read_secret_value(&path, true).is_err());
+
+        fs::write(&path, vec![b'a'; MAX_SECRET_VALUE_BYTES as usize + 1]).unwrap();
+        let err = read_secret_value(&path, false).unwrap_err();
+        assert!(err.to_string().contains("limited to"));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_write_new_file_missing_folder() {
+        let path = std::env::temp_dir()
+            .join(format!("vaultraider-test-{}", uuid::Uuid::new_v4()))
+            .join("cert.pfx");
+
+        let err = write_new_file(&path, b"data", false).unwrap_err();
+        assert!(err.to_string().contains("does not exist"));
+    }
+}
@@ -0,0 +1,105 @@
+//! Secret value formatting - pretty-print JSON secrets for display
+//!
+//! The formatted value is always derived from the cached `SecretBundle`,
+//! so no separate cache entry is kept for it.
+
+use super::service::get_secret;
+use crate::cache::azure_cache;
+use serde::Serialize;
+
+/// A secret value prepared for display
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FormattedSecret {
+    /// Pretty-printed JSON when `is_json` is true, otherwise the raw value
+    pub value: String,
+    pub is_json: bool,
+    pub content_type: Option<String>,
+}
+
+/// Fetch a secret and pretty-print its value if it is JSON.
+///
+/// # Arguments
+///
+/// * `keyvault_uri` - The Key Vault URI
+/// * `secret_name` - The name of the secret
+///
+/// # Returns
+///
+/// The formatted secret value or an error.
+pub async fn get_secret_formatted(
+    keyvault_uri: &str,
+    secret_name: &str,
+) -> Result<FormattedSecret, String> {
+    let uri = keyvault_uri.to_string();
+    let name = secret_name.to_string();
+    let bundle = azure_cache()
+        .get_secret_value_or_load(keyvault_uri, secret_name, || async move {
+            get_secret(&uri, &name, None).await
+        })
+        .await?;
+
+    Ok(format_secret_value(&bundle.value, bundle.content_type))
+}
+
+/// Pretty-print a secret value if it parses as JSON.
+///
+/// A JSON `content_type` alone isn't trusted: a value that doesn't parse
+/// is returned raw with `is_json` false. Bare scalars (numbers, strings, ...)
+/// are only treated as JSON when the content type says so, otherwise a
+/// secret like `12345` would be flagged as JSON.
+fn format_secret_value(value: &str, content_type: Option<String>) -> FormattedSecret {
+    let declared_json = content_type
+        .as_deref()
+        .is_some_and(|ct| ct.to_lowercase().contains("json"));
+
+    let pretty = serde_json::from_str::<serde_json::Value>(value)
+        .ok()
+        .filter(|parsed| declared_json || parsed.is_object() || parsed.is_array())
+        .and_then(|parsed| serde_json::to_string_pretty(&parsed).ok());
+
+    match pretty {
+        Some(formatted) => FormattedSecret {
+            value: formatted,
+            is_json: true,
+            content_type,
+        },
+        None => FormattedSecret {
+            value: value.to_string(),
+            is_json: false,
+            content_type,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_json_object() {
+        let result = format_secret_value(r#"{"user":"admin","port":5432}"#, None);
+        assert!(result.is_json);
+        assert_eq!(result.value, "{\n  \"user\": \"admin\",\n  \"port\": 5432\n}");
+    }
+
+    #[test]
+    fn test_format_plain_value_is_raw() {
+        let result = format_secret_value("hunter2", None);
+        assert!(!result.is_json);
+        assert_eq!(result.value, "hunter2");
+    }
+
+    #[test]
+    fn test_format_scalar_requires_content_type() {
+        assert!(!format_secret_value("12345", None).is_json);
+        assert!(format_secret_value("12345", Some("application/json".to_string())).is_json);
+    }
+
+    #[test]
+    fn test_format_invalid_json_with_json_content_type() {
+        let result = format_secret_value("{not json", Some("application/json".to_string()));
+        assert!(!result.is_json);
+        assert_eq!(result.value, "{not json");
+    }
+}
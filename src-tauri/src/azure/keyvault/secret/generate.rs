@@ -0,0 +1,324 @@
+//! Random secret values - break-glass rotation of password-style secrets
+
+use log::info;
+use rand::rngs::OsRng;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use super::service::update_secret;
+use super::types::SecretBundle;
+
+/// Shortest value that can be generated
+pub const MIN_GENERATED_LENGTH: usize = 8;
+
+/// Longest value that can be generated
+pub const MAX_GENERATED_LENGTH: usize = 1024;
+
+/// Characters a generated value is drawn from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SecretCharset {
+    /// `a-z`, `A-Z` and `0-9`
+    #[default]
+    Alphanumeric,
+    /// Alphanumeric plus punctuation, for password-style secrets
+    WithSymbols,
+    /// `0-9` and `a-f`, e.g. for keys stored as hex
+    Hex,
+    /// `0-9`, e.g. for PINs
+    Numeric,
+}
+
+const HEX: &[u8] = b"0123456789abcdef";
+const NUMERIC: &[u8] = b"0123456789";
+const LOWERCASE: &[u8] = b"abcdefghijklmnopqrstuvwxyz";
+const UPPERCASE: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+const SYMBOLS: &[u8] = b"!#$%&()*+,-./:;<=>?@[]^_{|}~";
+/// Characters that are easily mistaken for one another when read or typed
+const AMBIGUOUS: &[u8] = b"0Oo1lI|";
+
+impl SecretCharset {
+    /// The character classes the charset is made of
+    fn classes(&self) -> Vec<Vec<u8>> {
+        let classes: &[&[u8]] = match self {
+            SecretCharset::Alphanumeric => &[LOWERCASE, UPPERCASE, NUMERIC],
+            SecretCharset::WithSymbols => &[LOWERCASE, UPPERCASE, NUMERIC, SYMBOLS],
+            SecretCharset::Hex => &[HEX],
+            SecretCharset::Numeric => &[NUMERIC],
+        };
+        classes.iter().map(|class| class.to_vec()).collect()
+    }
+}
+
+/// Character classes and length of a generated value
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GeneratorOptions {
+    pub length: usize,
+    #[serde(default = "default_true")]
+    pub lowercase: bool,
+    #[serde(default = "default_true")]
+    pub uppercase: bool,
+    #[serde(default = "default_true")]
+    pub digits: bool,
+    #[serde(default)]
+    pub symbols: bool,
+    /// Leave out characters like `0`/`O` and `1`/`l`/`I`
+    #[serde(default)]
+    pub exclude_ambiguous: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl GeneratorOptions {
+    /// The enabled character classes, without ambiguous characters if requested
+    fn classes(&self) -> Vec<Vec<u8>> {
+        [
+            (self.lowercase, LOWERCASE),
+            (self.uppercase, UPPERCASE),
+            (self.digits, NUMERIC),
+            (self.symbols, SYMBOLS),
+        ]
+        .into_iter()
+        .filter(|(enabled, _)| *enabled)
+        .map(|(_, characters)| {
+            characters
+                .iter()
+                .copied()
+                .filter(|c| !(self.exclude_ambiguous && AMBIGUOUS.contains(c)))
+                .collect()
+        })
+        .collect()
+    }
+}
+
+fn check_length(length: usize) -> Result<(), String> {
+    if !(MIN_GENERATED_LENGTH..=MAX_GENERATED_LENGTH).contains(&length) {
+        return Err(format!(
+            "Length must be between {} and {} characters",
+            MIN_GENERATED_LENGTH, MAX_GENERATED_LENGTH
+        ));
+    }
+    Ok(())
+}
+
+/// Generate a random value with the requested character classes.
+///
+/// The value contains at least one character of every enabled class; the rest
+/// is drawn uniformly from all enabled classes, then the order is shuffled.
+///
+/// # Errors
+///
+/// Returns an error if `length` is out of bounds or no class is enabled.
+pub fn generate_with_options(options: &GeneratorOptions) -> Result<String, String> {
+    check_length(options.length)?;
+
+    let classes = options.classes();
+    if classes.is_empty() {
+        return Err("Select at least one character class".to_string());
+    }
+    Ok(generate_from_classes(options.length, &classes))
+}
+
+/// Generate a random value from the operating system's CSPRNG.
+///
+/// Uses the same generator as `generate_with_options`, with the charset's
+/// character classes.
+///
+/// # Errors
+///
+/// Returns an error if `length` is outside `MIN_GENERATED_LENGTH..=MAX_GENERATED_LENGTH`.
+pub fn generate_secret_value(length: usize, charset: SecretCharset) -> Result<String, String> {
+    check_length(length)?;
+    Ok(generate_from_classes(length, &charset.classes()))
+}
+
+/// At least one character of every class, the rest drawn from all of them, shuffled
+///
+/// `classes` must not be empty, and `length` must be at least the number of classes.
+fn generate_from_classes(length: usize, classes: &[Vec<u8>]) -> String {
+    let pool: Vec<u8> = classes.concat();
+
+    let mut rng = OsRng;
+    let mut value: Vec<u8> = classes
+        .iter()
+        .map(|class| class[rng.gen_range(0..class.len())])
+        .collect();
+    let remaining = length - value.len();
+    value.extend((0..remaining).map(|_| pool[rng.gen_range(0..pool.len())]));
+    value.shuffle(&mut rng);
+
+    value.into_iter().map(char::from).collect()
+}
+
+/// Rotate a secret to a newly generated random value.
+///
+/// Creates a new version of the secret (or the secret itself if it doesn't
+/// exist yet). The generated value is only returned in the bundle, never logged.
+///
+/// # Arguments
+///
+/// * `keyvault_uri` - The Key Vault URI
+/// * `secret_name` - The name of the secret
+/// * `length` - Number of characters to generate
+/// * `charset` - Characters to draw from
+///
+/// # Errors
+///
+/// This function will return an error if:
+/// - `length` is out of bounds
+/// - The secret can't be written
+pub async fn rotate_secret_random(
+    keyvault_uri: &str,
+    secret_name: &str,
+    length: usize,
+    charset: SecretCharset,
+) -> Result<SecretBundle, String> {
+    let value = generate_secret_value(length, charset)?;
+
+    info!(
+        "Rotating secret to a generated {:?} value of {} characters",
+        charset, length
+    );
+    update_secret(keyvault_uri, secret_name, &value).await
+}
+
+/// Rotate a secret to a value generated from `options`.
+///
+/// Like `rotate_secret_random`, but with explicit character classes.
+///
+/// # Errors
+///
+/// This function will return an error if:
+/// - The options are invalid
+/// - The secret can't be written
+pub async fn rotate_secret(
+    keyvault_uri: &str,
+    secret_name: &str,
+    options: &GeneratorOptions,
+) -> Result<SecretBundle, String> {
+    let value = generate_with_options(options)?;
+
+    info!(
+        "Rotating secret to a generated value of {} characters",
+        options.length
+    );
+    update_secret(keyvault_uri, secret_name, &value).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generated_value_uses_charset() {
+        let cases = [
+            SecretCharset::Alphanumeric,
+            SecretCharset::WithSymbols,
+            SecretCharset::Hex,
+            SecretCharset::Numeric,
+        ];
+
+        for charset in cases {
+            let classes = charset.classes();
+            let characters = classes.concat();
+            let value = generate_secret_value(MIN_GENERATED_LENGTH, charset).unwrap();
+            assert_eq!(value.len(), MIN_GENERATED_LENGTH);
+            assert!(value.bytes().all(|b| characters.contains(&b)));
+            for class in &classes {
+                assert!(value.bytes().any(|b| class.contains(&b)), "{}", value);
+            }
+        }
+    }
+
+    #[test]
+    fn test_generated_values_differ() {
+        let a = generate_secret_value(32, SecretCharset::Alphanumeric).unwrap();
+        let b = generate_secret_value(32, SecretCharset::Alphanumeric).unwrap();
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn test_length_bounds() {
+        assert!(generate_secret_value(MIN_GENERATED_LENGTH - 1, SecretCharset::Hex).is_err());
+        assert!(generate_secret_value(MAX_GENERATED_LENGTH + 1, SecretCharset::Hex).is_err());
+        assert!(generate_secret_value(MIN_GENERATED_LENGTH, SecretCharset::Hex).is_ok());
+        assert!(generate_secret_value(MAX_GENERATED_LENGTH, SecretCharset::Hex).is_ok());
+    }
+
+    #[test]
+    fn test_charset_names() {
+        let charset: SecretCharset = serde_json::from_str(r#""with-symbols""#).unwrap();
+        assert_eq!(charset, SecretCharset::WithSymbols);
+    }
+
+    fn options(length: usize) -> GeneratorOptions {
+        GeneratorOptions {
+            length,
+            lowercase: true,
+            uppercase: true,
+            digits: true,
+            symbols: true,
+            exclude_ambiguous: false,
+        }
+    }
+
+    #[test]
+    fn test_options_value_contains_every_class() {
+        // At the minimum length a missing class would be likely without the guarantee
+        for _ in 0..100 {
+            let value = generate_with_options(&options(MIN_GENERATED_LENGTH)).unwrap();
+            assert_eq!(value.len(), MIN_GENERATED_LENGTH);
+            for class in [LOWERCASE, UPPERCASE, NUMERIC, SYMBOLS] {
+                assert!(value.bytes().any(|b| class.contains(&b)), "{}", value);
+            }
+        }
+    }
+
+    #[test]
+    fn test_options_disabled_classes_are_left_out() {
+        let value = generate_with_options(&GeneratorOptions {
+            uppercase: false,
+            symbols: false,
+            ..options(256)
+        })
+        .unwrap();
+
+        assert_eq!(value.len(), 256);
+        assert!(value.bytes().all(|b| LOWERCASE.contains(&b) || NUMERIC.contains(&b)));
+    }
+
+    #[test]
+    fn test_options_exclude_ambiguous() {
+        let value = generate_with_options(&GeneratorOptions {
+            exclude_ambiguous: true,
+            ..options(MAX_GENERATED_LENGTH)
+        })
+        .unwrap();
+
+        assert!(value.bytes().all(|b| !AMBIGUOUS.contains(&b)));
+    }
+
+    #[test]
+    fn test_options_need_a_class() {
+        let none = GeneratorOptions {
+            lowercase: false,
+            uppercase: false,
+            digits: false,
+            symbols: false,
+            ..options(16)
+        };
+        assert!(generate_with_options(&none).is_err());
+        assert!(generate_with_options(&options(MIN_GENERATED_LENGTH - 1)).is_err());
+    }
+
+    #[test]
+    fn test_options_defaults() {
+        let options: GeneratorOptions = serde_json::from_str(r#"{"length": 20}"#).unwrap();
+        assert!(options.lowercase && options.uppercase && options.digits);
+        assert!(!options.symbols && !options.exclude_ambiguous);
+    }
+}
@@ -1,15 +1,121 @@
 //! Secret import functionality - business logic for parsing and importing secrets from various formats
 
 use anyhow::{Context, Result};
-use log::{debug, error, info};
+use log::{debug, error, info, warn};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use futures::stream::{self, StreamExt};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::future::Future;
+
+use super::encryption::{decrypt_export, looks_like_encrypted_export};
+use super::export::unguard_csv_cell;
+use super::service::{create_secret_with_attributes, get_secrets};
+use super::types::SecretWriteOptions;
+use super::validation::validate_secret_names;
+use crate::config::MAX_CONCURRENT_SECRET_FETCHES;
+
+/// Content type set on imported binary secrets, so readers know to base64-decode them
+pub const BINARY_CONTENT_TYPE: &str = "application/octet-stream";
 
 /// Parsed secret ready for import
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ImportedSecret {
     pub name: String,
     pub value: String,
+    /// The source value wasn't valid UTF-8, so `value` holds it base64-encoded
+    #[serde(default)]
+    pub binary: bool,
+}
+
+impl ImportedSecret {
+    /// Options to write the secret with; binary values get `BINARY_CONTENT_TYPE`
+    fn write_options(&self) -> SecretWriteOptions {
+        SecretWriteOptions {
+            content_type: self.binary.then(|| BINARY_CONTENT_TYPE.to_string()),
+            ..SecretWriteOptions::default()
+        }
+    }
+}
+
+/// What happened to one secret in a batch import
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportStatus {
+    Created,
+    /// Existed already and got a new version, as the strategy overwrites
+    Updated,
+    /// Existed already and was left alone, as the strategy skips
+    Skipped,
+    Failed,
+}
+
+/// What an import does with secrets that already exist in the vault
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ConflictStrategy {
+    /// Leave existing secrets alone
+    #[default]
+    SkipExisting,
+    /// Add a new version to existing secrets
+    OverwriteExisting,
+    /// Refuse the whole import if any secret exists
+    ErrorOnConflict,
+}
+
+/// What an import will do with one secret
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportAction {
+    Create,
+    Update,
+    Skip,
+}
+
+/// Planned action for one secret
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlannedImport {
+    pub name: String,
+    pub action: ImportAction,
+}
+
+/// Dry run of an import: what would happen to each secret, in input order
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportPlan {
+    pub secrets: Vec<PlannedImport>,
+    pub creates: usize,
+    pub updates: usize,
+    pub skips: usize,
+}
+
+/// Outcome of importing one secret
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SecretImportResult {
+    pub name: String,
+    pub status: ImportStatus,
+    pub error: Option<String>,
+}
+
+/// Error returned when an encrypted export is parsed without its passphrase
+const ENCRYPTED_WITHOUT_PASSPHRASE_ERROR: &str =
+    "This file is an encrypted export; enter its passphrase to import it";
+
+/// How imported secret names are rewritten before import
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum NameTransform {
+    /// Keep names as parsed
+    #[default]
+    AsIs,
+    /// `my-secret-name`
+    Kebab,
+    /// `MY-SECRET-NAME` - env var casing, with hyphens since Key Vault
+    /// doesn't allow underscores
+    UpperSnake,
 }
 
 /// Full export format structure (for parsing)
@@ -45,12 +151,27 @@ struct SimpleExportSecret {
     value: String,
 }
 
+/// Kubernetes `Secret` manifest (for parsing)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct K8sSecretManifest {
+    kind: String,
+    /// Base64-encoded values
+    #[serde(default)]
+    data: BTreeMap<String, String>,
+    /// Plain values, taking precedence over `data` like the API server does
+    #[serde(default)]
+    string_data: BTreeMap<String, String>,
+}
+
 /// Parse an import file and extract secrets.
 ///
 /// # Arguments
 ///
 /// * `content` - The file content to parse
 /// * `format` - Optional format hint. If None, will auto-detect.
+/// * `name_prefix` - Optional prefix prepended to every secret name
+/// * `name_transform` - How to rewrite names after prefixing (default: as-is)
 ///
 /// # Returns
 ///
@@ -62,14 +183,19 @@ struct SimpleExportSecret {
 /// - The content cannot be parsed in any known format
 /// - The content is empty
 /// - Required fields (name, value) are missing
+/// - A resulting name is not a valid Key Vault secret name
 pub fn parse_import_file(
     content: &str,
     format: Option<&str>,
+    name_prefix: Option<&str>,
+    name_transform: NameTransform,
 ) -> Result<Vec<ImportedSecret>, String> {
-    parse_import_file_internal(content, format).map_err(|e| {
-        error!("Failed to parse import file: {}", e);
-        e.to_string()
-    })
+    parse_import_file_internal(content, format)
+        .and_then(|secrets| apply_name_options(secrets, name_prefix, name_transform))
+        .map_err(|e| {
+            error!("Failed to parse import file: {}", e);
+            e.to_string()
+        })
 }
 
 fn parse_import_file_internal(content: &str, format: Option<&str>) -> Result<Vec<ImportedSecret>> {
@@ -84,15 +210,61 @@ fn parse_import_file_internal(content: &str, format: Option<&str>) -> Result<Vec
         Some("simple") => parse_simple_format(content),
         Some("keyValue") => parse_key_value_format(content),
         Some("dotenv") => parse_dotenv_format(content),
+        Some("k8s") => parse_k8s_secret_format(content),
+        Some("yaml") => parse_yaml_format(content),
+        Some("csv") => parse_csv_format(content),
+        Some("encrypted") => Err(anyhow::anyhow!(ENCRYPTED_WITHOUT_PASSPHRASE_ERROR)),
         Some(unknown) => Err(anyhow::anyhow!("Unknown format: {}", unknown)),
         None => auto_detect_and_parse(content),
     }
 }
 
+/// Decrypt an encrypted export and parse the full export inside it.
+///
+/// Names get the same prefix, transform and validation as in `parse_import_file`.
+///
+/// # Errors
+///
+/// This function will return an error if:
+/// - The passphrase is wrong (`WRONG_PASSPHRASE_ERROR`)
+/// - The content is not an encrypted export
+/// - The decrypted content is not a full export
+/// - A resulting name is not a valid Key Vault secret name
+pub fn parse_encrypted_import(
+    content: &str,
+    passphrase: &str,
+    name_prefix: Option<&str>,
+    name_transform: NameTransform,
+) -> Result<Vec<ImportedSecret>, String> {
+    decrypt_export(content, passphrase)
+        .and_then(|decrypted| parse_full_format(&decrypted))
+        .and_then(|secrets| apply_name_options(secrets, name_prefix, name_transform))
+        .map_err(|e| {
+            error!("Failed to parse encrypted import: {}", e);
+            e.to_string()
+        })
+}
+
 /// Auto-detect format and parse
 fn auto_detect_and_parse(content: &str) -> Result<Vec<ImportedSecret>> {
     info!("Auto-detecting import format");
 
+    if looks_like_encrypted_export(content) {
+        return Err(anyhow::anyhow!(ENCRYPTED_WITHOUT_PASSPHRASE_ERROR));
+    }
+
+    // Before dotenv, as base64 padding makes manifest lines contain '='
+    if looks_like_k8s_secret(content) {
+        debug!("Detected Kubernetes Secret manifest");
+        return parse_k8s_secret_format(content);
+    }
+
+    // Before dotenv, as values in a CSV file may contain '='
+    if looks_like_csv(content) {
+        debug!("Detected CSV format");
+        return parse_csv_format(content);
+    }
+
     // Try dotenv first (if it looks like it)
     if looks_like_dotenv(content) {
         debug!("Detected dotenv format");
@@ -124,6 +296,12 @@ fn auto_detect_and_parse(content: &str) -> Result<Vec<ImportedSecret>> {
         }
     }
 
+    // Neither JSON nor dotenv: try YAML
+    if let Ok(secrets) = parse_yaml_format(content) {
+        debug!("Detected YAML format");
+        return Ok(secrets);
+    }
+
     // Last resort: try dotenv
     if let Ok(secrets) = parse_dotenv_format(content) {
         if !secrets.is_empty() {
@@ -133,7 +311,7 @@ fn auto_detect_and_parse(content: &str) -> Result<Vec<ImportedSecret>> {
     }
 
     Err(anyhow::anyhow!(
-        "Could not detect file format. Supported formats: full JSON export, simple JSON, key-value JSON, or .env"
+        "Could not detect file format. Supported formats: full JSON export, simple JSON, key-value JSON, .env, YAML, CSV, or Kubernetes Secret YAML"
     ))
 }
 
@@ -157,6 +335,100 @@ fn looks_like_dotenv(content: &str) -> bool {
     matching as f64 / lines.len() as f64 > 0.5
 }
 
+/// Check if content looks like CSV: a header row with `name` and `value` columns
+///
+/// The header is read with the same CSV reader as `parse_csv_format`, so quoted
+/// column names are handled the same way.
+fn looks_like_csv(content: &str) -> bool {
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .from_reader(content.as_bytes());
+    let Ok(headers) = reader.headers() else {
+        return false;
+    };
+    let has_column = |name: &str| {
+        headers
+            .iter()
+            .any(|header| header.trim().eq_ignore_ascii_case(name))
+    };
+
+    has_column("name") && has_column("value")
+}
+
+/// Check if content looks like a Kubernetes `Secret` manifest
+fn looks_like_k8s_secret(content: &str) -> bool {
+    let top_level = |key: &str| {
+        content.lines().find_map(|line| {
+            line.strip_prefix(key)
+                .map(|value| value.trim().trim_matches(|c| c == '"' || c == '\''))
+        })
+    };
+
+    top_level("kind:") == Some("Secret") && top_level("apiVersion:").is_some()
+}
+
+/// Parse a Kubernetes `Secret` manifest
+///
+/// `data` entries are base64-decoded; values that aren't valid UTF-8 are
+/// kept base64-encoded and flagged as binary. `stringData` entries are
+/// taken as-is.
+fn parse_k8s_secret_format(content: &str) -> Result<Vec<ImportedSecret>> {
+    let manifest: K8sSecretManifest =
+        serde_yaml::from_str(content).context("Failed to parse as Kubernetes Secret manifest")?;
+
+    if manifest.kind != "Secret" {
+        return Err(anyhow::anyhow!(
+            "Expected a Kubernetes Secret, found kind '{}'",
+            manifest.kind
+        ));
+    }
+
+    let mut values: BTreeMap<String, ImportedSecret> = BTreeMap::new();
+
+    for (key, encoded) in manifest.data {
+        let decoded = BASE64
+            .decode(encoded.trim())
+            .with_context(|| format!("Value of '{}' is not valid base64", key))?;
+
+        let secret = match String::from_utf8(decoded) {
+            Ok(value) => ImportedSecret {
+                name: key.clone(),
+                value,
+                binary: false,
+            },
+            Err(_) => {
+                info!("Value of '{}' is binary, keeping it base64-encoded", key);
+                ImportedSecret {
+                    name: key.clone(),
+                    value: encoded.trim().to_string(),
+                    binary: true,
+                }
+            }
+        };
+        values.insert(key, secret);
+    }
+
+    for (key, value) in manifest.string_data {
+        values.insert(
+            key.clone(),
+            ImportedSecret {
+                name: key,
+                value,
+                binary: false,
+            },
+        );
+    }
+
+    let secrets: Vec<ImportedSecret> = values.into_values().collect();
+
+    if secrets.is_empty() {
+        return Err(anyhow::anyhow!("No data found in Kubernetes Secret"));
+    }
+
+    info!("Parsed {} secrets from Kubernetes Secret manifest", secrets.len());
+    Ok(secrets)
+}
+
 /// Parse full export format
 fn parse_full_format(content: &str) -> Result<Vec<ImportedSecret>> {
     let export: FullExportFormat =
@@ -168,6 +440,7 @@ fn parse_full_format(content: &str) -> Result<Vec<ImportedSecret>> {
         .map(|s| ImportedSecret {
             name: s.name,
             value: s.value.unwrap_or_default(),
+            binary: false,
         })
         .collect();
 
@@ -190,6 +463,7 @@ fn parse_simple_format(content: &str) -> Result<Vec<ImportedSecret>> {
         .map(|s| ImportedSecret {
             name: s.name,
             value: s.value,
+            binary: false,
         })
         .collect();
 
@@ -221,14 +495,17 @@ fn parse_key_value_format(content: &str) -> Result<Vec<ImportedSecret>> {
                 serde_json::Value::String(s) => Some(ImportedSecret {
                     name: key,
                     value: s,
+                    binary: false,
                 }),
                 serde_json::Value::Number(n) => Some(ImportedSecret {
                     name: key,
                     value: n.to_string(),
+                    binary: false,
                 }),
                 serde_json::Value::Bool(b) => Some(ImportedSecret {
                     name: key,
                     value: b.to_string(),
+                    binary: false,
                 }),
                 _ => None, // Skip arrays and objects
             }
@@ -243,8 +520,153 @@ fn parse_key_value_format(content: &str) -> Result<Vec<ImportedSecret>> {
     Ok(secrets)
 }
 
+/// Parse YAML: a `secrets:` list like the full export, or a flat `name: value` mapping
+///
+/// As with key-value JSON, nested mappings and lists in a flat mapping are skipped.
+fn parse_yaml_format(content: &str) -> Result<Vec<ImportedSecret>> {
+    let document: serde_yaml::Value =
+        serde_yaml::from_str(content).context("Failed to parse as YAML")?;
+    let serde_yaml::Value::Mapping(mapping) = document else {
+        return Err(anyhow::anyhow!("YAML document is not a mapping"));
+    };
+
+    let secrets: Vec<ImportedSecret> = if mapping
+        .get("secrets")
+        .is_some_and(serde_yaml::Value::is_sequence)
+    {
+        let export: FullExportFormat = serde_yaml::from_value(serde_yaml::Value::Mapping(mapping))
+            .context("Failed to parse YAML secrets list")?;
+        export
+            .secrets
+            .into_iter()
+            .map(|s| ImportedSecret {
+                name: s.name,
+                value: s.value.unwrap_or_default(),
+                binary: false,
+            })
+            .collect()
+    } else {
+        mapping
+            .into_iter()
+            .filter_map(|(key, value)| {
+                let name = key.as_str()?.to_string();
+                let value = match value {
+                    serde_yaml::Value::String(s) => s,
+                    serde_yaml::Value::Number(n) => n.to_string(),
+                    serde_yaml::Value::Bool(b) => b.to_string(),
+                    _ => return None, // Skip nulls, lists and nested mappings
+                };
+                Some(ImportedSecret {
+                    name,
+                    value,
+                    binary: false,
+                })
+            })
+            .collect()
+    };
+
+    if secrets.is_empty() {
+        return Err(anyhow::anyhow!("No secrets found in YAML"));
+    }
+
+    info!("Parsed {} secrets from YAML format", secrets.len());
+    Ok(secrets)
+}
+
+/// Parse CSV with a header row
+///
+/// The `name` and `value` columns are found by header (case-insensitive); other
+/// columns, such as the attributes a CSV export can include, are ignored.
+/// Rows without a name are skipped. Values lose the `'` a CSV export adds in
+/// front of formula-like cells.
+fn parse_csv_format(content: &str) -> Result<Vec<ImportedSecret>> {
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .from_reader(content.as_bytes());
+
+    let headers = reader.headers().context("Failed to read CSV header")?.clone();
+    let column = |name: &str| {
+        headers
+            .iter()
+            .position(|header| header.trim().eq_ignore_ascii_case(name))
+            .ok_or_else(|| anyhow::anyhow!("CSV has no '{}' column", name))
+    };
+    let name_column = column("name")?;
+    let value_column = column("value")?;
+
+    let mut secrets = Vec::new();
+    for (index, record) in reader.records().enumerate() {
+        // Line 1 is the header
+        let record = record.with_context(|| format!("Failed to parse CSV row {}", index + 2))?;
+        let name = unguard_csv_cell(record.get(name_column).unwrap_or_default().trim());
+        if name.is_empty() {
+            continue;
+        }
+        secrets.push(ImportedSecret {
+            name: name.to_string(),
+            value: unguard_csv_cell(record.get(value_column).unwrap_or_default()).to_string(),
+            binary: false,
+        });
+    }
+
+    if secrets.is_empty() {
+        return Err(anyhow::anyhow!("No secrets found in CSV"));
+    }
+
+    info!("Parsed {} secrets from CSV format", secrets.len());
+    Ok(secrets)
+}
+
+/// Prefix and transform the names of parsed secrets, then validate them
+fn apply_name_options(
+    secrets: Vec<ImportedSecret>,
+    name_prefix: Option<&str>,
+    name_transform: NameTransform,
+) -> Result<Vec<ImportedSecret>> {
+    let prefix = name_prefix.unwrap_or_default();
+
+    let secrets: Vec<ImportedSecret> = secrets
+        .into_iter()
+        .map(|s| ImportedSecret {
+            name: transform_name(&format!("{}{}", prefix, s.name), name_transform),
+            value: s.value,
+            binary: s.binary,
+        })
+        .collect();
+
+    validate_secret_names(secrets.iter().map(|s| s.name.as_str()))
+        .map_err(|e| anyhow::anyhow!(e))?;
+
+    Ok(secrets)
+}
+
+/// Rewrite a name, splitting words on any non-alphanumeric character
+fn transform_name(name: &str, transform: NameTransform) -> String {
+    let words = || {
+        name.split(|c: char| !c.is_ascii_alphanumeric())
+            .filter(|word| !word.is_empty())
+    };
+
+    match transform {
+        NameTransform::AsIs => name.to_string(),
+        NameTransform::Kebab => words()
+            .map(|word| word.to_lowercase())
+            .collect::<Vec<_>>()
+            .join("-"),
+        NameTransform::UpperSnake => words()
+            .map(|word| word.to_uppercase())
+            .collect::<Vec<_>>()
+            .join("-"),
+    }
+}
+
+/// Convert an environment variable name (UPPER_SNAKE_CASE) to a secret name (kebab-case)
+pub(crate) fn env_key_to_secret_name(key: &str) -> String {
+    key.to_lowercase().replace('_', "-")
+}
+
 /// Parse dotenv format
-fn parse_dotenv_format(content: &str) -> Result<Vec<ImportedSecret>> {
+pub(crate) fn parse_dotenv_format(content: &str) -> Result<Vec<ImportedSecret>> {
     let mut secrets = Vec::new();
 
     for line in content.lines() {
@@ -273,11 +695,12 @@ fn parse_dotenv_format(content: &str) -> Result<Vec<ImportedSecret>> {
             }
 
             // Convert env var format (UPPER_SNAKE_CASE) to kebab-case for secret names
-            let name = key.to_lowercase().replace('_', "-");
+            let name = env_key_to_secret_name(key);
 
             secrets.push(ImportedSecret {
                 name,
                 value: value.to_string(),
+                binary: false,
             });
         }
     }
@@ -290,9 +713,290 @@ fn parse_dotenv_format(content: &str) -> Result<Vec<ImportedSecret>> {
     Ok(secrets)
 }
 
+/// Plan an import without writing anything.
+///
+/// The vault's secrets are listed fresh, as `import_secrets` does, so the plan
+/// shows exactly what the import would do.
+///
+/// # Arguments
+///
+/// * `keyvault_uri` - The Key Vault URI
+/// * `secrets` - The parsed secrets to import
+/// * `strategy` - What to do with secrets that already exist
+///
+/// # Errors
+///
+/// This function will return an error if:
+/// - The vault's existing secrets can't be listed
+/// - The strategy is `ErrorOnConflict` and some secrets already exist
+pub async fn plan_import(
+    keyvault_uri: &str,
+    secrets: &[ImportedSecret],
+    strategy: ConflictStrategy,
+) -> Result<ImportPlan, String> {
+    let existing = existing_secret_names(keyvault_uri).await?;
+    let planned = plan_actions(secrets, &existing, strategy).map_err(|e| e.to_string())?;
+
+    let count = |action: ImportAction| planned.iter().filter(|p| p.action == action).count();
+    Ok(ImportPlan {
+        creates: count(ImportAction::Create),
+        updates: count(ImportAction::Update),
+        skips: count(ImportAction::Skip),
+        secrets: planned,
+    })
+}
+
+/// Lowercased names of the secrets in the vault
+///
+/// Listed fresh rather than from the cache, so a stale list can't cause an
+/// overwrite or make the plan disagree with the import.
+async fn existing_secret_names(keyvault_uri: &str) -> Result<HashSet<String>, String> {
+    Ok(get_secrets(keyvault_uri)
+        .await?
+        .iter()
+        .map(|secret| secret.name().to_lowercase())
+        .collect())
+}
+
+/// Decide the action for each secret
+///
+/// `existing` holds lowercased names, as Key Vault secret names are case-insensitive.
+fn plan_actions(
+    secrets: &[ImportedSecret],
+    existing: &HashSet<String>,
+    strategy: ConflictStrategy,
+) -> Result<Vec<PlannedImport>> {
+    let conflicts: Vec<&str> = secrets
+        .iter()
+        .filter(|s| existing.contains(&s.name.to_lowercase()))
+        .map(|s| s.name.as_str())
+        .collect();
+
+    if strategy == ConflictStrategy::ErrorOnConflict && !conflicts.is_empty() {
+        return Err(anyhow::anyhow!(
+            "{} secrets already exist in the vault: {}",
+            conflicts.len(),
+            conflicts.join(", ")
+        ));
+    }
+
+    Ok(secrets
+        .iter()
+        .map(|secret| {
+            let action = match (existing.contains(&secret.name.to_lowercase()), strategy) {
+                (false, _) => ImportAction::Create,
+                (true, ConflictStrategy::OverwriteExisting) => ImportAction::Update,
+                (true, _) => ImportAction::Skip,
+            };
+            PlannedImport {
+                name: secret.name.clone(),
+                action,
+            }
+        })
+        .collect())
+}
+
+/// Write parsed secrets to a Key Vault.
+///
+/// Secrets are written concurrently (bounded by `MAX_CONCURRENT_SECRET_FETCHES`)
+/// and a failure for one secret doesn't stop the others. Binary secrets are
+/// written base64-encoded, as parsed, with content type `BINARY_CONTENT_TYPE`.
+///
+/// # Arguments
+///
+/// * `keyvault_uri` - The Key Vault URI
+/// * `secrets` - The secrets to write
+/// * `strategy` - What to do with secrets that already exist, as in `plan_import`
+///
+/// # Returns
+///
+/// One result per secret, in input order.
+///
+/// # Errors
+///
+/// This function will return an error if:
+/// - A secret name isn't valid for Key Vault; nothing is written then
+/// - The vault's existing secrets can't be listed
+/// - The strategy is `ErrorOnConflict` and some secrets exist; nothing is written then
+pub async fn import_secrets(
+    keyvault_uri: &str,
+    secrets: Vec<ImportedSecret>,
+    strategy: ConflictStrategy,
+) -> Result<Vec<SecretImportResult>, String> {
+    // Checked upfront, so a bad name doesn't fail halfway through the batch
+    validate_secret_names(secrets.iter().map(|s| s.name.as_str())).map_err(|e| {
+        error!("Import refused: {}", e);
+        e
+    })?;
+
+    let existing = existing_secret_names(keyvault_uri).await?;
+
+    let plan = plan_actions(&secrets, &existing, strategy).map_err(|e| {
+        error!("Import refused: {}", e);
+        e.to_string()
+    })?;
+
+    info!(
+        "Importing {} secrets ({} already in the vault, {:?})",
+        secrets.len(),
+        plan.iter().filter(|p| p.action != ImportAction::Create).count(),
+        strategy
+    );
+
+    Ok(
+        write_imported_secrets(secrets, &plan, |secret| async move {
+            let options = secret.write_options();
+            create_secret_with_attributes(keyvault_uri, &secret.name, &secret.value, options)
+                .await
+                .map(|_| ())
+        })
+        .await,
+    )
+}
+
+/// Write each secret with `write`, following the action planned for it
+///
+/// `plan` has one entry per secret, in the same order.
+async fn write_imported_secrets<F, Fut>(
+    secrets: Vec<ImportedSecret>,
+    plan: &[PlannedImport],
+    write: F,
+) -> Vec<SecretImportResult>
+where
+    F: Fn(ImportedSecret) -> Fut,
+    Fut: Future<Output = Result<(), String>>,
+{
+    let write = &write;
+    let mut results: Vec<(usize, SecretImportResult)> =
+        stream::iter(secrets.into_iter().zip(plan).enumerate())
+            .map(|(index, (secret, planned))| async move {
+                let name = secret.name.clone();
+
+                let (status, error) = match planned.action {
+                    ImportAction::Skip => (ImportStatus::Skipped, None),
+                    action => match write(secret).await {
+                        Ok(()) if action == ImportAction::Update => (ImportStatus::Updated, None),
+                        Ok(()) => (ImportStatus::Created, None),
+                        Err(e) => {
+                            warn!("Failed to import secret '{}': {}", name, e);
+                            (ImportStatus::Failed, Some(e))
+                        }
+                    },
+                };
+
+                let result = SecretImportResult {
+                    name,
+                    status,
+                    error,
+                };
+                (index, result)
+            })
+            .buffer_unordered(MAX_CONCURRENT_SECRET_FETCHES)
+            .collect()
+            .await;
+
+    results.sort_by_key(|(index, _)| *index);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
+  use super::super::validation::{validate_secret_name, MAX_SECRET_NAME_LENGTH};
+
+    fn imported(name: &str) -> ImportedSecret {
+        ImportedSecret {
+            name: name.to_string(),
+            value: format!("{}-value", name),
+            binary: false,
+        }
+    }
+
+    /// A vault holding `existing` and `shared`, and an import overlapping on `Shared`
+    fn overlapping_import() -> (Vec<ImportedSecret>, HashSet<String>) {
+        let existing: HashSet<String> = ["existing".to_string(), "shared".to_string()].into();
+        (vec![imported("new"), imported("Shared")], existing)
+    }
+
+    fn actions(plan: &[PlannedImport]) -> Vec<(&str, ImportAction)> {
+        plan.iter().map(|p| (p.name.as_str(), p.action)).collect()
+    }
+
+    #[test]
+    fn test_plan_skip_existing() {
+        let (secrets, existing) = overlapping_import();
+        let plan = plan_actions(&secrets, &existing, ConflictStrategy::SkipExisting).unwrap();
+        assert_eq!(
+            actions(&plan),
+            vec![("new", ImportAction::Create), ("Shared", ImportAction::Skip)]
+        );
+    }
+
+    #[test]
+    fn test_plan_overwrite_existing() {
+        let (secrets, existing) = overlapping_import();
+        let plan = plan_actions(&secrets, &existing, ConflictStrategy::OverwriteExisting).unwrap();
+        assert_eq!(
+            actions(&plan),
+            vec![("new", ImportAction::Create), ("Shared", ImportAction::Update)]
+        );
+    }
+
+    #[test]
+    fn test_plan_error_on_conflict() {
+        let (secrets, existing) = overlapping_import();
+        let error = plan_actions(&secrets, &existing, ConflictStrategy::ErrorOnConflict)
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("Shared"), "{}", error);
+
+        // Without overlap nothing stands in the way
+        let plan =
+            plan_actions(&secrets[..1], &existing, ConflictStrategy::ErrorOnConflict).unwrap();
+        assert_eq!(actions(&plan), vec![("new", ImportAction::Create)]);
+    }
+
+    #[tokio::test]
+    async fn test_write_imported_secrets() {
+        let existing: HashSet<String> = ["existing".to_string()].into();
+        let written = std::sync::Mutex::new(Vec::new());
+
+        let write = |secret: ImportedSecret| {
+            let written = &written;
+            async move {
+                if secret.name == "forbidden" {
+                    return Err("Access denied".to_string());
+                }
+                written.lock().unwrap().push(secret.name);
+                Ok(())
+            }
+        };
+
+        let secrets = vec![imported("new"), imported("Existing"), imported("forbidden")];
+        let plan = plan_actions(&secrets, &existing, ConflictStrategy::SkipExisting).unwrap();
+        let results = write_imported_secrets(secrets, &plan, write).await;
+
+        let statuses: Vec<_> = results
+            .iter()
+            .map(|r| (r.name.as_str(), r.status))
+            .collect();
+        assert_eq!(
+            statuses,
+            vec![
+                ("new", ImportStatus::Created),
+                ("Existing", ImportStatus::Skipped),
+                ("forbidden", ImportStatus::Failed),
+            ]
+        );
+        assert_eq!(results[2].error.as_deref(), Some("Access denied"));
+        assert_eq!(*written.lock().unwrap(), vec!["new"]);
+
+        let secrets = vec![imported("existing")];
+        let plan = plan_actions(&secrets, &existing, ConflictStrategy::OverwriteExisting).unwrap();
+        let results = write_imported_secrets(secrets, &plan, write).await;
+        assert_eq!(results[0].status, ImportStatus::Updated);
+        assert!(results[0].error.is_none());
+    }
 
   #[test]
     fn test_parse_full_format() {
@@ -361,6 +1065,110 @@ ANOTHER_SECRET=value2
         assert_eq!(result.len(), 2);
     }
 
+    #[test]
+    fn test_name_prefix_and_transform() {
+        let secrets = || {
+            vec![ImportedSecret {
+                name: "database_URL".to_string(),
+                value: "value".to_string(),
+                binary: false,
+            }]
+        };
+
+        let cases = [
+            (None, NameTransform::Kebab, "database-url"),
+            (None, NameTransform::UpperSnake, "DATABASE-URL"),
+            (Some("svc-a-"), NameTransform::Kebab, "svc-a-database-url"),
+            (Some("svc_a_"), NameTransform::UpperSnake, "SVC-A-DATABASE-URL"),
+            (Some("svc-a-"), NameTransform::AsIs, "svc-a-database_URL"),
+        ];
+
+        for (prefix, transform, expected) in cases {
+            let name = transform_name(
+                &format!("{}{}", prefix.unwrap_or_default(), secrets()[0].name),
+                transform,
+            );
+            assert_eq!(name, expected);
+        }
+
+        let result = apply_name_options(secrets(), Some("svc-a-"), NameTransform::Kebab).unwrap();
+        assert_eq!(result[0].name, "svc-a-database-url");
+        assert!(validate_secret_name(&result[0].name).is_ok());
+    }
+
+    #[test]
+    fn test_invalid_names_rejected_after_transform() {
+        let secrets = vec![ImportedSecret {
+            name: "my_secret".to_string(),
+            value: "value".to_string(),
+            binary: false,
+        }];
+
+        assert!(apply_name_options(secrets.clone(), None, NameTransform::AsIs).is_err());
+        assert!(apply_name_options(secrets, Some("app."), NameTransform::Kebab).is_ok());
+        assert!(validate_secret_name("").is_err());
+        assert!(validate_secret_name(&"a".repeat(MAX_SECRET_NAME_LENGTH + 1)).is_err());
+    }
+
+    const K8S_SECRET_MANIFEST: &str = r#"
+apiVersion: v1
+kind: Secret
+metadata:
+  name: orders-api
+  namespace: shop
+  labels:
+    app: orders-api
+type: Opaque
+data:
+  DB_PASSWORD: cDRzc3cwcmQ=
+  api-key: c2stbGl2ZS0xMjM0
+  tls-key: //79
+stringData:
+  LOG_LEVEL: debug
+  api-key: sk-live-override
+"#;
+
+    #[test]
+    fn test_parse_k8s_secret_format() {
+        let result = parse_k8s_secret_format(K8S_SECRET_MANIFEST).unwrap();
+        assert_eq!(result.len(), 4);
+
+        let find = |name: &str| result.iter().find(|s| s.name == name).unwrap();
+        assert_eq!(find("DB_PASSWORD").value, "p4ssw0rd");
+        assert!(!find("DB_PASSWORD").binary);
+        assert_eq!(find("LOG_LEVEL").value, "debug");
+        // stringData wins over data for the same key
+        assert_eq!(find("api-key").value, "sk-live-override");
+        // 0xff 0xfe 0xfd is not UTF-8
+        assert_eq!(find("tls-key").value, "//79");
+        assert!(find("tls-key").binary);
+
+        // Binary values are written with a content type saying they're base64-encoded
+        assert_eq!(
+            find("tls-key").write_options().content_type.as_deref(),
+            Some(BINARY_CONTENT_TYPE)
+        );
+        assert_eq!(find("DB_PASSWORD").write_options().content_type, None);
+    }
+
+    #[test]
+    fn test_parse_k8s_secret_rejects_other_kinds_and_bad_base64() {
+        let config_map = "apiVersion: v1\nkind: ConfigMap\ndata:\n  key: value\n";
+        assert!(parse_k8s_secret_format(config_map).is_err());
+
+        let bad_base64 = "apiVersion: v1\nkind: Secret\ndata:\n  key: not*base64\n";
+        assert!(parse_k8s_secret_format(bad_base64).is_err());
+    }
+
+    #[test]
+    fn test_auto_detect_k8s_secret() {
+        assert!(looks_like_k8s_secret(K8S_SECRET_MANIFEST));
+        assert!(!looks_like_k8s_secret("KIND=Secret\nAPI_VERSION=v1"));
+
+        let result = auto_detect_and_parse(K8S_SECRET_MANIFEST.trim()).unwrap();
+        assert_eq!(result.len(), 4);
+    }
+
     #[test]
     fn test_auto_detect_json() {
         let content = r#"{"key1": "value1", "key2": "value2"}"#;
@@ -368,4 +1176,46 @@ ANOTHER_SECRET=value2
         let result = auto_detect_and_parse(content).unwrap();
         assert_eq!(result.len(), 2);
     }
+
+    #[test]
+    fn test_parse_yaml_format() {
+        let flat = "db-password: hunter2\nport: 5432\nnested:\n  skipped: true\n";
+        let result = parse_yaml_format(flat).unwrap();
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[1].name, "port");
+        assert_eq!(result[1].value, "5432");
+
+        let list = "vaultName: vault\nsecrets:\n  - name: secret1\n    value: value1\n";
+        let result = parse_yaml_format(list).unwrap();
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].value, "value1");
+
+        assert!(parse_yaml_format("- just\n- a list\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_csv_format() {
+        let content = "Enabled,Name,Value,Notes\n\
+                       true,db-password,\"p,a\"\"ss\",primary\n\
+                       false,,orphaned\n\
+                       true,api-key,abc123";
+
+        assert!(looks_like_csv(content));
+        let secrets = parse_import_file_internal(content, None).unwrap();
+
+        assert_eq!(secrets.len(), 2);
+        assert_eq!(secrets[0].name, "db-password");
+        assert_eq!(secrets[0].value, "p,a\"ss");
+        assert_eq!(secrets[1].name, "api-key");
+        assert_eq!(secrets[1].value, "abc123");
+    }
+
+    #[test]
+    fn test_parse_csv_requires_name_and_value_columns() {
+        let error = parse_csv_format("name,secret\ndb-password,hunter2").unwrap_err();
+        assert!(error.to_string().contains("'value'"), "{}", error);
+        assert!(!looks_like_csv("NAME=value\nOTHER=1"));
+        assert!(looks_like_csv("\"Name\",\"notes, free text\",\"Value\"\na,b,c"));
+        assert!(!looks_like_csv("\"name,value\",other\na,b"));
+    }
 }
@@ -2,7 +2,16 @@
 //!
 //! This module provides functionality for working with Key Vault secrets.
 
+pub mod audit;
+pub mod compare;
+pub mod duplicates;
+pub mod encryption;
 pub mod export;
+pub mod file;
+pub mod format;
+pub mod generate;
 pub mod import;
 pub mod service;
+pub mod stats;
 pub mod types;
+pub mod validation;
@@ -2,18 +2,76 @@
 
 use anyhow::{Context, Result};
 use log::{error, info};
-use serde::Serialize;
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
 
 use crate::azure::auth::token::get_token_for_scope;
+use crate::azure::auth::types::AzureListResponse;
 use crate::azure::http::{fetch_all_paginated, AzureHttpClient, AzureHttpError};
-use crate::config::{urls, KEYVAULT_SCOPE};
-
-use super::types::{DeletedSecretItem, Secret, SecretBundle};
+use crate::azure::keyvault::types::VaultRef;
+use crate::config::{
+    keyvault_scope, urls, DEFAULT_RETRY_BASE_DELAY_MS, MAX_CONCURRENT_SECRET_FETCHES,
+    MAX_CONCURRENT_VAULTS, SECRET_LIST_MAX_RETRIES,
+};
+
+use super::types::{
+    DeletedSecretBundle, DeletedSecretInfo, DeletedSecretItem, NewSecret, PrefetchResult,
+    ReadAccessReport, Secret, SecretBundle, SecretCreateResult, SecretEnabledResult,
+    SecretLocation, SecretWriteOptions, SecretsPage,
+};
+use super::validation::validate_secret_name;
 
 /// Request body for creating/updating a secret
 #[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
 struct SecretValue {
     value: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tags: Option<HashMap<String, String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    content_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    attributes: Option<SecretValueAttributes>,
+}
+
+/// The `attributes` object of a secret write request
+#[derive(Serialize)]
+struct SecretValueAttributes {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    exp: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    nbf: Option<u64>,
+}
+
+/// Body of the backup response and the restore request: an opaque, encrypted,
+/// base64url-encoded blob holding every version of the secret
+#[derive(Serialize, Deserialize)]
+struct SecretBackupBlob {
+    value: String,
+}
+
+impl SecretValue {
+    /// Build the request body; without options this is just `{"value": ...}`
+    fn new(value: &str, options: SecretWriteOptions) -> Self {
+        let has_attributes =
+            options.enabled.is_some() || options.exp.is_some() || options.nbf.is_some();
+
+        Self {
+            value: value.to_string(),
+            tags: options.tags,
+            content_type: options.content_type,
+            attributes: has_attributes.then_some(SecretValueAttributes {
+                enabled: options.enabled,
+                exp: options.exp,
+                nbf: options.nbf,
+            }),
+        }
+    }
 }
 
 /// Fetch all secrets from a Key Vault.
@@ -43,13 +101,18 @@ async fn get_secrets_internal(keyvault_uri: &str) -> Result<Vec<Secret>> {
     info!("Fetching secrets");
 
     let url = urls::secrets(keyvault_uri);
-    let token = get_token_for_scope(KEYVAULT_SCOPE)
+    let token = get_token_for_scope(keyvault_scope())
         .await
         .map_err(|e| anyhow::anyhow!(e))
         .context("Failed to retrieve Key Vault token")?;
 
-    let client =
-        AzureHttpClient::with_token(&token).context("Failed to create HTTP client with token")?;
+    // A throttled page late in a large listing shouldn't throw away the pages before it
+    let client = AzureHttpClient::with_scoped_token(&token, keyvault_scope())
+        .context("Failed to create HTTP client with token")?
+        .with_retry_policy(
+            SECRET_LIST_MAX_RETRIES,
+            Duration::from_millis(DEFAULT_RETRY_BASE_DELAY_MS),
+        );
 
     let secret_list = fetch_all_paginated::<Secret>(&url, &client)
         .await
@@ -60,6 +123,89 @@ async fn get_secrets_internal(keyvault_uri: &str) -> Result<Vec<Secret>> {
     Ok(secret_list)
 }
 
+/// Fetch a single page of secrets from a Key Vault.
+///
+/// Unlike `get_secrets` this doesn't follow `nextLink`, so large vaults can be
+/// paged through on demand. Pages aren't cached.
+///
+/// # Arguments
+///
+/// * `keyvault_uri` - The Key Vault URI
+/// * `continuation_token` - Token from the previous page, or `None` for the first page
+///
+/// # Returns
+///
+/// One page of Secret metadata and the token for the next page.
+///
+/// # Errors
+///
+/// This function will return an error if:
+/// - The continuation token doesn't belong to this vault
+/// - The user is not authenticated
+/// - Access to the Key Vault is denied
+/// - The API request fails
+pub async fn get_secrets_page(
+    keyvault_uri: &str,
+    continuation_token: Option<&str>,
+) -> Result<SecretsPage, String> {
+    get_secrets_page_internal(keyvault_uri, continuation_token)
+        .await
+        .map_err(|e| {
+            error!("Failed to get secrets page: {}", e);
+            e.to_string()
+        })
+}
+
+async fn get_secrets_page_internal(
+    keyvault_uri: &str,
+    continuation_token: Option<&str>,
+) -> Result<SecretsPage> {
+    let url = match continuation_token {
+        Some(token) => {
+            // The token is a nextLink URL; never send our bearer token anywhere else
+            if !is_vault_link(keyvault_uri, token) {
+                return Err(anyhow::anyhow!(
+                    "Continuation token does not belong to vault {}",
+                    keyvault_uri
+                ));
+            }
+            token.to_string()
+        }
+        None => urls::secrets(keyvault_uri),
+    };
+
+    let token = get_token_for_scope(keyvault_scope())
+        .await
+        .map_err(|e| anyhow::anyhow!(e))
+        .context("Failed to retrieve Key Vault token")?;
+
+    let client = AzureHttpClient::with_scoped_token(&token, keyvault_scope())
+        .context("Failed to create HTTP client with token")?;
+
+    let page: AzureListResponse<Secret> = client
+        .get(&url)
+        .await
+        .with_context(|| format!("Failed to fetch secrets page from {}", keyvault_uri))?;
+
+    info!("Fetched page of {} secrets", page.value.len());
+    Ok(SecretsPage {
+        secrets: page.value,
+        continuation_token: page.next_link,
+    })
+}
+
+/// Check that a link points at the given vault over https
+fn is_vault_link(keyvault_uri: &str, link: &str) -> bool {
+    let host = keyvault_uri
+        .trim_start_matches("https://")
+        .trim_end_matches('/')
+        .to_lowercase();
+    link.to_lowercase()
+        .strip_prefix("https://")
+        .and_then(|rest| rest.strip_prefix(host.as_str()))
+        .is_some_and(|path| path.starts_with('/'))
+}
+
 /// Fetch a specific secret with its value.
 ///
 /// # Arguments
@@ -99,13 +245,13 @@ async fn get_secret_internal(
     info!("Fetching secret");
 
     let url = urls::secret(keyvault_uri, secret_name, secret_version);
-    let token = get_token_for_scope(KEYVAULT_SCOPE)
+    let token = get_token_for_scope(keyvault_scope())
         .await
         .map_err(|e| anyhow::anyhow!(e))
         .context("Failed to retrieve Key Vault token")?;
 
-    let client =
-        AzureHttpClient::with_token(&token).context("Failed to create HTTP client with token")?;
+    let client = AzureHttpClient::with_scoped_token(&token, keyvault_scope())
+        .context("Failed to create HTTP client with token")?;
 
     let secret: SecretBundle = client.get(&url).await.with_context(|| {
         format!(
@@ -118,6 +264,65 @@ async fn get_secret_internal(
     Ok(secret)
 }
 
+/// Roll a secret back to the value of an earlier version.
+///
+/// The old value is written as a new current version, so the history stays intact
+/// and the rollback itself can be undone the same way.
+///
+/// # Arguments
+///
+/// * `keyvault_uri` - The Key Vault URI
+/// * `secret_name` - The name of the secret
+/// * `target_version` - The version whose value to restore
+///
+/// # Returns
+///
+/// The newly created current version.
+///
+/// # Errors
+///
+/// This function will return an error if:
+/// - The target version doesn't exist or is disabled
+/// - Writing the new version is denied
+pub async fn rollback_secret(
+    keyvault_uri: &str,
+    secret_name: &str,
+    target_version: &str,
+) -> Result<SecretBundle, String> {
+    rollback_secret_with(
+        secret_name,
+        target_version,
+        |version| async move { get_secret(keyvault_uri, secret_name, Some(&version)).await },
+        |value| async move { create_secret(keyvault_uri, secret_name, &value).await },
+    )
+    .await
+}
+
+/// `rollback_secret` with the target version fetched by `fetch` and written by `write`
+async fn rollback_secret_with<F, FetchFut, W, WriteFut>(
+    secret_name: &str,
+    target_version: &str,
+    fetch: F,
+    write: W,
+) -> Result<SecretBundle, String>
+where
+    F: FnOnce(String) -> FetchFut,
+    FetchFut: std::future::Future<Output = Result<SecretBundle, String>>,
+    W: FnOnce(String) -> WriteFut,
+    WriteFut: std::future::Future<Output = Result<SecretBundle, String>>,
+{
+    info!(
+        "Rolling back secret '{}' to version {}",
+        secret_name, target_version
+    );
+
+    let target = fetch(target_version.to_string()).await?;
+    let rolled_back = write(target.value).await?;
+
+    info!("Secret '{}' rolled back", secret_name);
+    Ok(rolled_back)
+}
+
 /// Fetch all versions of a specific secret.
 ///
 /// # Arguments
@@ -141,13 +346,13 @@ async fn get_secret_versions_internal(keyvault_uri: &str, secret_name: &str) ->
     info!("Fetching versions for secret '{}'", secret_name);
 
     let url = urls::secret_versions(keyvault_uri, secret_name);
-    let token = get_token_for_scope(KEYVAULT_SCOPE)
+    let token = get_token_for_scope(keyvault_scope())
         .await
         .map_err(|e| anyhow::anyhow!(e))
         .context("Failed to retrieve Key Vault token")?;
 
-    let client =
-        AzureHttpClient::with_token(&token).context("Failed to create HTTP client with token")?;
+    let client = AzureHttpClient::with_scoped_token(&token, keyvault_scope())
+        .context("Failed to create HTTP client with token")?;
 
     let versions = fetch_all_paginated::<Secret>(&url, &client)
         .await
@@ -183,43 +388,78 @@ async fn get_secret_versions_internal(keyvault_uri: &str, secret_name: &str) ->
 /// - The user is not authenticated
 /// - The secret doesn't exist
 /// - Access is denied
-pub async fn delete_secret(keyvault_uri: &str, secret_name: &str) -> Result<Secret, String> {
+pub async fn delete_secret(
+    keyvault_uri: &str,
+    secret_name: &str,
+) -> Result<DeletedSecretBundle, String> {
     delete_secret_internal(keyvault_uri, secret_name)
         .await
         .map_err(|e| {
             error!("Failed to delete secret: {}", e);
             // Extract the root cause error message for better user feedback
             if let Some(root_cause) = e.root_cause().downcast_ref::<AzureHttpError>() {
-                root_cause.to_string()
+                root_cause.user_message()
             } else {
                 e.to_string()
             }
         })
 }
 
-async fn delete_secret_internal(keyvault_uri: &str, secret_name: &str) -> Result<Secret> {
+async fn delete_secret_internal(
+    keyvault_uri: &str,
+    secret_name: &str,
+) -> Result<DeletedSecretBundle> {
     info!("Deleting secret");
 
     let url = urls::delete_secret(keyvault_uri, secret_name);
-    let token = get_token_for_scope(KEYVAULT_SCOPE)
+    let token = get_token_for_scope(keyvault_scope())
         .await
         .map_err(|e| anyhow::anyhow!(e))
         .context("Failed to retrieve Key Vault token")?;
 
-    let client =
-        AzureHttpClient::with_token(&token).context("Failed to create HTTP client with token")?;
+    let client = AzureHttpClient::with_scoped_token(&token, keyvault_scope())
+        .context("Failed to create HTTP client with token")?;
 
-    let deleted_secret: Secret = client.delete(&url).await.with_context(|| {
-        format!(
-            "Failed to delete secret '{}' from {}",
-            secret_name, keyvault_uri
-        )
-    })?;
+    let deleted_secret: DeletedSecretBundle =
+        client.delete(&url).await.with_context(|| {
+            format!(
+                "Failed to delete secret '{}' from {}",
+                secret_name, keyvault_uri
+            )
+        })?;
 
     info!("Secret deleted successfully");
     Ok(deleted_secret)
 }
 
+/// Delete a secret and report whether it can still be recovered or purged.
+///
+/// Wraps `delete_secret`, deriving the post-conditions from the vault's
+/// recovery level: on a purge-protected vault the secret can be recovered
+/// but not purged, so its name stays taken until the scheduled purge date.
+///
+/// # Arguments
+///
+/// * `keyvault_uri` - The Key Vault URI
+/// * `secret_name` - The name of the secret to delete
+///
+/// # Errors
+///
+/// Same as `delete_secret`.
+pub async fn delete_secret_with_info(
+    keyvault_uri: &str,
+    secret_name: &str,
+) -> Result<DeletedSecretInfo, String> {
+    let deleted = delete_secret(keyvault_uri, secret_name).await?;
+    let deleted_info = DeletedSecretInfo::new(deleted.into(), chrono::Utc::now());
+
+    info!(
+        "Secret deleted (recoverable: {}, purge protected: {}, days until purge: {:?})",
+        deleted_info.recoverable, deleted_info.purge_protected, deleted_info.days_until_purge
+    );
+    Ok(deleted_info)
+}
+
 /// Create a new secret in a Key Vault.
 ///
 /// # Arguments
@@ -243,15 +483,65 @@ pub async fn create_secret(
     secret_name: &str,
     secret_value: &str,
 ) -> Result<SecretBundle, String> {
-    create_secret_internal(keyvault_uri, secret_name, secret_value)
+    create_secret_internal(
+        keyvault_uri,
+        secret_name,
+        secret_value,
+        SecretWriteOptions::default(),
+    )
+    .await
+    .map_err(|e| {
+        error!("Failed to create secret: {}", e);
+        // Extract the root cause error message for better user feedback
+        if let Some(root_cause) = e.root_cause().downcast_ref::<AzureHttpError>() {
+            root_cause.user_message()
+        } else {
+            e.to_string()
+        }
+    })
+}
+
+/// Create a new secret with attributes, tags and a content type.
+///
+/// Like `create_secret`, but the expiry, activation date, enabled flag, tags
+/// and content type can be set in the same request.
+///
+/// # Arguments
+///
+/// * `keyvault_uri` - The Key Vault URI
+/// * `secret_name` - The name for the new secret
+/// * `secret_value` - The secret value
+/// * `options` - Attributes, tags and content type to set
+///
+/// # Returns
+///
+/// The created secret bundle.
+///
+/// # Errors
+///
+/// This function will return an error if:
+/// - The expiry isn't after the not-before time
+/// - The user is not authenticated
+/// - Access is denied
+pub async fn create_secret_with_attributes(
+    keyvault_uri: &str,
+    secret_name: &str,
+    secret_value: &str,
+    options: SecretWriteOptions,
+) -> Result<SecretBundle, String> {
+    if let (Some(exp), Some(nbf)) = (options.exp, options.nbf) {
+        if exp <= nbf {
+            return Err("The expiry date must be after the activation date".to_string());
+        }
+    }
+
+    create_secret_internal(keyvault_uri, secret_name, secret_value, options)
         .await
         .map_err(|e| {
             error!("Failed to create secret: {}", e);
-            // Extract the root cause error message for better user feedback
-            if let Some(root_cause) = e.root_cause().downcast_ref::<AzureHttpError>() {
-                root_cause.to_string()
-            } else {
-                e.to_string()
+            match e.root_cause().downcast_ref::<AzureHttpError>() {
+                Some(root_cause) => root_cause.user_message(),
+                None => e.to_string(),
             }
         })
 }
@@ -260,21 +550,21 @@ async fn create_secret_internal(
     keyvault_uri: &str,
     secret_name: &str,
     secret_value: &str,
+    options: SecretWriteOptions,
 ) -> Result<SecretBundle> {
+    validate_secret_name(secret_name).map_err(|e| anyhow::anyhow!(e))?;
     info!("Creating secret");
 
     let url = urls::create_secret(keyvault_uri, secret_name);
-    let token = get_token_for_scope(KEYVAULT_SCOPE)
+    let token = get_token_for_scope(keyvault_scope())
         .await
         .map_err(|e| anyhow::anyhow!(e))
         .context("Failed to retrieve Key Vault token")?;
 
-    let client =
-        AzureHttpClient::with_token(&token).context("Failed to create HTTP client with token")?;
+    let client = AzureHttpClient::with_scoped_token(&token, keyvault_scope())
+        .context("Failed to create HTTP client with token")?;
 
-    let body = SecretValue {
-        value: secret_value.to_string(),
-    };
+    let body = SecretValue::new(secret_value, options);
 
     let created_secret: SecretBundle = client.put(&url, &body).await.with_context(|| {
         format!(
@@ -287,6 +577,108 @@ async fn create_secret_internal(
     Ok(created_secret)
 }
 
+/// Create many secrets in a Key Vault.
+///
+/// All names are validated before anything is written. In strict mode any
+/// invalid name aborts the whole batch; otherwise invalid secrets are reported
+/// as failed and the valid ones are created, up to
+/// `MAX_CONCURRENT_SECRET_FETCHES` at a time.
+///
+/// # Returns
+///
+/// One result per requested secret, in request order.
+///
+/// # Errors
+///
+/// This function will return an error if `strict` is set and a name is
+/// invalid or duplicated within the batch.
+pub async fn create_secrets(
+    keyvault_uri: &str,
+    secrets: Vec<NewSecret>,
+    strict: bool,
+) -> Result<Vec<SecretCreateResult>, String> {
+    use futures::stream::{self, StreamExt};
+
+    let problems = validate_new_secret_names(&secrets);
+
+    if strict && problems.iter().any(Option::is_some) {
+        let invalid: Vec<&str> = secrets
+            .iter()
+            .zip(&problems)
+            .filter(|(_, problem)| problem.is_some())
+            .map(|(secret, _)| secret.name.as_str())
+            .collect();
+        let message = format!("Invalid secret names, nothing was created: {}", invalid.join(", "));
+        error!("{}", message);
+        return Err(message);
+    }
+
+    info!("Creating {} secrets", secrets.len());
+
+    let results: Vec<SecretCreateResult> = stream::iter(secrets.into_iter().zip(problems))
+        .map(|(secret, problem)| async move {
+            let error = match problem {
+                Some(problem) => Some(problem),
+                None => create_secret_internal(
+                    keyvault_uri,
+                    &secret.name,
+                    &secret.value,
+                    SecretWriteOptions {
+                        tags: secret.tags,
+                        ..SecretWriteOptions::default()
+                    },
+                )
+                .await
+                .err()
+                .map(|e| {
+                    error!("Failed to create secret '{}': {}", secret.name, e);
+                    match e.root_cause().downcast_ref::<AzureHttpError>() {
+                        Some(root_cause) => root_cause.user_message(),
+                        None => e.to_string(),
+                    }
+                }),
+            };
+
+            SecretCreateResult {
+                name: secret.name,
+                success: error.is_none(),
+                error,
+            }
+        })
+        .buffered(MAX_CONCURRENT_SECRET_FETCHES)
+        .collect()
+        .await;
+
+    let created = results.iter().filter(|r| r.success).count();
+    info!(
+        "Batch create complete: {} created, {} failed",
+        created,
+        results.len() - created
+    );
+    Ok(results)
+}
+
+/// Validate the names of a batch of new secrets
+///
+/// Returns one entry per secret: `None` if valid, otherwise why it's rejected.
+/// Names must follow Key Vault's rules and be unique (case-insensitively) in the batch.
+fn validate_new_secret_names(secrets: &[NewSecret]) -> Vec<Option<String>> {
+    let mut seen = std::collections::HashSet::new();
+
+    secrets
+        .iter()
+        .map(|secret| {
+            if let Err(problem) = validate_secret_name(&secret.name) {
+                Some(problem)
+            } else if !seen.insert(secret.name.to_lowercase()) {
+                Some("Duplicate secret name in batch".to_string())
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
 /// Update an existing secret in a Key Vault.
 ///
 /// # Arguments
@@ -315,7 +707,7 @@ pub async fn update_secret(
             error!("Failed to update secret: {}", e);
             // Extract the root cause error message for better user feedback
             if let Some(root_cause) = e.root_cause().downcast_ref::<AzureHttpError>() {
-                root_cause.to_string()
+                root_cause.user_message()
             } else {
                 e.to_string()
             }
@@ -327,20 +719,19 @@ async fn update_secret_internal(
     secret_name: &str,
     secret_value: &str,
 ) -> Result<SecretBundle> {
+    validate_secret_name(secret_name).map_err(|e| anyhow::anyhow!(e))?;
     info!("Updating secret");
 
     let url = urls::create_secret(keyvault_uri, secret_name);
-    let token = get_token_for_scope(KEYVAULT_SCOPE)
+    let token = get_token_for_scope(keyvault_scope())
         .await
         .map_err(|e| anyhow::anyhow!(e))
         .context("Failed to retrieve Key Vault token")?;
 
-    let client =
-        AzureHttpClient::with_token(&token).context("Failed to create HTTP client with token")?;
+    let client = AzureHttpClient::with_scoped_token(&token, keyvault_scope())
+        .context("Failed to create HTTP client with token")?;
 
-    let body = SecretValue {
-        value: secret_value.to_string(),
-    };
+    let body = SecretValue::new(secret_value, SecretWriteOptions::default());
 
     let updated_secret: SecretBundle = client.put(&url, &body).await.with_context(|| {
         format!(
@@ -353,113 +744,283 @@ async fn update_secret_internal(
     Ok(updated_secret)
 }
 
-// ============================================================================
-// Deleted Secret Operations
-// ============================================================================
-
-/// Fetch all deleted secrets from a Key Vault.
+/// Enable or disable the latest version of a secret.
 ///
-/// Requires soft-delete to be enabled on the vault.
-pub async fn get_deleted_secrets(keyvault_uri: &str) -> Result<Vec<DeletedSecretItem>, String> {
-    get_deleted_secrets_internal(keyvault_uri)
-        .await
-        .map_err(|e| {
-            error!("Failed to get deleted secrets: {}", e);
-            if let Some(root_cause) = e.root_cause().downcast_ref::<AzureHttpError>() {
-                root_cause.to_string()
-            } else {
-                e.to_string()
-            }
-        })
-}
-
-async fn get_deleted_secrets_internal(keyvault_uri: &str) -> Result<Vec<DeletedSecretItem>> {
-    info!("Fetching deleted secrets");
-
-    let url = urls::deleted_secrets(keyvault_uri);
-    let token = get_token_for_scope(KEYVAULT_SCOPE)
-        .await
-        .map_err(|e| anyhow::anyhow!(e))
-        .context("Failed to retrieve Key Vault token")?;
-
-    let client =
-        AzureHttpClient::with_token(&token).context("Failed to create HTTP client with token")?;
-
-    let deleted_list = fetch_all_paginated::<DeletedSecretItem>(&url, &client)
-        .await
-        .with_context(|| format!("Failed to fetch deleted secrets from {}", keyvault_uri))?;
-
-    info!(
-        "Successfully fetched {} deleted secrets",
-        deleted_list.len()
-    );
-    Ok(deleted_list)
-}
-
-/// Recover a deleted secret back to active state.
+/// # Arguments
 ///
-/// This is only possible if the vault has soft-delete enabled
-/// and the secret hasn't been purged yet.
-pub async fn recover_deleted_secret(
+/// * `keyvault_uri` - The Key Vault URI
+/// * `secret_name` - The name of the secret
+/// * `enabled` - Whether the secret should be readable
+///
+/// # Returns
+///
+/// The updated secret metadata (Key Vault doesn't return the value on update).
+///
+/// # Errors
+///
+/// This function will return an error if:
+/// - The user is not authenticated
+/// - The secret doesn't exist
+/// - Access is denied
+pub async fn set_secret_enabled(
     keyvault_uri: &str,
     secret_name: &str,
+    enabled: bool,
 ) -> Result<Secret, String> {
-    recover_deleted_secret_internal(keyvault_uri, secret_name)
+    set_secret_enabled_internal(keyvault_uri, secret_name, enabled)
         .await
         .map_err(|e| {
-            error!("Failed to recover deleted secret: {}", e);
+            error!("Failed to set secret enabled state: {}", e);
             if let Some(root_cause) = e.root_cause().downcast_ref::<AzureHttpError>() {
-                root_cause.to_string()
+                root_cause.user_message()
             } else {
                 e.to_string()
             }
         })
 }
 
-async fn recover_deleted_secret_internal(
+async fn set_secret_enabled_internal(
     keyvault_uri: &str,
     secret_name: &str,
+    enabled: bool,
 ) -> Result<Secret> {
-    info!("Recovering deleted secret '{}'", secret_name);
-
-    let url = urls::recover_deleted_secret(keyvault_uri, secret_name);
-    let token = get_token_for_scope(KEYVAULT_SCOPE)
+    let token = get_token_for_scope(keyvault_scope())
         .await
         .map_err(|e| anyhow::anyhow!(e))
         .context("Failed to retrieve Key Vault token")?;
 
-    let client =
-        AzureHttpClient::with_token(&token).context("Failed to create HTTP client with token")?;
-
-    // The recover API is a POST with an empty body and returns Secret (without value)
-    let recovered_secret: Secret = client
-        .post(&url, &serde_json::json!({}))
-        .await
-        .with_context(|| {
-            format!(
-                "Failed to recover deleted secret '{}' from {}",
-                secret_name, keyvault_uri
-            )
-        })?;
+    // Setting the enabled flag has the same effect when repeated
+    let client = AzureHttpClient::with_scoped_token(&token, keyvault_scope())
+        .context("Failed to create HTTP client with token")?
+        .with_non_idempotent_retries();
 
-    info!("Secret '{}' recovered successfully", secret_name);
-    Ok(recovered_secret)
+    patch_secret_enabled(&client, keyvault_uri, secret_name, enabled).await
 }
 
-/// Permanently delete (purge) a deleted secret.
-///
-/// This is irreversible. Only possible if the vault does NOT have
-/// purge protection enabled, or the scheduled purge date has passed.
-pub async fn purge_deleted_secret(
+/// PATCH the enabled flag of a secret's latest version with an existing client
+async fn patch_secret_enabled(
+    client: &AzureHttpClient,
     keyvault_uri: &str,
     secret_name: &str,
-) -> Result<(), String> {
-    purge_deleted_secret_internal(keyvault_uri, secret_name)
-        .await
+    enabled: bool,
+) -> Result<Secret> {
+    info!(
+        "{} secret '{}'",
+        if enabled { "Enabling" } else { "Disabling" },
+        secret_name
+    );
+
+    // An empty version targets the latest version, as the Azure SDKs do
+    let url = urls::secret(keyvault_uri, secret_name, Some(""));
+    let body = serde_json::json!({ "attributes": { "enabled": enabled } });
+
+    client.patch(&url, &body).await.with_context(|| {
+        format!(
+            "Failed to update secret '{}' in {}",
+            secret_name, keyvault_uri
+        )
+    })
+}
+
+/// Enable or disable the latest version of many secrets at once.
+///
+/// Secrets are updated concurrently (bounded by `MAX_CONCURRENT_SECRET_FETCHES`)
+/// and a failure for one secret doesn't stop the others.
+///
+/// # Arguments
+///
+/// * `keyvault_uri` - The Key Vault URI
+/// * `secret_names` - The secrets to update
+/// * `enabled` - Whether the secrets should be readable
+///
+/// # Returns
+///
+/// One result per secret, in input order.
+///
+/// # Errors
+///
+/// This function will return an error if no Key Vault token can be obtained.
+pub async fn set_secrets_enabled(
+    keyvault_uri: &str,
+    secret_names: Vec<String>,
+    enabled: bool,
+) -> Result<Vec<SecretEnabledResult>, String> {
+    let token = get_token_for_scope(keyvault_scope()).await.map_err(|e| {
+        error!("Failed to retrieve Key Vault token: {}", e);
+        e.to_string()
+    })?;
+    // Bulk updates are likely to be throttled, and repeating one is harmless
+    let client = AzureHttpClient::with_scoped_token(&token, keyvault_scope())
+        .map_err(|e| {
+            error!("Failed to create HTTP client with token: {}", e);
+            e.to_string()
+        })?
+        .with_non_idempotent_retries();
+
+    info!(
+        "{} {} secrets",
+        if enabled { "Enabling" } else { "Disabling" },
+        secret_names.len()
+    );
+
+    let client = &client;
+    let results = update_each_secret(secret_names, |name| async move {
+        patch_secret_enabled(client, keyvault_uri, &name, enabled)
+            .await
+            .map(|_| ())
+            .map_err(|e| {
+                error!("Failed to update secret '{}': {}", name, e);
+                match e.root_cause().downcast_ref::<AzureHttpError>() {
+                    Some(root_cause) => root_cause.user_message(),
+                    None => e.to_string(),
+                }
+            })
+    })
+    .await;
+
+    let updated = results.iter().filter(|r| r.success).count();
+    info!(
+        "Batch enabled-state update complete: {} updated, {} failed",
+        updated,
+        results.len() - updated
+    );
+    Ok(results)
+}
+
+/// Run `update` for each secret concurrently, collecting one result per secret in input order
+async fn update_each_secret<F, Fut>(
+    secret_names: Vec<String>,
+    update: F,
+) -> Vec<SecretEnabledResult>
+where
+    F: Fn(String) -> Fut,
+    Fut: std::future::Future<Output = Result<(), String>>,
+{
+    use futures::stream::{self, StreamExt};
+
+    let update = &update;
+    stream::iter(secret_names)
+        .map(|name| async move {
+            let error = update(name.clone()).await.err();
+            SecretEnabledResult {
+                name,
+                success: error.is_none(),
+                error,
+            }
+        })
+        .buffered(MAX_CONCURRENT_SECRET_FETCHES)
+        .collect()
+        .await
+}
+
+// ============================================================================
+// Deleted Secret Operations
+// ============================================================================
+
+/// Fetch all deleted secrets from a Key Vault.
+///
+/// Requires soft-delete to be enabled on the vault.
+pub async fn get_deleted_secrets(keyvault_uri: &str) -> Result<Vec<DeletedSecretItem>, String> {
+    get_deleted_secrets_internal(keyvault_uri)
+        .await
+        .map_err(|e| {
+            error!("Failed to get deleted secrets: {}", e);
+            if let Some(root_cause) = e.root_cause().downcast_ref::<AzureHttpError>() {
+                root_cause.user_message()
+            } else {
+                e.to_string()
+            }
+        })
+}
+
+async fn get_deleted_secrets_internal(keyvault_uri: &str) -> Result<Vec<DeletedSecretItem>> {
+    info!("Fetching deleted secrets");
+
+    let url = urls::deleted_secrets(keyvault_uri);
+    let token = get_token_for_scope(keyvault_scope())
+        .await
+        .map_err(|e| anyhow::anyhow!(e))
+        .context("Failed to retrieve Key Vault token")?;
+
+    let client = AzureHttpClient::with_scoped_token(&token, keyvault_scope())
+        .context("Failed to create HTTP client with token")?;
+
+    let deleted_list = fetch_all_paginated::<DeletedSecretItem>(&url, &client)
+        .await
+        .with_context(|| format!("Failed to fetch deleted secrets from {}", keyvault_uri))?;
+
+    info!(
+        "Successfully fetched {} deleted secrets",
+        deleted_list.len()
+    );
+    Ok(deleted_list)
+}
+
+/// Recover a deleted secret back to active state.
+///
+/// This is only possible if the vault has soft-delete enabled
+/// and the secret hasn't been purged yet.
+pub async fn recover_deleted_secret(
+    keyvault_uri: &str,
+    secret_name: &str,
+) -> Result<Secret, String> {
+    recover_deleted_secret_internal(keyvault_uri, secret_name)
+        .await
+        .map_err(|e| {
+            error!("Failed to recover deleted secret: {}", e);
+            if let Some(root_cause) = e.root_cause().downcast_ref::<AzureHttpError>() {
+                root_cause.user_message()
+            } else {
+                e.to_string()
+            }
+        })
+}
+
+async fn recover_deleted_secret_internal(
+    keyvault_uri: &str,
+    secret_name: &str,
+) -> Result<Secret> {
+    info!("Recovering deleted secret '{}'", secret_name);
+
+    let url = urls::recover_deleted_secret(keyvault_uri, secret_name);
+    let token = get_token_for_scope(keyvault_scope())
+        .await
+        .map_err(|e| anyhow::anyhow!(e))
+        .context("Failed to retrieve Key Vault token")?;
+
+    let client = AzureHttpClient::with_scoped_token(&token, keyvault_scope())
+        .context("Failed to create HTTP client with token")?;
+
+    // The recover API is a POST with an empty body and returns the secret without its value
+    // or recoverableDays; the ID includes the version
+    let recovered_secret: Secret = client
+        .post(&url, &serde_json::json!({}))
+        .await
+        .with_context(|| {
+            format!(
+                "Failed to recover deleted secret '{}' from {}",
+                secret_name, keyvault_uri
+            )
+        })?;
+
+    info!("Secret '{}' recovered successfully", secret_name);
+    Ok(recovered_secret)
+}
+
+/// Permanently delete (purge) a deleted secret.
+///
+/// This is irreversible. Only possible if the vault does NOT have
+/// purge protection enabled, or the scheduled purge date has passed.
+pub async fn purge_deleted_secret(
+    keyvault_uri: &str,
+    secret_name: &str,
+) -> Result<(), String> {
+    purge_deleted_secret_internal(keyvault_uri, secret_name)
+        .await
         .map_err(|e| {
             error!("Failed to purge deleted secret: {}", e);
             if let Some(root_cause) = e.root_cause().downcast_ref::<AzureHttpError>() {
-                root_cause.to_string()
+                root_cause.user_message()
             } else {
                 e.to_string()
             }
@@ -473,13 +1034,13 @@ async fn purge_deleted_secret_internal(
     info!("Purging deleted secret '{}'", secret_name);
 
     let url = urls::purge_deleted_secret(keyvault_uri, secret_name);
-    let token = get_token_for_scope(KEYVAULT_SCOPE)
+    let token = get_token_for_scope(keyvault_scope())
         .await
         .map_err(|e| anyhow::anyhow!(e))
         .context("Failed to retrieve Key Vault token")?;
 
-    let client =
-        AzureHttpClient::with_token(&token).context("Failed to create HTTP client with token")?;
+    let client = AzureHttpClient::with_scoped_token(&token, keyvault_scope())
+        .context("Failed to create HTTP client with token")?;
 
     // Purge uses DELETE and returns 204 No Content on success
     client.delete_no_content(&url).await.with_context(|| {
@@ -493,30 +1054,447 @@ async fn purge_deleted_secret_internal(
     Ok(())
 }
 
+// ============================================================================
+// Backup and Restore
+// ============================================================================
+
+/// Back up a secret, with all its versions, to an encrypted blob.
+///
+/// The blob can only be restored into a vault in the same subscription and
+/// Azure geography, and can't be decrypted outside Key Vault.
+///
+/// # Returns
+///
+/// The base64url-encoded backup blob.
+///
+/// # Errors
+///
+/// This function will return an error if:
+/// - The secret doesn't exist
+/// - The caller lacks the backup permission
+pub async fn backup_secret(keyvault_uri: &str, secret_name: &str) -> Result<String, String> {
+    backup_secret_internal(keyvault_uri, secret_name)
+        .await
+        .map_err(|e| {
+            error!("Failed to back up secret: {}", e);
+            if let Some(root_cause) = e.root_cause().downcast_ref::<AzureHttpError>() {
+                root_cause.user_message()
+            } else {
+                e.to_string()
+            }
+        })
+}
+
+async fn backup_secret_internal(keyvault_uri: &str, secret_name: &str) -> Result<String> {
+    info!("Backing up secret '{}'", secret_name);
+
+    let url = urls::backup_secret(keyvault_uri, secret_name);
+    let token = get_token_for_scope(keyvault_scope())
+        .await
+        .map_err(|e| anyhow::anyhow!(e))
+        .context("Failed to retrieve Key Vault token")?;
+
+    let client = AzureHttpClient::with_scoped_token(&token, keyvault_scope())
+        .context("Failed to create HTTP client with token")?;
+
+    let backup: SecretBackupBlob = client
+        .post(&url, &serde_json::json!({}))
+        .await
+        .with_context(|| {
+            format!(
+                "Failed to back up secret '{}' from {}",
+                secret_name, keyvault_uri
+            )
+        })?;
+
+    info!("Secret '{}' backed up successfully", secret_name);
+    Ok(backup.value)
+}
+
+/// Restore a secret, with all its versions, from a backup blob.
+///
+/// Fails if a secret with the same name already exists in the vault, including
+/// a soft-deleted one.
+///
+/// # Arguments
+///
+/// * `keyvault_uri` - The Key Vault URI to restore into
+/// * `blob` - The blob returned by `backup_secret`
+///
+/// # Returns
+///
+/// The restored secret, without its value.
+pub async fn restore_secret(keyvault_uri: &str, blob: &str) -> Result<Secret, String> {
+    restore_secret_internal(keyvault_uri, blob)
+        .await
+        .map_err(|e| {
+            error!("Failed to restore secret: {}", e);
+            if let Some(root_cause) = e.root_cause().downcast_ref::<AzureHttpError>() {
+                root_cause.user_message()
+            } else {
+                e.to_string()
+            }
+        })
+}
+
+async fn restore_secret_internal(keyvault_uri: &str, blob: &str) -> Result<Secret> {
+    info!("Restoring secret from backup");
+
+    let url = urls::restore_secret(keyvault_uri);
+    let token = get_token_for_scope(keyvault_scope())
+        .await
+        .map_err(|e| anyhow::anyhow!(e))
+        .context("Failed to retrieve Key Vault token")?;
+
+    let client = AzureHttpClient::with_scoped_token(&token, keyvault_scope())
+        .context("Failed to create HTTP client with token")?;
+
+    let body = SecretBackupBlob {
+        value: blob.trim().to_string(),
+    };
+    let restored: Secret = client
+        .post(&url, &body)
+        .await
+        .with_context(|| format!("Failed to restore secret into {}", keyvault_uri))?;
+
+    info!("Secret '{}' restored successfully", restored.name());
+    Ok(restored)
+}
+
+// ============================================================================
+// Prefetch Operations
+// ============================================================================
+
+/// Warm the secret value cache for a set of secrets in a vault.
+///
+/// Values are fetched concurrently (bounded by `MAX_CONCURRENT_SECRET_FETCHES`)
+/// and stored in the value cache, so subsequent `get_secret` calls are cache hits.
+/// Secrets that are already cached are counted as succeeded without a request.
+///
+/// # Arguments
+///
+/// * `keyvault_uri` - The Key Vault URI
+/// * `secret_names` - The names of the secrets to prefetch
+///
+/// # Returns
+///
+/// The number of secrets that were and weren't loaded into the cache.
+pub async fn prefetch_secret_values(keyvault_uri: &str, secret_names: Vec<String>) -> PrefetchResult {
+    use futures::stream::{self, StreamExt};
+
+    info!("Prefetching {} secret values", secret_names.len());
+
+    let outcomes: Vec<bool> = stream::iter(secret_names)
+        .map(|secret_name| {
+            let vault_uri = keyvault_uri.to_string();
+            async move {
+                let uri = vault_uri.clone();
+                let name = secret_name.clone();
+                let result = crate::cache::azure_cache()
+                    .get_secret_value_or_load(&vault_uri, &secret_name, || async move {
+                        get_secret(&uri, &name, None).await
+                    })
+                    .await;
+
+                if let Err(e) = &result {
+                    error!("Failed to prefetch secret '{}': {}", secret_name, e);
+                }
+                result.is_ok()
+            }
+        })
+        .buffer_unordered(MAX_CONCURRENT_SECRET_FETCHES)
+        .collect()
+        .await;
+
+    let succeeded = outcomes.iter().filter(|ok| **ok).count();
+    let failed = outcomes.len() - succeeded;
+
+    info!(
+        "Prefetch complete: {} succeeded, {} failed",
+        succeeded, failed
+    );
+    PrefetchResult { succeeded, failed }
+}
+
+// ============================================================================
+// Cross-Vault Lookup
+// ============================================================================
+
+/// Fetch the latest value of a secret from each of several vaults.
+///
+/// Vaults are queried concurrently (bounded by `MAX_CONCURRENT_VAULTS`) and
+/// cached values are reused. A vault without the secret is reported as
+/// `found: false` rather than as an error.
+///
+/// # Arguments
+///
+/// * `secret_name` - The name of the secret
+/// * `vault_uris` - The URIs of the vaults to look in
+///
+/// # Returns
+///
+/// One entry per vault, in the order the vaults were given.
+pub async fn get_secret_everywhere(secret_name: &str, vault_uris: Vec<String>) -> Vec<SecretLocation> {
+    use futures::stream::{self, StreamExt};
+
+    info!("Fetching secret from {} vaults", vault_uris.len());
+
+    stream::iter(vault_uris)
+        .map(|vault_uri| async move {
+            if let Some(cached) = crate::cache::azure_cache()
+                .get_secret_value(&vault_uri, secret_name)
+                .await
+            {
+                return SecretLocation {
+                    vault_uri,
+                    found: true,
+                    value: Some(cached.value),
+                    error: None,
+                };
+            }
+
+            match get_secret_internal(&vault_uri, secret_name, None).await {
+                Ok(bundle) => {
+                    let value = bundle.value.clone();
+                    crate::cache::azure_cache()
+                        .cache_secret_value(&vault_uri, bundle)
+                        .await;
+                    SecretLocation {
+                        vault_uri,
+                        found: true,
+                        value: Some(value),
+                        error: None,
+                    }
+                }
+                Err(e) => {
+                    let root_cause = e.root_cause().downcast_ref::<AzureHttpError>();
+                    let error = match root_cause {
+                        Some(AzureHttpError::ApiError { status: 404, .. }) => None,
+                        Some(root_cause) => Some(root_cause.user_message()),
+                        None => Some(e.to_string()),
+                    };
+                    if let Some(error) = &error {
+                        error!("Failed to fetch secret from {}: {}", vault_uri, error);
+                    }
+                    SecretLocation {
+                        vault_uri,
+                        found: false,
+                        value: None,
+                        error,
+                    }
+                }
+            }
+        })
+        .buffered(MAX_CONCURRENT_VAULTS)
+        .collect()
+        .await
+}
+
+// ============================================================================
+// Access Verification
+// ============================================================================
+
+/// Check that every enabled secret in a vault can still be read, e.g. after an RBAC change.
+///
+/// Each secret is read concurrently (bounded by `MAX_CONCURRENT_SECRET_FETCHES`)
+/// and parsed as metadata only, so its value is dropped with the response and
+/// never cached. Disabled secrets are skipped, as Key Vault refuses to read
+/// them regardless of permissions.
+///
+/// # Arguments
+///
+/// * `keyvault_uri` - The Key Vault URI
+///
+/// # Returns
+///
+/// Counts per outcome and the names of the secrets that returned 403.
+///
+/// # Errors
+///
+/// This function will return an error if the vault's secrets can't be listed.
+pub async fn verify_read_access(keyvault_uri: &str) -> Result<ReadAccessReport, String> {
+    verify_read_access_internal(keyvault_uri)
+        .await
+        .map_err(|e| {
+            error!("Failed to verify read access: {}", e);
+            if let Some(root_cause) = e.root_cause().downcast_ref::<AzureHttpError>() {
+                root_cause.user_message()
+            } else {
+                e.to_string()
+            }
+        })
+}
+
+/// Outcome of reading one secret during an access sweep
+enum ReadOutcome {
+    Readable,
+    Forbidden,
+    Failed,
+}
+
+async fn verify_read_access_internal(keyvault_uri: &str) -> Result<ReadAccessReport> {
+    use futures::stream::{self, StreamExt};
+
+    // Listed fresh rather than from the cache, as listing is part of what's being verified
+    let secrets = get_secrets_internal(keyvault_uri).await?;
+
+    let token = get_token_for_scope(keyvault_scope())
+        .await
+        .map_err(|e| anyhow::anyhow!(e))
+        .context("Failed to retrieve Key Vault token")?;
+
+    let client = AzureHttpClient::with_scoped_token(&token, keyvault_scope())
+        .context("Failed to create HTTP client with token")?;
+
+    let (enabled, disabled): (Vec<Secret>, Vec<Secret>) = secrets
+        .into_iter()
+        .partition(|secret| secret.attributes.enabled);
+
+    info!("Verifying read access to {} secrets", enabled.len());
+
+    let client = &client;
+    let outcomes: Vec<(String, ReadOutcome)> = stream::iter(enabled)
+        .map(|secret| async move {
+            let name = secret.name().to_string();
+            let url = urls::secret(keyvault_uri, &name, None);
+            // Parsed as `Secret`, so the value is discarded with the response body
+            let outcome = match client.get::<Secret>(&url).await {
+                Ok(_) => ReadOutcome::Readable,
+                Err(AzureHttpError::ApiError { status: 403, .. }) => ReadOutcome::Forbidden,
+                Err(e) => {
+                    error!("Failed to read secret '{}': {}", name, e);
+                    ReadOutcome::Failed
+                }
+            };
+            (name, outcome)
+        })
+        .buffer_unordered(MAX_CONCURRENT_SECRET_FETCHES)
+        .collect()
+        .await;
+
+    let report = tally_read_outcomes(outcomes, disabled.len());
+    info!(
+        "Read access sweep complete: {} readable, {} forbidden, {} failed",
+        report.readable,
+        report.forbidden.len(),
+        report.failed
+    );
+    Ok(report)
+}
+
+/// Summarize the outcomes of an access sweep, with forbidden names sorted
+fn tally_read_outcomes(
+    outcomes: Vec<(String, ReadOutcome)>,
+    skipped_disabled: usize,
+) -> ReadAccessReport {
+    let mut report = ReadAccessReport {
+        checked: outcomes.len(),
+        readable: 0,
+        failed: 0,
+        skipped_disabled,
+        forbidden: Vec::new(),
+    };
+    for (name, outcome) in outcomes {
+        match outcome {
+            ReadOutcome::Readable => report.readable += 1,
+            ReadOutcome::Forbidden => report.forbidden.push(name),
+            ReadOutcome::Failed => report.failed += 1,
+        }
+    }
+    report.forbidden.sort();
+    report
+}
+
 // ============================================================================
 // Global Search Operations
 // ============================================================================
 
+/// How the search query is matched against names, values and tags
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchMode {
+    /// The query appears anywhere in the text
+    #[default]
+    Substring,
+    /// The query is a regular expression (`regex` crate syntax)
+    Regex,
+}
+
+/// The query in the form it is matched in, prepared once per search
+#[derive(Clone)]
+enum QueryMatcher {
+    /// Lowercased up front unless the search is case-sensitive
+    Substring { query: String, case_sensitive: bool },
+    Regex(Regex),
+}
+
+impl QueryMatcher {
+    fn is_match(&self, text: &str) -> bool {
+        match self {
+            QueryMatcher::Substring {
+                query,
+                case_sensitive: true,
+            } => text.contains(query.as_str()),
+            QueryMatcher::Substring { query, .. } => text.to_lowercase().contains(query.as_str()),
+            QueryMatcher::Regex(regex) => regex.is_match(text),
+        }
+    }
+}
+
 /// Determines which search modes are active
 #[derive(Clone)]
 struct SearchConfig {
-    query_lower: String,
+    matcher: QueryMatcher,
     search_in_keys: bool,
     search_in_values: bool,
+    /// Match tag keys and values (search type `"tag"`)
+    search_in_tags: bool,
     search_type: String,
 }
 
 impl SearchConfig {
-    fn new(query: &str, search_type: &str) -> Self {
-        Self {
-            query_lower: query.to_lowercase(),
+    /// Prepare a search; a regex query is compiled here, once
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `mode` is regex and the query isn't a valid pattern.
+    fn new(
+        query: &str,
+        search_type: &str,
+        mode: SearchMode,
+        case_sensitive: bool,
+    ) -> Result<Self, String> {
+        let matcher = match mode {
+            SearchMode::Substring => QueryMatcher::Substring {
+                query: if case_sensitive {
+                    query.to_string()
+                } else {
+                    query.to_lowercase()
+                },
+                case_sensitive,
+            },
+            SearchMode::Regex => RegexBuilder::new(query)
+                .case_insensitive(!case_sensitive)
+                .build()
+                .map(QueryMatcher::Regex)
+                .map_err(|e| format!("Invalid regular expression: {}", e))?,
+        };
+
+        Ok(Self {
+            matcher,
             search_in_keys: search_type == "key" || search_type == "both",
             search_in_values: search_type == "value" || search_type == "both",
+            search_in_tags: search_type == "tag",
             search_type: search_type.to_string(),
-        }
+        })
     }
 }
 
+/// Whether any tag key or value matches the query
+fn tags_match(tags: &HashMap<String, String>, matcher: &QueryMatcher) -> bool {
+    tags.iter().any(|(key, value)| matcher.is_match(key) || matcher.is_match(value))
+}
+
 /// Extract secret name from the full secret ID URL
 fn extract_secret_name(secret_id: &str) -> String {
     secret_id
@@ -545,8 +1523,24 @@ async fn process_secret(
     config: SearchConfig,
 ) -> Option<crate::commands::keyvault::SearchResult> {
     let secret_name = extract_secret_name(&secret.id);
-    let name_lower = secret_name.to_lowercase();
-    let name_matches = name_lower.contains(&config.query_lower);
+    let name_matches = config.matcher.is_match(&secret_name);
+
+    // Tags come with the secrets list, so no value has to be fetched
+    if config.search_in_tags {
+        if !tags_match(&secret.tags, &config.matcher) {
+            return None;
+        }
+        return Some(crate::commands::keyvault::SearchResult {
+            secret_id: secret.id.clone(),
+            secret_name,
+            vault_name,
+            vault_uri,
+            subscription_id,
+            match_type: "tag".to_string(),
+            secret_value: None,
+            attributes: secret.attributes,
+        });
+    }
 
     // Fast path: key-only search with name match
     if config.search_in_keys && !config.search_in_values && name_matches {
@@ -592,7 +1586,7 @@ async fn process_secret_with_value(
     // Use cache for secret value
     let uri_clone = vault_uri.clone();
     let name_clone = secret_name.clone();
-    let secret_result = crate::cache::AZURE_CACHE
+    let secret_result = crate::cache::azure_cache()
         .get_secret_value_or_load(&vault_uri, &secret_name, || async move {
             get_secret(&uri_clone, &name_clone, None).await
         })
@@ -600,8 +1594,7 @@ async fn process_secret_with_value(
 
     match secret_result {
         Ok(secret_bundle) => {
-            let value_lower = secret_bundle.value.to_lowercase();
-            let value_matches = value_lower.contains(&config.query_lower);
+            let value_matches = config.matcher.is_match(&secret_bundle.value);
 
             let should_include = match config.search_type.as_str() {
                 "value" => value_matches,
@@ -646,17 +1639,19 @@ async fn process_secret_with_value(
 }
 
 /// Search all secrets in a single vault
+///
+/// Fails only if the vault's secrets can't be listed.
 async fn search_vault(
     vault_uri: String,
     vault_name: String,
     subscription_id: String,
     config: SearchConfig,
-) -> Vec<crate::commands::keyvault::SearchResult> {
+) -> Result<Vec<crate::commands::keyvault::SearchResult>, String> {
     use futures::stream::{self, StreamExt};
 
     // Fetch secrets list for this vault using cache
     let uri_clone = vault_uri.clone();
-    let secrets = match crate::cache::AZURE_CACHE
+    let secrets = match crate::cache::azure_cache()
         .get_secrets_list_or_load(&vault_uri, || async move {
             get_secrets(&uri_clone).await
         })
@@ -665,7 +1660,7 @@ async fn search_vault(
         Ok(s) => s,
         Err(e) => {
             error!("Failed to fetch secrets from {}: {}", vault_name, e);
-            return Vec::new();
+            return Err(e);
         }
     };
 
@@ -680,7 +1675,7 @@ async fn search_vault(
                 process_secret(secret, vault_uri, vault_name, subscription_id, config).await
             }
         })
-        .buffer_unordered(20) // Process up to 20 secrets concurrently per vault
+        .buffer_unordered(MAX_CONCURRENT_SECRET_FETCHES)
         .collect()
         .await;
 
@@ -693,58 +1688,543 @@ async fn search_vault(
         vault_name
     );
 
-    vault_results
+    Ok(vault_results)
+}
+
+/// Search vaults in parallel, returning all matches and the vaults that couldn't be searched
+///
+/// `on_vault_searched` is called from within the stream as each vault completes,
+/// while holding the progress counter lock so the events arrive in `processed`
+/// order; it must be cheap, as other vaults finishing meanwhile wait for it.
+///
+/// Once `cancel` fires, vaults still being searched are dropped and no new ones
+/// are started; the matches found until then are returned.
+async fn search_vaults<F>(
+    vaults: Vec<VaultRef>,
+    config: SearchConfig,
+    cancel: &CancellationToken,
+    on_vault_searched: F,
+) -> (
+    Vec<crate::commands::keyvault::SearchResult>,
+    Vec<crate::commands::keyvault::InaccessibleVault>,
+)
+where
+    F: Fn(crate::commands::keyvault::SearchResultBatch, crate::commands::keyvault::SearchProgress)
+        + Send
+        + Sync,
+{
+    use futures::stream::{self, StreamExt};
+
+    let total = vaults.len();
+    // (vaults processed, matches so far), updated together so events stay consistent
+    let counts = std::sync::Mutex::new((0usize, 0usize));
+
+    // Process vaults in parallel with a concurrency limit
+    let outcomes: Vec<_> = stream::iter(vaults.into_iter().enumerate())
+        .map(|(idx, vault)| {
+            let config = config.clone();
+            let on_vault_searched = &on_vault_searched;
+            let counts = &counts;
+            async move {
+                info!("Searching vault {}: {}", idx + 1, vault.name);
+                let result = search_vault(
+                    vault.uri.clone(),
+                    vault.name.clone(),
+                    vault.subscription_id.clone(),
+                    config,
+                )
+                .await;
+
+                let batch = result.as_ref().map(Vec::clone).unwrap_or_default();
+                {
+                    let mut counts = counts.lock().unwrap_or_else(|e| e.into_inner());
+                    counts.0 += 1;
+                    counts.1 += batch.len();
+                    let (processed, matches_so_far) = *counts;
+                    on_vault_searched(
+                        crate::commands::keyvault::SearchResultBatch {
+                            vault_name: vault.name.clone(),
+                            vault_uri: vault.uri.clone(),
+                            results: batch,
+                        },
+                        crate::commands::keyvault::SearchProgress {
+                            vault_name: vault.name.clone(),
+                            processed,
+                            total,
+                            matches_so_far,
+                        },
+                    );
+                }
+
+                result.map_err(|error| crate::commands::keyvault::InaccessibleVault {
+                    vault_uri: vault.uri,
+                    vault_name: vault.name,
+                    subscription_id: vault.subscription_id,
+                    error,
+                })
+            }
+        })
+        .buffer_unordered(MAX_CONCURRENT_VAULTS)
+        .take_until(cancel.cancelled())
+        .collect()
+        .await;
+
+    if cancel.is_cancelled() {
+        info!("Search cancelled after {} of {} vaults", outcomes.len(), total);
+    }
+
+    let mut results = Vec::new();
+    let mut inaccessible = Vec::new();
+    for outcome in outcomes {
+        match outcome {
+            Ok(vault_results) => results.extend(vault_results),
+            Err(vault) => inaccessible.push(vault),
+        }
+    }
+
+    (results, inaccessible)
 }
 
 /// Global search across multiple key vaults with parallelization.
 ///
-/// This function processes vaults in parallel (up to 10 at a time), and within
-/// each vault, processes secrets in parallel (up to 20 at a time) for maximum performance.
-pub async fn global_search_secrets(
-    vault_uris: Vec<String>,
-    vault_names: Vec<String>,
-    subscription_ids: Vec<String>,
+/// This function processes vaults in parallel (up to `MAX_CONCURRENT_VAULTS` at a time),
+/// and within each vault, processes secrets in parallel (up to
+/// `MAX_CONCURRENT_SECRET_FETCHES` at a time) for maximum performance.
+///
+/// `on_vault_searched` receives each vault's matches and the overall progress
+/// as soon as that vault is done. Cancelling `cancel` stops the search early and
+/// returns the matches found so far.
+///
+/// # Errors
+///
+/// Returns an error if `mode` is regex and the query isn't a valid pattern.
+pub async fn global_search_secrets<F>(
+    vaults: Vec<VaultRef>,
     query: &str,
     search_type: &str,
-) -> Result<Vec<crate::commands::keyvault::SearchResult>, String> {
-    use futures::stream::{self, StreamExt};
-
+    mode: SearchMode,
+    case_sensitive: bool,
+    cancel: &CancellationToken,
+    on_vault_searched: F,
+) -> Result<Vec<crate::commands::keyvault::SearchResult>, String>
+where
+    F: Fn(crate::commands::keyvault::SearchResultBatch, crate::commands::keyvault::SearchProgress)
+        + Send
+        + Sync,
+{
     info!(
-        "Starting global search across {} vaults for query: '{}' (type: {})",
-        vault_uris.len(),
+        "Starting global search across {} vaults for query: '{}' (type: {}, mode: {:?})",
+        vaults.len(),
         query,
-        search_type
+        search_type,
+        mode
     );
 
-    let config = SearchConfig::new(query, search_type);
+    let config = SearchConfig::new(query, search_type, mode, case_sensitive)?;
+    let (all_results, _) = search_vaults(vaults, config, cancel, on_vault_searched).await;
+
+    info!("Global search complete: {} total matches", all_results.len());
+    Ok(all_results)
+}
 
-    // Create tuples of (vault_uri, vault_name, subscription_id)
-    let vault_data: Vec<(String, String, String)> = vault_uris
+/// Global search across every accessible vault in every subscription.
+///
+/// Vaults are enumerated through the vault tree (and its caches), so the
+/// caller doesn't need to assemble the list of vaults.
+///
+/// # Returns
+///
+/// All matches, plus the vaults whose secrets couldn't be listed and the
+/// subscriptions whose vaults couldn't be listed.
+///
+/// # Errors
+///
+/// This function will return an error if the subscriptions can't be listed.
+pub async fn global_search_all<F>(
+    query: &str,
+    search_type: &str,
+    mode: SearchMode,
+    case_sensitive: bool,
+    cancel: &CancellationToken,
+    on_vault_searched: F,
+) -> Result<crate::commands::keyvault::GlobalSearchResults, String>
+where
+    F: Fn(crate::commands::keyvault::SearchResultBatch, crate::commands::keyvault::SearchProgress)
+        + Send
+        + Sync,
+{
+    // Reject an invalid pattern before enumerating every vault
+    let config = SearchConfig::new(query, search_type, mode, case_sensitive)?;
+    let tree = crate::azure::keyvault::tree::get_vault_tree().await?;
+
+    let vaults: Vec<VaultRef> = tree
+        .subscriptions
         .into_iter()
-        .zip(vault_names.into_iter())
-        .zip(subscription_ids.into_iter())
-        .map(|((uri, name), sub_id)| (uri, name, sub_id))
+        .flat_map(|subscription| {
+            let subscription_id = subscription.subscription.subscription_id;
+            subscription
+                .resource_groups
+                .into_iter()
+                .flat_map(|group| group.vaults)
+                .map(move |vault| VaultRef {
+                    uri: vault.properties.vault_uri,
+                    name: vault.name,
+                    subscription_id: subscription_id.clone(),
+                })
+        })
         .collect();
 
-    // Process vaults in parallel with a concurrency limit
-    let results: Vec<Vec<crate::commands::keyvault::SearchResult>> = stream::iter(
-        vault_data.into_iter().enumerate(),
-    )
-    .map(|(idx, (vault_uri, vault_name, subscription_id))| {
-        let config = config.clone();
-        async move {
-            info!("Searching vault {}: {}", idx + 1, vault_name);
-            search_vault(vault_uri, vault_name, subscription_id, config).await
-        }
+    info!(
+        "Starting global search across all {} accessible vaults for query: '{}' (type: {})",
+        vaults.len(),
+        query,
+        search_type
+    );
+
+    let (results, inaccessible_vaults) =
+        search_vaults(vaults, config, cancel, on_vault_searched).await;
+
+    info!(
+        "Global search complete: {} matches, {} vaults inaccessible, {} subscriptions skipped",
+        results.len(),
+        inaccessible_vaults.len(),
+        tree.skipped_subscriptions.len()
+    );
+    Ok(crate::commands::keyvault::GlobalSearchResults {
+        results,
+        inaccessible_vaults,
+        skipped_subscriptions: tree.skipped_subscriptions,
     })
-    .buffer_unordered(10) // Process up to 10 vaults concurrently
-    .collect()
-    .await;
+}
 
-    // Flatten all results
-    let all_results: Vec<crate::commands::keyvault::SearchResult> =
-        results.into_iter().flatten().collect();
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::azure::keyvault::secret::types::{RecoveryLevel, SecretAttributes};
+
+    #[test]
+    fn test_secret_value_without_options_only_sends_value() {
+        let body = SecretValue::new("hunter2", SecretWriteOptions::default());
+        assert_eq!(
+            serde_json::to_value(&body).unwrap(),
+            serde_json::json!({ "value": "hunter2" })
+        );
+    }
 
-    info!("Global search complete: {} total matches", all_results.len());
-    Ok(all_results)
+    #[test]
+    fn test_secret_value_with_options_matches_rest_shape() {
+        let body = SecretValue::new(
+            "hunter2",
+            SecretWriteOptions {
+                enabled: Some(false),
+                exp: Some(1_900_000_000),
+                nbf: None,
+                tags: Some(HashMap::from([("env".to_string(), "prod".to_string())])),
+                content_type: Some("text/plain".to_string()),
+            },
+        );
+        assert_eq!(
+            serde_json::to_value(&body).unwrap(),
+            serde_json::json!({
+                "value": "hunter2",
+                "tags": { "env": "prod" },
+                "contentType": "text/plain",
+                "attributes": { "enabled": false, "exp": 1_900_000_000 }
+            })
+        );
+    }
+
+    #[test]
+    fn test_secret_value_omits_attributes_when_only_tags_are_set() {
+        let body = SecretValue::new(
+            "hunter2",
+            SecretWriteOptions {
+                tags: Some(HashMap::new()),
+                ..SecretWriteOptions::default()
+            },
+        );
+        let json = serde_json::to_value(&body).unwrap();
+        assert!(json.get("attributes").is_none());
+        assert!(json.get("contentType").is_none());
+    }
+
+    #[test]
+    fn test_is_vault_link() {
+        let vault = "https://myvault.vault.azure.net/";
+        assert!(is_vault_link(
+            vault,
+            "https://myvault.vault.azure.net/secrets?api-version=7.4&$skiptoken=abc"
+        ));
+        assert!(!is_vault_link(
+            vault,
+            "https://myvault.vault.azure.net.evil.com/secrets"
+        ));
+        assert!(!is_vault_link(vault, "http://myvault.vault.azure.net/secrets"));
+        assert!(!is_vault_link(vault, "https://other.vault.azure.net/secrets"));
+    }
+
+    #[test]
+    fn test_validate_new_secret_names() {
+        let secret = |name: &str| NewSecret {
+            name: name.to_string(),
+            value: "value".to_string(),
+            tags: None,
+        };
+        let secrets = vec![
+            secret("db-password"),
+            secret("bad_name"),
+            secret("DB-PASSWORD"),
+            secret("api-key"),
+        ];
+
+        let problems = validate_new_secret_names(&secrets);
+
+        assert!(problems[0].is_none());
+        assert!(problems[1].as_deref().unwrap().starts_with("Invalid secret name"));
+        assert_eq!(problems[2].as_deref(), Some("Duplicate secret name in batch"));
+        assert!(problems[3].is_none());
+    }
+
+    #[test]
+    fn test_tally_read_outcomes() {
+        let report = tally_read_outcomes(
+            vec![
+                ("zeta".to_string(), ReadOutcome::Forbidden),
+                ("alpha".to_string(), ReadOutcome::Readable),
+                ("beta".to_string(), ReadOutcome::Forbidden),
+                ("gamma".to_string(), ReadOutcome::Failed),
+            ],
+            2,
+        );
+
+        assert_eq!(report.checked, 4);
+        assert_eq!(report.readable, 1);
+        assert_eq!(report.failed, 1);
+        assert_eq!(report.skipped_disabled, 2);
+        assert_eq!(report.forbidden, vec!["beta", "zeta"]);
+    }
+
+    fn tagged_secret(name: &str, tags: &[(&str, &str)]) -> Secret {
+        serde_json::from_value(serde_json::json!({
+            "id": format!("https://vault.vault.azure.net/secrets/{}", name),
+            "attributes": { "enabled": true, "created": 0, "updated": 0 },
+            "tags": tags.iter().cloned().collect::<HashMap<_, _>>(),
+        }))
+        .unwrap()
+    }
+
+    async fn search_one(
+        secret: Secret,
+        query: &str,
+        search_type: &str,
+    ) -> Option<crate::commands::keyvault::SearchResult> {
+        let config = SearchConfig::new(query, search_type, SearchMode::Substring, false).unwrap();
+        search_with(secret, config).await
+    }
+
+    async fn search_with(
+        secret: Secret,
+        config: SearchConfig,
+    ) -> Option<crate::commands::keyvault::SearchResult> {
+        process_secret(
+            secret,
+            "https://vault.vault.azure.net".to_string(),
+            "vault".to_string(),
+            "sub-123".to_string(),
+            config,
+        )
+        .await
+    }
+
+    #[test]
+    fn test_secret_tags_default_to_empty() {
+        let secret: Secret = serde_json::from_value(serde_json::json!({
+            "id": "https://vault.vault.azure.net/secrets/db-password",
+            "attributes": { "enabled": true, "created": 0, "updated": 0 }
+        }))
+        .unwrap();
+        assert!(secret.tags.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_tag_search_matches_keys_and_values() {
+        let secret = tagged_secret(
+            "db-password",
+            &[("Environment", "Production"), ("owner", "team-a")],
+        );
+
+        let by_value = search_one(secret.clone(), "prod", "tag").await.unwrap();
+        assert_eq!(by_value.match_type, "tag");
+        assert_eq!(by_value.secret_name, "db-password");
+
+        let by_key = search_one(secret.clone(), "OWNER", "tag").await.unwrap();
+        assert_eq!(by_key.match_type, "tag");
+
+        assert!(search_one(secret, "staging", "tag").await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_tags_ignored_by_name_search() {
+        let secret = tagged_secret("db-password", &[("environment", "production")]);
+
+        assert!(search_one(secret.clone(), "production", "key").await.is_none());
+
+        let by_name = search_one(secret, "db-", "key").await.unwrap();
+        assert_eq!(by_name.match_type, "key");
+    }
+
+    #[test]
+    fn test_invalid_regex_is_an_error() {
+        let err = SearchConfig::new("db-(password", "key", SearchMode::Regex, false)
+            .err()
+            .unwrap();
+        assert!(err.starts_with("Invalid regular expression"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_regex_search_matches_names() {
+        let config = SearchConfig::new(r"^db-.*-(password|pwd)$", "key", SearchMode::Regex, false)
+            .unwrap();
+
+        let result = search_with(tagged_secret("DB-prod-password", &[]), config.clone()).await;
+        assert_eq!(result.unwrap().match_type, "key");
+        assert!(search_with(tagged_secret("api-prod-password", &[]), config).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_case_sensitive_search() {
+        let secret = tagged_secret("DbPassword", &[("Owner", "Team-A")]);
+
+        let exact = SearchConfig::new("dbpassword", "key", SearchMode::Substring, true).unwrap();
+        assert!(search_with(secret.clone(), exact).await.is_none());
+
+        let regex = SearchConfig::new("^dbpass", "key", SearchMode::Regex, true).unwrap();
+        assert!(search_with(secret.clone(), regex).await.is_none());
+
+        let tags = SearchConfig::new("team-a", "tag", SearchMode::Substring, true).unwrap();
+        assert!(search_with(secret.clone(), tags).await.is_none());
+
+        let matching = SearchConfig::new("DbPass", "key", SearchMode::Substring, true).unwrap();
+        assert!(search_with(secret, matching).await.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_search_makes_no_requests() {
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+        let searched = std::sync::atomic::AtomicUsize::new(0);
+
+        // Searching this vault for real would fail (no login) and report it inaccessible
+        let vaults = vec![VaultRef {
+            uri: "https://cancelled-search.vault.azure.net".to_string(),
+            name: "cancelled-search".to_string(),
+            subscription_id: "sub-123".to_string(),
+        }];
+        let config = SearchConfig::new("db", "key", SearchMode::Substring, false).unwrap();
+
+        let (results, inaccessible) = search_vaults(vaults, config, &cancel, |_, _| {
+            searched.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        })
+        .await;
+
+        assert!(results.is_empty());
+        assert!(inaccessible.is_empty());
+        assert_eq!(searched.load(std::sync::atomic::Ordering::SeqCst), 0);
+    }
+
+    fn version_bundle(version: &str, value: &str, enabled: bool) -> SecretBundle {
+        SecretBundle {
+            id: format!("https://vault.vault.azure.net/secrets/db-password/{}", version),
+            attributes: SecretAttributes {
+                enabled,
+                created: if version == "a" { 1_700_000_000 } else { 1_700_100_000 },
+                updated: 1_700_100_000,
+                recovery_level: RecoveryLevel::Recoverable,
+                recoverable_days: 90,
+                exp: None,
+                nbf: None,
+            },
+            value: value.to_string(),
+            content_type: None,
+            tags: HashMap::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rollback_writes_old_value_as_new_version() {
+        let calls = std::sync::Mutex::new(Vec::new());
+        let calls = &calls;
+
+        let rolled_back = rollback_secret_with(
+            "db-password",
+            "a",
+            |version| async move {
+                calls.lock().unwrap().push(format!("fetch {}", version));
+                Ok(version_bundle(&version, "old-value", true))
+            },
+            |value| async move {
+                calls.lock().unwrap().push(format!("write {}", value));
+                Ok(version_bundle("c", &value, true))
+            },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(*calls.lock().unwrap(), vec!["fetch a", "write old-value"]);
+        assert_eq!(rolled_back.value, "old-value");
+        assert!(rolled_back.id.ends_with("/c"));
+    }
+
+    #[tokio::test]
+    async fn test_rollback_does_not_write_when_fetch_fails() {
+        let written = std::sync::atomic::AtomicBool::new(false);
+
+        let result = rollback_secret_with(
+            "db-password",
+            "missing",
+            |_| async { Err("Secret not found".to_string()) },
+            |value| {
+                written.store(true, std::sync::atomic::Ordering::SeqCst);
+                async move { Ok(version_bundle("c", &value, true)) }
+            },
+        )
+        .await;
+
+        assert_eq!(result.unwrap_err(), "Secret not found");
+        assert!(!written.load(std::sync::atomic::Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn test_update_each_secret_reports_partial_failures_in_order() {
+        let updated = std::sync::Mutex::new(Vec::new());
+        let update = |name: String| {
+            let updated = &updated;
+            async move {
+                if name == "forbidden" {
+                    return Err("Access denied".to_string());
+                }
+                updated.lock().unwrap().push(name);
+                Ok(())
+            }
+        };
+
+        let names = ["legacy-a", "forbidden", "legacy-b"].map(String::from).to_vec();
+        let results = update_each_secret(names, update).await;
+
+        let outcomes: Vec<_> = results
+            .iter()
+            .map(|r| (r.name.as_str(), r.success, r.error.as_deref()))
+            .collect();
+        assert_eq!(
+            outcomes,
+            vec![
+                ("legacy-a", true, None),
+                ("forbidden", false, Some("Access denied")),
+                ("legacy-b", true, None),
+            ]
+        );
+        let mut updated = updated.into_inner().unwrap();
+        updated.sort();
+        assert_eq!(updated, vec!["legacy-a", "legacy-b"]);
+    }
 }
@@ -0,0 +1,168 @@
+//! Vault statistics - secret counts and sizes for capacity planning
+//!
+//! Counts and timestamps come from the cached secrets list. Value sizes need
+//! every value to be read, so they are only computed when asked for.
+
+use super::service::{get_secret, get_secrets};
+use super::types::Secret;
+use crate::cache::azure_cache;
+use crate::config::MAX_CONCURRENT_SECRET_FETCHES;
+use anyhow::Result;
+use futures::stream::{self, StreamExt};
+use log::{error, info, warn};
+use serde::Serialize;
+
+/// Secret statistics for a vault
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultStats {
+    pub secret_count: usize,
+    pub disabled_count: usize,
+    /// Secrets whose expiry date has passed, whether enabled or not
+    pub expired_count: usize,
+    /// Earliest `updated` time among the secrets (unix seconds)
+    pub oldest_updated: Option<u64>,
+    /// Latest `updated` time among the secrets (unix seconds)
+    pub newest_updated: Option<u64>,
+    /// Total size of the readable values in bytes, if values were read
+    pub value_bytes: Option<u64>,
+    /// Enabled secrets whose value couldn't be read, if values were read
+    pub unreadable_values: Option<usize>,
+}
+
+/// Compute secret statistics for a vault.
+///
+/// # Arguments
+///
+/// * `keyvault_uri` - The Key Vault URI
+/// * `include_value_bytes` - Also read every enabled secret's value (through the
+///   value cache, bounded by `MAX_CONCURRENT_SECRET_FETCHES`) to total their size
+///
+/// # Returns
+///
+/// The vault's statistics. Value fields are `None` unless `include_value_bytes` is set.
+///
+/// # Errors
+///
+/// This function will return an error if the vault's secrets can't be listed.
+pub async fn get_vault_stats(
+    keyvault_uri: &str,
+    include_value_bytes: bool,
+) -> Result<VaultStats, String> {
+    get_vault_stats_internal(keyvault_uri, include_value_bytes)
+        .await
+        .map_err(|e| {
+            error!("Failed to compute vault stats: {}", e);
+            e.to_string()
+        })
+}
+
+async fn get_vault_stats_internal(
+    keyvault_uri: &str,
+    include_value_bytes: bool,
+) -> Result<VaultStats> {
+    info!("Computing vault stats (values: {})", include_value_bytes);
+
+    let uri = keyvault_uri.to_string();
+    let secrets = azure_cache()
+        .get_secrets_list_or_load(keyvault_uri, || async move { get_secrets(&uri).await })
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+
+    let now = chrono::Utc::now().timestamp().max(0) as u64;
+    let mut stats = summarize_secrets(&secrets, now);
+
+    if include_value_bytes {
+        let sizes = value_sizes(keyvault_uri, &secrets).await;
+        stats.value_bytes = Some(sizes.iter().flatten().sum());
+        stats.unreadable_values = Some(sizes.iter().filter(|size| size.is_none()).count());
+    }
+
+    info!("Vault stats: {} secrets", stats.secret_count);
+    Ok(stats)
+}
+
+/// Value size in bytes of each enabled secret, `None` where it couldn't be read
+///
+/// Disabled secrets are left out, as Key Vault refuses to read them.
+async fn value_sizes(keyvault_uri: &str, secrets: &[Secret]) -> Vec<Option<u64>> {
+    stream::iter(secrets.iter().filter(|secret| secret.attributes.enabled))
+        .map(|secret| {
+            let vault_uri = keyvault_uri.to_string();
+            let name = secret.name().to_string();
+            async move {
+                let uri = vault_uri.clone();
+                let secret_name = name.clone();
+                match azure_cache()
+                    .get_secret_value_or_load(&vault_uri, &name, || async move {
+                        get_secret(&uri, &secret_name, None).await
+                    })
+                    .await
+                {
+                    Ok(bundle) => Some(bundle.value.len() as u64),
+                    Err(e) => {
+                        warn!("Failed to fetch value for secret '{}': {}", name, e);
+                        None
+                    }
+                }
+            }
+        })
+        .buffer_unordered(MAX_CONCURRENT_SECRET_FETCHES)
+        .collect()
+        .await
+}
+
+/// Statistics derived from secret metadata alone
+fn summarize_secrets(secrets: &[Secret], now: u64) -> VaultStats {
+    VaultStats {
+        secret_count: secrets.len(),
+        disabled_count: secrets.iter().filter(|s| !s.attributes.enabled).count(),
+        expired_count: secrets
+            .iter()
+            .filter(|s| s.attributes.exp.is_some_and(|exp| exp <= now))
+            .count(),
+        oldest_updated: secrets.iter().map(|s| s.attributes.updated).min(),
+        newest_updated: secrets.iter().map(|s| s.attributes.updated).max(),
+        value_bytes: None,
+        unreadable_values: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::azure::keyvault::secret::types::fixtures;
+
+    fn secret(name: &str, enabled: bool, updated: u64, exp: Option<u64>) -> Secret {
+        let mut secret = fixtures::secret(name);
+        secret.attributes.enabled = enabled;
+        secret.attributes.created = updated;
+        secret.attributes.updated = updated;
+        secret.attributes.exp = exp;
+        secret
+    }
+
+    #[test]
+    fn test_summarize_secrets() {
+        let now = 1_700_000_000;
+        let secrets = vec![
+            secret("a", true, 1_600_000_000, None),
+            secret("b", false, 1_650_000_000, Some(now - 1)),
+            secret("c", true, 1_690_000_000, Some(now + 1)),
+            secret("d", true, 1_620_000_000, Some(now)),
+        ];
+
+        let stats = summarize_secrets(&secrets, now);
+        assert_eq!(stats.secret_count, 4);
+        assert_eq!(stats.disabled_count, 1);
+        assert_eq!(stats.expired_count, 2);
+        assert_eq!(stats.oldest_updated, Some(1_600_000_000));
+        assert_eq!(stats.newest_updated, Some(1_690_000_000));
+        assert_eq!(stats.value_bytes, None);
+    }
+
+    #[test]
+    fn test_summarize_empty_vault() {
+        assert_eq!(summarize_secrets(&[], 0), VaultStats::default());
+    }
+}
@@ -1,4 +1,6 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -6,16 +8,154 @@ use serde::{Deserialize, Serialize};
 pub struct Secret {
     pub id: String,
     pub attributes: SecretAttributes,
+    /// Tags set on the secret; Azure leaves the field out when there are none
+    #[serde(default)]
+    pub tags: HashMap<String, String>,
 }
 
+impl Secret {
+    /// The secret name, i.e. the segment after `/secrets/` in its ID
+    ///
+    /// IDs returned by the delete and recover APIs include the version, so the
+    /// name isn't always the last segment.
+    pub fn name(&self) -> &str {
+        match self.id.split_once("/secrets/") {
+            Some((_, rest)) => rest.split('/').next().unwrap_or(rest),
+            None => self.id.rsplit('/').next().unwrap_or(&self.id),
+        }
+    }
+}
+
+/// Secret attributes as returned by Key Vault
+///
+/// Timestamps are unix seconds. Azure leaves out fields that aren't set, so
+/// everything except the creation and update times has a default.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SecretAttributes {
+    /// Azure treats secrets without an explicit flag as enabled
+    #[serde(default = "default_enabled")]
     pub enabled: bool,
+    /// Creation time (unix seconds)
     pub created: u64,
+    /// Last update time (unix seconds)
     pub updated: u64,
-    pub recovery_level: String,
+    #[serde(default)]
+    pub recovery_level: RecoveryLevel,
+    /// Soft-delete retention in days; Azure omits it when soft-delete is off,
+    /// and the delete and recover APIs leave it out as well
+    #[serde(default)]
     pub recoverable_days: u8,
+    /// Expiry time (unix seconds), if set
+    #[serde(default)]
+    pub exp: Option<u64>,
+    /// Not-before time (unix seconds), if set
+    #[serde(default)]
+    pub nbf: Option<u64>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl SecretAttributes {
+    /// Whether the secret can be used at `now` (unix seconds)
+    ///
+    /// A disabled secret is reported as disabled regardless of its dates.
+    pub fn status(&self, now: u64) -> SecretStatus {
+        if !self.enabled {
+            SecretStatus::Disabled
+        } else if self.exp.is_some_and(|exp| exp <= now) {
+            SecretStatus::Expired
+        } else if self.nbf.is_some_and(|nbf| nbf > now) {
+            SecretStatus::Pending
+        } else {
+            SecretStatus::Active
+        }
+    }
+}
+
+/// Usability of a secret based on its enabled flag and validity window
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SecretStatus {
+    Active,
+    Expired,
+    /// Not valid yet (`nbf` is in the future)
+    Pending,
+    Disabled,
+}
+
+/// Secret metadata with its computed status
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SecretWithStatus {
+    #[serde(flatten)]
+    pub secret: Secret,
+    pub status: SecretStatus,
+}
+
+/// How a deleted secret can be recovered, and whether it can be purged early
+///
+/// Values match Azure's `DeletionRecoveryLevel`; unrecognised values
+/// deserialize to `Unknown` instead of failing the whole response, as does a
+/// missing value.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RecoveryLevel {
+    /// Deletion is permanent
+    Purgeable,
+    /// Soft-deleted, and can be purged before the retention period ends
+    #[serde(rename = "Recoverable+Purgeable")]
+    RecoverablePurgeable,
+    /// Soft-deleted with purge protection
+    Recoverable,
+    /// Soft-deleted with purge protection, and the subscription can't be cancelled meanwhile
+    #[serde(rename = "Recoverable+ProtectedSubscription")]
+    RecoverableProtectedSubscription,
+    /// Soft-deleted with a custom retention period, and can be purged early
+    #[serde(rename = "CustomizedRecoverable+Purgeable")]
+    CustomizedRecoverablePurgeable,
+    /// Soft-deleted with a custom retention period and purge protection
+    CustomizedRecoverable,
+    /// Custom retention, purge protection and a protected subscription
+    #[serde(rename = "CustomizedRecoverable+ProtectedSubscription")]
+    CustomizedRecoverableProtectedSubscription,
+    #[default]
+    #[serde(other)]
+    Unknown,
+}
+
+impl RecoveryLevel {
+    /// The level as Azure spells it
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RecoveryLevel::Purgeable => "Purgeable",
+            RecoveryLevel::RecoverablePurgeable => "Recoverable+Purgeable",
+            RecoveryLevel::Recoverable => "Recoverable",
+            RecoveryLevel::RecoverableProtectedSubscription => "Recoverable+ProtectedSubscription",
+            RecoveryLevel::CustomizedRecoverablePurgeable => "CustomizedRecoverable+Purgeable",
+            RecoveryLevel::CustomizedRecoverable => "CustomizedRecoverable",
+            RecoveryLevel::CustomizedRecoverableProtectedSubscription => {
+                "CustomizedRecoverable+ProtectedSubscription"
+            }
+            RecoveryLevel::Unknown => "Unknown",
+        }
+    }
+
+    /// Whether deleted secrets can be recovered at all
+    pub fn is_recoverable(&self) -> bool {
+        !matches!(self, RecoveryLevel::Purgeable | RecoveryLevel::Unknown)
+    }
+
+    /// Whether deleted secrets can be purged before the retention period ends
+    pub fn is_purgeable(&self) -> bool {
+        matches!(
+            self,
+            RecoveryLevel::Purgeable
+                | RecoveryLevel::RecoverablePurgeable
+                | RecoveryLevel::CustomizedRecoverablePurgeable
+        )
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -24,6 +164,47 @@ pub struct SecretBundle {
     pub id: String,
     pub attributes: SecretAttributes,
     pub value: String,
+    #[serde(default)]
+    pub content_type: Option<String>,
+    #[serde(default)]
+    pub tags: HashMap<String, String>,
+}
+
+/// What differs between two versions of a secret
+///
+/// Values are only compared, never included, so the diff is safe to log.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SecretVersionDiff {
+    pub secret_name: String,
+    pub version_a: String,
+    pub version_b: String,
+    pub value_changed: bool,
+    /// Names of the differing fields, e.g. `enabled`, `exp`, `contentType` or `tags`
+    pub attributes_changed: Vec<String>,
+    /// Creation time of version A (unix seconds)
+    pub a_created: u64,
+    /// Creation time of version B (unix seconds)
+    pub b_created: u64,
+}
+
+/// A fetched secret, flagged if its value changed since the user last viewed it
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ViewedSecret {
+    #[serde(flatten)]
+    pub secret: SecretBundle,
+    /// Always false for the first view and for specific versions
+    pub changed_since_last_view: bool,
+}
+
+/// One page of a vault's secrets list
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SecretsPage {
+    pub secrets: Vec<Secret>,
+    /// Opaque token for the next page, `None` on the last page
+    pub continuation_token: Option<String>,
 }
 
 /// A deleted secret item returned by the list deleted secrets API.
@@ -33,7 +214,539 @@ pub struct SecretBundle {
 pub struct DeletedSecretItem {
     pub id: String,
     pub attributes: SecretAttributes,
+    #[serde(default)]
     pub recovery_id: Option<String>,
-    pub deleted_date: Option<u64>,
-    pub scheduled_purge_date: Option<u64>,
-}
\ No newline at end of file
+    #[serde(default, with = "chrono::serde::ts_seconds_option")]
+    pub deleted_date: Option<DateTime<Utc>>,
+    #[serde(default, with = "chrono::serde::ts_seconds_option")]
+    pub scheduled_purge_date: Option<DateTime<Utc>>,
+}
+
+/// A deleted secret returned by the delete secret API.
+///
+/// Same deletion fields as `DeletedSecretItem`, but the ID includes the version.
+/// Azure may include the value; it isn't kept.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeletedSecretBundle {
+    pub id: String,
+    pub attributes: SecretAttributes,
+    #[serde(default)]
+    pub content_type: Option<String>,
+    #[serde(default)]
+    pub recovery_id: Option<String>,
+    #[serde(default, with = "chrono::serde::ts_seconds_option")]
+    pub deleted_date: Option<DateTime<Utc>>,
+    #[serde(default, with = "chrono::serde::ts_seconds_option")]
+    pub scheduled_purge_date: Option<DateTime<Utc>>,
+}
+
+impl DeletedSecretItem {
+    /// Whole days left until the secret is permanently deleted, if a purge is scheduled
+    ///
+    /// Zero once the purge date has passed.
+    pub fn days_until_purge(&self, now: DateTime<Utc>) -> Option<i64> {
+        self.scheduled_purge_date
+            .map(|purge_date| (purge_date - now).num_days().max(0))
+    }
+}
+
+impl From<DeletedSecretBundle> for DeletedSecretItem {
+    /// Drops the version from the ID, so it matches the deleted secrets list
+    fn from(bundle: DeletedSecretBundle) -> Self {
+        let id = match bundle.id.split_once("/secrets/") {
+            Some((base, rest)) => {
+                format!("{}/secrets/{}", base, rest.split('/').next().unwrap_or(rest))
+            }
+            None => bundle.id,
+        };
+
+        DeletedSecretItem {
+            id,
+            attributes: bundle.attributes,
+            recovery_id: bundle.recovery_id,
+            deleted_date: bundle.deleted_date,
+            scheduled_purge_date: bundle.scheduled_purge_date,
+        }
+    }
+}
+
+/// A just-deleted secret with what can still be done about it
+///
+/// Lets the UI say "recoverable until X, permanently deleted on Y" instead
+/// of leaving users to discover purge protection later.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeletedSecretInfo {
+    #[serde(flatten)]
+    pub deleted: DeletedSecretItem,
+    /// Can be recovered until `scheduledPurgeDate`
+    pub recoverable: bool,
+    /// Can be permanently deleted before `scheduledPurgeDate`
+    pub purgeable: bool,
+    /// Recoverable but not purgeable: the name stays taken until the purge date
+    pub purge_protected: bool,
+    /// Whole days until the secret is permanently deleted, if a purge is scheduled
+    pub days_until_purge: Option<i64>,
+}
+
+impl DeletedSecretInfo {
+    /// Derive the post-deletion options from the secret's recovery level
+    pub fn new(deleted: DeletedSecretItem, now: DateTime<Utc>) -> Self {
+        let recovery_level = deleted.attributes.recovery_level;
+        let recoverable = recovery_level.is_recoverable();
+        let purgeable = recovery_level.is_purgeable();
+
+        DeletedSecretInfo {
+            recoverable,
+            purgeable,
+            purge_protected: recoverable && !purgeable,
+            days_until_purge: deleted.days_until_purge(now),
+            deleted,
+        }
+    }
+}
+
+/// Optional attributes, tags and content type to set when writing a secret
+///
+/// Fields left out keep Key Vault's defaults: enabled, no validity window,
+/// no tags and no content type.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SecretWriteOptions {
+    /// Whether the secret can be read
+    #[serde(default)]
+    pub enabled: Option<bool>,
+    /// Expiry time (unix seconds)
+    #[serde(default)]
+    pub exp: Option<u64>,
+    /// Not-before time (unix seconds)
+    #[serde(default)]
+    pub nbf: Option<u64>,
+    #[serde(default)]
+    pub tags: Option<HashMap<String, String>>,
+    #[serde(default)]
+    pub content_type: Option<String>,
+}
+
+/// A secret to create as part of a batch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NewSecret {
+    pub name: String,
+    pub value: String,
+    #[serde(default)]
+    pub tags: Option<HashMap<String, String>>,
+}
+
+/// Outcome of creating one secret in a batch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SecretCreateResult {
+    pub name: String,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+/// Outcome of enabling or disabling one secret in a batch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SecretEnabledResult {
+    pub name: String,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+/// Outcome of warming the secret value cache for a set of secrets
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrefetchResult {
+    pub succeeded: usize,
+    pub failed: usize,
+}
+
+/// Result of checking that every secret in a vault can be read
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadAccessReport {
+    /// Enabled secrets that were read
+    pub checked: usize,
+    pub readable: usize,
+    /// Reads that failed for a reason other than 403
+    pub failed: usize,
+    /// Disabled secrets, which can't be read by anyone
+    pub skipped_disabled: usize,
+    /// Names of the secrets that returned 403
+    pub forbidden: Vec<String>,
+}
+
+/// The value of one secret name in one vault, for side-by-side comparison
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SecretLocation {
+    pub vault_uri: String,
+    /// False when the vault has no secret with this name
+    pub found: bool,
+    pub value: Option<String>,
+    /// Set when the lookup failed for a reason other than the secret not existing
+    pub error: Option<String>,
+}
+
+/// Secret payloads shaped like Azure's responses, shared by the secret module tests
+#[cfg(test)]
+pub(crate) mod fixtures {
+    use super::Secret;
+    use serde_json::{json, Value};
+
+    /// ID of secret `name` in the test vault, versioned if `version` is given
+    pub(crate) fn secret_id(name: &str, version: Option<&str>) -> String {
+        let id = format!("https://vault.vault.azure.net/secrets/{}", name);
+        match version {
+            Some(version) => format!("{}/{}", id, version),
+            None => id,
+        }
+    }
+
+    /// An enabled secret item with the given ID and recovery level
+    ///
+    /// Tests add or override fields before deserializing it.
+    pub(crate) fn secret_json(id: &str, recovery_level: &str) -> Value {
+        json!({
+            "id": id,
+            "attributes": {
+                "enabled": true,
+                "created": 1700000000,
+                "updated": 1700000000,
+                "recoveryLevel": recovery_level,
+                "recoverableDays": 90
+            }
+        })
+    }
+
+    /// An enabled, recoverable secret named `name` without tags
+    pub(crate) fn secret(name: &str) -> Secret {
+        serde_json::from_value(secret_json(&secret_id(name, None), "Recoverable")).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::fixtures::{secret, secret_id, secret_json};
+    use super::*;
+
+    #[test]
+    fn test_recovery_level_known_values() {
+        let cases = [
+            ("Purgeable", RecoveryLevel::Purgeable),
+            ("Recoverable+Purgeable", RecoveryLevel::RecoverablePurgeable),
+            ("Recoverable", RecoveryLevel::Recoverable),
+            (
+                "Recoverable+ProtectedSubscription",
+                RecoveryLevel::RecoverableProtectedSubscription,
+            ),
+            (
+                "CustomizedRecoverable+Purgeable",
+                RecoveryLevel::CustomizedRecoverablePurgeable,
+            ),
+            ("CustomizedRecoverable", RecoveryLevel::CustomizedRecoverable),
+            (
+                "CustomizedRecoverable+ProtectedSubscription",
+                RecoveryLevel::CustomizedRecoverableProtectedSubscription,
+            ),
+        ];
+
+        for (raw, expected) in cases {
+            let parsed: RecoveryLevel = serde_json::from_str(&format!("\"{}\"", raw)).unwrap();
+            assert_eq!(parsed, expected);
+            assert_eq!(parsed.as_str(), raw);
+            assert_eq!(serde_json::to_string(&parsed).unwrap(), format!("\"{}\"", raw));
+        }
+    }
+
+    #[test]
+    fn test_secret_name_from_id() {
+        assert_eq!(secret("db-password").name(), "db-password");
+    }
+
+    #[test]
+    fn test_secret_name_from_versioned_id() {
+        let id = secret_id("db-password", Some("4387e9f3d6e1"));
+        let secret: Secret = serde_json::from_value(secret_json(&id, "Recoverable")).unwrap();
+
+        assert_eq!(secret.name(), "db-password");
+    }
+
+    #[test]
+    fn test_secret_list_item_payload() {
+        // A list item as returned by GET {vault}/secrets
+        let secret: Secret = serde_json::from_str(
+            r#"{
+                "id": "https://vault.vault.azure.net/secrets/db-password",
+                "attributes": {
+                    "enabled": true,
+                    "created": 1700000000,
+                    "updated": 1700000100,
+                    "recoveryLevel": "Recoverable+Purgeable",
+                    "recoverableDays": 90
+                },
+                "contentType": "text/plain",
+                "tags": { "env": "prod" }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(secret.attributes.updated, 1_700_000_100);
+        assert_eq!(secret.attributes.recovery_level, RecoveryLevel::RecoverablePurgeable);
+        assert_eq!(secret.attributes.recoverable_days, 90);
+    }
+
+    #[test]
+    fn test_secret_bundle_payload() {
+        // A bundle as returned by GET {vault}/secrets/{name}/{version}
+        let bundle: SecretBundle = serde_json::from_str(
+            r#"{
+                "value": "hunter2",
+                "contentType": "text/plain",
+                "id": "https://vault.vault.azure.net/secrets/db-password/4387e9f3d6e1",
+                "attributes": {
+                    "enabled": true,
+                    "created": 1700000000,
+                    "updated": 1700000000,
+                    "recoveryLevel": "Recoverable+Purgeable",
+                    "recoverableDays": 7
+                },
+                "tags": {}
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(bundle.value, "hunter2");
+        assert_eq!(bundle.content_type.as_deref(), Some("text/plain"));
+        assert_eq!(bundle.attributes.recoverable_days, 7);
+    }
+
+    #[test]
+    fn test_recovered_secret_payload() {
+        // The recover response has a versioned ID, no value and no recoverableDays
+        let secret: Secret = serde_json::from_str(
+            r#"{
+                "id": "https://vault.vault.azure.net/secrets/db-password/4387e9f3d6e1",
+                "attributes": {
+                    "enabled": true,
+                    "created": 1700000000,
+                    "updated": 1700000000,
+                    "recoveryLevel": "Recoverable+Purgeable"
+                }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(secret.name(), "db-password");
+        assert_eq!(secret.attributes.recoverable_days, 0);
+    }
+
+    #[test]
+    fn test_deleted_secret_bundle_payload() {
+        // A deleted bundle as returned by DELETE {vault}/secrets/{name}
+        let deleted: DeletedSecretBundle = serde_json::from_str(
+            r#"{
+                "recoveryId": "https://vault.vault.azure.net/deletedsecrets/db-password",
+                "deletedDate": 1700000000,
+                "scheduledPurgeDate": 1707776000,
+                "id": "https://vault.vault.azure.net/secrets/db-password/4387e9f3d6e1",
+                "attributes": {
+                    "enabled": true,
+                    "created": 1690000000,
+                    "updated": 1690000000,
+                    "recoveryLevel": "Recoverable+Purgeable",
+                    "recoverableDays": 90
+                }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            deleted.recovery_id.as_deref(),
+            Some("https://vault.vault.azure.net/deletedsecrets/db-password")
+        );
+        assert_eq!(deleted.deleted_date.unwrap().timestamp(), 1_700_000_000);
+        assert_eq!(deleted.scheduled_purge_date.unwrap().timestamp(), 1_707_776_000);
+    }
+
+    #[test]
+    fn test_deleted_secret_item_without_soft_delete_fields() {
+        // Items in vaults without soft-delete have no recovery fields
+        let item: DeletedSecretItem = serde_json::from_str(
+            r#"{
+                "id": "https://vault.vault.azure.net/secrets/db-password",
+                "attributes": {
+                    "enabled": true,
+                    "created": 1700000000,
+                    "updated": 1700000000,
+                    "recoveryLevel": "Purgeable"
+                }
+            }"#,
+        )
+        .unwrap();
+
+        assert!(item.recovery_id.is_none());
+        assert!(item.scheduled_purge_date.is_none());
+        assert_eq!(item.days_until_purge(chrono::Utc::now()), None);
+    }
+
+    #[test]
+    fn test_secret_status() {
+        let attributes = |enabled: bool, exp: Option<u64>, nbf: Option<u64>| SecretAttributes {
+            enabled,
+            created: 0,
+            updated: 0,
+            recovery_level: RecoveryLevel::Recoverable,
+            recoverable_days: 90,
+            exp,
+            nbf,
+        };
+        let now = 1_700_000_000;
+
+        assert_eq!(attributes(true, None, None).status(now), SecretStatus::Active);
+        assert_eq!(
+            attributes(true, Some(now + 1), Some(now - 1)).status(now),
+            SecretStatus::Active
+        );
+        assert_eq!(attributes(true, Some(now), None).status(now), SecretStatus::Expired);
+        assert_eq!(attributes(true, None, Some(now + 1)).status(now), SecretStatus::Pending);
+        assert_eq!(
+            attributes(false, Some(now - 1), None).status(now),
+            SecretStatus::Disabled
+        );
+    }
+
+    #[test]
+    fn test_secret_attributes_parse_validity_window() {
+        let attributes: SecretAttributes = serde_json::from_str(
+            r#"{
+                "enabled": true,
+                "created": 1700000000,
+                "updated": 1700000000,
+                "recoveryLevel": "Recoverable",
+                "recoverableDays": 90,
+                "exp": 1800000000,
+                "nbf": 1600000000
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(attributes.exp, Some(1_800_000_000));
+        assert_eq!(attributes.nbf, Some(1_600_000_000));
+    }
+
+    #[test]
+    fn test_secret_attributes_without_optional_fields() {
+        // Only the fields Azure always returns
+        let attributes: SecretAttributes = serde_json::from_str(
+            r#"{
+                "created": 1700000000,
+                "updated": 1700000500
+            }"#,
+        )
+        .unwrap();
+
+        assert!(attributes.enabled);
+        assert_eq!(attributes.created, 1_700_000_000);
+        assert_eq!(attributes.updated, 1_700_000_500);
+        assert_eq!(attributes.recovery_level, RecoveryLevel::Unknown);
+        assert_eq!(attributes.recoverable_days, 0);
+        assert_eq!(attributes.exp, None);
+        assert_eq!(attributes.nbf, None);
+        assert_eq!(attributes.status(1_700_000_000), SecretStatus::Active);
+    }
+
+    #[test]
+    fn test_secret_attributes_disabled() {
+        let attributes: SecretAttributes = serde_json::from_str(
+            r#"{
+                "enabled": false,
+                "created": 1700000000,
+                "updated": 1700000000,
+                "recoveryLevel": "Purgeable"
+            }"#,
+        )
+        .unwrap();
+
+        assert!(!attributes.enabled);
+        assert_eq!(attributes.recovery_level, RecoveryLevel::Purgeable);
+    }
+
+    #[test]
+    fn test_recovery_level_unknown_value() {
+        let parsed: RecoveryLevel = serde_json::from_str("\"SomethingNew\"").unwrap();
+        assert_eq!(parsed, RecoveryLevel::Unknown);
+        assert!(!parsed.is_recoverable());
+    }
+
+    #[test]
+    fn test_deleted_secret_dates() {
+        let item: DeletedSecretItem = serde_json::from_str(
+            r#"{
+                "id": "https://vault.vault.azure.net/secrets/db-password",
+                "attributes": {
+                    "enabled": true,
+                    "created": 1700000000,
+                    "updated": 1700000000,
+                    "recoveryLevel": "Recoverable+Purgeable",
+                    "recoverableDays": 90
+                },
+                "recoveryId": "https://vault.vault.azure.net/deletedsecrets/db-password",
+                "deletedDate": 1700000000,
+                "scheduledPurgeDate": 1707776000
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            item.recovery_id.as_deref(),
+            Some("https://vault.vault.azure.net/deletedsecrets/db-password")
+        );
+        let deleted = item.deleted_date.unwrap();
+        assert_eq!(deleted.timestamp(), 1_700_000_000);
+        assert_eq!(item.days_until_purge(deleted), Some(90));
+        assert_eq!(
+            item.days_until_purge(item.scheduled_purge_date.unwrap() + chrono::Duration::days(1)),
+            Some(0)
+        );
+    }
+
+    #[test]
+    fn test_deleted_secret_info() {
+        let bundle = |recovery_level: &str| -> DeletedSecretBundle {
+            let id = secret_id("db-password", Some("4387e9f3d6e1"));
+            let recovery_id = "https://vault.vault.azure.net/deletedsecrets/db-password";
+            let mut payload = secret_json(&id, recovery_level);
+            payload["recoveryId"] = serde_json::json!(recovery_id);
+            payload["deletedDate"] = serde_json::json!(1_700_000_000);
+            payload["scheduledPurgeDate"] = serde_json::json!(1_707_776_000);
+            serde_json::from_value(payload).unwrap()
+        };
+        let deleted_at = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+
+        let protected = DeletedSecretInfo::new(bundle("Recoverable").into(), deleted_at);
+        assert_eq!(protected.deleted.id, "https://vault.vault.azure.net/secrets/db-password");
+        assert!(protected.recoverable);
+        assert!(!protected.purgeable);
+        assert!(protected.purge_protected);
+        assert_eq!(protected.days_until_purge, Some(90));
+
+        let purgeable =
+            DeletedSecretInfo::new(bundle("Recoverable+Purgeable").into(), deleted_at);
+        assert!(purgeable.recoverable);
+        assert!(purgeable.purgeable);
+        assert!(!purgeable.purge_protected);
+
+        let permanent = DeletedSecretInfo::new(bundle("Purgeable").into(), deleted_at);
+        assert!(!permanent.recoverable);
+        assert!(!permanent.purge_protected);
+
+        let json = serde_json::to_value(&protected).unwrap();
+        assert_eq!(json["scheduledPurgeDate"], 1_707_776_000);
+        assert_eq!(json["purgeProtected"], true);
+    }
+}
@@ -0,0 +1,107 @@
+//! Secret name validation - catch names Azure would reject before sending a request
+
+use regex::Regex;
+
+/// Maximum length of a Key Vault secret name
+pub const MAX_SECRET_NAME_LENGTH: usize = 127;
+
+lazy_static::lazy_static! {
+    /// Characters Key Vault allows in a secret name
+    static ref SECRET_NAME_PATTERN: Regex = Regex::new("^[0-9a-zA-Z-]+$").unwrap();
+}
+
+/// Check a name against Key Vault's rules: 1-127 of `[0-9a-zA-Z-]`.
+///
+/// # Errors
+///
+/// Returns a message naming the problem, e.g. the first character that isn't allowed.
+pub fn validate_secret_name(name: &str) -> Result<(), String> {
+    if name.is_empty() {
+        return Err("Invalid secret name: the name is empty".to_string());
+    }
+
+    let length = name.chars().count();
+    if length > MAX_SECRET_NAME_LENGTH {
+        return Err(format!(
+            "Invalid secret name: {} characters long, the maximum is {}",
+            length, MAX_SECRET_NAME_LENGTH
+        ));
+    }
+
+    if !SECRET_NAME_PATTERN.is_match(name) {
+        let invalid = name
+            .chars()
+            .find(|c| !c.is_ascii_alphanumeric() && *c != '-')
+            .unwrap_or_default();
+        return Err(format!(
+            "Invalid secret name '{}': '{}' is not allowed, only letters, digits and '-' are",
+            name, invalid
+        ));
+    }
+
+    Ok(())
+}
+
+/// Validate a batch of names, reporting every invalid one.
+///
+/// # Errors
+///
+/// Returns the messages of all invalid names, one per line.
+pub fn validate_secret_names<'a>(names: impl IntoIterator<Item = &'a str>) -> Result<(), String> {
+    let problems: Vec<String> = names
+        .into_iter()
+        .filter_map(|name| validate_secret_name(name).err())
+        .collect();
+
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(problems.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_valid_names() {
+        for name in ["db-password", "API-KEY-2", "a"] {
+            assert_eq!(validate_secret_name(name), Ok(()), "{}", name);
+        }
+        assert!(validate_secret_name(&"a".repeat(MAX_SECRET_NAME_LENGTH)).is_ok());
+    }
+
+    #[test]
+    fn test_underscores_rejected() {
+        let error = validate_secret_name("DB_PASSWORD").unwrap_err();
+        assert!(error.contains("'DB_PASSWORD'"), "{}", error);
+        assert!(error.contains("'_' is not allowed"), "{}", error);
+    }
+
+    #[test]
+    fn test_empty_name_rejected() {
+        assert!(validate_secret_name("").unwrap_err().contains("empty"));
+    }
+
+    #[test]
+    fn test_too_long_name_rejected() {
+        let error = validate_secret_name(&"a".repeat(MAX_SECRET_NAME_LENGTH + 1)).unwrap_err();
+        assert!(error.contains("128 characters"), "{}", error);
+    }
+
+    #[test]
+    fn test_non_ascii_letters_rejected() {
+        assert!(validate_secret_name("wachtwoord-é").is_err());
+        assert!(validate_secret_name("with space").is_err());
+    }
+
+    #[test]
+    fn test_batch_reports_every_invalid_name() {
+        assert_eq!(validate_secret_names(["ok", "also-ok"]), Ok(()));
+
+        let error = validate_secret_names(["ok", "not_ok", "also.bad"]).unwrap_err();
+        assert_eq!(error.lines().count(), 2);
+        assert!(error.contains("not_ok") && error.contains("also.bad"));
+    }
+}
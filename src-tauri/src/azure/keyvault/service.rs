@@ -1,16 +1,23 @@
 //! Key Vault service - business logic for Azure Key Vault operations
 
 use anyhow::{Context, Result};
-use log::{debug, error, info};
+use log::{debug, error, info, warn};
 
 use crate::azure::auth::token::{get_token_for_scope, get_token_from_state};
 use crate::azure::http::{fetch_all_paginated, AzureHttpClient, AzureHttpError};
 use crate::azure::resource_group::service::get_resource_group_by_name;
 use crate::azure::subscription::service::get_subscription;
-use crate::cache::AZURE_CACHE;
-use crate::config::{urls, KEYVAULT_SCOPE, MANAGEMENT_SCOPE};
+use crate::cache::azure_cache;
+use crate::config::{keyvault_scope, management_scope, urls};
 
-use super::types::{CreateVaultRequest, KeyVault, KeyVaultAccessCheck, Properties, Sku};
+use super::types::{
+    AccessPolicy, CheckNameAvailabilityRequest, CheckNameAvailabilityResult,
+    CreateKeyVaultOptions, CreateVaultRequest, KeyVault, KeyVaultAccessCheck, KeyVaultDeletion,
+    NetworkRuleSet, Permissions, Properties, Sku, VaultPatchProperties, VaultPatchRequest,
+};
+
+/// Soft-delete retention Azure applies when a vault doesn't set one
+const DEFAULT_SOFT_DELETE_RETENTION_DAYS: u8 = 90;
 
 /// Fetch all Key Vaults for a specific subscription.
 ///
@@ -45,8 +52,8 @@ async fn get_keyvaults_internal(subscription_id: &str) -> Result<Vec<KeyVault>>
 
     debug!("Successfully retrieved authentication token");
 
-    let client =
-        AzureHttpClient::with_token(&token).context("Failed to create HTTP client with token")?;
+    let client = AzureHttpClient::with_scoped_token(&token, management_scope())
+        .context("Failed to create HTTP client with token")?;
 
     let url = urls::keyvaults(subscription_id);
     debug!("Calling Azure API: {}", url);
@@ -80,7 +87,7 @@ pub async fn check_keyvault_access(keyvault_uri: &str) -> Result<KeyVaultAccessC
     info!("Checking access to Key Vault");
 
     // Try to get a token for the Key Vault data plane
-    let token = match get_token_for_scope(KEYVAULT_SCOPE).await {
+    let token = match get_token_for_scope(keyvault_scope()).await {
         Ok(t) => {
             debug!("Successfully obtained token for Key Vault access");
             t
@@ -96,7 +103,7 @@ pub async fn check_keyvault_access(keyvault_uri: &str) -> Result<KeyVaultAccessC
         }
     };
 
-    let client = match AzureHttpClient::with_token(&token) {
+    let client = match AzureHttpClient::with_scoped_token(&token, keyvault_scope()) {
         Ok(c) => c,
         Err(e) => {
             // Span::current().record("has_access", false);
@@ -137,6 +144,94 @@ pub async fn check_keyvault_access(keyvault_uri: &str) -> Result<KeyVaultAccessC
     }
 }
 
+/// Validate a Key Vault name against Azure's naming rules.
+///
+/// Names must be 3-24 characters long, contain only alphanumerics and hyphens,
+/// start with a letter, end with a letter or digit, and not contain consecutive hyphens.
+pub fn validate_keyvault_name(name: &str) -> Result<(), String> {
+    if name.len() < 3 || name.len() > 24 {
+        return Err(format!(
+            "Key Vault name '{}' must be between 3 and 24 characters long",
+            name
+        ));
+    }
+    if !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+        return Err(format!(
+            "Key Vault name '{}' may only contain letters, digits and hyphens",
+            name
+        ));
+    }
+    if !name.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        return Err(format!("Key Vault name '{}' must start with a letter", name));
+    }
+    if name.ends_with('-') {
+        return Err(format!(
+            "Key Vault name '{}' must end with a letter or digit",
+            name
+        ));
+    }
+    if name.contains("--") {
+        return Err(format!(
+            "Key Vault name '{}' must not contain consecutive hyphens",
+            name
+        ));
+    }
+    Ok(())
+}
+
+/// Check whether a Key Vault name is globally available.
+///
+/// # Arguments
+///
+/// * `subscription_id` - The Azure subscription ID to issue the check from
+/// * `keyvault_name` - The name to check
+///
+/// # Returns
+///
+/// The availability result, including Azure's reason when the name is taken.
+pub async fn check_keyvault_name_availability(
+    subscription_id: &str,
+    keyvault_name: &str,
+) -> Result<CheckNameAvailabilityResult, String> {
+    check_keyvault_name_availability_internal(subscription_id, keyvault_name)
+        .await
+        .map_err(|e| {
+            error!("Failed to check keyvault name availability: {}", e);
+            e.to_string()
+        })
+}
+
+async fn check_keyvault_name_availability_internal(
+    subscription_id: &str,
+    keyvault_name: &str,
+) -> Result<CheckNameAvailabilityResult> {
+    let url = urls::keyvault_name_availability(subscription_id);
+
+    let token = get_token_for_scope(management_scope())
+        .await
+        .map_err(|e| anyhow::anyhow!(e))
+        .context("Failed to retrieve management token")?;
+
+    let client = AzureHttpClient::with_scoped_token(&token, management_scope())
+        .context("Failed to create HTTP client with token")?;
+
+    let body = CheckNameAvailabilityRequest {
+        name: keyvault_name.to_string(),
+        r#type: "Microsoft.KeyVault/vaults".to_string(),
+    };
+
+    let result: CheckNameAvailabilityResult = client
+        .post(&url, &body)
+        .await
+        .with_context(|| format!("Failed to check availability of '{}'", keyvault_name))?;
+
+    debug!(
+        "Name '{}' available: {}",
+        keyvault_name, result.name_available
+    );
+    Ok(result)
+}
+
 /// Create a new Key Vault.
 ///
 /// # Arguments
@@ -144,6 +239,7 @@ pub async fn check_keyvault_access(keyvault_uri: &str) -> Result<KeyVaultAccessC
 /// * `subscription_id` - The Azure subscription ID
 /// * `resource_group` - The resource group name
 /// * `keyvault_name` - The name for the new Key Vault
+/// * `options` - Optional vault settings (SKU, RBAC, soft delete, ...)
 ///
 /// # Returns
 ///
@@ -152,6 +248,7 @@ pub async fn check_keyvault_access(keyvault_uri: &str) -> Result<KeyVaultAccessC
 /// # Errors
 ///
 /// This function will return an error if:
+/// - The name breaks Azure's naming rules (see `validate_keyvault_name`)
 /// - The user is not authenticated
 /// - The resource group doesn't exist
 /// - The API request fails
@@ -159,8 +256,12 @@ pub async fn create_keyvault(
     subscription_id: &str,
     resource_group: &str,
     keyvault_name: &str,
+    options: &CreateKeyVaultOptions,
 ) -> Result<KeyVault, String> {
-    create_keyvault_internal(subscription_id, resource_group, keyvault_name)
+    // Same check as the preview, so an invalid name fails before any request
+    validate_keyvault_name(keyvault_name)?;
+
+    create_keyvault_internal(subscription_id, resource_group, keyvault_name, options)
         .await
         .map_err(|e| {
             error!("Failed to create keyvault: {}", e);
@@ -179,23 +280,80 @@ async fn create_keyvault_internal(
     subscription_id: &str,
     resource_group: &str,
     keyvault_name: &str,
+    options: &CreateKeyVaultOptions,
 ) -> Result<KeyVault> {
     let url = urls::keyvault(subscription_id, resource_group, keyvault_name);
 
-    let token = get_token_for_scope(MANAGEMENT_SCOPE)
+    let token = get_token_for_scope(management_scope())
         .await
         .map_err(|e| anyhow::anyhow!(e))
         .context("Failed to retrieve management token")?;
 
-    let client =
-        AzureHttpClient::with_token(&token).context("Failed to create HTTP client with token")?;
+    let client = AzureHttpClient::with_scoped_token(&token, management_scope())
+        .context("Failed to create HTTP client with token")?;
+
+    let body = build_create_vault_request(subscription_id, resource_group, options).await?;
+
+    info!("Creating keyvault");
+
+    let created_vault: KeyVault = client
+        .put(&url, &body)
+        .await
+        .with_context(|| format!("Failed to create keyvault '{}'", keyvault_name))?;
+
+    info!(
+        "Keyvault created successfully with id: {}",
+        created_vault.id
+    );
+
+    Ok(created_vault)
+}
+
+/// Build the exact request body `create_keyvault` would send, without sending it.
+///
+/// # Arguments
+///
+/// * `subscription_id` - The Azure subscription ID
+/// * `resource_group` - The resource group name
+/// * `keyvault_name` - The name for the new Key Vault
+/// * `options` - Optional vault settings (SKU, RBAC, soft delete, ...)
+///
+/// # Returns
+///
+/// The request body as pretty-printed JSON.
+pub async fn preview_create_keyvault(
+    subscription_id: &str,
+    resource_group: &str,
+    keyvault_name: &str,
+    options: &CreateKeyVaultOptions,
+) -> Result<String, String> {
+    validate_keyvault_name(keyvault_name)?;
+
+    build_create_vault_request(subscription_id, resource_group, options)
+        .await
+        .and_then(|body| {
+            serde_json::to_string_pretty(&body).context("Failed to serialize create vault request")
+        })
+        .map_err(|e| {
+            error!("Failed to preview keyvault creation: {}", e);
+            e.to_string()
+        })
+}
 
+/// Look up the resource group location and subscription tenant, then build the create body.
+///
+/// Shared by `create_keyvault` and `preview_create_keyvault` so the preview can't drift.
+async fn build_create_vault_request(
+    subscription_id: &str,
+    resource_group: &str,
+    options: &CreateKeyVaultOptions,
+) -> Result<CreateVaultRequest> {
     let rg = get_resource_group_by_name(subscription_id, resource_group)
         .await
         .map_err(|e| anyhow::anyhow!(e))
         .with_context(|| format!("Failed to get resource group '{}'", resource_group))?;
 
-    let subscription = AZURE_CACHE
+    let subscription = azure_cache()
         .get_subscription_or_load(subscription_id, || async {
             get_subscription(subscription_id).await
         })
@@ -208,14 +366,47 @@ async fn create_keyvault_internal(
         ));
     }
 
-    let body = CreateVaultRequest {
-        location: rg.location,
+    Ok(create_vault_request_body(
+        rg.location,
+        subscription?.tenant_id.to_string(),
+        options,
+    ))
+}
+
+/// Build the create vault request body from already-resolved inputs
+fn create_vault_request_body(
+    location: String,
+    tenant_id: String,
+    options: &CreateKeyVaultOptions,
+) -> CreateVaultRequest {
+    let sku = match &options.sku_name {
+        Some(name) => Sku {
+            name: name.to_lowercase(),
+            ..Sku::new()
+        },
+        None => Sku::new(),
+    };
+    let access_policies = options
+        .secret_access_object_id
+        .iter()
+        .map(|object_id| AccessPolicy {
+            tenant_id: tenant_id.clone(),
+            object_id: object_id.clone(),
+            permissions: Permissions {
+                secrets: ["get", "list", "set"].map(String::from).to_vec(),
+                ..Permissions::default()
+            },
+        })
+        .collect();
+
+    CreateVaultRequest {
+        location,
         properties: Properties {
-            access_policies: vec![],
+            access_policies,
             create_mode: None,
-            enable_purge_protection: None,
-            enable_rbac_authorization: false,
-            enable_soft_delete: false,
+            enable_purge_protection: options.enable_purge_protection,
+            enable_rbac_authorization: options.enable_rbac_authorization,
+            enable_soft_delete: options.enable_soft_delete,
             enabled_for_deployment: false,
             enabled_for_disk_encryption: None,
             enabled_for_template_deployment: None,
@@ -224,30 +415,20 @@ async fn create_keyvault_internal(
             private_endpoint_connections: None,
             provisioning_state: "".to_string(),
             public_network_access: "".to_string(),
-            sku: Sku::new(),
-            soft_delete_retention_in_days: None,
-            tenant_id: subscription?.tenant_id.to_string(),
+            sku,
+            soft_delete_retention_in_days: options.soft_delete_retention_in_days,
+            tenant_id,
             vault_uri: "".to_string(),
         },
-    };
-
-    info!("Creating keyvault");
-
-    let created_vault: KeyVault = client
-        .put(&url, &body)
-        .await
-        .with_context(|| format!("Failed to create keyvault '{}'", keyvault_name))?;
-
-    info!(
-        "Keyvault created successfully with id: {}",
-        created_vault.id
-    );
-
-    Ok(created_vault)
+    }
 }
 
 /// Delete an existing Key Vault.
 ///
+/// The vault is looked up first to learn its soft-delete settings: a
+/// soft-deleted vault moves to the deleted vaults, where it can be recovered
+/// or purged, and its name stays reserved until the retention period ends.
+///
 /// # Arguments
 ///
 /// * `subscription_id` - The Azure subscription ID
@@ -256,7 +437,7 @@ async fn create_keyvault_internal(
 ///
 /// # Returns
 ///
-/// Success or an error.
+/// What happened to the vault, with a message for the user, or an error.
 ///
 /// # Errors
 ///
@@ -269,7 +450,7 @@ pub async fn delete_keyvault(
     subscription_id: &str,
     resource_group: &str,
     keyvault_name: &str,
-) -> Result<(), String> {
+) -> Result<KeyVaultDeletion, String> {
     delete_keyvault_internal(subscription_id, resource_group, keyvault_name)
         .await
         .map_err(|e| {
@@ -287,16 +468,25 @@ async fn delete_keyvault_internal(
     subscription_id: &str,
     resource_group: &str,
     keyvault_name: &str,
-) -> Result<()> {
+) -> Result<KeyVaultDeletion> {
     let url = urls::keyvault(subscription_id, resource_group, keyvault_name);
 
-    let token = get_token_for_scope(MANAGEMENT_SCOPE)
+    let token = get_token_for_scope(management_scope())
         .await
         .map_err(|e| anyhow::anyhow!(e))
         .context("Failed to retrieve management token")?;
 
-    let client =
-        AzureHttpClient::with_token(&token).context("Failed to create HTTP client with token")?;
+    let client = AzureHttpClient::with_scoped_token(&token, management_scope())
+        .context("Failed to create HTTP client with token")?;
+
+    // Only used to describe the outcome, so a failed lookup doesn't stop the delete
+    let vault = match client.get::<KeyVault>(&url).await {
+        Ok(vault) => Some(vault),
+        Err(e) => {
+            warn!("Failed to look up keyvault '{}' before deleting it: {}", keyvault_name, e);
+            None
+        }
+    };
 
     info!("Deleting keyvault: {}", keyvault_name);
 
@@ -305,8 +495,426 @@ async fn delete_keyvault_internal(
         .await
         .with_context(|| format!("Failed to delete keyvault '{}'", keyvault_name))?;
 
-    info!("Keyvault deleted successfully: {}", keyvault_name);
+    let deletion = match vault {
+        Some(vault) => describe_deletion(keyvault_name, &vault.properties),
+        None => describe_unknown_deletion(keyvault_name),
+    };
+    info!("{}", deletion.message);
+
+    Ok(deletion)
+}
+
+/// Describe what deleting a vault with these properties did
+fn describe_deletion(keyvault_name: &str, properties: &Properties) -> KeyVaultDeletion {
+    let purge_protected = properties.enable_purge_protection.unwrap_or(false);
+
+    if !properties.enable_soft_delete {
+        return KeyVaultDeletion {
+            soft_deleted: false,
+            retention_days: None,
+            purge_protected,
+            message: format!("Key Vault '{}' was deleted", keyvault_name),
+        };
+    }
+
+    let retention_days = properties
+        .soft_delete_retention_in_days
+        .unwrap_or(DEFAULT_SOFT_DELETE_RETENTION_DAYS);
+    let purge_note = if purge_protected {
+        "Purge protection is on, so it can't be purged before then"
+    } else {
+        "Purge it to free the name sooner"
+    };
+
+    KeyVaultDeletion {
+        soft_deleted: true,
+        retention_days: Some(retention_days),
+        purge_protected,
+        message: format!(
+            "Key Vault '{}' was moved to the deleted vaults. It can be recovered for {} days \
+             and its name stays reserved until then. {}",
+            keyvault_name, retention_days, purge_note
+        ),
+    }
+}
+
+/// Describe a deletion when the vault's properties couldn't be read beforehand
+fn describe_unknown_deletion(keyvault_name: &str) -> KeyVaultDeletion {
+    KeyVaultDeletion {
+        soft_deleted: false,
+        retention_days: None,
+        purge_protected: false,
+        message: format!(
+            "Key Vault '{}' was deleted. Check the deleted vaults to see whether it can be \
+             recovered",
+            keyvault_name
+        ),
+    }
+}
+
+/// Delete a Key Vault identified by its full ARM resource ID.
+///
+/// The subscription, resource group and vault name are parsed from the ID,
+/// so callers holding a `KeyVault` don't have to pass them separately.
+///
+/// # Arguments
+///
+/// * `vault_id` - The full ARM resource ID of the Key Vault
+///
+/// # Returns
+///
+/// The subscription ID the vault belonged to and what happened to the vault, or an error.
+pub async fn delete_keyvault_by_id(vault_id: &str) -> Result<(String, KeyVaultDeletion), String> {
+    let vault = KeyVault {
+        id: vault_id.to_string(),
+        ..Default::default()
+    };
+    let (Some(subscription_id), Some(resource_group), Some(keyvault_name)) = (
+        vault.subscription_id(),
+        vault.resource_group(),
+        urls::arm_id_segment(vault_id, "vaults"),
+    ) else {
+        return Err(format!("'{}' is not a valid Key Vault resource ID", vault_id));
+    };
+
+    let deletion = delete_keyvault(subscription_id, resource_group, keyvault_name).await?;
+    Ok((subscription_id.to_string(), deletion))
+}
+
+/// Validate a Key Vault IP rule.
+///
+/// Key Vault accepts a single IPv4 address (`1.2.3.4`) or an IPv4 CIDR range (`1.2.3.0/24`).
+pub fn validate_ip_rule(value: &str) -> Result<(), String> {
+    let (address, prefix) = match value.split_once('/') {
+        Some((address, prefix)) => (address, Some(prefix)),
+        None => (value, None),
+    };
+
+    address
+        .parse::<std::net::Ipv4Addr>()
+        .map_err(|_| format!("'{}' is not a valid IPv4 address or CIDR range", value))?;
+
+    if let Some(prefix) = prefix {
+        match prefix.parse::<u8>() {
+            Ok(bits) if bits <= 32 => {}
+            _ => {
+                return Err(format!(
+                    "'{}' has an invalid CIDR prefix length (must be 0-32)",
+                    value
+                ))
+            }
+        }
+    }
 
     Ok(())
 }
 
+/// Set the network rules (firewall) of an existing Key Vault.
+///
+/// # Arguments
+///
+/// * `subscription_id` - The Azure subscription ID
+/// * `resource_group` - The resource group name
+/// * `keyvault_name` - The name of the Key Vault
+/// * `rules` - The complete network rule set to apply
+///
+/// # Returns
+///
+/// The updated Key Vault object or an error.
+///
+/// # Errors
+///
+/// This function will return an error if:
+/// - An IP rule is not a valid IPv4 address or CIDR range
+/// - The user is not authenticated
+/// - The API request fails
+pub async fn set_vault_network_rules(
+    subscription_id: &str,
+    resource_group: &str,
+    keyvault_name: &str,
+    rules: NetworkRuleSet,
+) -> Result<KeyVault, String> {
+    for rule in &rules.ip_rules {
+        validate_ip_rule(&rule.value)?;
+    }
+
+    let body = VaultPatchRequest {
+        properties: VaultPatchProperties {
+            network_acls: Some(rules),
+            ..Default::default()
+        },
+    };
+
+    patch_keyvault_internal(subscription_id, resource_group, keyvault_name, &body)
+        .await
+        .map_err(|e| {
+            error!("Failed to set keyvault network rules: {}", e);
+            if let Some(root_cause) = e.root_cause().downcast_ref::<AzureHttpError>() {
+                root_cause.to_string()
+            } else {
+                e.to_string()
+            }
+        })
+}
+
+/// Minimum soft-delete retention period allowed by Azure
+const MIN_SOFT_DELETE_RETENTION_DAYS: u8 = 7;
+
+/// Maximum soft-delete retention period allowed by Azure
+const MAX_SOFT_DELETE_RETENTION_DAYS: u8 = 90;
+
+/// Enable purge protection on an existing Key Vault.
+///
+/// Purge protection can only be enabled. Once on, Azure refuses any attempt
+/// to turn it off again, so there is deliberately no "disable" counterpart.
+///
+/// # Arguments
+///
+/// * `subscription_id` - The Azure subscription ID
+/// * `resource_group` - The resource group name
+/// * `keyvault_name` - The name of the Key Vault
+///
+/// # Returns
+///
+/// The updated Key Vault object or an error.
+pub async fn enable_purge_protection(
+    subscription_id: &str,
+    resource_group: &str,
+    keyvault_name: &str,
+) -> Result<KeyVault, String> {
+    let body = VaultPatchRequest {
+        properties: VaultPatchProperties {
+            enable_purge_protection: Some(true),
+            ..Default::default()
+        },
+    };
+
+    patch_keyvault_internal(subscription_id, resource_group, keyvault_name, &body)
+        .await
+        .map_err(|e| {
+            error!("Failed to enable purge protection: {}", e);
+            explain_vault_property_error(&e)
+        })
+}
+
+/// Enable soft delete on an existing Key Vault and set its retention period.
+///
+/// # Arguments
+///
+/// * `subscription_id` - The Azure subscription ID
+/// * `resource_group` - The resource group name
+/// * `keyvault_name` - The name of the Key Vault
+/// * `retention_days` - Days to retain deleted items (7-90)
+///
+/// # Returns
+///
+/// The updated Key Vault object or an error.
+pub async fn set_soft_delete_retention(
+    subscription_id: &str,
+    resource_group: &str,
+    keyvault_name: &str,
+    retention_days: u8,
+) -> Result<KeyVault, String> {
+    if !(MIN_SOFT_DELETE_RETENTION_DAYS..=MAX_SOFT_DELETE_RETENTION_DAYS).contains(&retention_days) {
+        return Err(format!(
+            "Soft delete retention must be between {} and {} days",
+            MIN_SOFT_DELETE_RETENTION_DAYS, MAX_SOFT_DELETE_RETENTION_DAYS
+        ));
+    }
+
+    let body = VaultPatchRequest {
+        properties: VaultPatchProperties {
+            enable_soft_delete: Some(true),
+            soft_delete_retention_in_days: Some(retention_days),
+            ..Default::default()
+        },
+    };
+
+    patch_keyvault_internal(subscription_id, resource_group, keyvault_name, &body)
+        .await
+        .map_err(|e| {
+            error!("Failed to set soft delete retention: {}", e);
+            explain_vault_property_error(&e)
+        })
+}
+
+/// Turn Azure's rejections of one-way vault property changes into a clear message.
+///
+/// Soft delete and purge protection can only ever be switched on. Azure reports
+/// attempts to switch them off (or to change locked settings) as generic bad requests.
+fn explain_vault_property_error(e: &anyhow::Error) -> String {
+    let message = if let Some(root_cause) = e.root_cause().downcast_ref::<AzureHttpError>() {
+        root_cause.to_string()
+    } else {
+        e.to_string()
+    };
+
+    let lower = message.to_lowercase();
+    if lower.contains("purge protection") || lower.contains("enablepurgeprotection") {
+        format!(
+            "Azure rejected the change: purge protection cannot be disabled once it is enabled. ({})",
+            message
+        )
+    } else if lower.contains("soft delete") || lower.contains("softdelete") {
+        format!(
+            "Azure rejected the change: soft delete cannot be disabled and its retention may be locked once set. ({})",
+            message
+        )
+    } else {
+        message
+    }
+}
+
+/// Partially update a Key Vault's properties via PATCH
+async fn patch_keyvault_internal(
+    subscription_id: &str,
+    resource_group: &str,
+    keyvault_name: &str,
+    body: &VaultPatchRequest,
+) -> Result<KeyVault> {
+    let url = urls::keyvault(subscription_id, resource_group, keyvault_name);
+
+    let token = get_token_for_scope(management_scope())
+        .await
+        .map_err(|e| anyhow::anyhow!(e))
+        .context("Failed to retrieve management token")?;
+
+    let client = AzureHttpClient::with_scoped_token(&token, management_scope())
+        .context("Failed to create HTTP client with token")?;
+
+    info!("Updating keyvault: {}", keyvault_name);
+
+    let updated_vault: KeyVault = client
+        .patch(&url, body)
+        .await
+        .with_context(|| format!("Failed to update keyvault '{}'", keyvault_name))?;
+
+    info!("Keyvault updated successfully: {}", keyvault_name);
+
+    Ok(updated_vault)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_keyvault_name() {
+        assert!(validate_keyvault_name("my-vault-01").is_ok());
+        assert!(validate_keyvault_name("kv").is_err()); // too short
+        assert!(validate_keyvault_name("a-very-long-vault-name-123").is_err()); // too long
+        assert!(validate_keyvault_name("1vault").is_err()); // starts with digit
+        assert!(validate_keyvault_name("vault-").is_err()); // ends with hyphen
+        assert!(validate_keyvault_name("my--vault").is_err()); // consecutive hyphens
+        assert!(validate_keyvault_name("my_vault").is_err()); // invalid character
+    }
+
+    #[test]
+    fn test_validate_ip_rule() {
+        assert!(validate_ip_rule("203.0.113.7").is_ok());
+        assert!(validate_ip_rule("203.0.113.0/24").is_ok());
+        assert!(validate_ip_rule("0.0.0.0/0").is_ok());
+        assert!(validate_ip_rule("203.0.113.0/33").is_err());
+        assert!(validate_ip_rule("203.0.113.0/").is_err());
+        assert!(validate_ip_rule("300.0.0.1").is_err());
+        assert!(validate_ip_rule("not-an-ip").is_err());
+    }
+
+    #[test]
+    fn test_create_vault_request_body_defaults() {
+        let body = create_vault_request_body(
+            "westeurope".to_string(),
+            "tenant-123".to_string(),
+            &CreateKeyVaultOptions::default(),
+        );
+        let json = serde_json::to_value(&body).unwrap();
+
+        assert_eq!(json["location"], "westeurope");
+        assert_eq!(json["properties"]["tenantId"], "tenant-123");
+        assert_eq!(json["properties"]["sku"]["family"], "A");
+        assert_eq!(json["properties"]["sku"]["name"], "standard");
+        assert_eq!(json["properties"]["enableRbacAuthorization"], false);
+        assert!(json["properties"]["enablePurgeProtection"].is_null());
+        assert_eq!(json["properties"]["accessPolicies"], serde_json::json!([]));
+    }
+
+    #[test]
+    fn test_create_vault_request_body_grants_secret_access() {
+        let options = CreateKeyVaultOptions {
+            secret_access_object_id: Some("caller-oid".to_string()),
+            ..CreateKeyVaultOptions::default()
+        };
+        let body = create_vault_request_body("westeurope".to_string(), "t".to_string(), &options);
+        let json = serde_json::to_value(&body).unwrap();
+
+        let policies = json["properties"]["accessPolicies"].as_array().unwrap();
+        assert_eq!(policies.len(), 1);
+        assert_eq!(policies[0]["tenantId"], "t");
+        assert_eq!(policies[0]["objectId"], "caller-oid");
+        assert_eq!(
+            policies[0]["permissions"]["secrets"],
+            serde_json::json!(["get", "list", "set"])
+        );
+    }
+
+    #[test]
+    fn test_create_vault_request_body_with_options() {
+        let options = CreateKeyVaultOptions {
+            sku_name: Some("Premium".to_string()),
+            enable_rbac_authorization: true,
+            enable_soft_delete: true,
+            enable_purge_protection: Some(true),
+            soft_delete_retention_in_days: Some(30),
+            secret_access_object_id: None,
+        };
+        let body = create_vault_request_body("eastus".to_string(), "t".to_string(), &options);
+        let json = serde_json::to_value(&body).unwrap();
+
+        assert_eq!(json["properties"]["sku"]["name"], "premium");
+        assert_eq!(json["properties"]["enableRbacAuthorization"], true);
+        assert_eq!(json["properties"]["enableSoftDelete"], true);
+        assert_eq!(json["properties"]["enablePurgeProtection"], true);
+        assert_eq!(json["properties"]["softDeleteRetentionInDays"], 30);
+    }
+
+    #[test]
+    fn test_describe_soft_deletion() {
+        let properties = Properties {
+            enable_soft_delete: true,
+            soft_delete_retention_in_days: Some(30),
+            enable_purge_protection: Some(true),
+            ..Default::default()
+        };
+        let deletion = describe_deletion("kv-prod", &properties);
+
+        assert!(deletion.soft_deleted);
+        assert!(deletion.purge_protected);
+        assert_eq!(deletion.retention_days, Some(30));
+        assert!(deletion.message.contains("moved to the deleted vaults"));
+        assert!(deletion.message.contains("30 days"));
+    }
+
+    #[test]
+    fn test_describe_deletion_defaults() {
+        let soft_deleted = Properties {
+            enable_soft_delete: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            describe_deletion("kv", &soft_deleted).retention_days,
+            Some(DEFAULT_SOFT_DELETE_RETENTION_DAYS)
+        );
+
+        let deletion = describe_deletion("kv", &Properties::default());
+        assert!(!deletion.soft_deleted);
+        assert_eq!(deletion.message, "Key Vault 'kv' was deleted");
+    }
+
+    #[test]
+    fn test_describe_unknown_deletion() {
+        let deletion = describe_unknown_deletion("kv");
+        assert!(!deletion.soft_deleted);
+        assert_eq!(deletion.retention_days, None);
+        assert!(deletion.message.starts_with("Key Vault 'kv' was deleted."));
+    }
+}
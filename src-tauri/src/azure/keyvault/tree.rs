@@ -0,0 +1,207 @@
+//! Vault tree - all accessible vaults grouped by subscription and resource group
+
+use futures::stream::{self, StreamExt};
+use log::{info, warn};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+use crate::azure::subscription::service::get_subscriptions;
+use crate::azure::subscription::types::Subscription;
+use crate::cache::azure_cache;
+use crate::config::MAX_CONCURRENT_SUBSCRIPTIONS;
+
+use super::service::get_keyvaults;
+use super::types::KeyVault;
+
+/// A subscription and its vaults, grouped by resource group
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultTreeSubscription {
+    pub subscription: Subscription,
+    pub resource_groups: Vec<VaultTreeResourceGroup>,
+}
+
+/// A resource group and the vaults in it
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultTreeResourceGroup {
+    pub name: String,
+    pub vaults: Vec<KeyVault>,
+}
+
+/// A subscription whose vaults couldn't be listed or parsed
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkippedSubscription {
+    pub subscription_id: String,
+    pub display_name: String,
+    pub error: String,
+}
+
+/// The vault tree, plus the subscriptions left out of it
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultTree {
+    pub subscriptions: Vec<VaultTreeSubscription>,
+    pub skipped_subscriptions: Vec<SkippedSubscription>,
+}
+
+/// Build the subscription → resource group → vault tree.
+///
+/// Subscriptions are queried concurrently (up to `MAX_CONCURRENT_SUBSCRIPTIONS`
+/// at a time) through the cache. Subscriptions whose vaults can't be listed are
+/// reported as skipped instead of failing the tree. Resource groups are sorted
+/// case-insensitively by name.
+///
+/// # Returns
+///
+/// One entry per subscription, in the order the subscriptions were listed,
+/// and the skipped subscriptions with their errors.
+///
+/// # Errors
+///
+/// This function will return an error if the subscription list can't be fetched.
+pub async fn get_vault_tree() -> Result<VaultTree, String> {
+    let subscriptions = azure_cache()
+        .get_subscriptions_or_load(|| async { get_subscriptions().await })
+        .await?;
+
+    info!("Building vault tree for {} subscriptions", subscriptions.len());
+
+    let listed: Vec<(Subscription, Result<Vec<KeyVault>, String>)> = stream::iter(subscriptions)
+        .map(|subscription| async move {
+            let sub_id = subscription.subscription_id.clone();
+            let result = azure_cache()
+                .get_keyvaults_or_load(&subscription.subscription_id, || async move {
+                    get_keyvaults(&sub_id).await
+                })
+                .await;
+            (subscription, result)
+        })
+        .buffered(MAX_CONCURRENT_SUBSCRIPTIONS)
+        .collect()
+        .await;
+
+    Ok(build_tree(listed))
+}
+
+/// Group each subscription's vaults, or record why they couldn't be listed
+fn build_tree(listed: Vec<(Subscription, Result<Vec<KeyVault>, String>)>) -> VaultTree {
+    let mut tree = VaultTree {
+        subscriptions: Vec::new(),
+        skipped_subscriptions: Vec::new(),
+    };
+
+    for (subscription, result) in listed {
+        match result {
+            Ok(vaults) => tree.subscriptions.push(VaultTreeSubscription {
+                subscription,
+                resource_groups: group_by_resource_group(vaults),
+            }),
+            Err(error) => {
+                warn!(
+                    "Skipping subscription {} in vault tree: {}",
+                    subscription.subscription_id, error
+                );
+                tree.skipped_subscriptions.push(SkippedSubscription {
+                    subscription_id: subscription.subscription_id,
+                    display_name: subscription.display_name,
+                    error,
+                });
+            }
+        }
+    }
+
+    tree
+}
+
+/// Group vaults by the resource group in their ARM ID
+fn group_by_resource_group(vaults: Vec<KeyVault>) -> Vec<VaultTreeResourceGroup> {
+    // Keyed by lowercased name, as resource group names are case-insensitive
+    let mut groups: BTreeMap<String, VaultTreeResourceGroup> = BTreeMap::new();
+
+    for vault in vaults {
+        let Some(name) = vault.resource_group().map(str::to_string) else {
+            warn!("Vault {} has no resource group in its ID", vault.name);
+            continue;
+        };
+        groups
+            .entry(name.to_lowercase())
+            .or_insert_with(|| VaultTreeResourceGroup {
+                name,
+                vaults: Vec::new(),
+            })
+            .vaults
+            .push(vault);
+    }
+
+    groups.into_values().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vault(id: &str) -> KeyVault {
+        KeyVault {
+            id: id.to_string(),
+            name: id.rsplit('/').next().unwrap().to_string(),
+            r#type: "Microsoft.KeyVault/vaults".to_string(),
+            location: "westeurope".to_string(),
+            tags: Default::default(),
+            system_data: Default::default(),
+            properties: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_group_by_resource_group() {
+        let groups = group_by_resource_group(vec![
+            vault("/subscriptions/s/resourceGroups/rg-b/providers/Microsoft.KeyVault/vaults/kv1"),
+            vault("/subscriptions/s/resourceGroups/rg-a/providers/Microsoft.KeyVault/vaults/kv2"),
+            vault("/subscriptions/s/resourcegroups/RG-B/providers/Microsoft.KeyVault/vaults/kv3"),
+            vault("not-an-arm-id"),
+        ]);
+
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].name, "rg-a");
+        assert_eq!(groups[1].name, "rg-b");
+        assert_eq!(groups[1].vaults.len(), 2);
+    }
+
+    fn subscription(id: &str) -> Subscription {
+        serde_json::from_value(serde_json::json!({
+            "id": format!("/subscriptions/{}", id),
+            "subscriptionId": id,
+            "tenantId": "tenant",
+            "displayName": format!("Subscription {}", id),
+            "state": "Enabled",
+            "subscriptionPolicies": {
+                "locationPlacementId": "Public_2014-09-01",
+                "quotaId": "PayAsYouGo_2014-09-01",
+                "spendingLimit": "Off"
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_unlistable_subscriptions_are_reported_as_skipped() {
+        let tree = build_tree(vec![
+            (
+                subscription("a"),
+                Ok(vec![vault(
+                    "/subscriptions/a/resourceGroups/rg/providers/Microsoft.KeyVault/vaults/kv",
+                )]),
+            ),
+            (subscription("b"), Err("Forbidden".to_string())),
+        ]);
+
+        assert_eq!(tree.subscriptions.len(), 1);
+        assert_eq!(tree.subscriptions[0].subscription.subscription_id, "a");
+        assert_eq!(tree.skipped_subscriptions.len(), 1);
+        assert_eq!(tree.skipped_subscriptions[0].subscription_id, "b");
+        assert_eq!(tree.skipped_subscriptions[0].display_name, "Subscription b");
+        assert_eq!(tree.skipped_subscriptions[0].error, "Forbidden");
+    }
+}
@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use crate::config::urls;
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct KeyVault {
@@ -12,6 +14,19 @@ pub struct KeyVault {
     pub properties: Properties,
 }
 
+impl KeyVault {
+    /// The subscription ID from the vault's ARM ID
+    /// (`/subscriptions/{s}/resourceGroups/{rg}/...`), or `None` if the ID is malformed
+    pub fn subscription_id(&self) -> Option<&str> {
+        urls::arm_id_segment(&self.id, "subscriptions")
+    }
+
+    /// The resource group name from the vault's ARM ID, or `None` if the ID is malformed
+    pub fn resource_group(&self) -> Option<&str> {
+        urls::extract_resource_group(&self.id)
+    }
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Tags {}
@@ -79,6 +94,31 @@ pub struct Permissions {
     pub storage: Option<Vec<String>>,
 }
 
+/// Outcome of deleting a Key Vault
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyVaultDeletion {
+    /// Whether the vault moved to the deleted vaults instead of being removed outright
+    ///
+    /// Also false if the vault's settings couldn't be read before deleting it.
+    pub soft_deleted: bool,
+    /// Days the deleted vault can be recovered, while its name stays reserved
+    pub retention_days: Option<u8>,
+    /// Whether the deleted vault can't be purged before the retention period ends
+    pub purge_protected: bool,
+    /// Summary for the user
+    pub message: String,
+}
+
+/// Identifies a vault for operations spanning several vaults
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultRef {
+    pub uri: String,
+    pub name: String,
+    pub subscription_id: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct KeyVaultAccessCheck {
@@ -88,6 +128,34 @@ pub struct KeyVaultAccessCheck {
     pub error_message: Option<String>,
 }
 
+/// What a vault supports and what the signed-in user may do in it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultCapabilities {
+    pub soft_delete_enabled: bool,
+    pub purge_protection_enabled: bool,
+    pub rbac_enabled: bool,
+    pub can_read: bool,
+    pub can_write: bool,
+    pub can_delete: bool,
+}
+
+/// User-selectable settings for a new Key Vault
+/// Defaults match the settings VaultRaider has always created vaults with
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CreateKeyVaultOptions {
+    pub sku_name: Option<String>, // "standard" or "premium"
+    pub enable_rbac_authorization: bool,
+    pub enable_soft_delete: bool,
+    pub enable_purge_protection: Option<bool>,
+    pub soft_delete_retention_in_days: Option<u8>,
+    /// Object ID given an access policy with secret get, list and set.
+    /// Set internally (e.g. for the caller of a clone), never by the frontend
+    #[serde(skip)]
+    pub secret_access_object_id: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateVaultRequest {
@@ -95,17 +163,34 @@ pub struct CreateVaultRequest {
     pub properties: Properties,
 }
 
+// https://learn.microsoft.com/en-us/rest/api/keyvault/keyvault/vaults/create-or-update?view=rest-keyvault-keyvault-2024-11-01&tabs=HTTP#networkruleset
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct NetworkRuleSet {
-    // TODO@JOREN: these are not strings, see docs
-    // https://learn.microsoft.com/en-us/rest/api/keyvault/keyvault/vaults/create-or-update?view=rest-keyvault-keyvault-2024-11-01&tabs=HTTP#networkruleset
-    pub bypass: String,
-    pub default_action: String,
+    pub bypass: NetworkRuleBypassOptions,
+    pub default_action: NetworkRuleAction,
+    #[serde(default)]
     pub ip_rules: Vec<IpRule>,
+    #[serde(default)]
     pub virtual_network_rules: Vec<VirtualNetworkRule>,
 }
 
+/// Which traffic can bypass the network rules
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NetworkRuleBypassOptions {
+    #[default]
+    AzureServices,
+    None,
+}
+
+/// The default action when no IP or virtual network rule matches
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NetworkRuleAction {
+    #[default]
+    Allow,
+    Deny,
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct VirtualNetworkRule {
@@ -126,3 +211,98 @@ pub struct PrivateEndpointConnectionItem {
     pub id: String,
     // TODO@JOREN: there is more but docs are unclear
 }
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckNameAvailabilityRequest {
+    pub name: String,
+    pub r#type: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckNameAvailabilityResult {
+    pub name_available: bool,
+    pub reason: Option<String>, // 'AccountNameInvalid' or 'AlreadyExists'
+    pub message: Option<String>,
+}
+
+/// Request body for partially updating a Key Vault (PATCH)
+/// Only the properties that are set are sent to Azure
+#[derive(Default, Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultPatchRequest {
+    pub properties: VaultPatchProperties,
+}
+
+#[derive(Default, Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultPatchProperties {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub network_acls: Option<NetworkRuleSet>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enable_purge_protection: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enable_soft_delete: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub soft_delete_retention_in_days: Option<u8>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vault_with_id(id: &str) -> KeyVault {
+        KeyVault {
+            id: id.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_keyvault_id_parsing() {
+        let vault = vault_with_id(
+            "/subscriptions/sub-123/resourceGroups/my-rg/providers/Microsoft.KeyVault/vaults/kv",
+        );
+        assert_eq!(vault.subscription_id(), Some("sub-123"));
+        assert_eq!(vault.resource_group(), Some("my-rg"));
+    }
+
+    #[test]
+    fn test_keyvault_id_parsing_malformed() {
+        for id in ["", "kv", "/subscriptions/", "/subscriptions//resourceGroups/"] {
+            let vault = vault_with_id(id);
+            assert_eq!(vault.subscription_id(), None, "id: {:?}", id);
+            assert_eq!(vault.resource_group(), None, "id: {:?}", id);
+        }
+
+        let vault = vault_with_id("/subscriptions/sub-123");
+        assert_eq!(vault.subscription_id(), Some("sub-123"));
+        assert_eq!(vault.resource_group(), None);
+    }
+
+    #[test]
+    fn test_vault_ref_deserialization() {
+        let vaults: Vec<VaultRef> = serde_json::from_str(
+            r#"[{
+                "uri": "https://kv.vault.azure.net/",
+                "name": "kv",
+                "subscriptionId": "sub-123"
+            }]"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            vaults,
+            vec![VaultRef {
+                uri: "https://kv.vault.azure.net/".to_string(),
+                name: "kv".to_string(),
+                subscription_id: "sub-123".to_string(),
+            }]
+        );
+
+        // All fields are required, so a half-filled entry is rejected instead of misattributed
+        assert!(serde_json::from_str::<VaultRef>(r#"{"uri": "https://kv.vault.azure.net/"}"#)
+            .is_err());
+    }
+}
@@ -0,0 +1,7 @@
+//! Azure RBAC module
+//!
+//! This module provides functionality for working with Azure role-based
+//! access control (role assignments and role definitions) on Key Vaults.
+
+pub mod service;
+pub mod types;
@@ -0,0 +1,454 @@
+//! RBAC service - business logic for Azure role assignments
+
+use anyhow::{Context, Result};
+use futures::future::join_all;
+use log::{debug, error, info, warn};
+use std::collections::HashMap;
+
+use crate::azure::activity_log::graph::resolve_caller_identities;
+use crate::azure::auth::token::get_token_for_scope;
+use crate::azure::http::{fetch_all_paginated, AzureHttpClient, AzureHttpError};
+use crate::cache::azure_cache;
+use crate::config::{management_scope, urls};
+
+use super::types::{
+    CreateRoleAssignmentProperties, CreateRoleAssignmentRequest, KnownRole, Permission,
+    RoleAssignment, RoleAssignmentResult, RoleDefinition, VaultRoleAssignment,
+};
+
+/// Built-in Key Vault data plane roles: (role name, role definition GUID)
+const KNOWN_KEYVAULT_ROLES: &[(&str, &str)] = &[
+    ("Key Vault Administrator", "00482a5a-887f-4fb3-b363-3b7fe8e74483"),
+    ("Key Vault Secrets Officer", "b86a8fe4-44ce-4948-aee5-eccb2c155cd7"),
+    ("Key Vault Secrets User", "4633458b-17de-408a-b874-0445c86b69e6"),
+    ("Key Vault Reader", "21090545-7ca7-4776-b22c-e363652d74d2"),
+];
+
+/// Get the well-known built-in Key Vault roles, for offering friendly names in the UI
+pub fn get_known_keyvault_roles() -> Vec<KnownRole> {
+    KNOWN_KEYVAULT_ROLES
+        .iter()
+        .map(|(name, id)| KnownRole {
+            role_name: name.to_string(),
+            role_definition_id: id.to_string(),
+        })
+        .collect()
+}
+
+/// Fetch all role assignments that apply to a Key Vault.
+///
+/// Includes assignments inherited from the vault's resource group and
+/// subscription. Role definition IDs are resolved to role names, and principal
+/// IDs are resolved to display names via Microsoft Graph where possible.
+///
+/// # Arguments
+///
+/// * `vault_resource_id` - The full ARM resource ID of the Key Vault
+///
+/// # Returns
+///
+/// A vector of role assignments with resolved names, or an error.
+///
+/// # Errors
+///
+/// This function will return an error if:
+/// - The user is not authenticated
+/// - The user isn't allowed to read role assignments on the vault
+/// - The API request fails
+pub async fn get_vault_role_assignments(
+    vault_resource_id: &str,
+) -> Result<Vec<VaultRoleAssignment>, String> {
+    get_vault_role_assignments_internal(vault_resource_id)
+        .await
+        .map_err(|e| {
+            error!("Failed to get vault role assignments: {}", e);
+            e.to_string()
+        })
+}
+
+async fn get_vault_role_assignments_internal(
+    vault_resource_id: &str,
+) -> Result<Vec<VaultRoleAssignment>> {
+    info!("Fetching role assignments for vault");
+
+    let token = get_token_for_scope(management_scope())
+        .await
+        .map_err(|e| anyhow::anyhow!(e))
+        .context("Failed to retrieve management token")?;
+
+    let client = AzureHttpClient::with_scoped_token(&token, management_scope())
+        .context("Failed to create HTTP client with token")?;
+
+    let url = urls::role_assignments(vault_resource_id);
+    let assignments = fetch_all_paginated::<RoleAssignment>(&url, &client)
+        .await
+        .with_context(|| format!("Failed to fetch role assignments for {}", vault_resource_id))?;
+
+    let definition_ids = assignments
+        .iter()
+        .map(|a| a.properties.role_definition_id.clone())
+        .collect();
+    let role_names = resolve_role_names(&client, definition_ids).await;
+
+    let principal_ids: Vec<String> = assignments
+        .iter()
+        .map(|a| a.properties.principal_id.clone())
+        .collect();
+    let principals = resolve_caller_identities(principal_ids)
+        .await
+        .unwrap_or_else(|e| {
+            warn!("Failed to resolve principal names: {}", e);
+            HashMap::new()
+        });
+
+    let results: Vec<VaultRoleAssignment> = assignments
+        .into_iter()
+        .map(|a| VaultRoleAssignment {
+            principal_display_name: principals
+                .get(&a.properties.principal_id)
+                .map(|p| p.display_name.clone()),
+            role_name: role_names
+                .get(&a.properties.role_definition_id.to_lowercase())
+                .cloned(),
+            id: a.id,
+            principal_id: a.properties.principal_id,
+            principal_type: a.properties.principal_type,
+            role_definition_id: a.properties.role_definition_id,
+            scope: a.properties.scope,
+        })
+        .collect();
+
+    info!("Successfully retrieved {} role assignments", results.len());
+    Ok(results)
+}
+
+/// Resolve role definition IDs to their display names.
+///
+/// Names are cached for a long time, as role definitions rarely change.
+/// Definitions that can't be read map to their GUID.
+///
+/// # Arguments
+///
+/// * `scope` - An ARM resource ID in the subscription the roles belong to
+/// * `role_definition_ids` - Role definition GUIDs or full role definition IDs
+///
+/// # Returns
+///
+/// A map from each given ID (as passed in) to its role name or GUID.
+///
+/// # Errors
+///
+/// This function will return an error if the user is not authenticated.
+pub async fn resolve_role_definitions(
+    scope: &str,
+    role_definition_ids: Vec<String>,
+) -> Result<HashMap<String, String>, String> {
+    resolve_role_definitions_internal(scope, role_definition_ids)
+        .await
+        .map_err(|e| {
+            error!("Failed to resolve role definitions: {}", e);
+            e.to_string()
+        })
+}
+
+async fn resolve_role_definitions_internal(
+    scope: &str,
+    role_definition_ids: Vec<String>,
+) -> Result<HashMap<String, String>> {
+    let token = get_token_for_scope(management_scope())
+        .await
+        .map_err(|e| anyhow::anyhow!(e))
+        .context("Failed to retrieve management token")?;
+
+    let client = AzureHttpClient::with_scoped_token(&token, management_scope())
+        .context("Failed to create HTTP client with token")?;
+
+    let full_ids = role_definition_ids
+        .iter()
+        .map(|id| full_role_definition_id(scope, id))
+        .collect();
+    let names = resolve_role_names(&client, full_ids).await;
+
+    Ok(role_definition_ids
+        .into_iter()
+        .map(|id| {
+            let name = names
+                .get(&full_role_definition_id(scope, &id).to_lowercase())
+                .cloned()
+                .unwrap_or_else(|| role_definition_guid(&id).to_string());
+            (id, name)
+        })
+        .collect())
+}
+
+/// Resolve the role names for a set of full role definition IDs, through the cache.
+///
+/// Returns a map keyed by lowercased role definition ID. Definitions that fail to
+/// resolve are logged and left out, so callers fall back to showing the ID.
+async fn resolve_role_names(
+    client: &AzureHttpClient,
+    mut definition_ids: Vec<String>,
+) -> HashMap<String, String> {
+    definition_ids.sort_by_key(|id| id.to_lowercase());
+    definition_ids.dedup_by_key(|id| id.to_lowercase());
+
+    debug!("Resolving {} role definitions", definition_ids.len());
+
+    let lookups = definition_ids.into_iter().map(|definition_id| async move {
+        let result = azure_cache()
+            .get_role_definition_name_or_load(&definition_id, || async {
+                let url = urls::role_definition(&definition_id);
+                client
+                    .get::<RoleDefinition>(&url)
+                    .await
+                    .map(|definition| definition.properties.role_name)
+                    .map_err(|e| e.to_string())
+            })
+            .await;
+
+        match result {
+            Ok(role_name) => Some((definition_id.to_lowercase(), role_name)),
+            Err(e) => {
+                warn!("Failed to resolve role definition {}: {}", definition_id, e);
+                None
+            }
+        }
+    });
+
+    join_all(lookups).await.into_iter().flatten().collect()
+}
+
+/// The GUID at the end of a role definition ID, shown when its name can't be resolved
+pub(crate) fn role_definition_guid(role_definition_id: &str) -> &str {
+    role_definition_id
+        .rsplit('/')
+        .next()
+        .unwrap_or(role_definition_id)
+}
+
+/// Build the full role definition ID from a bare GUID, scoped to the subscription of `scope`.
+///
+/// Full IDs (containing a `/`) are returned unchanged.
+fn full_role_definition_id(scope: &str, role_definition_id: &str) -> String {
+    if role_definition_id.contains('/') {
+        return role_definition_id.to_string();
+    }
+    format!(
+        "/subscriptions/{}/providers/Microsoft.Authorization/roleDefinitions/{}",
+        urls::extract_subscription_id(scope),
+        role_definition_id
+    )
+}
+
+/// Assign an RBAC role to a principal on a Key Vault.
+///
+/// If the principal already holds the role on the vault, the existing
+/// assignment is returned instead of an error.
+///
+/// # Arguments
+///
+/// * `vault_resource_id` - The full ARM resource ID of the Key Vault
+/// * `principal_id` - The object ID of the user, group or service principal
+/// * `role_definition_id` - A role definition GUID or full role definition ID
+///
+/// # Returns
+///
+/// The created (or pre-existing) role assignment.
+pub async fn assign_vault_role(
+    vault_resource_id: &str,
+    principal_id: &str,
+    role_definition_id: &str,
+) -> Result<RoleAssignmentResult, String> {
+    assign_vault_role_internal(vault_resource_id, principal_id, role_definition_id)
+        .await
+        .map_err(|e| {
+            error!("Failed to assign vault role: {}", e);
+            if let Some(root_cause) = e.root_cause().downcast_ref::<AzureHttpError>() {
+                root_cause.to_string()
+            } else {
+                e.to_string()
+            }
+        })
+}
+
+async fn assign_vault_role_internal(
+    vault_resource_id: &str,
+    principal_id: &str,
+    role_definition_id: &str,
+) -> Result<RoleAssignmentResult> {
+    info!("Assigning role on vault to principal {}", principal_id);
+
+    let token = get_token_for_scope(management_scope())
+        .await
+        .map_err(|e| anyhow::anyhow!(e))
+        .context("Failed to retrieve management token")?;
+
+    let client = AzureHttpClient::with_scoped_token(&token, management_scope())
+        .context("Failed to create HTTP client with token")?;
+
+    let role_definition_id = full_role_definition_id(vault_resource_id, role_definition_id);
+    let assignment_name = uuid::Uuid::new_v4().to_string();
+    let url = urls::role_assignment(vault_resource_id, &assignment_name);
+
+    let body = CreateRoleAssignmentRequest {
+        properties: CreateRoleAssignmentProperties {
+            role_definition_id: role_definition_id.clone(),
+            principal_id: principal_id.to_string(),
+        },
+    };
+
+    match client.put::<RoleAssignment, _>(&url, &body).await {
+        Ok(assignment) => {
+            info!("Role assignment created: {}", assignment.id);
+            Ok(RoleAssignmentResult {
+                assignment,
+                already_existed: false,
+            })
+        }
+        Err(AzureHttpError::ApiError { status: 409, .. }) => {
+            info!("Role assignment already exists, looking up existing assignment");
+
+            let list_url = urls::role_assignments(vault_resource_id);
+            let existing = fetch_all_paginated::<RoleAssignment>(&list_url, &client)
+                .await
+                .context("Failed to look up existing role assignment")?
+                .into_iter()
+                .find(|a| {
+                    a.properties.principal_id.eq_ignore_ascii_case(principal_id)
+                        && a.properties
+                            .role_definition_id
+                            .eq_ignore_ascii_case(&role_definition_id)
+                })
+                .ok_or_else(|| {
+                    anyhow::anyhow!("Role assignment already exists but could not be found")
+                })?;
+
+            Ok(RoleAssignmentResult {
+                assignment: existing,
+                already_existed: true,
+            })
+        }
+        Err(e) => Err(anyhow::Error::new(e).context("Failed to create role assignment")),
+    }
+}
+
+/// Fetch the signed-in user's effective permissions on a resource.
+///
+/// Each entry corresponds to one role the caller holds (directly, via a
+/// group, or inherited from a parent scope).
+pub(crate) async fn get_caller_permissions(resource_id: &str) -> Result<Vec<Permission>> {
+    let token = get_token_for_scope(management_scope())
+        .await
+        .map_err(|e| anyhow::anyhow!(e))
+        .context("Failed to retrieve management token")?;
+
+    let client = AzureHttpClient::with_scoped_token(&token, management_scope())
+        .context("Failed to create HTTP client with token")?;
+
+    let url = urls::permissions(resource_id);
+    fetch_all_paginated::<Permission>(&url, &client)
+        .await
+        .with_context(|| format!("Failed to fetch permissions for {}", resource_id))
+}
+
+/// Check whether a set of permissions grants a data action.
+///
+/// A data action is granted if any single permission entry allows it and
+/// doesn't also exclude it via `notDataActions`.
+pub(crate) fn permits_data_action(permissions: &[Permission], action: &str) -> bool {
+    permissions.iter().any(|permission| {
+        permission
+            .data_actions
+            .iter()
+            .any(|pattern| action_matches(pattern, action))
+            && !permission
+                .not_data_actions
+                .iter()
+                .any(|pattern| action_matches(pattern, action))
+    })
+}
+
+/// Match an action against an Azure RBAC action pattern, where `*` matches any run of characters
+fn action_matches(pattern: &str, action: &str) -> bool {
+    let pattern = pattern.to_lowercase();
+    let action = action.to_lowercase();
+
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or("");
+    let Some(mut rest) = action.strip_prefix(first) else {
+        return false;
+    };
+
+    let remaining: Vec<&str> = parts.collect();
+    let Some((last, middle)) = remaining.split_last() else {
+        // No wildcard: the whole action must match
+        return rest.is_empty();
+    };
+
+    for part in middle {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_full_role_definition_id_from_guid() {
+        let vault_id = "/subscriptions/sub-123/resourceGroups/rg/providers/Microsoft.KeyVault/vaults/kv";
+        assert_eq!(
+            full_role_definition_id(vault_id, "4633458b-17de-408a-b874-0445c86b69e6"),
+            "/subscriptions/sub-123/providers/Microsoft.Authorization/roleDefinitions/4633458b-17de-408a-b874-0445c86b69e6"
+        );
+    }
+
+    #[test]
+    fn test_role_definition_guid() {
+        assert_eq!(
+            role_definition_guid(
+                "/subscriptions/s/providers/Microsoft.Authorization/roleDefinitions/4633458b"
+            ),
+            "4633458b"
+        );
+        assert_eq!(role_definition_guid("4633458b"), "4633458b");
+    }
+
+    #[test]
+    fn test_full_role_definition_id_passthrough() {
+        let full = "/subscriptions/sub-123/providers/Microsoft.Authorization/roleDefinitions/abc";
+        assert_eq!(full_role_definition_id("/subscriptions/other", full), full);
+    }
+
+    #[test]
+    fn test_action_matches() {
+        let set_secret = "Microsoft.KeyVault/vaults/secrets/setSecret/action";
+        assert!(action_matches("*", set_secret));
+        assert!(action_matches("Microsoft.KeyVault/vaults/secrets/*", set_secret));
+        assert!(action_matches("microsoft.keyvault/vaults/*/action", set_secret));
+        assert!(action_matches(set_secret, set_secret));
+        assert!(!action_matches("Microsoft.KeyVault/vaults/secrets/getSecret/action", set_secret));
+        assert!(!action_matches("Microsoft.KeyVault/vaults/keys/*", set_secret));
+    }
+
+    #[test]
+    fn test_permits_data_action_respects_not_data_actions() {
+        let permissions = vec![Permission {
+            data_actions: vec!["Microsoft.KeyVault/vaults/secrets/*".to_string()],
+            not_data_actions: vec!["Microsoft.KeyVault/vaults/secrets/delete".to_string()],
+            ..Default::default()
+        }];
+        assert!(permits_data_action(
+            &permissions,
+            "Microsoft.KeyVault/vaults/secrets/setSecret/action"
+        ));
+        assert!(!permits_data_action(
+            &permissions,
+            "Microsoft.KeyVault/vaults/secrets/delete"
+        ));
+    }
+}
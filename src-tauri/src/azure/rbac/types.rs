@@ -0,0 +1,96 @@
+//! Types for Azure RBAC role assignments and role definitions
+//!
+//! API Reference: https://learn.microsoft.com/en-us/rest/api/authorization/role-assignments
+
+use serde::{Deserialize, Serialize};
+
+/// A role assignment as returned by the Azure Authorization API
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RoleAssignment {
+    pub id: String,
+    pub name: String,
+    pub properties: RoleAssignmentProperties,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RoleAssignmentProperties {
+    pub role_definition_id: String,
+    pub principal_id: String,
+    pub principal_type: Option<String>, // 'User', 'Group', 'ServicePrincipal', ...
+    pub scope: String,
+}
+
+/// Request body for creating a role assignment
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateRoleAssignmentRequest {
+    pub properties: CreateRoleAssignmentProperties,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateRoleAssignmentProperties {
+    pub role_definition_id: String,
+    pub principal_id: String,
+}
+
+/// Outcome of assigning a role
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RoleAssignmentResult {
+    pub assignment: RoleAssignment,
+    /// True if the principal already had this role at this scope
+    pub already_existed: bool,
+}
+
+/// A well-known built-in Key Vault role
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KnownRole {
+    pub role_name: String,
+    /// The role definition GUID (same in every subscription for built-in roles)
+    pub role_definition_id: String,
+}
+
+/// A role definition as returned by the Azure Authorization API
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RoleDefinition {
+    pub id: String,
+    pub name: String,
+    pub properties: RoleDefinitionProperties,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RoleDefinitionProperties {
+    pub role_name: String,
+    pub description: Option<String>,
+    pub r#type: Option<String>, // 'BuiltInRole' or 'CustomRole'
+}
+
+/// A role assignment on a vault with resolved role and principal names
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultRoleAssignment {
+    pub id: String,
+    pub principal_id: String,
+    pub principal_type: Option<String>,
+    pub principal_display_name: Option<String>,
+    pub role_definition_id: String,
+    pub role_name: Option<String>,
+    /// The scope the assignment was made at (may be a parent of the vault)
+    pub scope: String,
+}
+
+/// The caller's effective permissions on a scope, from the Authorization permissions API
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Permission {
+    pub actions: Vec<String>,
+    pub not_actions: Vec<String>,
+    pub data_actions: Vec<String>,
+    pub not_data_actions: Vec<String>,
+}
@@ -1,11 +1,14 @@
 //! Resource Group service - business logic for Azure Resource Group operations
 
 use anyhow::{Context, Result};
-use log::{debug, error, info};
+use futures::stream::{self, StreamExt};
+use log::{debug, error, info, warn};
 
 use crate::azure::auth::token::get_token_from_state;
 use crate::azure::http::{fetch_all_paginated, AzureHttpClient};
-use crate::config::urls;
+use crate::azure::subscription::service::get_subscriptions;
+use crate::cache::azure_cache;
+use crate::config::{management_scope, urls, MAX_CONCURRENT_SUBSCRIPTIONS};
 
 use super::types::ResourceGroup;
 
@@ -43,8 +46,8 @@ async fn get_resource_groups_internal(subscription_id: &str) -> Result<Vec<Resou
 
     debug!("Successfully retrieved authentication token");
 
-    let client =
-        AzureHttpClient::with_token(&token).context("Failed to create HTTP client with token")?;
+    let client = AzureHttpClient::with_scoped_token(&token, management_scope())
+        .context("Failed to create HTTP client with token")?;
 
     let url = urls::resource_groups(subscription_id);
     debug!("Calling Azure API: {}", url);
@@ -105,8 +108,8 @@ async fn get_resource_group_by_name_internal(
 
     debug!("Successfully retrieved authentication token");
 
-    let client =
-        AzureHttpClient::with_token(&token).context("Failed to create HTTP client with token")?;
+    let client = AzureHttpClient::with_scoped_token(&token, management_scope())
+        .context("Failed to create HTTP client with token")?;
 
     let rg_response: ResourceGroup = client.get(&url).await.with_context(|| {
         format!(
@@ -118,3 +121,53 @@ async fn get_resource_group_by_name_internal(
     info!("Resource group fetched successfully");
     Ok(rg_response)
 }
+
+/// Fetch the Resource Groups of every subscription the user can access.
+///
+/// Subscriptions are queried concurrently (up to `MAX_CONCURRENT_SUBSCRIPTIONS`
+/// at a time) through the cache. Subscriptions whose resource groups can't be
+/// fetched are logged and skipped rather than failing the whole request.
+///
+/// # Returns
+///
+/// A vector of `(subscription_id, resource_groups)` pairs or an error.
+///
+/// # Errors
+///
+/// This function will return an error if the subscription list can't be fetched.
+pub async fn fetch_all_resource_groups() -> Result<Vec<(String, Vec<ResourceGroup>)>, String> {
+    let subscriptions = azure_cache()
+        .get_subscriptions_or_load(|| async { get_subscriptions().await })
+        .await?;
+
+    info!(
+        "Fetching resource groups across {} subscriptions",
+        subscriptions.len()
+    );
+
+    let results: Vec<Option<(String, Vec<ResourceGroup>)>> = stream::iter(subscriptions)
+        .map(|subscription| async move {
+            let sub_id = subscription.subscription_id.clone();
+            let result = azure_cache()
+                .get_resource_groups_or_load(&subscription.subscription_id, || async move {
+                    get_resource_groups(&sub_id).await
+                })
+                .await;
+
+            match result {
+                Ok(groups) => Some((subscription.subscription_id, groups)),
+                Err(e) => {
+                    warn!(
+                        "Skipping subscription {}: {}",
+                        subscription.subscription_id, e
+                    );
+                    None
+                }
+            }
+        })
+        .buffer_unordered(MAX_CONCURRENT_SUBSCRIPTIONS)
+        .collect()
+        .await;
+
+    Ok(results.into_iter().flatten().collect())
+}
@@ -4,10 +4,10 @@ use anyhow::{Context, Result};
 use log::{error, info};
 
 use crate::azure::auth::token::get_token_from_state;
-use crate::azure::http::AzureHttpClient;
-use crate::config::urls;
+use crate::azure::http::{fetch_all_paginated_limited, AzureHttpClient};
+use crate::config::{management_scope, urls, MAX_PAGINATION_PAGES};
 
-use super::types::{Subscription, SubscriptionListResponse};
+use super::types::Subscription;
 
 /// Fetch all subscriptions for the authenticated user.
 ///
@@ -35,23 +35,43 @@ async fn get_subscriptions_internal() -> Result<Vec<Subscription>> {
         .map_err(|e| anyhow::anyhow!(e))
         .context("Failed to retrieve authentication token")?;
 
-    let client = AzureHttpClient::new()
-        .with_bearer_token(&token)
+    let client = AzureHttpClient::with_scoped_token(&token, management_scope())
         .context("Failed to create HTTP client with token")?;
 
     let url = urls::subscriptions();
 
-    let sub_list: SubscriptionListResponse = client
-        .get(&url)
-        .await
-        .context("Failed to fetch subscriptions from Azure")?;
+    // Everything else is reached through this list, so a listing cut short by
+    // the page limit is better than failing outright
+    let subscriptions =
+        fetch_all_paginated_limited::<Subscription>(&url, &client, MAX_PAGINATION_PAGES)
+            .await
+            .context("Failed to fetch subscriptions from Azure")?;
+
+    // Span::current().record("subscription_count", subscriptions.len());
+    info!("Successfully fetched {} subscriptions", subscriptions.len());
+    Ok(subscriptions)
+}
 
-    // Span::current().record("subscription_count", sub_list.value.len());
-    info!(
-        "Successfully fetched {} subscriptions",
-        sub_list.value.len()
-    );
-    Ok(sub_list.value)
+/// Filter subscriptions by state and/or tenant.
+///
+/// Both filters are optional and compared case-insensitively. Passing `None`
+/// for both returns the list unchanged.
+///
+/// # Arguments
+///
+/// * `subscriptions` - The subscriptions to filter
+/// * `state` - Only keep subscriptions in this state (e.g. "Enabled")
+/// * `tenant_id` - Only keep subscriptions belonging to this tenant
+pub fn filter_subscriptions(
+    subscriptions: Vec<Subscription>,
+    state: Option<&str>,
+    tenant_id: Option<&str>,
+) -> Vec<Subscription> {
+    subscriptions
+        .into_iter()
+        .filter(|sub| state.is_none_or(|s| sub.state.eq_ignore_ascii_case(s)))
+        .filter(|sub| tenant_id.is_none_or(|t| sub.tenant_id.eq_ignore_ascii_case(t)))
+        .collect()
 }
 
 pub async fn get_subscription(subscription_id: &str) -> Result<Subscription> {
@@ -7,8 +7,12 @@ use serde::{Deserialize, Serialize};
 #[serde(rename_all = "camelCase")]
 pub struct Subscription {
     pub id: String,
+    #[serde(default)]
     pub authorization_source: String,
-    pub managed_by_tenants: Vec<Tenant>,
+    /// Tenants managing this subscription through Lighthouse or CSP;
+    /// missing on subscriptions listed through older API versions
+    #[serde(default)]
+    pub managed_by_tenants: Vec<ManagedByTenant>,
     pub subscription_id: String,
     pub tenant_id: String,
     pub display_name: String,
@@ -17,21 +21,13 @@ pub struct Subscription {
     pub tags: Option<serde_json::Value>,
 }
 
-/// Azure AD Tenant reference
+/// A tenant managing a subscription, as listed in `managedByTenants`
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct Tenant {
+pub struct ManagedByTenant {
     pub tenant_id: String,
 }
 
-/// Azure subscription list response
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct SubscriptionListResponse {
-    pub value: Vec<Subscription>,
-    pub next_link: Option<String>,
-}
-
 /// Subscription policy information
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -40,3 +36,58 @@ pub struct SubscriptionPolicy {
     pub quota_id: String,
     pub spending_limit: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::azure::auth::types::AzureListResponse;
+
+    #[test]
+    fn test_subscription_managed_by_tenants() {
+        // A Lighthouse-delegated subscription as listed by GET /subscriptions
+        let list: AzureListResponse<Subscription> = serde_json::from_str(
+            r#"{
+                "value": [
+                    {
+                        "id": "/subscriptions/00000000-0000-0000-0000-000000000001",
+                        "authorizationSource": "RoleBased",
+                        "managedByTenants": [
+                            { "tenantId": "11111111-1111-1111-1111-111111111111" },
+                            { "tenantId": "22222222-2222-2222-2222-222222222222" }
+                        ],
+                        "subscriptionId": "00000000-0000-0000-0000-000000000001",
+                        "tenantId": "33333333-3333-3333-3333-333333333333",
+                        "displayName": "Customer Production",
+                        "state": "Enabled",
+                        "subscriptionPolicies": {
+                            "locationPlacementId": "Public_2014-09-01",
+                            "quotaId": "CSP_2015-05-01",
+                            "spendingLimit": "Off"
+                        }
+                    },
+                    {
+                        "id": "/subscriptions/00000000-0000-0000-0000-000000000002",
+                        "subscriptionId": "00000000-0000-0000-0000-000000000002",
+                        "tenantId": "33333333-3333-3333-3333-333333333333",
+                        "displayName": "Dev",
+                        "state": "Enabled",
+                        "subscriptionPolicies": {
+                            "locationPlacementId": "Public_2014-09-01",
+                            "quotaId": "PayAsYouGo_2014-09-01",
+                            "spendingLimit": "Off"
+                        },
+                        "tags": { "team": "platform" }
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(list.value.len(), 2);
+        let managed = &list.value[0].managed_by_tenants;
+        assert_eq!(managed.len(), 2);
+        assert_eq!(managed[1].tenant_id, "22222222-2222-2222-2222-222222222222");
+        assert!(list.value[1].managed_by_tenants.is_empty());
+        assert!(list.next_link.is_none());
+    }
+}
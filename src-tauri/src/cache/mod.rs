@@ -1,7 +1,11 @@
 //! Caching module for Azure API calls
 //!
-//! Provides in-memory caching with TTL-based expiration using Moka.
+//! Provides in-memory caching with TTL-based expiration using Moka, with a
+//! per-account disk snapshot of the list caches that survives app restarts.
 
 mod moka_cache;
 
-pub use moka_cache::{CacheStatistics, AZURE_CACHE};
+pub use moka_cache::{
+    azure_cache, forget_snapshot_owner, replace_azure_cache, AzureCache, CacheStatistics, CacheTtls,
+    SnapshotOwner,
+};
@@ -5,18 +5,37 @@
 //! - Automatic loading on cache miss
 //! - Thread-safe access
 //! - Per-key eviction
+//! - Optional refresh-ahead for the secrets-list and keyvaults caches
+//! - A disk snapshot of the list caches, so they survive app restarts
+//!
+//! Secret values are never written to the snapshot. Each account (cloud,
+//! tenant and object id) has its own snapshot file, which is only restored
+//! once that account has signed in and is deleted when the caches are cleared.
 
 use anyhow::{Result};
-use log::{debug, info};
+use log::{debug, info, warn};
 use moka::future::Cache;
+use moka::notification::RemovalCause;
+use moka::Expiry;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
-use std::sync::Arc;
-use std::time::Duration;
-
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::azure::auth::types::OpenIdConfiguration;
+use crate::azure::keyvault::key::types::Key;
 use crate::azure::keyvault::secret::types::{Secret, SecretBundle};
 use crate::azure::keyvault::types::KeyVault;
 use crate::azure::resource_group::types::ResourceGroup;
 use crate::azure::subscription::types::Subscription;
+use crate::config::CloudEnvironment;
+use crate::user_config::constants::APP_NAME;
 
 /// Default TTL for subscriptions (10 minutes - they don't change often)
 const SUBSCRIPTION_TTL_SECS: u64 = 3_600;
@@ -30,25 +49,278 @@ const KEYVAULT_TTL_SECS: u64 = 3_600;
 /// Default TTL for secrets list (3 minutes)
 const SECRETS_LIST_TTL_SECS: u64 = 3_600;
 
+/// Default TTL for keys list
+const KEYS_LIST_TTL_SECS: u64 = 3_600;
+
 /// Default TTL for secret values (3 minutes)
 const SECRET_VALUE_TTL_SECS: u64 = 3_600;
 
+/// TTL for role definition names (24 hours - built-in roles never change, custom ones rarely)
+const ROLE_DEFINITION_TTL_SECS: u64 = 86_400;
+
+/// TTL for OpenID discovery documents (5 minutes - short, as they're fetched for diagnostics)
+const OPENID_CONFIG_TTL_SECS: u64 = 300;
+
 /// Maximum cache entries
 const MAX_CACHE_ENTRIES: u64 = 50_000;
 
+/// Prefix of the cache snapshot file names, in the config directory
+const SNAPSHOT_FILE_PREFIX: &str = "cache_snapshot";
+
+/// Snapshot format version; snapshots with another version are ignored
+const SNAPSHOT_VERSION: u32 = 1;
+
+/// With refresh-ahead on, a hit on an entry older than this share of its TTL
+/// triggers a background reload
+const REFRESH_AHEAD_AFTER_PERCENT: u32 = 80;
+
+/// Time-to-live of each cache that can be configured
+///
+/// Role definitions and OpenID documents keep their fixed TTLs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CacheTtls {
+    pub subscriptions: Duration,
+    pub resource_groups: Duration,
+    pub keyvaults: Duration,
+    pub secrets_list: Duration,
+    pub keys_list: Duration,
+    pub secret_values: Duration,
+}
+
+impl Default for CacheTtls {
+    fn default() -> Self {
+        Self {
+            subscriptions: Duration::from_secs(SUBSCRIPTION_TTL_SECS),
+            resource_groups: Duration::from_secs(RESOURCE_GROUP_TTL_SECS),
+            keyvaults: Duration::from_secs(KEYVAULT_TTL_SECS),
+            secrets_list: Duration::from_secs(SECRETS_LIST_TTL_SECS),
+            keys_list: Duration::from_secs(KEYS_LIST_TTL_SECS),
+            secret_values: Duration::from_secs(SECRET_VALUE_TTL_SECS),
+        }
+    }
+}
+
 /// Wrapper to store Vec in cache (since Moka needs Clone)
+///
+/// Remembers when the list was loaded from Azure, so a list restored from the
+/// disk snapshot still expires when it originally would have.
 #[derive(Clone, Debug)]
-pub struct CachedVec<T: Clone>(pub Vec<T>);
+pub struct CachedVec<T: Clone> {
+    pub items: Vec<T>,
+    pub loaded_at: SystemTime,
+}
+
+impl<T: Clone> CachedVec<T> {
+    /// Time since the list was loaded from Azure
+    fn age(&self) -> Duration {
+        self.loaded_at.elapsed().unwrap_or_default()
+    }
+}
 
 impl<T: Clone> From<Vec<T>> for CachedVec<T> {
     fn from(v: Vec<T>) -> Self {
-        CachedVec(v)
+        CachedVec {
+            items: v,
+            loaded_at: SystemTime::now(),
+        }
     }
 }
 
 impl<T: Clone> From<CachedVec<T>> for Vec<T> {
     fn from(cv: CachedVec<T>) -> Self {
-        cv.0
+        cv.items
+    }
+}
+
+/// Expires a cached list `ttl` after it was loaded from Azure rather than after
+/// it was inserted, which differ for lists restored from the disk snapshot
+struct LoadedAtExpiry {
+    ttl: Duration,
+}
+
+impl<T: Clone> Expiry<String, CachedVec<T>> for LoadedAtExpiry {
+    fn expire_after_create(
+        &self,
+        _key: &String,
+        value: &CachedVec<T>,
+        _created_at: Instant,
+    ) -> Option<Duration> {
+        Some(self.ttl.saturating_sub(value.age()))
+    }
+
+    fn expire_after_update(
+        &self,
+        _key: &String,
+        value: &CachedVec<T>,
+        _updated_at: Instant,
+        _duration_until_expiry: Option<Duration>,
+    ) -> Option<Duration> {
+        Some(self.ttl.saturating_sub(value.age()))
+    }
+}
+
+/// A cached list as stored in the disk snapshot
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SnapshotEntry<T> {
+    key: String,
+    /// Unix timestamp (seconds) of when the list was loaded from Azure
+    loaded_at: u64,
+    items: Vec<T>,
+}
+
+/// The list caches as written to disk; secret values are deliberately absent
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CacheSnapshot {
+    version: u32,
+    subscriptions: Vec<SnapshotEntry<Subscription>>,
+    resource_groups: Vec<SnapshotEntry<ResourceGroup>>,
+    keyvaults: Vec<SnapshotEntry<KeyVault>>,
+    secrets_list: Vec<SnapshotEntry<Secret>>,
+}
+
+/// Snapshot file of the signed-in account; `None` until a sign-in completes
+static SNAPSHOT_PATH: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+/// The account a cache snapshot belongs to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SnapshotOwner {
+    pub cloud: CloudEnvironment,
+    pub tenant_id: String,
+    /// Object id of the signed-in user or service principal
+    pub object_id: String,
+}
+
+impl SnapshotOwner {
+    /// File name of the owner's snapshot; the ids are hashed so they can't shape the path
+    fn file_name(&self) -> String {
+        let owner = format!("{:?}/{}/{}", self.cloud, self.tenant_id, self.object_id);
+        format!("{}_{:x}.json", SNAPSHOT_FILE_PREFIX, Sha256::digest(owner))
+    }
+}
+
+/// Get the snapshot file path of `owner`
+fn get_snapshot_path(owner: &SnapshotOwner) -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join(APP_NAME).join(owner.file_name()))
+}
+
+/// The snapshot file of the signed-in account, if any
+fn current_snapshot_path() -> Option<PathBuf> {
+    SNAPSHOT_PATH
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+}
+
+/// Stop saving the snapshot for the signed-in account, e.g. on logout.
+///
+/// The file is left in place; `AzureCache::clear_all` deletes it.
+pub fn forget_snapshot_owner() {
+    *SNAPSHOT_PATH.lock().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+/// Write `content` to a temp file only the user can read, then rename it over `path`,
+/// so a crash mid-write never leaves a truncated snapshot
+fn write_snapshot_file(path: &Path, content: &str) -> std::io::Result<()> {
+    let temp_path = path.with_extension("json.tmp");
+    // The mode only applies to new files, so never reuse a leftover temp file
+    let _ = fs::remove_file(&temp_path);
+
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+
+    let mut file = options.open(&temp_path)?;
+    file.write_all(content.as_bytes())?;
+    file.sync_all()?;
+    fs::rename(&temp_path, path)
+}
+
+/// Delete the snapshot file at `path`; a missing file is fine
+fn remove_snapshot_file(path: &Path) {
+    match fs::remove_file(path) {
+        Ok(()) => info!("Deleted cache snapshot {:?}", path),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => warn!("Failed to delete cache snapshot {:?}: {}", path, e),
+    }
+}
+
+/// Collect a cache's entries for the snapshot
+fn snapshot_entries<T>(cache: &Cache<String, CachedVec<T>>) -> Vec<SnapshotEntry<T>>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    cache
+        .iter()
+        .map(|(key, value)| SnapshotEntry {
+            key: key.as_ref().clone(),
+            loaded_at: value
+                .loaded_at
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            items: value.items,
+        })
+        .collect()
+}
+
+/// Put snapshot entries back into a cache, skipping those already past `ttl`.
+///
+/// Returns the number of entries restored.
+async fn restore_entries<T>(
+    cache: &Cache<String, CachedVec<T>>,
+    tracker: Option<&RefreshTracker>,
+    entries: Vec<SnapshotEntry<T>>,
+    ttl: Duration,
+) -> usize
+where
+    T: Clone + Send + Sync + 'static,
+{
+    let mut restored = 0;
+    for entry in entries {
+        let value = CachedVec {
+            items: entry.items,
+            loaded_at: UNIX_EPOCH + Duration::from_secs(entry.loaded_at),
+        };
+        let age = value.age();
+        if age >= ttl {
+            continue;
+        }
+        if let Some(tracker) = tracker {
+            if let Some(loaded_at) = tracker.now().checked_sub(age) {
+                tracker.mark_loaded_at(&entry.key, loaded_at);
+            }
+        }
+        cache.insert(entry.key, value).await;
+        restored += 1;
+    }
+    restored
+}
+
+fn read_snapshot<T: DeserializeOwned>(path: &Path) -> Result<T, String> {
+    let content = fs::read_to_string(path).map_err(|e| format!("Failed to read file: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse: {}", e))
+}
+
+/// Latest-value keys of the secret values cache per vault (key: vault_uri)
+type SecretValueKeys = Arc<Mutex<HashMap<String, HashSet<String>>>>;
+
+/// Drop `key` (`vault_uri::secret_name`) from the vault's tracked keys
+fn untrack_secret_value_key(index: &Mutex<HashMap<String, HashSet<String>>>, key: &str) {
+    let Some((vault_uri, _)) = key.split_once("::") else {
+        return;
+    };
+    let mut tracked = index.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(keys) = tracked.get_mut(vault_uri) {
+        keys.remove(key);
+        if keys.is_empty() {
+            tracked.remove(vault_uri);
+        }
     }
 }
 
@@ -68,39 +340,370 @@ pub struct AzureCache {
 
     /// Cache for secret values (key: "vault_uri::secret_name")
     secret_values: Cache<String, SecretBundle>,
+
+    /// Latest-value keys in `secret_values` per vault (key: vault_uri), so a vault's
+    /// values can be invalidated together; Moka has no prefix invalidation.
+    /// Pruned by the eviction listener of `secret_values`.
+    secret_value_keys: SecretValueKeys,
+
+    /// Cache for keys list per vault (key: vault_uri)
+    keys_list: Cache<String, CachedVec<Key>>,
+
+    /// Cache for role definition names (key: lowercased full role definition ID)
+    role_definitions: Cache<String, String>,
+
+    /// Cache for OpenID discovery documents (key: discovery document URL)
+    openid_configs: Cache<String, OpenIdConfiguration>,
+
+    /// Whether near-expired keyvaults and secrets-list entries are refreshed in the background
+    refresh_ahead: AtomicBool,
+
+    /// Load times for refresh-ahead of the keyvaults cache
+    keyvaults_refresh: RefreshTracker,
+
+    /// Load times for refresh-ahead of the secrets-list cache
+    secrets_list_refresh: RefreshTracker,
+
+    /// Hits and misses of the `*_or_load` lookups, per cache
+    counters: CacheCounters,
+
+    /// TTLs the caches were built with, also used when restoring the snapshot
+    ttls: CacheTtls,
+}
+
+/// Hit and miss counts of one cache
+///
+/// Relaxed atomics: the counts are informational, so they needn't be ordered
+/// with anything else.
+#[derive(Default)]
+struct HitCounter {
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl HitCounter {
+    /// Count a lookup; `fresh` means the value had to be loaded
+    fn record(&self, fresh: bool) {
+        let counter = if fresh { &self.misses } else { &self.hits };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn hits(&self) -> u64 {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    fn misses(&self) -> u64 {
+        self.misses.load(Ordering::Relaxed)
+    }
+
+    fn reset(&self) {
+        self.hits.store(0, Ordering::Relaxed);
+        self.misses.store(0, Ordering::Relaxed);
+    }
+}
+
+/// Hit and miss counts per cache, as reported in `CacheStatistics`
+#[derive(Default)]
+struct CacheCounters {
+    subscriptions: HitCounter,
+    resource_groups: HitCounter,
+    keyvaults: HitCounter,
+    secrets_list: HitCounter,
+    secret_values: HitCounter,
+    keys_list: HitCounter,
+    role_definitions: HitCounter,
+}
+
+impl CacheCounters {
+    fn all(&self) -> [&HitCounter; 7] {
+        [
+            &self.subscriptions,
+            &self.resource_groups,
+            &self.keyvaults,
+            &self.secrets_list,
+            &self.secret_values,
+            &self.keys_list,
+            &self.role_definitions,
+        ]
+    }
+}
+
+/// Source of the current time, so tests don't have to wait for it
+type Clock = Arc<dyn Fn() -> Instant + Send + Sync>;
+
+/// Tracks when entries were loaded and which ones are being refreshed
+///
+/// Load times are dropped when their entries leave the cache, see `forget_on_eviction`.
+#[derive(Clone)]
+struct RefreshTracker {
+    refresh_after: Duration,
+    loaded_at: Arc<Mutex<HashMap<String, Instant>>>,
+    in_flight: Arc<Mutex<HashSet<String>>>,
+    clock: Clock,
+}
+
+impl RefreshTracker {
+    fn new(ttl: Duration) -> Self {
+        Self::with_clock(ttl, Arc::new(Instant::now))
+    }
+
+    fn with_clock(ttl: Duration, clock: Clock) -> Self {
+        Self {
+            refresh_after: ttl * REFRESH_AHEAD_AFTER_PERCENT / 100,
+            loaded_at: Arc::new(Mutex::new(HashMap::new())),
+            in_flight: Arc::new(Mutex::new(HashSet::new())),
+            clock,
+        }
+    }
+
+    fn now(&self) -> Instant {
+        (self.clock)()
+    }
+
+    fn mark_loaded(&self, key: &str) {
+        self.mark_loaded_at(key, self.now());
+    }
+
+    fn mark_loaded_at(&self, key: &str, loaded_at: Instant) {
+        self.loaded_at
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(key.to_string(), loaded_at);
+    }
+
+    fn forget(&self, key: &str) {
+        self.loaded_at
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(key);
+    }
+
+    fn forget_all(&self) {
+        self.loaded_at
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clear();
+    }
+
+    /// Claim a background refresh for `key` if it is near expiry and none is running.
+    ///
+    /// Returns the load time the refresh started from, so its result can be
+    /// dropped if the entry was invalidated or reloaded in the meantime.
+    fn try_start(&self, key: &str) -> Option<Instant> {
+        let loaded_at = *self
+            .loaded_at
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(key)?;
+        if self.now().saturating_duration_since(loaded_at) < self.refresh_after {
+            return None;
+        }
+        self.in_flight
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(key.to_string())
+            .then_some(loaded_at)
+    }
+
+    /// Release the claim; returns whether the refreshed value should be stored
+    fn finish(&self, key: &str, started_from: Instant) -> bool {
+        self.in_flight
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(key);
+        self.loaded_at
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(key)
+            == Some(&started_from)
+    }
+}
+
+/// Eviction listener that drops the load time of entries that left the cache
+///
+/// A replaced entry is still cached, so its (new) load time is kept.
+fn forget_on_eviction<V>(
+    tracker: &RefreshTracker,
+) -> impl Fn(Arc<String>, V, RemovalCause) + Send + Sync + 'static {
+    let tracker = tracker.clone();
+    move |key, _, cause| {
+        if cause != RemovalCause::Replaced {
+            tracker.forget(&key);
+        }
+    }
+}
+
+/// Get a value from a cache, running `loader` on a miss.
+///
+/// Concurrent misses for the same key are coalesced: only one caller runs its
+/// loader and the rest wait for and share its result. Errors are not cached.
+///
+/// Returns the value and whether it was freshly loaded.
+async fn get_or_load<V, Fut>(
+    cache: &Cache<String, V>,
+    key: &str,
+    loader: Fut,
+) -> Result<(V, bool), String>
+where
+    V: Clone + Send + Sync + 'static,
+    Fut: std::future::Future<Output = Result<V, String>>,
+{
+    cache
+        .entry(key.to_string())
+        .or_try_insert_with(loader)
+        .await
+        .map(|entry| {
+            let fresh = entry.is_fresh();
+            (entry.into_value(), fresh)
+        })
+        .map_err(|e| e.as_ref().clone())
+}
+
+/// Like [`get_or_load`], but with refresh-ahead when `refresh_ahead` is set.
+///
+/// A hit on a near-expired entry returns the cached value immediately and
+/// reloads it in the background, so the next access doesn't pay for the
+/// reload. A failed background reload leaves the cached value in place.
+async fn get_or_load_refreshing<V, F, Fut>(
+    cache: &Cache<String, V>,
+    tracker: &RefreshTracker,
+    refresh_ahead: bool,
+    key: &str,
+    loader: F,
+) -> Result<(V, bool), String>
+where
+    V: Clone + Send + Sync + 'static,
+    F: FnOnce() -> Fut,
+    Fut: std::future::Future<Output = Result<V, String>> + Send + 'static,
+{
+    if refresh_ahead {
+        if let Some(value) = cache.get(key).await {
+            if let Some(started_from) = tracker.try_start(key) {
+                debug!(
+                    "Refreshing near-expired cache entry {} in the background",
+                    key
+                );
+                let cache = cache.clone();
+                let tracker = tracker.clone();
+                let key = key.to_string();
+                let reload = loader();
+                tokio::spawn(async move {
+                    let result = reload.await;
+                    if !tracker.finish(&key, started_from) {
+                        debug!("Discarding background refresh of invalidated entry {}", key);
+                        return;
+                    }
+                    match result {
+                        Ok(value) => {
+                            cache.insert(key.clone(), value).await;
+                            tracker.mark_loaded(&key);
+                        }
+                        Err(e) => warn!("Background refresh of {} failed: {}", key, e),
+                    }
+                });
+            }
+            return Ok((value, false));
+        }
+    }
+
+    let (value, fresh) = get_or_load(cache, key, loader()).await?;
+    if fresh {
+        tracker.mark_loaded(key);
+    }
+    Ok((value, fresh))
 }
 
 impl AzureCache {
     /// Create a new cache instance with default TTLs
     pub fn new() -> Self {
+        Self::with_ttls(CacheTtls::default())
+    }
+
+    /// Create a new cache instance with the given TTLs
+    pub fn with_ttls(ttls: CacheTtls) -> Self {
+        let secret_value_keys = SecretValueKeys::default();
+        let evicted_keys = secret_value_keys.clone();
+        let keyvaults_refresh = RefreshTracker::new(ttls.keyvaults);
+        let secrets_list_refresh = RefreshTracker::new(ttls.secrets_list);
+
         Self {
             subscriptions: Cache::builder()
                 .max_capacity(100)
-                .time_to_live(Duration::from_secs(SUBSCRIPTION_TTL_SECS))
+                .expire_after(LoadedAtExpiry {
+                    ttl: ttls.subscriptions,
+                })
                 .build(),
 
             resource_groups: Cache::builder()
                 .max_capacity(1_000)
-                .time_to_live(Duration::from_secs(RESOURCE_GROUP_TTL_SECS))
+                .expire_after(LoadedAtExpiry {
+                    ttl: ttls.resource_groups,
+                })
                 .build(),
 
             keyvaults: Cache::builder()
                 .max_capacity(1_000)
-                .time_to_live(Duration::from_secs(KEYVAULT_TTL_SECS))
+                .expire_after(LoadedAtExpiry {
+                    ttl: ttls.keyvaults,
+                })
+                .eviction_listener(forget_on_eviction(&keyvaults_refresh))
                 .build(),
 
             secrets_list: Cache::builder()
                 .max_capacity(1_000)
-                .time_to_live(Duration::from_secs(SECRETS_LIST_TTL_SECS))
+                .expire_after(LoadedAtExpiry {
+                    ttl: ttls.secrets_list,
+                })
+                .eviction_listener(forget_on_eviction(&secrets_list_refresh))
                 .build(),
 
             secret_values: Cache::builder()
                 .max_capacity(MAX_CACHE_ENTRIES)
-                .time_to_live(Duration::from_secs(SECRET_VALUE_TTL_SECS))
+                .time_to_live(ttls.secret_values)
+                // Expired and evicted values leave the index; a replaced one is still cached
+                .eviction_listener(move |key: Arc<String>, _, cause| {
+                    if cause != RemovalCause::Replaced {
+                        untrack_secret_value_key(&evicted_keys, &key);
+                    }
+                })
+                .build(),
+
+            secret_value_keys,
+
+            keys_list: Cache::builder()
+                .max_capacity(1_000)
+                .expire_after(LoadedAtExpiry {
+                    ttl: ttls.keys_list,
+                })
+                .build(),
+
+            role_definitions: Cache::builder()
+                .max_capacity(1_000)
+                .time_to_live(Duration::from_secs(ROLE_DEFINITION_TTL_SECS))
+                .build(),
+
+            openid_configs: Cache::builder()
+                .max_capacity(10)
+                .time_to_live(Duration::from_secs(OPENID_CONFIG_TTL_SECS))
                 .build(),
+
+            refresh_ahead: AtomicBool::new(false),
+            keyvaults_refresh,
+            secrets_list_refresh,
+            counters: CacheCounters::default(),
+            ttls,
         }
     }
 
+    /// Enable or disable refresh-ahead for the keyvaults and secrets-list caches
+    pub fn set_refresh_ahead(&self, enabled: bool) {
+        self.refresh_ahead.store(enabled, Ordering::Relaxed);
+        debug!(
+            "Cache refresh-ahead {}",
+            if enabled { "enabled" } else { "disabled" }
+        );
+    }
+
     // ==================== Subscription ====================
 
     /// Get subscription from cache by id
@@ -109,10 +712,10 @@ impl AzureCache {
         if result.is_some() {
             debug!("Cache hit for subscription");
         }
-        result.map(|v| {
-            v.0.into_iter()
+        result.and_then(|v| {
+            v.items
+                .into_iter()
                 .find(|s| s.subscription_id == subscription_id)
-                .unwrap()
         })
     }
 
@@ -129,6 +732,7 @@ impl AzureCache {
         // Try to get from cache first
         if let Some(cached) = self.get_subscription(subscription_id).await {
             debug!("Cache hit for subscription");
+            self.counters.subscriptions.record(false);
             return Ok(cached);
         }
 
@@ -136,19 +740,20 @@ impl AzureCache {
 
         // Load from Azure
         let subscription = loader().await?;
+        self.counters.subscriptions.record(true);
 
         // Store in cache
         let mut subscriptions: Vec<Subscription> = self
             .subscriptions
             .get("subscriptions")
             .await
-            .map(|v| v.0)
+            .map(|v| v.items)
             .unwrap_or_else(Vec::new);
 
         subscriptions.push(subscription.clone());
 
         self.subscriptions
-            .insert("subscriptions".to_string(), CachedVec(subscriptions))
+            .insert("subscriptions".to_string(), CachedVec::from(subscriptions))
             .await;
 
         info!("Cached subscription {}", subscription_id);
@@ -166,27 +771,18 @@ impl AzureCache {
         F: FnOnce() -> Fut,
         Fut: std::future::Future<Output = Result<Vec<Subscription>, String>>,
     {
-        // Try to get from cache first
-        if let Some(cached) = self.subscriptions.get("subscriptions").await {
+        let (subscriptions, fresh) = get_or_load(&self.subscriptions, "subscriptions", async {
+            loader().await.map(CachedVec::from)
+        })
+        .await?;
+        self.counters.subscriptions.record(fresh);
+
+        if fresh {
+            info!("Cached {} subscriptions", subscriptions.items.len());
+        } else {
             debug!("Cache hit for subscriptions");
-            return Ok(cached.0);
         }
-
-        debug!("Cache miss for subscriptions, loading...");
-
-        // Load from Azure
-        let subscriptions = loader().await?;
-
-        // Store in cache
-        self.subscriptions
-            .insert(
-                "subscriptions".to_string(),
-                CachedVec(subscriptions.clone()),
-            )
-            .await;
-
-        info!("Cached {} subscriptions", subscriptions.len());
-        Ok(subscriptions)
+        Ok(subscriptions.items)
     }
 
     /// Invalidate subscriptions cache
@@ -207,40 +803,28 @@ impl AzureCache {
         F: FnOnce() -> Fut,
         Fut: std::future::Future<Output = Result<Vec<ResourceGroup>, String>>,
     {
-        // Try to get from cache first
-        if let Some(cached) = self.resource_groups.get(subscription_id).await {
+        let (resource_groups, fresh) =
+            get_or_load(&self.resource_groups, subscription_id, async {
+                loader().await.map(CachedVec::from)
+            })
+            .await?;
+        self.counters.resource_groups.record(fresh);
+
+        if fresh {
+            info!(
+                "Cached {} resource groups for subscription {}",
+                resource_groups.items.len(),
+                subscription_id
+            );
+        } else {
             debug!(
                 "Cache hit for resource groups in subscription {}",
                 subscription_id
             );
-            return Ok(cached.0);
         }
-
-        debug!(
-            "Cache miss for resource groups in subscription {}, loading...",
-            subscription_id
-        );
-
-        // Load from Azure
-        let resource_groups = loader().await?;
-
-        // Store in cache
-        self.resource_groups
-            .insert(
-                subscription_id.to_string(),
-                CachedVec(resource_groups.clone()),
-            )
-            .await;
-
-        info!(
-            "Cached {} resource groups for subscription {}",
-            resource_groups.len(),
-            subscription_id
-        );
-        Ok(resource_groups)
+        Ok(resource_groups.items)
     }
 
-
     /// Invalidate resource groups cache for a subscription
     pub async fn invalidate_resource_groups(&self, subscription_id: &str) {
         self.resource_groups.invalidate(subscription_id).await;
@@ -260,40 +844,60 @@ impl AzureCache {
     ) -> Result<Vec<KeyVault>, String>
     where
         F: FnOnce() -> Fut,
-        Fut: std::future::Future<Output = Result<Vec<KeyVault>, String>>,
+        Fut: std::future::Future<Output = Result<Vec<KeyVault>, String>> + Send + 'static,
     {
-        // Try to get from cache first
-        if let Some(cached) = self.keyvaults.get(subscription_id).await {
+        let (keyvaults, fresh) = get_or_load_refreshing(
+            &self.keyvaults,
+            &self.keyvaults_refresh,
+            self.refresh_ahead.load(Ordering::Relaxed),
+            subscription_id,
+            || {
+                let load = loader();
+                async move { load.await.map(CachedVec::from) }
+            },
+        )
+        .await?;
+        self.counters.keyvaults.record(fresh);
+
+        if fresh {
+            info!(
+                "Cached {} keyvaults for subscription {}",
+                keyvaults.items.len(),
+                subscription_id
+            );
+        } else {
             debug!(
                 "Cache hit for keyvaults in subscription {}",
                 subscription_id
             );
-            return Ok(cached.0);
         }
+        Ok(keyvaults.items)
+    }
 
-        debug!(
-            "Cache miss for keyvaults in subscription {}, loading...",
-            subscription_id
-        );
-
-        // Load from Azure
-        let keyvaults = loader().await?;
-
-        // Store in cache
-        self.keyvaults
-            .insert(subscription_id.to_string(), CachedVec(keyvaults.clone()))
-            .await;
-
-        info!(
-            "Cached {} keyvaults for subscription {}",
-            keyvaults.len(),
-            subscription_id
-        );
-        Ok(keyvaults)
+    /// Replace a single vault in the cached keyvaults list for a subscription
+    /// Does nothing if the list isn't cached or doesn't contain the vault
+    pub async fn update_keyvault(&self, subscription_id: &str, vault: KeyVault) {
+        if let Some(mut cached) = self.keyvaults.get(subscription_id).await {
+            if let Some(existing) = cached
+                .items
+                .iter_mut()
+                .find(|kv| kv.id.eq_ignore_ascii_case(&vault.id))
+            {
+                *existing = vault;
+                self.keyvaults
+                    .insert(subscription_id.to_string(), cached)
+                    .await;
+                debug!(
+                    "Updated cached keyvault in subscription {}",
+                    subscription_id
+                );
+            }
+        }
     }
 
     /// Invalidate keyvaults cache for a subscription
     pub async fn invalidate_keyvaults(&self, subscription_id: &str) {
+        self.keyvaults_refresh.forget(subscription_id);
         self.keyvaults.invalidate(subscription_id).await;
         debug!(
             "Invalidated keyvaults cache for subscription {}",
@@ -311,33 +915,36 @@ impl AzureCache {
     ) -> Result<Vec<Secret>, String>
     where
         F: FnOnce() -> Fut,
-        Fut: std::future::Future<Output = Result<Vec<Secret>, String>>,
+        Fut: std::future::Future<Output = Result<Vec<Secret>, String>> + Send + 'static,
     {
-        // Try to get from cache first
-        if let Some(cached) = self.secrets_list.get(vault_uri).await {
+        let (secrets, fresh) = get_or_load_refreshing(
+            &self.secrets_list,
+            &self.secrets_list_refresh,
+            self.refresh_ahead.load(Ordering::Relaxed),
+            vault_uri,
+            || {
+                let load = loader();
+                async move { load.await.map(CachedVec::from) }
+            },
+        )
+        .await?;
+        self.counters.secrets_list.record(fresh);
+
+        if fresh {
+            info!(
+                "Cached {} secrets for vault {}",
+                secrets.items.len(),
+                vault_uri
+            );
+        } else {
             debug!("Cache hit for secrets list in vault {}", vault_uri);
-            return Ok(cached.0);
         }
-
-        debug!(
-            "Cache miss for secrets list in vault {}, loading...",
-            vault_uri
-        );
-
-        // Load from Azure
-        let secrets = loader().await?;
-
-        // Store in cache
-        self.secrets_list
-            .insert(vault_uri.to_string(), CachedVec(secrets.clone()))
-            .await;
-
-        info!("Cached {} secrets for vault {}", secrets.len(), vault_uri);
-        Ok(secrets)
+        Ok(secrets.items)
     }
 
     /// Invalidate secrets list cache for a vault
     pub async fn invalidate_secrets_list(&self, vault_uri: &str) {
+        self.secrets_list_refresh.forget(vault_uri);
         self.secrets_list.invalidate(vault_uri).await;
         self.secrets_list.run_pending_tasks().await;
         debug!("Invalidated secrets list cache for vault {}", vault_uri);
@@ -350,6 +957,31 @@ impl AzureCache {
         format!("{}::{}", vault_uri, secret_name)
     }
 
+    /// Record that `key` holds a secret value of `vault_uri`
+    ///
+    /// The key is dropped again when its entry is invalidated, expires or is evicted.
+    fn track_secret_value_key(&self, vault_uri: &str, key: &str) {
+        self.secret_value_keys
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry(vault_uri.to_string())
+            .or_default()
+            .insert(key.to_string());
+    }
+
+    /// Get a cached secret value without loading on miss
+    pub async fn get_secret_value(&self, vault_uri: &str, secret_name: &str) -> Option<SecretBundle> {
+        let key = Self::secret_key(vault_uri, secret_name);
+        let result = self.secret_values.get(&key).await;
+        if result.is_some() {
+            debug!(
+                "Cache hit for secret {} in vault {}",
+                secret_name, vault_uri
+            );
+        }
+        result
+    }
+
     /// Get secret value with automatic loading on cache miss
     pub async fn get_secret_value_or_load<F, Fut>(
         &self,
@@ -363,27 +995,18 @@ impl AzureCache {
     {
         let key = Self::secret_key(vault_uri, secret_name);
 
-        // Try to get from cache first
-        if let Some(cached) = self.secret_values.get(&key).await {
+        let (secret, fresh) = get_or_load(&self.secret_values, &key, loader()).await?;
+        self.counters.secret_values.record(fresh);
+        self.track_secret_value_key(vault_uri, &key);
+
+        if fresh {
+            debug!("Cached secret {} for vault {}", secret_name, vault_uri);
+        } else {
             debug!(
                 "Cache hit for secret {} in vault {}",
                 secret_name, vault_uri
             );
-            return Ok(cached);
         }
-
-        debug!(
-            "Cache miss for secret {} in vault {}, loading...",
-            secret_name, vault_uri
-        );
-
-        // Load from Azure
-        let secret = loader().await?;
-
-        // Store in cache
-        self.secret_values.insert(key, secret.clone()).await;
-
-        debug!("Cached secret {} for vault {}", secret_name, vault_uri);
         Ok(secret)
     }
 
@@ -391,14 +1014,56 @@ impl AzureCache {
     pub async fn cache_secret_value(&self, vault_uri: &str, secret: SecretBundle) {
         let name = secret.id.split('/').last().unwrap_or("").to_string();
         let key = Self::secret_key(vault_uri, &name);
+        self.track_secret_value_key(vault_uri, &key);
         self.secret_values.insert(key, secret).await;
     }
 
+    /// Build the cache key for a specific secret version
+    fn secret_version_key(vault_uri: &str, secret_name: &str, version: &str) -> String {
+        format!("{}::{}::{}", vault_uri, secret_name, version)
+    }
+
+    /// Get a specific secret version with automatic loading on cache miss
+    ///
+    /// Versions are immutable, so these entries are never invalidated by writes
+    /// and simply expire with the TTL.
+    pub async fn get_secret_version_or_load<F, Fut>(
+        &self,
+        vault_uri: &str,
+        secret_name: &str,
+        version: &str,
+        loader: F,
+    ) -> Result<SecretBundle, String>
+    where
+        F: FnOnce() -> Fut,
+        Fut: std::future::Future<Output = Result<SecretBundle, String>>,
+    {
+        let key = Self::secret_version_key(vault_uri, secret_name, version);
+
+        let (secret, fresh) = get_or_load(&self.secret_values, &key, loader()).await?;
+        self.counters.secret_values.record(fresh);
+
+        if fresh {
+            debug!(
+                "Cached version {} of secret {} for vault {}",
+                version, secret_name, vault_uri
+            );
+        } else {
+            debug!(
+                "Cache hit for version {} of secret {} in vault {}",
+                version, secret_name, vault_uri
+            );
+        }
+        Ok(secret)
+    }
+
     /// Invalidate a secret value
     pub async fn invalidate_secret_value(&self, vault_uri: &str, secret_name: &str) {
         let key = Self::secret_key(vault_uri, secret_name);
         self.secret_values.invalidate(&key).await;
         self.secret_values.run_pending_tasks().await;
+        untrack_secret_value_key(&self.secret_value_keys, &key);
+
         debug!(
             "Invalidated secret {} cache for vault {}",
             secret_name, vault_uri
@@ -406,37 +1071,307 @@ impl AzureCache {
     }
 
     /// Invalidate all secrets for a vault (both list and values)
+    ///
+    /// Cached versions are left alone, as they can't change.
     pub async fn invalidate_vault_secrets(&self, vault_uri: &str) {
         // Invalidate the secrets list
+        self.secrets_list_refresh.forget(vault_uri);
         self.secrets_list.invalidate(vault_uri).await;
         self.secrets_list.run_pending_tasks().await;
 
-        // Note: We can't easily invalidate all secret values for a vault
-        // since Moka doesn't support prefix-based invalidation.
-        // The TTL will handle expiration, or we could track keys separately.
-        debug!("Invalidated secrets list for vault {}", vault_uri);
+        // Invalidate the latest values, using the keys tracked for this vault
+        let keys = self
+            .secret_value_keys
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(vault_uri)
+            .unwrap_or_default();
+        for key in &keys {
+            self.secret_values.invalidate(key).await;
+        }
+        self.secret_values.run_pending_tasks().await;
+
+        debug!(
+            "Invalidated secrets list and {} secret values for vault {}",
+            keys.len(),
+            vault_uri
+        );
+    }
+
+    // ==================== Keys List ====================
+
+    /// Get keys list with automatic loading on cache miss
+    pub async fn get_keys_list_or_load<F, Fut>(
+        &self,
+        vault_uri: &str,
+        loader: F,
+    ) -> Result<Vec<Key>, String>
+    where
+        F: FnOnce() -> Fut,
+        Fut: std::future::Future<Output = Result<Vec<Key>, String>>,
+    {
+        let (keys, fresh) = get_or_load(&self.keys_list, vault_uri, async {
+            loader().await.map(CachedVec::from)
+        })
+        .await?;
+        self.counters.keys_list.record(fresh);
+
+        if fresh {
+            info!("Cached {} keys for vault {}", keys.items.len(), vault_uri);
+        } else {
+            debug!("Cache hit for keys list in vault {}", vault_uri);
+        }
+        Ok(keys.items)
+    }
+
+    /// Invalidate keys list cache for a vault
+    pub async fn invalidate_keys_list(&self, vault_uri: &str) {
+        self.keys_list.invalidate(vault_uri).await;
+        debug!("Invalidated keys list cache for vault {}", vault_uri);
+    }
+
+    // ==================== Role Definitions ====================
+
+    /// Get a role definition's name with automatic loading on cache miss
+    pub async fn get_role_definition_name_or_load<F, Fut>(
+        &self,
+        role_definition_id: &str,
+        loader: F,
+    ) -> Result<String, String>
+    where
+        F: FnOnce() -> Fut,
+        Fut: std::future::Future<Output = Result<String, String>>,
+    {
+        let key = role_definition_id.to_lowercase();
+        let (name, fresh) = get_or_load(&self.role_definitions, &key, loader()).await?;
+        self.counters.role_definitions.record(fresh);
+
+        if fresh {
+            debug!("Cached role definition {}", role_definition_id);
+        } else {
+            debug!("Cache hit for role definition {}", role_definition_id);
+        }
+        Ok(name)
+    }
+
+    // ==================== OpenID Configuration ====================
+
+    /// Get a tenant's OpenID discovery document with automatic loading on cache miss
+    pub async fn get_openid_config_or_load<F, Fut>(
+        &self,
+        discovery_url: &str,
+        loader: F,
+    ) -> Result<OpenIdConfiguration, String>
+    where
+        F: FnOnce() -> Fut,
+        Fut: std::future::Future<Output = Result<OpenIdConfiguration, String>>,
+    {
+        let (config, fresh) = get_or_load(&self.openid_configs, discovery_url, loader()).await?;
+
+        if fresh {
+            debug!("Cached OpenID configuration from {}", discovery_url);
+        } else {
+            debug!("Cache hit for OpenID configuration from {}", discovery_url);
+        }
+        Ok(config)
+    }
+
+    // ==================== Disk Snapshot ====================
+
+    /// Write the subscriptions, resource groups, keyvaults and secrets-list
+    /// caches to the signed-in account's snapshot file in the config directory.
+    ///
+    /// Secret values are never written. Without a signed-in account nothing is saved.
+    pub fn save_to_disk(&self) -> Result<(), String> {
+        match current_snapshot_path() {
+            Some(path) => self.save_to_path(&path),
+            None => {
+                debug!("No signed-in account, cache snapshot not saved");
+                Ok(())
+            }
+        }
+    }
+
+    /// The list caches as they would be written to disk
+    fn snapshot(&self) -> CacheSnapshot {
+        CacheSnapshot {
+            version: SNAPSHOT_VERSION,
+            subscriptions: snapshot_entries(&self.subscriptions),
+            resource_groups: snapshot_entries(&self.resource_groups),
+            keyvaults: snapshot_entries(&self.keyvaults),
+            secrets_list: snapshot_entries(&self.secrets_list),
+        }
+    }
+
+    fn save_to_path(&self, path: &Path) -> Result<(), String> {
+        let snapshot = self.snapshot();
+
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| format!("Failed to create directory: {}", e))?;
+        }
+        let content =
+            serde_json::to_string(&snapshot).map_err(|e| format!("Failed to serialize: {}", e))?;
+        write_snapshot_file(path, &content).map_err(|e| format!("Failed to write file: {}", e))?;
+
+        info!("Saved cache snapshot to {:?}", path);
+        Ok(())
+    }
+
+    /// Restore the list caches from `owner`'s snapshot file, if there is one,
+    /// and save to that file from now on.
+    ///
+    /// Call once `owner` has signed in. Signing in again as the same account
+    /// keeps the current entries. Entries already past their TTL are skipped,
+    /// and restored entries expire when they would have without the restart.
+    /// A missing or unreadable snapshot leaves the caches empty.
+    pub async fn load_from_disk(&self, owner: &SnapshotOwner) {
+        let Some(path) = get_snapshot_path(owner) else {
+            warn!("Could not determine config directory, cache snapshot not loaded");
+            return;
+        };
+
+        {
+            let mut current = SNAPSHOT_PATH.lock().unwrap_or_else(|e| e.into_inner());
+            if current.as_ref() == Some(&path) {
+                return;
+            }
+            *current = Some(path.clone());
+        }
+
+        if path.exists() {
+            self.load_from_path(&path).await;
+        } else {
+            debug!("No cache snapshot found for the signed-in account");
+        }
+    }
+
+    async fn load_from_path(&self, path: &Path) {
+        let snapshot: CacheSnapshot = match read_snapshot(path) {
+            Ok(snapshot) => snapshot,
+            Err(e) => {
+                warn!("Ignoring unreadable cache snapshot: {}", e);
+                return;
+            }
+        };
+        if snapshot.version != SNAPSHOT_VERSION {
+            warn!(
+                "Ignoring cache snapshot with unsupported version {}",
+                snapshot.version
+            );
+            return;
+        }
+
+        let restored = self.restore_snapshot(snapshot).await;
+        info!("Restored {} cache entries from {:?}", restored, path);
+    }
+
+    /// Copy the list caches and the refresh-ahead setting of `previous`,
+    /// e.g. into a cache rebuilt with new TTLs.
+    ///
+    /// Like a snapshot restore, entries already past this cache's TTLs are
+    /// skipped and secret values aren't copied.
+    pub async fn carry_over(&self, previous: &AzureCache) {
+        self.set_refresh_ahead(previous.refresh_ahead.load(Ordering::Relaxed));
+        let carried = self.restore_snapshot(previous.snapshot()).await;
+        info!("Carried {} cache entries over to the new cache", carried);
+    }
+
+    /// Put a snapshot's entries into the list caches; returns how many were restored
+    async fn restore_snapshot(&self, snapshot: CacheSnapshot) -> usize {
+        restore_entries(
+            &self.subscriptions,
+            None,
+            snapshot.subscriptions,
+            self.ttls.subscriptions,
+        )
+        .await
+            + restore_entries(
+                &self.resource_groups,
+                None,
+                snapshot.resource_groups,
+                self.ttls.resource_groups,
+            )
+            .await
+            + restore_entries(
+                &self.keyvaults,
+                Some(&self.keyvaults_refresh),
+                snapshot.keyvaults,
+                self.ttls.keyvaults,
+            )
+            .await
+            + restore_entries(
+                &self.secrets_list,
+                Some(&self.secrets_list_refresh),
+                snapshot.secrets_list,
+                self.ttls.secrets_list,
+            )
+            .await
     }
 
     // ==================== Statistics ====================
 
     /// Get cache statistics
     pub fn get_stats(&self) -> CacheStatistics {
+        let counters = &self.counters;
+        let (hits, misses) = counters
+            .all()
+            .iter()
+            .fold((0, 0), |(hits, misses), c| (hits + c.hits(), misses + c.misses()));
+
         CacheStatistics {
             subscriptions_count: self.subscriptions.entry_count(),
             resource_groups_count: self.resource_groups.entry_count(),
             keyvaults_count: self.keyvaults.entry_count(),
             secrets_list_count: self.secrets_list.entry_count(),
             secret_values_count: self.secret_values.entry_count(),
+            keys_list_count: self.keys_list.entry_count(),
+            role_definitions_count: self.role_definitions.entry_count(),
+            subscriptions_hits: counters.subscriptions.hits(),
+            subscriptions_misses: counters.subscriptions.misses(),
+            resource_groups_hits: counters.resource_groups.hits(),
+            resource_groups_misses: counters.resource_groups.misses(),
+            keyvaults_hits: counters.keyvaults.hits(),
+            keyvaults_misses: counters.keyvaults.misses(),
+            secrets_list_hits: counters.secrets_list.hits(),
+            secrets_list_misses: counters.secrets_list.misses(),
+            secret_value_hits: counters.secret_values.hits(),
+            secret_value_misses: counters.secret_values.misses(),
+            keys_list_hits: counters.keys_list.hits(),
+            keys_list_misses: counters.keys_list.misses(),
+            role_definitions_hits: counters.role_definitions.hits(),
+            role_definitions_misses: counters.role_definitions.misses(),
+            hit_ratio: hit_ratio(hits, misses),
+        }
+    }
+
+    /// Reset the hit and miss counts; cached entries are kept
+    pub fn reset_stats(&self) {
+        for counter in self.counters.all() {
+            counter.reset();
         }
+        debug!("Reset cache hit and miss counts");
     }
 
-    /// Clear all caches
+    /// Clear all caches and delete the signed-in account's snapshot file
     pub async fn clear_all(&self) {
+        if let Some(path) = current_snapshot_path() {
+            remove_snapshot_file(&path);
+        }
+
         self.subscriptions.invalidate_all();
         self.resource_groups.invalidate_all();
         self.keyvaults.invalidate_all();
         self.secrets_list.invalidate_all();
         self.secret_values.invalidate_all();
+        self.secret_value_keys
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clear();
+        self.keys_list.invalidate_all();
+        self.role_definitions.invalidate_all();
+        self.openid_configs.invalidate_all();
+        self.keyvaults_refresh.forget_all();
+        self.secrets_list_refresh.forget_all();
 
         // Run pending tasks to ensure invalidations are processed immediately
         self.subscriptions.run_pending_tasks().await;
@@ -444,6 +1379,9 @@ impl AzureCache {
         self.keyvaults.run_pending_tasks().await;
         self.secrets_list.run_pending_tasks().await;
         self.secret_values.run_pending_tasks().await;
+        self.keys_list.run_pending_tasks().await;
+        self.role_definitions.run_pending_tasks().await;
+        self.openid_configs.run_pending_tasks().await;
 
         info!("Cleared all caches");
     }
@@ -455,7 +1393,16 @@ impl Default for AzureCache {
     }
 }
 
+/// Share of lookups served from the cache, or `None` before the first lookup
+fn hit_ratio(hits: u64, misses: u64) -> Option<f64> {
+    let lookups = hits + misses;
+    (lookups > 0).then(|| hits as f64 / lookups as f64)
+}
+
 /// Cache statistics
+///
+/// Hits and misses count the `*_or_load` lookups since startup or the last
+/// `reset_stats`. Lookups whose load failed aren't counted.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CacheStatistics {
@@ -464,9 +1411,521 @@ pub struct CacheStatistics {
     pub keyvaults_count: u64,
     pub secrets_list_count: u64,
     pub secret_values_count: u64,
+    pub keys_list_count: u64,
+    pub role_definitions_count: u64,
+    pub subscriptions_hits: u64,
+    pub subscriptions_misses: u64,
+    pub resource_groups_hits: u64,
+    pub resource_groups_misses: u64,
+    pub keyvaults_hits: u64,
+    pub keyvaults_misses: u64,
+    pub secrets_list_hits: u64,
+    pub secrets_list_misses: u64,
+    /// Latest values and specific versions together
+    pub secret_value_hits: u64,
+    pub secret_value_misses: u64,
+    pub keys_list_hits: u64,
+    pub keys_list_misses: u64,
+    pub role_definitions_hits: u64,
+    pub role_definitions_misses: u64,
+    /// Share of all lookups served from the cache (0.0 - 1.0)
+    pub hit_ratio: Option<f64>,
+}
+
+// Global cache instance, replaced as a whole when the TTLs change
+static AZURE_CACHE: OnceLock<RwLock<Arc<AzureCache>>> = OnceLock::new();
+
+fn global_cache() -> &'static RwLock<Arc<AzureCache>> {
+    AZURE_CACHE.get_or_init(|| RwLock::new(Arc::new(AzureCache::new())))
+}
+
+/// Get the global cache
+///
+/// Callers that are mid-operation when the cache is replaced finish with the
+/// instance they got; it is dropped once they're done.
+pub fn azure_cache() -> Arc<AzureCache> {
+    global_cache()
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
 }
 
-// Global cache instance
-lazy_static::lazy_static! {
-    pub static ref AZURE_CACHE: Arc<AzureCache> = Arc::new(AzureCache::new());
+/// Replace the global cache, e.g. with one built with different TTLs
+pub fn replace_azure_cache(cache: AzureCache) {
+    *global_cache().write().unwrap_or_else(|e| e.into_inner()) = Arc::new(cache);
+    info!("Replaced the cache");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::azure::keyvault::secret::types::{RecoveryLevel, SecretAttributes};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn secret_bundle(value: &str) -> SecretBundle {
+        SecretBundle {
+            id: "https://vault.vault.azure.net/secrets/db-password".to_string(),
+            attributes: SecretAttributes {
+                enabled: true,
+                created: 0,
+                updated: 0,
+                recovery_level: RecoveryLevel::Recoverable,
+                recoverable_days: 90,
+                exp: None,
+                nbf: None,
+            },
+            value: value.to_string(),
+            content_type: None,
+            tags: HashMap::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_misses_load_once() {
+        let cache = Arc::new(AzureCache::new());
+        let loads = Arc::new(AtomicUsize::new(0));
+
+        let handles: Vec<_> = (0..50)
+            .map(|_| {
+                let cache = cache.clone();
+                let loads = loads.clone();
+                tokio::spawn(async move {
+                    cache
+                        .get_secret_value_or_load("https://vault", "db-password", || async move {
+                            loads.fetch_add(1, Ordering::SeqCst);
+                            tokio::time::sleep(Duration::from_millis(50)).await;
+                            Ok(secret_bundle("hunter2"))
+                        })
+                        .await
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            assert_eq!(handle.await.unwrap().unwrap().value, "hunter2");
+        }
+        assert_eq!(loads.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_failed_load_is_not_cached() {
+        let cache = AzureCache::new();
+
+        let first = cache
+            .get_secrets_list_or_load("https://vault", || async { Err("boom".to_string()) })
+            .await;
+        assert_eq!(first.unwrap_err(), "boom");
+
+        let second = cache
+            .get_secrets_list_or_load("https://vault", || async { Ok(Vec::new()) })
+            .await;
+        assert!(second.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_secret_versions_are_cached_separately() {
+        let cache = AzureCache::new();
+
+        cache
+            .get_secret_value_or_load("https://vault", "db-password", || async {
+                Ok(secret_bundle("latest"))
+            })
+            .await
+            .unwrap();
+        let version = cache
+            .get_secret_version_or_load("https://vault", "db-password", "abc", || async {
+                Ok(secret_bundle("old"))
+            })
+            .await
+            .unwrap();
+        assert_eq!(version.value, "old");
+
+        // Writing a new value must not affect cached versions
+        cache.invalidate_secret_value("https://vault", "db-password").await;
+        let version = cache
+            .get_secret_version_or_load("https://vault", "db-password", "abc", || async {
+                Err("should not load".to_string())
+            })
+            .await
+            .unwrap();
+        assert_eq!(version.value, "old");
+    }
+
+    #[tokio::test]
+    async fn test_invalidate_vault_secrets_clears_values() {
+        let cache = AzureCache::new();
+
+        cache
+            .get_secret_value_or_load("https://vault", "db-password", || async {
+                Ok(secret_bundle("hunter2"))
+            })
+            .await
+            .unwrap();
+        let mut api_key = secret_bundle("abc123");
+        api_key.id = "https://vault.vault.azure.net/secrets/api-key".to_string();
+        cache.cache_secret_value("https://vault", api_key).await;
+        cache
+            .cache_secret_value("https://other-vault", secret_bundle("untouched"))
+            .await;
+
+        cache.invalidate_vault_secrets("https://vault").await;
+
+        assert!(cache.get_secret_value("https://vault", "db-password").await.is_none());
+        assert!(cache.get_secret_value("https://vault", "api-key").await.is_none());
+        assert!(cache
+            .get_secret_value("https://other-vault", "db-password")
+            .await
+            .is_some());
+    }
+
+    #[tokio::test]
+    async fn test_invalidated_secret_value_is_untracked() {
+        let cache = AzureCache::new();
+
+        cache
+            .cache_secret_value("https://vault", secret_bundle("hunter2"))
+            .await;
+        cache.invalidate_secret_value("https://vault", "db-password").await;
+
+        assert!(cache
+            .secret_value_keys
+            .lock()
+            .unwrap()
+            .get("https://vault")
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_evicted_secret_value_is_untracked() {
+        let cache = AzureCache::new();
+
+        cache
+            .cache_secret_value("https://vault", secret_bundle("hunter2"))
+            .await;
+        cache
+            .cache_secret_value("https://vault", secret_bundle("hunter3"))
+            .await;
+        cache.secret_values.run_pending_tasks().await;
+        assert_eq!(cache.secret_value_keys.lock().unwrap()["https://vault"].len(), 1);
+
+        // Bypasses `invalidate_secret_value`, so only the eviction listener can untrack it
+        cache.secret_values.invalidate_all();
+        cache.secret_values.run_pending_tasks().await;
+
+        assert!(cache.secret_value_keys.lock().unwrap().is_empty());
+    }
+
+    fn secret_item(name: &str) -> Secret {
+        Secret {
+            id: format!("https://vault.vault.azure.net/secrets/{}", name),
+            attributes: secret_bundle("").attributes,
+            tags: HashMap::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_round_trip_without_secret_values() {
+        let path = std::env::temp_dir()
+            .join(format!("vaultraider-test-{}", uuid::Uuid::new_v4()))
+            .join("cache_snapshot.json");
+
+        let cache = AzureCache::new();
+        cache
+            .get_secrets_list_or_load("https://vault", || async {
+                Ok(vec![secret_item("db-password")])
+            })
+            .await
+            .unwrap();
+        cache
+            .get_secret_value_or_load("https://vault", "db-password", || async {
+                Ok(secret_bundle("hunter2"))
+            })
+            .await
+            .unwrap();
+        cache.save_to_path(&path).unwrap();
+
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(content.contains("db-password"));
+        assert!(!path.with_extension("json.tmp").exists());
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+        assert!(
+            !content.contains("hunter2"),
+            "secret values must never be written"
+        );
+
+        let restored = AzureCache::new();
+        restored.load_from_path(&path).await;
+        let secrets = restored
+            .get_secrets_list_or_load("https://vault", || async {
+                Err("should not load".to_string())
+            })
+            .await
+            .unwrap();
+        assert_eq!(secrets.len(), 1);
+        let value = restored.get_secret_value("https://vault", "db-password").await;
+        assert!(value.is_none());
+
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_carry_over_keeps_lists_but_not_secret_values() {
+        let previous = AzureCache::new();
+        previous.set_refresh_ahead(true);
+        previous
+            .get_secrets_list_or_load("https://vault", || async {
+                Ok(vec![secret_item("db-password")])
+            })
+            .await
+            .unwrap();
+        previous
+            .get_secret_value_or_load("https://vault", "db-password", || async {
+                Ok(secret_bundle("hunter2"))
+            })
+            .await
+            .unwrap();
+
+        let rebuilt = AzureCache::new();
+        rebuilt.carry_over(&previous).await;
+
+        let secrets = rebuilt
+            .get_secrets_list_or_load("https://vault", || async {
+                Err("should not load".to_string())
+            })
+            .await
+            .unwrap();
+        assert_eq!(secrets.len(), 1);
+        assert!(rebuilt.get_secret_value("https://vault", "db-password").await.is_none());
+        assert!(rebuilt.refresh_ahead.load(Ordering::Relaxed));
+        let tracked = rebuilt.secrets_list_refresh.loaded_at.lock().unwrap();
+        assert!(tracked.contains_key("https://vault"));
+    }
+
+    #[test]
+    fn test_snapshot_file_is_per_account() {
+        let owner = SnapshotOwner {
+            cloud: CloudEnvironment::Public,
+            tenant_id: "tenant-1".to_string(),
+            object_id: "user-1".to_string(),
+        };
+        let other_user = SnapshotOwner {
+            object_id: "user-2".to_string(),
+            ..owner.clone()
+        };
+        let other_tenant = SnapshotOwner {
+            tenant_id: "tenant-2".to_string(),
+            ..owner.clone()
+        };
+        let other_cloud = SnapshotOwner {
+            cloud: CloudEnvironment::UsGov,
+            ..owner.clone()
+        };
+
+        let name = owner.file_name();
+        assert!(name.starts_with("cache_snapshot_") && name.ends_with(".json"));
+        assert!(!name.contains("tenant-1") && !name.contains("user-1"));
+        assert_eq!(name, owner.clone().file_name());
+        for other in [other_user, other_tenant, other_cloud] {
+            assert_ne!(other.file_name(), name);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_skips_expired_entries() {
+        let cache: Cache<String, CachedVec<Secret>> = Cache::builder()
+            .expire_after(LoadedAtExpiry {
+                ttl: Duration::from_secs(60),
+            })
+            .build();
+        let loaded_secs_ago = |secs: u64| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_secs()
+                - secs
+        };
+
+        let restored = restore_entries(
+            &cache,
+            None,
+            vec![
+                SnapshotEntry {
+                    key: "fresh".to_string(),
+                    loaded_at: loaded_secs_ago(10),
+                    items: vec![secret_item("a")],
+                },
+                SnapshotEntry {
+                    key: "expired".to_string(),
+                    loaded_at: loaded_secs_ago(120),
+                    items: vec![secret_item("b")],
+                },
+            ],
+            Duration::from_secs(60),
+        )
+        .await;
+
+        assert_eq!(restored, 1);
+        assert!(cache.get("fresh").await.is_some());
+        assert!(cache.get("expired").await.is_none());
+    }
+
+    /// A clock for `RefreshTracker` that only moves when the returned handle is advanced
+    fn test_clock() -> (Clock, Arc<Mutex<Instant>>) {
+        let now = Arc::new(Mutex::new(Instant::now()));
+        let current = now.clone();
+        (Arc::new(move || *current.lock().unwrap()), now)
+    }
+
+    /// Yield until `condition` holds, so spawned refreshes can finish without sleeping
+    async fn until(condition: impl Fn() -> bool) {
+        for _ in 0..1_000 {
+            if condition() {
+                return;
+            }
+            tokio::task::yield_now().await;
+        }
+        panic!("Condition not reached");
+    }
+
+    #[tokio::test]
+    async fn test_refresh_ahead_serves_stale_value_while_reloading() {
+        let ttl = Duration::from_secs(60);
+        let cache: Cache<String, String> = Cache::builder().time_to_live(ttl).build();
+        let (clock, now) = test_clock();
+        let tracker = RefreshTracker::with_clock(ttl, clock);
+
+        let (value, fresh) = get_or_load_refreshing(&cache, &tracker, true, "k", || async {
+            Ok("old".to_string())
+        })
+        .await
+        .unwrap();
+        assert_eq!((value.as_str(), fresh), ("old", true));
+
+        // Past the refresh-ahead threshold but before expiry
+        *now.lock().unwrap() += ttl * 9 / 10;
+        let (release, released) = tokio::sync::oneshot::channel::<()>();
+        let (value, fresh) = get_or_load_refreshing(&cache, &tracker, true, "k", || async move {
+            released.await.ok();
+            Ok("new".to_string())
+        })
+        .await
+        .unwrap();
+        assert_eq!((value.as_str(), fresh), ("old", false));
+        assert!(tracker.in_flight.lock().unwrap().contains("k"));
+
+        // Once the reload finished, the refreshed value is served without another load
+        release.send(()).unwrap();
+        let reloaded_at = *now.lock().unwrap();
+        until(|| tracker.loaded_at.lock().unwrap().get("k") == Some(&reloaded_at)).await;
+        let (value, fresh) = get_or_load_refreshing(&cache, &tracker, true, "k", || async {
+            Err("should not load".to_string())
+        })
+        .await
+        .unwrap();
+        assert_eq!((value.as_str(), fresh), ("new", false));
+    }
+
+    #[tokio::test]
+    async fn test_refresh_ahead_discards_reload_of_invalidated_entry() {
+        let ttl = Duration::from_secs(60);
+        let cache: Cache<String, String> = Cache::builder().time_to_live(ttl).build();
+        let (clock, now) = test_clock();
+        let tracker = RefreshTracker::with_clock(ttl, clock);
+
+        get_or_load_refreshing(&cache, &tracker, true, "k", || async { Ok("old".to_string()) })
+            .await
+            .unwrap();
+        *now.lock().unwrap() += ttl * 9 / 10;
+        let (release, released) = tokio::sync::oneshot::channel::<()>();
+        get_or_load_refreshing(&cache, &tracker, true, "k", || async move {
+            released.await.ok();
+            Ok("stale".to_string())
+        })
+        .await
+        .unwrap();
+
+        tracker.forget("k");
+        cache.invalidate("k").await;
+        release.send(()).unwrap();
+        until(|| tracker.in_flight.lock().unwrap().is_empty()).await;
+
+        assert!(cache.get("k").await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_evicted_entries_leave_the_refresh_tracker() {
+        let cache = AzureCache::new();
+        cache
+            .get_keyvaults_or_load("sub", || async { Ok(Vec::new()) })
+            .await
+            .unwrap();
+        assert!(cache.keyvaults_refresh.loaded_at.lock().unwrap().contains_key("sub"));
+
+        // Bypasses `invalidate_keyvaults`, so only the eviction listener can forget it
+        cache.keyvaults.invalidate_all();
+        cache.keyvaults.run_pending_tasks().await;
+
+        assert!(cache.keyvaults_refresh.loaded_at.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_secret_value_miss_then_hit_is_counted() {
+        let cache = AzureCache::new();
+        let load = || async { Ok(secret_bundle("v1")) };
+
+        cache
+            .get_secret_value_or_load("https://vault", "db-password", load)
+            .await
+            .unwrap();
+        cache
+            .get_secret_value_or_load("https://vault", "db-password", load)
+            .await
+            .unwrap();
+
+        let stats = cache.get_stats();
+        assert_eq!(stats.secret_value_misses, 1);
+        assert_eq!(stats.secret_value_hits, 1);
+        assert_eq!(stats.hit_ratio, Some(0.5));
+
+        cache.reset_stats();
+        let stats = cache.get_stats();
+        assert_eq!((stats.secret_value_hits, stats.secret_value_misses), (0, 0));
+        assert_eq!(stats.hit_ratio, None);
+        assert_eq!(stats.secret_values_count, cache.secret_values.entry_count());
+    }
+
+    #[tokio::test]
+    async fn test_with_ttls_honors_durations() {
+        let ttls = CacheTtls {
+            secrets_list: Duration::from_secs(60),
+            secret_values: Duration::from_secs(30),
+            ..CacheTtls::default()
+        };
+        let cache = AzureCache::with_ttls(ttls);
+        let default_cache = AzureCache::new();
+
+        assert_eq!(
+            cache.secret_values.policy().time_to_live(),
+            Some(Duration::from_secs(30))
+        );
+
+        // Loaded two minutes ago: past the 60s TTL, within the default one
+        let loaded_two_minutes_ago = || CachedVec {
+            items: vec![secret_item("a")],
+            loaded_at: SystemTime::now() - Duration::from_secs(120),
+        };
+        for cache in [&cache, &default_cache] {
+            cache
+                .secrets_list
+                .insert("https://vault".to_string(), loaded_two_minutes_ago())
+                .await;
+        }
+        assert!(cache.secrets_list.get("https://vault").await.is_none());
+        assert!(default_cache.secrets_list.get("https://vault").await.is_some());
+    }
 }
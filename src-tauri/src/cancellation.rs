@@ -0,0 +1,126 @@
+//! Cancellation of long-running operations
+//!
+//! Operations that the frontend may abandon (global search, device code login)
+//! register a `CancellationToken` under an id chosen by the caller. The
+//! `cancel_operation` command cancels it by that id.
+
+use log::{debug, info};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use tokio_util::sync::CancellationToken;
+
+/// Source of registration numbers, to tell registrations under the same id apart
+static NEXT_REGISTRATION: AtomicU64 = AtomicU64::new(0);
+
+lazy_static::lazy_static! {
+    /// Tokens of the operations currently running, keyed by operation id,
+    /// with the number of the registration that owns each
+    static ref OPERATIONS: Mutex<HashMap<String, (u64, CancellationToken)>> =
+        Mutex::new(HashMap::new());
+}
+
+/// A registered operation; unregisters itself when dropped
+pub struct OperationRegistration {
+    id: String,
+    number: u64,
+    token: CancellationToken,
+}
+
+impl OperationRegistration {
+    /// The token to check or await while the operation runs
+    pub fn token(&self) -> &CancellationToken {
+        &self.token
+    }
+}
+
+impl Drop for OperationRegistration {
+    fn drop(&mut self) {
+        let mut operations = OPERATIONS.lock().unwrap_or_else(|e| e.into_inner());
+        // A newer operation may have reused the id; leave its token alone
+        if operations
+            .get(&self.id)
+            .is_some_and(|(number, _)| *number == self.number)
+        {
+            operations.remove(&self.id);
+        }
+    }
+}
+
+/// Register an operation under `id` and get its cancellation token.
+///
+/// An operation already registered under the same id is cancelled first, so a
+/// restarted search doesn't leave the previous one running.
+pub fn register_operation(id: &str) -> OperationRegistration {
+    let token = CancellationToken::new();
+    let number = NEXT_REGISTRATION.fetch_add(1, Ordering::Relaxed);
+    let previous = OPERATIONS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(id.to_string(), (number, token.clone()));
+
+    if let Some((_, previous)) = previous {
+        debug!("Cancelling previous operation registered as '{}'", id);
+        previous.cancel();
+    }
+
+    OperationRegistration {
+        id: id.to_string(),
+        number,
+        token,
+    }
+}
+
+/// Cancel the operation registered under `id`.
+///
+/// # Returns
+///
+/// Returns `true` if an operation with that id was running.
+pub fn cancel_operation(id: &str) -> bool {
+    let token = OPERATIONS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .remove(id);
+
+    match token {
+        Some((_, token)) => {
+            info!("Cancelling operation '{}'", id);
+            token.cancel();
+            true
+        }
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cancel_registered_operation() {
+        let registration = register_operation("test-cancel");
+        assert!(!registration.token().is_cancelled());
+
+        assert!(cancel_operation("test-cancel"));
+        assert!(registration.token().is_cancelled());
+        assert!(!cancel_operation("test-cancel"));
+    }
+
+    #[test]
+    fn test_registration_is_removed_on_drop() {
+        drop(register_operation("test-drop"));
+        assert!(!cancel_operation("test-drop"));
+    }
+
+    #[test]
+    fn test_reregistering_cancels_previous_operation() {
+        let first = register_operation("test-rerun");
+        let second = register_operation("test-rerun");
+        assert!(first.token().is_cancelled());
+
+        // Dropping the stale registration keeps the newer one cancellable
+        drop(first);
+        assert!(cancel_operation("test-rerun"));
+        assert!(second.token().is_cancelled());
+    }
+}
@@ -1,17 +1,32 @@
 //! Activity Log related Tauri commands
 
 use crate::azure::activity_log::graph::{resolve_caller_identities, ResolvedCaller};
-use crate::azure::activity_log::service::get_activity_logs;
-use crate::azure::activity_log::types::ActivityLogEvent;
+use crate::azure::activity_log::service::{get_activity_logs, get_activity_logs_resolved};
+use crate::azure::activity_log::types::{
+    ActivityLogEvent, ActivityLogFilter, ResolvedActivityLogEvent,
+};
 use std::collections::HashMap;
 
 /// Fetch activity log (audit) events for a specific Key Vault
+/// Without a filter every event is returned
 #[tauri::command]
 pub async fn fetch_activity_logs(
     vault_id: String,
     days: Option<u32>,
+    filter: Option<ActivityLogFilter>,
 ) -> Result<Vec<ActivityLogEvent>, String> {
-    get_activity_logs(&vault_id, days).await
+    get_activity_logs(&vault_id, days, &filter.unwrap_or_default()).await
+}
+
+/// Fetch activity log events for a Key Vault with their callers resolved to display names
+/// Without a filter every event is returned
+#[tauri::command]
+pub async fn fetch_activity_logs_resolved(
+    vault_id: String,
+    days: Option<u32>,
+    filter: Option<ActivityLogFilter>,
+) -> Result<Vec<ResolvedActivityLogEvent>, String> {
+    get_activity_logs_resolved(&vault_id, days, &filter.unwrap_or_default()).await
 }
 
 /// Resolve caller GUIDs to display names via Microsoft Graph API
@@ -0,0 +1,19 @@
+//! Local audit log Tauri commands
+
+use crate::audit_log::{clear_audit_entries, get_audit_entries, AuditEntry};
+
+/// Default number of entries returned when no limit is given
+const DEFAULT_AUDIT_LOG_LIMIT: usize = 500;
+
+/// Get the most recent local audit log entries, newest first
+#[tauri::command]
+pub async fn get_local_audit_log(limit: Option<usize>) -> Result<Vec<AuditEntry>, String> {
+    get_audit_entries(limit.unwrap_or(DEFAULT_AUDIT_LOG_LIMIT)).await
+}
+
+/// Clear the local audit log
+#[tauri::command]
+pub async fn clear_local_audit_log() -> Result<String, String> {
+    clear_audit_entries().await?;
+    Ok("Local audit log cleared".to_string())
+}
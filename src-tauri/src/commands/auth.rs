@@ -1,11 +1,20 @@
 //! Authentication-related Tauri commands
 
+use crate::azure::auth::auth_code::{
+    complete_auth_code_login, is_auth_code_login_pending, start_auth_code_login,
+    LOGIN_BROWSER_CLOSED_ERROR,
+};
 use crate::azure::auth::interactive::{
-    complete_interactive_browser_login, start_interactive_browser_login,
+    cancel_interactive_login, complete_interactive_browser_login, reset_interactive_login,
+    start_interactive_browser_login, LOGIN_CODE_EXPIRED_ERROR, LOGIN_TIMED_OUT_ERROR,
 };
 use crate::azure::auth::service::{get_user_info, is_authenticated, login, logout};
-use crate::azure::auth::types::{AuthResult, DeviceCodeInfo};
-use crate::cache::AZURE_CACHE;
+use crate::azure::auth::types::{
+    AuthResult, AuthStatus, AuthStatusDetail, BrowserLoginInfo, OpenIdConfiguration,
+    ServicePrincipalTestResult,
+};
+use crate::cache::azure_cache;
+use tauri::Emitter;
 
 /// User information returned to the frontend
 #[derive(serde::Serialize)]
@@ -21,18 +30,75 @@ pub async fn azure_login() -> Result<AuthResult, String> {
 }
 
 /// Start interactive browser authentication (RECOMMENDED - no secret needed!)
+/// Uses the device code flow, or with `use_redirect` opens the system browser
+/// and captures the sign-in on a localhost redirect
 #[tauri::command]
-pub async fn start_browser_login() -> Result<DeviceCodeInfo, String> {
-    start_interactive_browser_login().await
+pub async fn start_browser_login(use_redirect: Option<bool>) -> Result<BrowserLoginInfo, String> {
+    if use_redirect.unwrap_or(false) {
+        start_auth_code_login().await.map(BrowserLoginInfo::Redirect)
+    } else {
+        start_interactive_browser_login()
+            .await
+            .map(BrowserLoginInfo::DeviceCode)
+    }
 }
 
 /// Complete browser authentication with authorization code
+/// An empty `auth_code` waits for the redirect (or polls the device code flow);
+/// a given code is only accepted with the matching `state`
 #[tauri::command]
 pub async fn complete_browser_login(
-    _auth_code: String,
-    _state: String,
+    app: tauri::AppHandle,
+    auth_code: String,
+    state: String,
 ) -> Result<AuthResult, String> {
-    complete_interactive_browser_login().await
+    let result = if is_auth_code_login_pending().await {
+        let auth_code = Some(auth_code).filter(|code| !code.is_empty());
+        let state = Some(state).filter(|state| !state.is_empty());
+        complete_auth_code_login(auth_code, state).await
+    } else {
+        complete_interactive_browser_login().await
+    };
+
+    // Let the UI distinguish an expired code from other failures without parsing errors
+    if matches!(&result, Err(e) if e == LOGIN_TIMED_OUT_ERROR) {
+        if let Err(e) = app.emit("login-timed-out", ()) {
+            log::warn!("Failed to emit login timeout event: {}", e);
+        }
+    }
+    if matches!(&result, Err(e) if e == LOGIN_CODE_EXPIRED_ERROR) {
+        if let Err(e) = app.emit("login-code-expired", ()) {
+            log::warn!("Failed to emit login code expired event: {}", e);
+        }
+    }
+    if matches!(&result, Err(e) if e == LOGIN_BROWSER_CLOSED_ERROR) {
+        if let Err(e) = app.emit("login-browser-closed", ()) {
+            log::warn!("Failed to emit login browser closed event: {}", e);
+        }
+    }
+
+    result
+}
+
+/// Cancel a browser login that is waiting for the user
+/// The pending `complete_browser_login` call returns "Authentication cancelled"
+#[tauri::command]
+pub async fn cancel_login() -> bool {
+    cancel_interactive_login().await
+}
+
+/// Reset a stuck browser login flow so `start_browser_login` can be retried
+/// Returns whether a flow was actually in progress
+#[tauri::command]
+pub async fn reset_auth_flow() -> bool {
+    reset_interactive_login().await
+}
+
+/// Get the decoded claims of the current management token (whoami)
+/// The raw token is never returned
+#[tauri::command]
+pub async fn get_token_claims() -> Result<serde_json::Map<String, serde_json::Value>, String> {
+    crate::azure::auth::service::get_token_claims().await
 }
 
 /// Check authentication status
@@ -49,12 +115,49 @@ pub async fn get_current_user() -> Option<UserInfo> {
         .map(|(email, name)| UserInfo { email, name })
 }
 
+/// Get the combined authentication status (user, tenant, method, token expiry)
+/// Replaces separate `check_auth` + `get_current_user` round-trips
+#[tauri::command]
+pub async fn get_auth_status() -> AuthStatus {
+    crate::azure::auth::service::get_auth_status().await
+}
+
+/// Check whether the sign-in is still usable: authenticated, expired or not authenticated
+/// Lets the UI prompt for a new login before an Azure call fails
+#[tauri::command]
+pub async fn auth_status_detail() -> AuthStatusDetail {
+    crate::azure::auth::service::get_auth_status_detail().await
+}
+
 /// Logout from Azure
 /// Clears all cached data
 #[tauri::command]
 pub async fn azure_logout() -> Result<String, String> {
+    // Clear all cached Azure data, including the account's snapshot, before
+    // logging out forgets which account it was
+    azure_cache().clear_all().await;
     logout().await;
-    // Clear all cached Azure data on logout
-    AZURE_CACHE.clear_all().await;
     Ok("Logged out successfully".to_string())
 }
+
+/// Check service principal credentials without storing them or changing the login state
+#[tauri::command]
+pub async fn test_service_principal(
+    client_id: String,
+    tenant_id: String,
+    client_secret: String,
+) -> ServicePrincipalTestResult {
+    crate::azure::auth::service_principal::test_service_principal(
+        &client_id,
+        &tenant_id,
+        &client_secret,
+    )
+    .await
+}
+
+/// Fetch the OpenID configuration for the configured tenant
+/// Verifies the tenant exists and its endpoints resolve before a login attempt
+#[tauri::command]
+pub async fn get_openid_config() -> Result<OpenIdConfiguration, String> {
+    crate::azure::auth::openid::get_openid_config().await
+}
@@ -2,32 +2,82 @@
 //!
 //! Provides commands for cache management and statistics.
 
-use crate::cache::{CacheStatistics, AZURE_CACHE};
+use serde::Deserialize;
+
+use crate::cache::{azure_cache, CacheStatistics};
+use crate::user_config::{get_config, rebuild_cache, update_config};
+
+/// Cache TTLs in seconds; `None` means the built-in default
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheTtlSettings {
+    pub subscription_ttl_secs: Option<u64>,
+    pub resource_group_ttl_secs: Option<u64>,
+    pub keyvault_ttl_secs: Option<u64>,
+    pub secrets_list_ttl_secs: Option<u64>,
+    pub keys_list_ttl_secs: Option<u64>,
+    pub secret_value_ttl_secs: Option<u64>,
+}
 
 /// Get cache statistics
 #[tauri::command]
 pub fn get_cache_stats() -> CacheStatistics {
-    AZURE_CACHE.get_stats()
+    azure_cache().get_stats()
+}
+
+/// Reset the cache hit and miss counts
+/// Returns the statistics as they were just before the reset
+#[tauri::command]
+pub fn reset_cache_stats() -> CacheStatistics {
+    let stats = azure_cache().get_stats();
+    azure_cache().reset_stats();
+    stats
 }
 
 /// Clear all caches
 #[tauri::command]
 pub async fn clear_cache() -> Result<String, String> {
-    AZURE_CACHE.clear_all().await;
+    azure_cache().clear_all().await;
     Ok("Cache cleared successfully".to_string())
 }
 
+/// Save new cache TTLs and swap in a fresh cache that uses them
+/// Moka can't change the TTL of a built cache; cached lists still within the new
+/// TTLs carry over, secret values are dropped
+#[tauri::command]
+pub async fn rebuild_cache_with_ttls(ttls: CacheTtlSettings) -> Result<String, String> {
+    let mut config = get_config().await;
+    config.subscription_ttl_secs = ttls.subscription_ttl_secs;
+    config.resource_group_ttl_secs = ttls.resource_group_ttl_secs;
+    config.keyvault_ttl_secs = ttls.keyvault_ttl_secs;
+    config.secrets_list_ttl_secs = ttls.secrets_list_ttl_secs;
+    config.keys_list_ttl_secs = ttls.keys_list_ttl_secs;
+    config.secret_value_ttl_secs = ttls.secret_value_ttl_secs;
+    update_config(config.clone()).await?;
+
+    rebuild_cache(&config).await;
+    Ok("Cache rebuilt with the new TTLs".to_string())
+}
+
+/// Save the subscription, resource group, vault and secret-list caches to disk
+/// Secret values are never written; the snapshot is restored when the account next signs in
+#[tauri::command]
+pub fn persist_cache() -> Result<String, String> {
+    azure_cache().save_to_disk()?;
+    Ok("Cache saved to disk".to_string())
+}
+
 /// Invalidate subscriptions cache (force refresh on next fetch)
 #[tauri::command]
 pub async fn invalidate_subscriptions_cache() -> Result<String, String> {
-    AZURE_CACHE.invalidate_subscriptions().await;
+    azure_cache().invalidate_subscriptions().await;
     Ok("Subscriptions cache invalidated".to_string())
 }
 
 /// Invalidate keyvaults cache for a subscription
 #[tauri::command]
 pub async fn invalidate_keyvaults_cache(subscription_id: String) -> Result<String, String> {
-    AZURE_CACHE.invalidate_keyvaults(&subscription_id).await;
+    azure_cache().invalidate_keyvaults(&subscription_id).await;
     Ok(format!(
         "Keyvaults cache invalidated for subscription {}",
         subscription_id
@@ -37,7 +87,7 @@ pub async fn invalidate_keyvaults_cache(subscription_id: String) -> Result<Strin
 /// Invalidate resource groups cache for a subscription
 #[tauri::command]
 pub async fn invalidate_resource_groups_cache(subscription_id: String) -> Result<String, String> {
-    AZURE_CACHE
+    azure_cache()
         .invalidate_resource_groups(&subscription_id)
         .await;
     Ok(format!(
@@ -49,6 +99,6 @@ pub async fn invalidate_resource_groups_cache(subscription_id: String) -> Result
 /// Invalidate secrets cache for a vault
 #[tauri::command]
 pub async fn invalidate_vault_cache(vault_uri: String) -> Result<String, String> {
-    AZURE_CACHE.invalidate_vault_secrets(&vault_uri).await;
+    azure_cache().invalidate_vault_secrets(&vault_uri).await;
     Ok(format!("Secrets cache invalidated for vault {}", vault_uri))
 }
@@ -1,8 +1,13 @@
 ﻿//! Configuration-related Tauri commands
 
+use crate::azure::auth::service::logout;
+use crate::cache::azure_cache;
+use crate::config::CloudEnvironment;
 use crate::user_config::constants::{MULTI_TENANT_ENDPOINT, VAULTRAIDER_CLIENT_ID};
 use crate::user_config::types::UserConfig;
-use crate::user_config::{get_client_id, get_config, get_tenant_id, update_config};
+use crate::user_config::{
+    get_authority_host, get_client_id, get_config, get_tenant_id, update_config,
+};
 
 /// Azure configuration returned to the frontend
 #[derive(serde::Serialize, serde::Deserialize)]
@@ -21,6 +26,26 @@ pub struct AzureConfig {
     pub default_tenant_id: String,
     /// Auto-login on app startup
     pub auto_login: bool,
+    /// Additional OAuth scopes requested at login
+    pub extra_scopes: Vec<String>,
+    /// User-configured authority host (None means AZURE_AUTHORITY_HOST or the public cloud)
+    pub authority_host: Option<String>,
+    /// The effective authority host being used
+    pub effective_authority_host: String,
+    /// Refresh near-expired vault and secret lists in the background
+    pub cache_refresh_ahead: bool,
+    /// Whether commands that change anything in Azure are blocked
+    pub read_only: bool,
+    /// Client ID of the user-assigned managed identity (None means system-assigned)
+    pub managed_identity_client_id: Option<String>,
+    /// Seconds before an Azure API request times out
+    pub http_timeout_secs: u64,
+    /// Fewest concurrent Azure requests when Azure throttles
+    pub adaptive_concurrency_min: usize,
+    /// Most concurrent Azure requests
+    pub adaptive_concurrency_max: usize,
+    /// Azure cloud the app signs in to and calls
+    pub cloud_environment: CloudEnvironment,
 }
 
 /// Get the current Azure configuration
@@ -29,6 +54,7 @@ pub async fn get_azure_config() -> Result<AzureConfig, String> {
     let config = get_config().await;
     let effective_client_id = get_client_id().await;
     let effective_tenant_id = get_tenant_id().await;
+    let effective_authority_host = get_authority_host().await;
 
     Ok(AzureConfig {
         client_id: config.client_id,
@@ -38,6 +64,16 @@ pub async fn get_azure_config() -> Result<AzureConfig, String> {
         default_client_id: VAULTRAIDER_CLIENT_ID.to_string(),
         default_tenant_id: MULTI_TENANT_ENDPOINT.to_string(),
         auto_login: config.auto_login,
+        extra_scopes: config.extra_scopes,
+        authority_host: config.authority_host,
+        effective_authority_host,
+        cache_refresh_ahead: config.cache_refresh_ahead,
+        read_only: config.read_only,
+        managed_identity_client_id: config.managed_identity_client_id,
+        http_timeout_secs: config.http_timeout_secs,
+        adaptive_concurrency_min: config.adaptive_concurrency_min,
+        adaptive_concurrency_max: config.adaptive_concurrency_max,
+        cloud_environment: config.cloud_environment,
     })
 }
 
@@ -63,7 +99,8 @@ pub async fn save_azure_config(client_id: String, tenant_id: String) -> Result<(
     let new_config = UserConfig {
         client_id: client_id_opt,
         tenant_id: tenant_id_opt,
-        auto_login: current_config.auto_login, // Preserve auto_login setting
+        // Preserve auto_login and any other settings
+        ..current_config
     };
     update_config(new_config).await
 }
@@ -76,6 +113,96 @@ pub async fn set_auto_login(enabled: bool) -> Result<(), String> {
     update_config(config).await
 }
 
+/// Set additional OAuth scopes to request at login (advanced)
+/// Takes effect on the next login
+#[tauri::command]
+pub async fn set_extra_scopes(scopes: Vec<String>) -> Result<(), String> {
+    let mut config = get_config().await;
+    config.extra_scopes = scopes
+        .into_iter()
+        .map(|scope| scope.trim().to_string())
+        .filter(|scope| !scope.is_empty())
+        .collect();
+    update_config(config).await
+}
+
+/// Set the Azure AD authority host used for login, e.g. for a sovereign cloud
+/// Accepts an https:// URL or a bare host name; ARM and Key Vault endpoints are not affected
+/// Pass an empty string to fall back to AZURE_AUTHORITY_HOST or the cloud environment
+/// Takes effect on the next login
+#[tauri::command]
+pub async fn set_authority_host(authority_host: String) -> Result<(), String> {
+    let mut config = get_config().await;
+    config.authority_host = Some(authority_host.trim().to_string()).filter(|host| !host.is_empty());
+    update_config(config).await
+}
+
+/// Set the client ID of a user-assigned managed identity
+/// Pass an empty string to use the system-assigned identity
+/// Takes effect on the next login
+#[tauri::command]
+pub async fn set_managed_identity_client_id(client_id: String) -> Result<(), String> {
+    let mut config = get_config().await;
+    config.managed_identity_client_id =
+        Some(client_id.trim().to_string()).filter(|id| !id.is_empty());
+    update_config(config).await
+}
+
+/// Enable or disable background refresh of near-expired vault and secret lists
+/// Takes effect immediately
+#[tauri::command]
+pub async fn set_cache_refresh_ahead(enabled: bool) -> Result<(), String> {
+    let mut config = get_config().await;
+    config.cache_refresh_ahead = enabled;
+    update_config(config).await
+}
+
+/// Enable or disable read-only mode
+/// While enabled, every command that changes anything in Azure fails without calling Azure
+#[tauri::command]
+pub async fn set_read_only(enabled: bool) -> Result<(), String> {
+    let mut config = get_config().await;
+    config.read_only = enabled;
+    update_config(config).await
+}
+
+/// Set how many seconds an Azure API request may take before it times out
+/// Takes effect for requests started afterwards
+#[tauri::command]
+pub async fn set_http_timeout(timeout_secs: u64) -> Result<(), String> {
+    let mut config = get_config().await;
+    config.http_timeout_secs = timeout_secs;
+    update_config(config).await
+}
+
+/// Set the bounds the adaptive request concurrency stays within
+/// Takes effect immediately; requests already running keep their slots
+#[tauri::command]
+pub async fn set_request_concurrency(min: usize, max: usize) -> Result<(), String> {
+    let mut config = get_config().await;
+    config.adaptive_concurrency_min = min;
+    config.adaptive_concurrency_max = max;
+    update_config(config).await
+}
+
+/// Set the Azure cloud to sign in to (public, US Government or China)
+/// Switching clouds logs out and clears cached data, as tokens and resources don't carry over
+#[tauri::command]
+pub async fn set_cloud_environment(cloud: CloudEnvironment) -> Result<(), String> {
+    let mut config = get_config().await;
+    if config.cloud_environment == cloud {
+        return Ok(());
+    }
+
+    config.cloud_environment = cloud;
+    update_config(config).await?;
+
+    // Cleared before logging out, while the snapshot of the signed-in account is still known
+    azure_cache().clear_all().await;
+    logout().await;
+    Ok(())
+}
+
 /// Get the auto-login preference
 #[tauri::command]
 pub async fn get_auto_login() -> Result<bool, String> {
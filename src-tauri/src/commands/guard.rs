@@ -0,0 +1,95 @@
+//! Guards shared by command handlers
+
+use crate::user_config::get_config;
+
+/// Error returned by mutating commands while read-only mode is enabled
+pub const READ_ONLY_ERROR: &str = "Read-only mode is enabled";
+
+/// Fail if read-only mode is enabled
+///
+/// Called first in every command that changes anything in Azure, so nothing
+/// is sent during a read-only review.
+pub(crate) async fn ensure_writable() -> Result<(), String> {
+    if get_config().await.read_only {
+        log::warn!("Blocked a change while read-only mode is enabled");
+        return Err(READ_ONLY_ERROR.to_string());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::azure::http::request_metrics;
+    use crate::commands::keyvault;
+    use crate::user_config::constants::USER_CONFIG;
+    use crate::user_config::types::UserConfig;
+
+    const VAULT_URI: &str = "https://read-only-guard.vault.azure.net";
+
+    /// Set the flag on the in-memory config only, so the test never writes config.json
+    ///
+    /// Returns the previous value, for restoring it afterwards.
+    async fn set_read_only_in_memory(enabled: bool) -> bool {
+        let mut config = USER_CONFIG
+            .get_or_init(|| tokio::sync::RwLock::new(UserConfig::default()))
+            .write()
+            .await;
+        std::mem::replace(&mut config.read_only, enabled)
+    }
+
+    fn assert_blocked<T: std::fmt::Debug>(command: &str, result: Result<T, String>) {
+        assert_eq!(
+            result.unwrap_err(),
+            READ_ONLY_ERROR,
+            "{} was not blocked",
+            command
+        );
+    }
+
+    // One test, since the flag is process-wide
+    #[tokio::test]
+    async fn test_write_commands_blocked_in_read_only_mode() {
+        let previous = set_read_only_in_memory(true).await;
+        let requests_before = request_metrics().requests_sent;
+
+        // Tests run signed out, so any command that got as far as calling Azure
+        // would fail with a token error instead of the read-only error
+        let name = || "db-password".to_string();
+        let uri = || VAULT_URI.to_string();
+        assert_blocked(
+            "create_secret",
+            keyvault::create_secret(uri(), name(), "value".to_string()).await,
+        );
+        assert_blocked(
+            "update_secret",
+            keyvault::update_secret(uri(), name(), "value".to_string()).await,
+        );
+        assert_blocked(
+            "delete_secret",
+            keyvault::delete_secret(uri(), name()).await,
+        );
+        assert_blocked(
+            "purge_deleted_secret",
+            keyvault::purge_deleted_secret(uri(), name()).await,
+        );
+        assert_blocked(
+            "import_secrets",
+            keyvault::import_secrets(uri(), Vec::new(), None).await,
+        );
+        assert_blocked(
+            "create_keyvault",
+            keyvault::create_keyvault("sub".to_string(), "rg".to_string(), name(), None).await,
+        );
+        assert_blocked(
+            "delete_keyvault",
+            keyvault::delete_keyvault("sub".to_string(), "rg".to_string(), name()).await,
+        );
+
+        assert_eq!(request_metrics().requests_sent, requests_before, "a request was sent");
+
+        set_read_only_in_memory(false).await;
+        assert_eq!(ensure_writable().await, Ok(()));
+        set_read_only_in_memory(previous).await;
+    }
+}
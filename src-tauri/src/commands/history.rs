@@ -0,0 +1,16 @@
+//! Recently viewed secrets Tauri commands
+
+use crate::history::{RecentSecret, RECENT_SECRETS};
+
+/// Get the recently viewed secrets, most recent first
+#[tauri::command]
+pub async fn get_recent_secrets() -> Vec<RecentSecret> {
+    RECENT_SECRETS.lock().await.list()
+}
+
+/// Clear the recently viewed secrets history
+#[tauri::command]
+pub async fn clear_recent_secrets() -> Result<String, String> {
+    RECENT_SECRETS.lock().await.clear();
+    Ok("Recent secrets cleared".to_string())
+}
@@ -1,18 +1,50 @@
 //! Key Vault related Tauri commands
 
-use crate::azure::keyvault::secret::export::ExportOptions;
-use crate::azure::keyvault::secret::import::ImportedSecret;
-use crate::azure::keyvault::secret::types::{DeletedSecretItem, Secret, SecretBundle};
+use crate::audit_log::{record_audit_event, AuditOperation};
+use crate::azure::keyvault::access_report::VaultAccessEntry;
+use crate::azure::keyvault::certificate::types::ExpiringCertificates;
+use crate::azure::keyvault::clone::CloneVaultSummary;
+use crate::azure::keyvault::compliance::UnprotectedVault;
+use crate::azure::keyvault::connectivity::VaultConnectivity;
+use crate::azure::keyvault::key::types::{Key, KeyBundle};
+use crate::azure::keyvault::restore::RestoreOperation;
+use crate::azure::keyvault::secret::audit::SecretValueAudit;
+use crate::azure::keyvault::secret::compare::{EnvComparisonEntry, SecretVersionComparison};
+use crate::azure::keyvault::secret::duplicates::DuplicateValueGroup;
+use crate::azure::keyvault::secret::export::{ExportOptions, ExportResult, SubscriptionExportResult};
+use crate::azure::keyvault::secret::format::FormattedSecret;
+use crate::azure::keyvault::secret::generate::{GeneratorOptions, SecretCharset};
+use crate::azure::keyvault::secret::import::{
+    ConflictStrategy, ImportPlan, ImportStatus, ImportedSecret, NameTransform, SecretImportResult,
+};
+use crate::azure::keyvault::secret::service::SearchMode;
+use crate::azure::keyvault::secret::stats::VaultStats;
+use crate::azure::keyvault::secret::types::{
+    DeletedSecretBundle, DeletedSecretInfo, DeletedSecretItem, NewSecret, PrefetchResult,
+    ReadAccessReport, Secret, SecretBundle, SecretCreateResult, SecretEnabledResult,
+    SecretLocation, SecretVersionDiff, SecretWithStatus, SecretWriteOptions, SecretsPage,
+    ViewedSecret,
+};
 use crate::azure::keyvault::service::get_keyvaults;
-use crate::azure::keyvault::types::{KeyVault, KeyVaultAccessCheck};
-use crate::cache::AZURE_CACHE;
+use crate::azure::keyvault::tree::{SkippedSubscription, VaultTree};
+use crate::azure::keyvault::types::{
+    CreateKeyVaultOptions, KeyVault, KeyVaultAccessCheck, KeyVaultDeletion, NetworkRuleSet,
+    VaultCapabilities, VaultRef,
+};
+use crate::cache::azure_cache;
+use crate::cancellation::{register_operation, OperationRegistration};
+use crate::commands::guard::ensure_writable;
+use crate::history::record_secret_view;
+use crate::view_fingerprints::record_value_view;
+use tauri::Emitter;
+use tokio_util::sync::CancellationToken;
 
 /// Fetch all Key Vaults for a subscription
 /// Uses caching with automatic loading on cache miss
 #[tauri::command]
 pub async fn fetch_keyvaults(subscription_id: String) -> Result<Vec<KeyVault>, String> {
     let sub_id = subscription_id.clone();
-    AZURE_CACHE
+    azure_cache()
         .get_keyvaults_or_load(
             &subscription_id,
             || async move { get_keyvaults(&sub_id).await },
@@ -20,6 +52,22 @@ pub async fn fetch_keyvaults(subscription_id: String) -> Result<Vec<KeyVault>, S
         .await
 }
 
+/// Fetch every accessible vault grouped by subscription and resource group
+/// Uses the subscription and keyvault caches
+#[tauri::command]
+pub async fn get_vault_tree() -> Result<VaultTree, String> {
+    crate::azure::keyvault::tree::get_vault_tree().await
+}
+
+/// Compliance report: vaults in a subscription without soft-delete or purge protection
+/// Uses the keyvault cache
+#[tauri::command]
+pub async fn find_vaults_without_soft_delete(
+    subscription_id: String,
+) -> Result<Vec<UnprotectedVault>, String> {
+    crate::azure::keyvault::compliance::find_vaults_without_soft_delete(&subscription_id).await
+}
+
 /// Check if we have access to a specific Key Vault
 #[tauri::command]
 pub async fn check_keyvault_access(keyvault_uri: String) -> Result<KeyVaultAccessCheck, String> {
@@ -33,22 +81,86 @@ pub async fn create_keyvault(
     subscription_id: String,
     resource_group: String,
     keyvault_name: String,
+    options: Option<CreateKeyVaultOptions>,
 ) -> Result<KeyVault, String> {
+    ensure_writable().await?;
+
     let result = crate::azure::keyvault::service::create_keyvault(
         &subscription_id,
         &resource_group,
         &keyvault_name,
+        &options.unwrap_or_default(),
     )
     .await;
 
     if result.is_ok() {
         // Invalidate keyvaults cache for this subscription
-        AZURE_CACHE.invalidate_keyvaults(&subscription_id).await;
+        azure_cache().invalidate_keyvaults(&subscription_id).await;
     }
 
     result
 }
 
+/// Get what a vault supports and which secret operations the user may perform
+/// Lets the UI disable actions up front instead of failing mid-click
+#[tauri::command]
+pub async fn get_vault_capabilities(
+    subscription_id: String,
+    resource_group: String,
+    vault_name: String,
+    vault_uri: String,
+) -> Result<VaultCapabilities, String> {
+    crate::azure::keyvault::capabilities::get_vault_capabilities(
+        &subscription_id,
+        &resource_group,
+        &vault_name,
+        &vault_uri,
+    )
+    .await
+}
+
+/// List every identity with access to a vault, from access policies and RBAC combined
+/// Intended for access reviews; principals are resolved to names where possible
+#[tauri::command]
+pub async fn get_vault_access_report(
+    subscription_id: String,
+    resource_group: String,
+    vault_name: String,
+    vault_resource_id: String,
+) -> Result<Vec<VaultAccessEntry>, String> {
+    crate::azure::keyvault::access_report::get_vault_access_report(
+        &subscription_id,
+        &resource_group,
+        &vault_name,
+        &vault_resource_id,
+    )
+    .await
+}
+
+/// Check whether a vault can be reached, classifying DNS, network, TLS, firewall and auth failures
+/// Separates "can't reach the vault" from "not allowed", which otherwise look alike
+#[tauri::command]
+pub async fn ping_vault(keyvault_uri: String) -> VaultConnectivity {
+    crate::azure::keyvault::connectivity::ping_vault(&keyvault_uri).await
+}
+
+/// Preview the request body `create_keyvault` would send, without creating anything
+#[tauri::command]
+pub async fn preview_create_keyvault(
+    subscription_id: String,
+    resource_group: String,
+    keyvault_name: String,
+    options: Option<CreateKeyVaultOptions>,
+) -> Result<String, String> {
+    crate::azure::keyvault::service::preview_create_keyvault(
+        &subscription_id,
+        &resource_group,
+        &keyvault_name,
+        &options.unwrap_or_default(),
+    )
+    .await
+}
+
 /// Delete a Key Vault
 /// Invalidates the keyvaults cache after successful deletion
 #[tauri::command]
@@ -56,7 +168,9 @@ pub async fn delete_keyvault(
     subscription_id: String,
     resource_group: String,
     keyvault_name: String,
-) -> Result<(), String> {
+) -> Result<KeyVaultDeletion, String> {
+    ensure_writable().await?;
+
     let result = crate::azure::keyvault::service::delete_keyvault(
         &subscription_id,
         &resource_group,
@@ -66,7 +180,151 @@ pub async fn delete_keyvault(
 
     if result.is_ok() {
         // Invalidate keyvaults cache for this subscription
-        AZURE_CACHE.invalidate_keyvaults(&subscription_id).await;
+        azure_cache().invalidate_keyvaults(&subscription_id).await;
+    }
+
+    result
+}
+
+/// Delete a Key Vault by its ARM resource ID
+/// Invalidates the keyvaults cache after successful deletion
+#[tauri::command]
+pub async fn delete_keyvault_by_id(vault_id: String) -> Result<KeyVaultDeletion, String> {
+    ensure_writable().await?;
+
+    let (subscription_id, deletion) =
+        crate::azure::keyvault::service::delete_keyvault_by_id(&vault_id).await?;
+    azure_cache().invalidate_keyvaults(&subscription_id).await;
+    Ok(deletion)
+}
+
+/// Set the network rules (firewall) of a Key Vault
+/// Invalidates the keyvaults cache after a successful update
+#[tauri::command]
+pub async fn set_vault_network_rules(
+    subscription_id: String,
+    resource_group: String,
+    vault_name: String,
+    rules: NetworkRuleSet,
+) -> Result<KeyVault, String> {
+    ensure_writable().await?;
+
+    let result = crate::azure::keyvault::service::set_vault_network_rules(
+        &subscription_id,
+        &resource_group,
+        &vault_name,
+        rules,
+    )
+    .await;
+
+    if result.is_ok() {
+        // Invalidate keyvaults cache so the new firewall state is fetched
+        azure_cache().invalidate_keyvaults(&subscription_id).await;
+    }
+
+    result
+}
+
+/// Enable purge protection on a Key Vault (cannot be undone)
+/// Refreshes the cached entry for this vault after a successful update
+#[tauri::command]
+pub async fn enable_purge_protection(
+    subscription_id: String,
+    resource_group: String,
+    vault_name: String,
+) -> Result<KeyVault, String> {
+    ensure_writable().await?;
+
+    let vault = crate::azure::keyvault::service::enable_purge_protection(
+        &subscription_id,
+        &resource_group,
+        &vault_name,
+    )
+    .await?;
+
+    azure_cache()
+        .update_keyvault(&subscription_id, vault.clone())
+        .await;
+
+    Ok(vault)
+}
+
+/// Enable soft delete on a Key Vault and set its retention period (7-90 days)
+/// Refreshes the cached entry for this vault after a successful update
+#[tauri::command]
+pub async fn set_soft_delete_retention(
+    subscription_id: String,
+    resource_group: String,
+    vault_name: String,
+    retention_days: u8,
+) -> Result<KeyVault, String> {
+    ensure_writable().await?;
+
+    let vault = crate::azure::keyvault::service::set_soft_delete_retention(
+        &subscription_id,
+        &resource_group,
+        &vault_name,
+        retention_days,
+    )
+    .await?;
+
+    azure_cache()
+        .update_keyvault(&subscription_id, vault.clone())
+        .await;
+
+    Ok(vault)
+}
+
+/// Create a new Key Vault and copy every secret from a source vault into it
+/// Emits `clone-vault-progress` events after each secret copy attempt
+#[tauri::command]
+pub async fn clone_vault(
+    app: tauri::AppHandle,
+    source_uri: String,
+    target_subscription: String,
+    target_resource_group: String,
+    target_vault_name: String,
+) -> Result<CloneVaultSummary, String> {
+    ensure_writable().await?;
+
+    crate::azure::keyvault::clone::clone_vault(
+        &source_uri,
+        &target_subscription,
+        &target_resource_group,
+        &target_vault_name,
+        |progress| {
+            if let Err(e) = app.emit("clone-vault-progress", progress) {
+                log::warn!("Failed to emit clone progress event: {}", e);
+            }
+        },
+    )
+    .await
+}
+
+/// Restore a full vault backup from a storage blob and wait for it to finish
+/// Emits `restore-vault-progress` events after each status check
+/// Invalidates the vault's secret caches on completion
+#[tauri::command]
+pub async fn restore_vault_backup(
+    app: tauri::AppHandle,
+    keyvault_uri: String,
+    sas_url: String,
+) -> Result<RestoreOperation, String> {
+    ensure_writable().await?;
+
+    let result = crate::azure::keyvault::restore::restore_vault_backup(
+        &keyvault_uri,
+        &sas_url,
+        |progress| {
+            if let Err(e) = app.emit("restore-vault-progress", progress) {
+                log::warn!("Failed to emit restore progress event: {}", e);
+            }
+        },
+    )
+    .await;
+
+    if result.is_ok() {
+        azure_cache().invalidate_vault_secrets(&keyvault_uri).await;
     }
 
     result
@@ -77,26 +335,68 @@ pub async fn delete_keyvault(
 #[tauri::command]
 pub async fn get_secrets(keyvault_uri: String) -> Result<Vec<Secret>, String> {
     let uri = keyvault_uri.clone();
-    AZURE_CACHE
+    azure_cache()
         .get_secrets_list_or_load(&keyvault_uri, || async move {
             crate::azure::keyvault::secret::service::get_secrets(&uri).await
         })
         .await
 }
 
+/// Fetch all secrets from a Key Vault with a computed status
+/// ("active", "expired", "pending", "disabled"); no secret values are read
+#[tauri::command]
+pub async fn get_secrets_with_status(
+    keyvault_uri: String,
+) -> Result<Vec<SecretWithStatus>, String> {
+    let secrets = get_secrets(keyvault_uri).await?;
+    let now = chrono::Utc::now().timestamp().max(0) as u64;
+
+    Ok(secrets
+        .into_iter()
+        .map(|secret| SecretWithStatus {
+            status: secret.attributes.status(now),
+            secret,
+        })
+        .collect())
+}
+
+/// Fetch only the secret names of a Key Vault, e.g. for autocomplete
+/// Derived from the cached secrets list to keep the payload small for large vaults
+#[tauri::command]
+pub async fn get_secret_names(keyvault_uri: String) -> Result<Vec<String>, String> {
+    let secrets = get_secrets(keyvault_uri).await?;
+    Ok(secrets.iter().map(|secret| secret.name().to_string()).collect())
+}
+
+/// Fetch one page of secrets from a Key Vault
+/// Pass the returned continuation token to fetch the next page; pages bypass the cache
+#[tauri::command]
+pub async fn get_secrets_page(
+    keyvault_uri: String,
+    continuation_token: Option<String>,
+) -> Result<SecretsPage, String> {
+    crate::azure::keyvault::secret::service::get_secrets_page(
+        &keyvault_uri,
+        continuation_token.as_deref(),
+    )
+    .await
+}
+
 /// Fetch a specific secret
 /// Uses caching with automatic loading on cache miss (only for latest version)
+/// Successful fetches are recorded in the recently viewed secrets history, and
+/// the latest version is flagged if its value changed since it was last viewed
 #[tauri::command]
 pub async fn get_secret(
     keyvault_uri: String,
     secret_name: String,
     secret_version: Option<String>,
-) -> Result<SecretBundle, String> {
+) -> Result<ViewedSecret, String> {
     // Only cache latest version (when no specific version is requested)
-    if secret_version.is_none() {
+    let result = if secret_version.is_none() {
         let uri = keyvault_uri.clone();
         let name = secret_name.clone();
-        AZURE_CACHE
+        azure_cache()
             .get_secret_value_or_load(&keyvault_uri, &secret_name, || async move {
                 crate::azure::keyvault::secret::service::get_secret(&uri, &name, None).await
             })
@@ -109,7 +409,161 @@ pub async fn get_secret(
             secret_version.as_deref(),
         )
         .await
+    };
+
+    let secret = result?;
+    record_secret_view(&keyvault_uri, &secret_name).await;
+    record_audit_event(AuditOperation::Read, &keyvault_uri, Some(&secret_name)).await;
+
+    let changed_since_last_view = secret_version.is_none()
+        && record_value_view(&keyvault_uri, &secret_name, &secret.value).await;
+
+    Ok(ViewedSecret {
+        secret,
+        changed_since_last_view,
+    })
+}
+
+/// Fetch a secret with its value pretty-printed if it is JSON
+/// Derived from the cached secret value, not cached separately
+#[tauri::command]
+pub async fn get_secret_formatted(
+    keyvault_uri: String,
+    secret_name: String,
+) -> Result<FormattedSecret, String> {
+    let result =
+        crate::azure::keyvault::secret::format::get_secret_formatted(&keyvault_uri, &secret_name)
+            .await;
+
+    if result.is_ok() {
+        record_secret_view(&keyvault_uri, &secret_name).await;
+        record_audit_event(AuditOperation::Read, &keyvault_uri, Some(&secret_name)).await;
     }
+
+    result
+}
+
+/// Save a secret value to a file, optionally base64-decoding it first
+/// Refuses to replace an existing file unless `overwrite` is set; returns the bytes written
+#[tauri::command]
+pub async fn save_secret_to_file(
+    keyvault_uri: String,
+    secret_name: String,
+    file_path: String,
+    decode_base64: bool,
+    overwrite: Option<bool>,
+) -> Result<usize, String> {
+    let result = crate::azure::keyvault::secret::file::save_secret_to_file(
+        &keyvault_uri,
+        &secret_name,
+        &file_path,
+        decode_base64,
+        overwrite.unwrap_or(false),
+    )
+    .await;
+
+    if result.is_ok() {
+        record_audit_event(AuditOperation::Read, &keyvault_uri, Some(&secret_name)).await;
+    }
+
+    result
+}
+
+/// Classify every secret value in a vault for a data-quality report
+/// Returns names and classifications only, never values
+#[tauri::command]
+pub async fn audit_secret_values(keyvault_uri: String) -> Result<Vec<SecretValueAudit>, String> {
+    crate::azure::keyvault::secret::audit::audit_secret_values(&keyvault_uri).await
+}
+
+/// Find groups of secrets in a vault that share the same value
+/// Returns secret names only, never values
+#[tauri::command]
+pub async fn find_duplicate_values(
+    keyvault_uri: String,
+) -> Result<Vec<DuplicateValueGroup>, String> {
+    crate::azure::keyvault::secret::duplicates::find_duplicate_values(&keyvault_uri).await
+}
+
+/// Warm the secret value cache for the given secrets
+/// Opt-in: trades upfront reads for faster subsequent `get_secret` calls
+#[tauri::command]
+pub async fn prefetch_secret_values(
+    keyvault_uri: String,
+    secret_names: Vec<String>,
+) -> PrefetchResult {
+    crate::azure::keyvault::secret::service::prefetch_secret_values(&keyvault_uri, secret_names)
+        .await
+}
+
+/// Check that every enabled secret in a vault is still readable, e.g. after an RBAC change
+/// Values are never cached; returns counts and the names that returned 403
+#[tauri::command]
+pub async fn verify_read_access(keyvault_uri: String) -> Result<ReadAccessReport, String> {
+    crate::azure::keyvault::secret::service::verify_read_access(&keyvault_uri).await
+}
+
+/// Compute secret counts, expiry and update times for a vault dashboard
+/// Value sizes require reading every value, so they are only included when requested
+#[tauri::command]
+pub async fn get_vault_stats(
+    keyvault_uri: String,
+    include_value_bytes: Option<bool>,
+) -> Result<VaultStats, String> {
+    crate::azure::keyvault::secret::stats::get_vault_stats(
+        &keyvault_uri,
+        include_value_bytes.unwrap_or(false),
+    )
+    .await
+}
+
+/// Fetch the same secret from several vaults for side-by-side comparison
+#[tauri::command]
+pub async fn get_secret_everywhere(
+    secret_name: String,
+    vault_uris: Vec<String>,
+) -> Vec<SecretLocation> {
+    crate::azure::keyvault::secret::service::get_secret_everywhere(&secret_name, vault_uris).await
+}
+
+/// Fetch all keys from a Key Vault, without key material
+/// Uses caching with automatic loading on cache miss
+#[tauri::command]
+pub async fn get_keys(keyvault_uri: String) -> Result<Vec<Key>, String> {
+    let uri = keyvault_uri.clone();
+    azure_cache()
+        .get_keys_list_or_load(&keyvault_uri, || async move {
+            crate::azure::keyvault::key::service::get_keys(&uri).await
+        })
+        .await
+}
+
+/// Fetch a key (optionally a specific version) with its public key material only
+#[tauri::command]
+pub async fn get_key(
+    keyvault_uri: String,
+    key_name: String,
+    key_version: Option<String>,
+) -> Result<KeyBundle, String> {
+    crate::azure::keyvault::key::service::get_key(
+        &keyvault_uri,
+        &key_name,
+        key_version.as_deref(),
+    )
+    .await
+}
+
+/// Find certificates expired or expiring within `days` across the given vaults
+/// Returns a flat list, soonest expiry first, plus the vaults that couldn't be read
+#[tauri::command]
+pub async fn get_expiring_certificates(
+    vault_uris: Vec<String>,
+    days: u32,
+) -> Result<ExpiringCertificates, String> {
+    let expiring =
+        crate::azure::keyvault::certificate::service::get_expiring_certificates(vault_uris, days)
+            .await;
+    Ok(expiring)
 }
 
 /// Fetch all versions of a specific secret
@@ -124,16 +578,48 @@ pub async fn get_secret_versions(
 /// Delete a secret
 /// Invalidates the cache after successful deletion
 #[tauri::command]
-pub async fn delete_secret(keyvault_uri: String, secret_name: String) -> Result<Secret, String> {
+pub async fn delete_secret(
+    keyvault_uri: String,
+    secret_name: String,
+) -> Result<DeletedSecretBundle, String> {
+    ensure_writable().await?;
+
     let result =
         crate::azure::keyvault::secret::service::delete_secret(&keyvault_uri, &secret_name).await;
 
     if result.is_ok() {
         // Invalidate both the secret value and the secrets list cache
-        AZURE_CACHE
+        azure_cache()
             .invalidate_secret_value(&keyvault_uri, &secret_name)
             .await;
-        AZURE_CACHE.invalidate_secrets_list(&keyvault_uri).await;
+        azure_cache().invalidate_secrets_list(&keyvault_uri).await;
+        record_audit_event(AuditOperation::Delete, &keyvault_uri, Some(&secret_name)).await;
+    }
+
+    result
+}
+
+/// Delete a secret and report whether it can still be recovered or purged
+/// Lets the UI show "recoverable until X, permanently deleted on Y"
+#[tauri::command]
+pub async fn delete_secret_with_info(
+    keyvault_uri: String,
+    secret_name: String,
+) -> Result<DeletedSecretInfo, String> {
+    ensure_writable().await?;
+
+    let result = crate::azure::keyvault::secret::service::delete_secret_with_info(
+        &keyvault_uri,
+        &secret_name,
+    )
+    .await;
+
+    if result.is_ok() {
+        azure_cache()
+            .invalidate_secret_value(&keyvault_uri, &secret_name)
+            .await;
+        azure_cache().invalidate_secrets_list(&keyvault_uri).await;
+        record_audit_event(AuditOperation::Delete, &keyvault_uri, Some(&secret_name)).await;
     }
 
     result
@@ -147,6 +633,8 @@ pub async fn create_secret(
     secret_name: String,
     secret_value: String,
 ) -> Result<SecretBundle, String> {
+    ensure_writable().await?;
+
     let result = crate::azure::keyvault::secret::service::create_secret(
         &keyvault_uri,
         &secret_name,
@@ -156,16 +644,81 @@ pub async fn create_secret(
 
     if let Ok(ref secret_bundle) = result {
         // Cache the new secret value
-        AZURE_CACHE
+        azure_cache()
             .cache_secret_value(&keyvault_uri, secret_bundle.clone())
             .await;
         // Invalidate secrets list so it gets refreshed
-        AZURE_CACHE.invalidate_secrets_list(&keyvault_uri).await;
+        azure_cache().invalidate_secrets_list(&keyvault_uri).await;
+        record_audit_event(AuditOperation::Create, &keyvault_uri, Some(&secret_name)).await;
+    }
+
+    result
+}
+
+/// Create a secret with optional attributes (expiry, activation date, enabled), tags and content type
+#[tauri::command]
+pub async fn create_secret_with_attributes(
+    keyvault_uri: String,
+    secret_name: String,
+    secret_value: String,
+    options: Option<SecretWriteOptions>,
+) -> Result<SecretBundle, String> {
+    ensure_writable().await?;
+
+    let result = crate::azure::keyvault::secret::service::create_secret_with_attributes(
+        &keyvault_uri,
+        &secret_name,
+        &secret_value,
+        options.unwrap_or_default(),
+    )
+    .await;
+
+    if let Ok(ref secret_bundle) = result {
+        azure_cache()
+            .cache_secret_value(&keyvault_uri, secret_bundle.clone())
+            .await;
+        azure_cache().invalidate_secrets_list(&keyvault_uri).await;
+        record_audit_event(AuditOperation::Create, &keyvault_uri, Some(&secret_name)).await;
     }
 
     result
 }
 
+/// Create many secrets at once, returning a result per secret
+/// With `strict`, any invalid name aborts the batch before anything is written
+#[tauri::command]
+pub async fn create_secrets(
+    keyvault_uri: String,
+    secrets: Vec<NewSecret>,
+    strict: Option<bool>,
+) -> Result<Vec<SecretCreateResult>, String> {
+    ensure_writable().await?;
+
+    let results = crate::azure::keyvault::secret::service::create_secrets(
+        &keyvault_uri,
+        secrets,
+        strict.unwrap_or(false),
+    )
+    .await?;
+
+    let created: Vec<&str> = results
+        .iter()
+        .filter(|r| r.success)
+        .map(|r| r.name.as_str())
+        .collect();
+
+    if !created.is_empty() {
+        // Invalidate secrets list once for the whole batch
+        azure_cache().invalidate_secrets_list(&keyvault_uri).await;
+    }
+    for name in created {
+        azure_cache().invalidate_secret_value(&keyvault_uri, name).await;
+        record_audit_event(AuditOperation::Create, &keyvault_uri, Some(name)).await;
+    }
+
+    Ok(results)
+}
+
 /// Update an existing secret
 /// Invalidates old cache and caches the updated secret
 #[tauri::command]
@@ -174,6 +727,8 @@ pub async fn update_secret(
     secret_name: String,
     secret_value: String,
 ) -> Result<SecretBundle, String> {
+    ensure_writable().await?;
+
     let result = crate::azure::keyvault::secret::service::update_secret(
         &keyvault_uri,
         &secret_name,
@@ -182,38 +737,387 @@ pub async fn update_secret(
     .await;
 
     if let Ok(ref secret_bundle) = result {
-        // Invalidate the old cached secret value
-        AZURE_CACHE
+        refresh_after_update(&keyvault_uri, &secret_name, secret_bundle).await;
+    }
+
+    result
+}
+
+/// Refresh the caches and record the audit event after a secret got a new version
+///
+/// The new version replaces the cached value, and the secrets list is
+/// invalidated so its updated timestamp is refreshed.
+async fn refresh_after_update(
+    keyvault_uri: &str,
+    secret_name: &str,
+    secret_bundle: &SecretBundle,
+) {
+    azure_cache()
+        .invalidate_secret_value(keyvault_uri, secret_name)
+        .await;
+    azure_cache()
+        .cache_secret_value(keyvault_uri, secret_bundle.clone())
+        .await;
+    azure_cache().invalidate_secrets_list(keyvault_uri).await;
+    record_audit_event(AuditOperation::Update, keyvault_uri, Some(secret_name)).await;
+}
+
+/// Enable or disable the latest version of a secret
+/// Invalidates the cached value and the secrets list
+#[tauri::command]
+pub async fn set_secret_enabled(
+    keyvault_uri: String,
+    secret_name: String,
+    enabled: bool,
+) -> Result<Secret, String> {
+    ensure_writable().await?;
+
+    let result = crate::azure::keyvault::secret::service::set_secret_enabled(
+        &keyvault_uri,
+        &secret_name,
+        enabled,
+    )
+    .await;
+
+    if result.is_ok() {
+        azure_cache()
             .invalidate_secret_value(&keyvault_uri, &secret_name)
             .await;
-        // Cache the updated secret value
-        AZURE_CACHE
-            .cache_secret_value(&keyvault_uri, secret_bundle.clone())
-            .await;
-        // Invalidate secrets list so updated timestamp is refreshed
-        AZURE_CACHE.invalidate_secrets_list(&keyvault_uri).await;
+        azure_cache().invalidate_secrets_list(&keyvault_uri).await;
+        record_audit_event(AuditOperation::Update, &keyvault_uri, Some(&secret_name)).await;
+    }
+
+    result
+}
+
+/// Enable or disable many secrets at once, returning a result per secret
+#[tauri::command]
+pub async fn set_secrets_enabled(
+    keyvault_uri: String,
+    secret_names: Vec<String>,
+    enabled: bool,
+) -> Result<Vec<SecretEnabledResult>, String> {
+    ensure_writable().await?;
+
+    let results = crate::azure::keyvault::secret::service::set_secrets_enabled(
+        &keyvault_uri,
+        secret_names,
+        enabled,
+    )
+    .await?;
+
+    let updated: Vec<&str> = results
+        .iter()
+        .filter(|r| r.success)
+        .map(|r| r.name.as_str())
+        .collect();
+
+    if !updated.is_empty() {
+        // Invalidate secrets list once for the whole batch
+        azure_cache().invalidate_secrets_list(&keyvault_uri).await;
+    }
+    for name in updated {
+        azure_cache().invalidate_secret_value(&keyvault_uri, name).await;
+        record_audit_event(AuditOperation::Update, &keyvault_uri, Some(name)).await;
+    }
+
+    Ok(results)
+}
+
+/// Create or update a secret with the contents of a file, optionally base64-encoded
+/// Caches the new value like `update_secret`
+#[tauri::command]
+pub async fn set_secret_from_file(
+    keyvault_uri: String,
+    secret_name: String,
+    file_path: String,
+    encode_base64: bool,
+) -> Result<SecretBundle, String> {
+    ensure_writable().await?;
+
+    let result = crate::azure::keyvault::secret::file::set_secret_from_file(
+        &keyvault_uri,
+        &secret_name,
+        &file_path,
+        encode_base64,
+    )
+    .await;
+
+    if let Ok(ref secret_bundle) = result {
+        refresh_after_update(&keyvault_uri, &secret_name, secret_bundle).await;
+    }
+
+    result
+}
+
+/// Rotate a secret to a newly generated random value (break-glass rotation)
+/// Charset defaults to alphanumeric; the value is only returned, never logged
+#[tauri::command]
+pub async fn rotate_secret_random(
+    keyvault_uri: String,
+    secret_name: String,
+    length: usize,
+    charset: Option<SecretCharset>,
+) -> Result<SecretBundle, String> {
+    ensure_writable().await?;
+
+    let result = crate::azure::keyvault::secret::generate::rotate_secret_random(
+        &keyvault_uri,
+        &secret_name,
+        length,
+        charset.unwrap_or_default(),
+    )
+    .await;
+
+    if let Ok(ref secret_bundle) = result {
+        refresh_after_update(&keyvault_uri, &secret_name, secret_bundle).await;
+    }
+
+    result
+}
+
+/// Generate a random secret value without writing it anywhere
+#[tauri::command]
+pub fn generate_secret_value(options: GeneratorOptions) -> Result<String, String> {
+    crate::azure::keyvault::secret::generate::generate_with_options(&options)
+}
+
+/// Rotate a secret to a value generated from the given options
+#[tauri::command]
+pub async fn rotate_secret(
+    keyvault_uri: String,
+    secret_name: String,
+    options: GeneratorOptions,
+) -> Result<SecretBundle, String> {
+    ensure_writable().await?;
+
+    let result = crate::azure::keyvault::secret::generate::rotate_secret(
+        &keyvault_uri,
+        &secret_name,
+        &options,
+    )
+    .await;
+
+    if let Ok(ref secret_bundle) = result {
+        refresh_after_update(&keyvault_uri, &secret_name, secret_bundle).await;
     }
 
     result
 }
 
 /// Export secrets to a formatted string
+/// Emits `export-progress` events while secret values are fetched
 #[tauri::command]
 pub async fn export_secrets(
+    app: tauri::AppHandle,
     vault_name: String,
     vault_uri: String,
     options: ExportOptions,
 ) -> Result<String, String> {
-    crate::azure::keyvault::secret::export::export_secrets(&vault_name, &vault_uri, options).await
+    export_secrets_with_summary(app, vault_name, vault_uri, options)
+        .await
+        .map(|result| result.content)
+}
+
+/// Export secrets, also reporting which secrets were exported without their value
+/// Emits `export-progress` events while secret values are fetched
+#[tauri::command]
+pub async fn export_secrets_with_summary(
+    app: tauri::AppHandle,
+    vault_name: String,
+    vault_uri: String,
+    options: ExportOptions,
+) -> Result<ExportResult, String> {
+    crate::azure::keyvault::secret::export::export_secrets(
+        &vault_name,
+        &vault_uri,
+        options,
+        |progress| {
+            if let Err(e) = app.emit("export-progress", progress) {
+                log::warn!("Failed to emit export progress event: {}", e);
+            }
+        },
+    )
+    .await
+}
+
+/// Export every vault in a subscription into one JSON document keyed by vault name
+/// Emits `export-subscription-progress` events after each vault
+#[tauri::command]
+pub async fn export_subscription(
+    app: tauri::AppHandle,
+    subscription_id: String,
+    options: ExportOptions,
+) -> Result<SubscriptionExportResult, String> {
+    crate::azure::keyvault::secret::export::export_subscription(
+        &subscription_id,
+        options,
+        |progress| {
+            if let Err(e) = app.emit("export-subscription-progress", progress) {
+                log::warn!("Failed to emit subscription export progress event: {}", e);
+            }
+        },
+    )
+    .await
 }
 
 /// Parse an import file and extract secrets
+/// Names can optionally be prefixed and transformed, e.g. to namespace per service
 #[tauri::command]
 pub fn parse_import_file(
     content: String,
     format: Option<String>,
+    name_prefix: Option<String>,
+    name_transform: Option<NameTransform>,
 ) -> Result<Vec<ImportedSecret>, String> {
-    crate::azure::keyvault::secret::import::parse_import_file(&content, format.as_deref())
+    crate::azure::keyvault::secret::import::parse_import_file(
+        &content,
+        format.as_deref(),
+        name_prefix.as_deref(),
+        name_transform.unwrap_or_default(),
+    )
+}
+
+/// Decrypt an encrypted export with its passphrase and extract the secrets
+/// Names are prefixed and transformed like in `parse_import_file`
+#[tauri::command]
+pub fn parse_encrypted_import(
+    content: String,
+    passphrase: String,
+    name_prefix: Option<String>,
+    name_transform: Option<NameTransform>,
+) -> Result<Vec<ImportedSecret>, String> {
+    crate::azure::keyvault::secret::import::parse_encrypted_import(
+        &content,
+        &passphrase,
+        name_prefix.as_deref(),
+        name_transform.unwrap_or_default(),
+    )
+}
+
+/// Preview an import: what would happen to each secret under `strategy`
+/// Nothing is written, so this doesn't require write access
+#[tauri::command]
+pub async fn plan_import(
+    keyvault_uri: String,
+    secrets: Vec<ImportedSecret>,
+    strategy: Option<ConflictStrategy>,
+) -> Result<ImportPlan, String> {
+    crate::azure::keyvault::secret::import::plan_import(
+        &keyvault_uri,
+        &secrets,
+        strategy.unwrap_or_default(),
+    )
+    .await
+}
+
+/// Write parsed secrets to a Key Vault, returning a result per secret
+/// Existing secrets are handled per `strategy` (default: skipped), as previewed by `plan_import`
+#[tauri::command]
+pub async fn import_secrets(
+    keyvault_uri: String,
+    secrets: Vec<ImportedSecret>,
+    strategy: Option<ConflictStrategy>,
+) -> Result<Vec<SecretImportResult>, String> {
+    ensure_writable().await?;
+
+    let results = crate::azure::keyvault::secret::import::import_secrets(
+        &keyvault_uri,
+        secrets,
+        strategy.unwrap_or_default(),
+    )
+    .await?;
+
+    let written: Vec<&SecretImportResult> = results
+        .iter()
+        .filter(|r| matches!(r.status, ImportStatus::Created | ImportStatus::Updated))
+        .collect();
+
+    if !written.is_empty() {
+        // Invalidate secrets list once for the whole batch
+        azure_cache().invalidate_secrets_list(&keyvault_uri).await;
+    }
+    for result in written {
+        let operation = match result.status {
+            ImportStatus::Created => AuditOperation::Create,
+            _ => AuditOperation::Update,
+        };
+        azure_cache().invalidate_secret_value(&keyvault_uri, &result.name).await;
+        record_audit_event(operation, &keyvault_uri, Some(&result.name)).await;
+    }
+
+    Ok(results)
+}
+
+/// Compare a local .env file against the secrets in a Key Vault
+/// Only value fingerprints are returned, never plaintext values
+#[tauri::command]
+pub async fn compare_env_with_vault(
+    keyvault_uri: String,
+    env_content: String,
+) -> Result<Vec<EnvComparisonEntry>, String> {
+    crate::azure::keyvault::secret::compare::compare_env_with_vault(&keyvault_uri, &env_content)
+        .await
+}
+
+/// Compare the values of two versions of a secret
+/// Only value fingerprints are returned, never plaintext values
+#[tauri::command]
+pub async fn compare_secret_versions(
+    keyvault_uri: String,
+    secret_name: String,
+    version_a: String,
+    version_b: String,
+) -> Result<SecretVersionComparison, String> {
+    crate::azure::keyvault::secret::compare::compare_secret_versions(
+        &keyvault_uri,
+        &secret_name,
+        &version_a,
+        &version_b,
+    )
+    .await
+}
+
+/// Restore the value of an earlier version of a secret as a new current version
+/// Refreshes the caches the same way `update_secret` does
+#[tauri::command]
+pub async fn rollback_secret(
+    keyvault_uri: String,
+    secret_name: String,
+    target_version: String,
+) -> Result<SecretBundle, String> {
+    ensure_writable().await?;
+
+    let result = crate::azure::keyvault::secret::service::rollback_secret(
+        &keyvault_uri,
+        &secret_name,
+        &target_version,
+    )
+    .await;
+
+    if let Ok(ref secret_bundle) = result {
+        refresh_after_update(&keyvault_uri, &secret_name, secret_bundle).await;
+    }
+
+    result
+}
+
+/// Report what changed between two versions of a secret
+/// Only says whether the values differ, never what they are
+#[tauri::command]
+pub async fn diff_secret_versions(
+    keyvault_uri: String,
+    secret_name: String,
+    version_a: String,
+    version_b: String,
+) -> Result<SecretVersionDiff, String> {
+    crate::azure::keyvault::secret::compare::diff_secret_versions(
+        &keyvault_uri,
+        &secret_name,
+        &version_a,
+        &version_b,
+    )
+    .await
 }
 
 /// Fetch all deleted secrets from a Key Vault
@@ -229,13 +1133,47 @@ pub async fn recover_deleted_secret(
     keyvault_uri: String,
     secret_name: String,
 ) -> Result<Secret, String> {
+    ensure_writable().await?;
+
     let result =
         crate::azure::keyvault::secret::service::recover_deleted_secret(&keyvault_uri, &secret_name)
             .await;
 
     if result.is_ok() {
         // Invalidate secrets list so the recovered secret shows up
-        AZURE_CACHE.invalidate_secrets_list(&keyvault_uri).await;
+        azure_cache().invalidate_secrets_list(&keyvault_uri).await;
+        record_audit_event(AuditOperation::Recover, &keyvault_uri, Some(&secret_name)).await;
+    }
+
+    result
+}
+
+/// Back up a secret with all its versions to an encrypted, base64url-encoded blob
+/// The blob can only be restored into a vault in the same subscription and geography
+#[tauri::command]
+pub async fn backup_secret(keyvault_uri: String, secret_name: String) -> Result<String, String> {
+    let result =
+        crate::azure::keyvault::secret::service::backup_secret(&keyvault_uri, &secret_name).await;
+
+    if result.is_ok() {
+        record_audit_event(AuditOperation::Backup, &keyvault_uri, Some(&secret_name)).await;
+    }
+
+    result
+}
+
+/// Restore a secret with all its versions from a backup blob
+/// Invalidates the target vault's secrets list so the restored secret shows up
+#[tauri::command]
+pub async fn restore_secret(keyvault_uri: String, blob: String) -> Result<Secret, String> {
+    ensure_writable().await?;
+
+    let result =
+        crate::azure::keyvault::secret::service::restore_secret(&keyvault_uri, &blob).await;
+
+    if let Ok(secret) = &result {
+        azure_cache().invalidate_secrets_list(&keyvault_uri).await;
+        record_audit_event(AuditOperation::Restore, &keyvault_uri, Some(secret.name())).await;
     }
 
     result
@@ -247,11 +1185,21 @@ pub async fn purge_deleted_secret(
     keyvault_uri: String,
     secret_name: String,
 ) -> Result<(), String> {
-    crate::azure::keyvault::secret::service::purge_deleted_secret(&keyvault_uri, &secret_name).await
+    ensure_writable().await?;
+
+    let result =
+        crate::azure::keyvault::secret::service::purge_deleted_secret(&keyvault_uri, &secret_name)
+            .await;
+
+    if result.is_ok() {
+        record_audit_event(AuditOperation::Purge, &keyvault_uri, Some(&secret_name)).await;
+    }
+
+    result
 }
 
 /// Search result for global search across key vaults
-#[derive(serde::Serialize)]
+#[derive(Clone, serde::Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SearchResult {
     pub secret_id: String,
@@ -259,28 +1207,159 @@ pub struct SearchResult {
     pub vault_name: String,
     pub vault_uri: String,
     pub subscription_id: String,
-    pub match_type: String, // "key", "value", or "both"
+    pub match_type: String, // "key", "value", "both" or "tag"
     pub secret_value: Option<String>,
     pub attributes: crate::azure::keyvault::secret::types::SecretAttributes,
 }
 
+/// Payload of the `search-progress` event, emitted after each vault is searched
+///
+/// `{ operationId, vaultName, processed, total, matchesSoFar }`; a vault that
+/// couldn't be searched still counts as processed.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchProgress {
+    pub vault_name: String,
+    /// Vaults searched so far, including this one
+    pub processed: usize,
+    pub total: usize,
+    /// Matches across all vaults searched so far
+    pub matches_so_far: usize,
+}
+
+/// Payload of the `search-result-batch` event: the matches found in one vault
+///
+/// `{ operationId, vaultName, vaultUri, results: SearchResult[] }`. Emitted
+/// before the vault's `search-progress` event, and also for vaults without matches.
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchResultBatch {
+    pub vault_name: String,
+    pub vault_uri: String,
+    pub results: Vec<SearchResult>,
+}
+
+/// A vault whose secrets couldn't be listed during a global search
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InaccessibleVault {
+    pub vault_uri: String,
+    pub vault_name: String,
+    pub subscription_id: String,
+    pub error: String,
+}
+
+/// Global search matches plus the vaults and subscriptions that couldn't be searched
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GlobalSearchResults {
+    pub results: Vec<SearchResult>,
+    pub inaccessible_vaults: Vec<InaccessibleVault>,
+    /// Subscriptions whose vaults couldn't be listed, so none of their vaults were searched
+    pub skipped_subscriptions: Vec<SkippedSubscription>,
+}
+
+/// Register a search under the caller's operation id, if it gave one
+///
+/// Returns the registration (kept alive for the duration of the search) and
+/// the token to pass down; without an id the search can't be cancelled.
+fn register_search(
+    operation_id: Option<&str>,
+) -> (Option<OperationRegistration>, CancellationToken) {
+    let registration = operation_id.map(register_operation);
+    let token = registration
+        .as_ref()
+        .map(|registration| registration.token().clone())
+        .unwrap_or_default();
+    (registration, token)
+}
+
+/// A search event payload tagged with the `operation_id` the search was started with
+///
+/// Flattened, so concurrent searches can tell their events apart by `operationId`.
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SearchEvent<'a, T> {
+    operation_id: Option<&'a str>,
+    #[serde(flatten)]
+    payload: T,
+}
+
+/// Emit the `search-result-batch` and `search-progress` events for a searched vault
+///
+/// Sent only to the window that started the search.
+fn emit_search_progress(
+    window: &tauri::WebviewWindow,
+    operation_id: Option<&str>,
+    batch: SearchResultBatch,
+    progress: SearchProgress,
+) {
+    let batch = SearchEvent {
+        operation_id,
+        payload: batch,
+    };
+    if let Err(e) = window.emit_to(window.label(), "search-result-batch", batch) {
+        log::warn!("Failed to emit search result batch event: {}", e);
+    }
+    let progress = SearchEvent {
+        operation_id,
+        payload: progress,
+    };
+    if let Err(e) = window.emit_to(window.label(), "search-progress", progress) {
+        log::warn!("Failed to emit search progress event: {}", e);
+    }
+}
+
 /// Global search across multiple key vaults
 /// Parallelizes requests to Azure for better performance
+/// Emits `search-result-batch` and `search-progress` events to the calling window as
+/// each vault completes; the aggregated results are still returned at the end
+/// Pass an `operation_id` to be able to stop it with `cancel_operation`; a cancelled
+/// search returns the matches found until then
 #[tauri::command]
 pub async fn global_search_secrets(
-    vault_uris: Vec<String>,
-    vault_names: Vec<String>,
-    subscription_ids: Vec<String>,
+    window: tauri::WebviewWindow,
+    vaults: Vec<VaultRef>,
     query: String,
-    search_type: String, // "key", "value", or "both"
+    search_type: String, // "key", "value", "both" or "tag"
+    mode: Option<SearchMode>, // "substring" (default) or "regex"
+    case_sensitive: Option<bool>,
+    operation_id: Option<String>,
 ) -> Result<Vec<SearchResult>, String> {
+    let (_registration, cancel) = register_search(operation_id.as_deref());
     crate::azure::keyvault::secret::service::global_search_secrets(
-        vault_uris,
-        vault_names,
-        subscription_ids,
+        vaults,
         &query,
         &search_type,
+        mode.unwrap_or_default(),
+        case_sensitive.unwrap_or(false),
+        &cancel,
+        |batch, progress| emit_search_progress(&window, operation_id.as_deref(), batch, progress),
     )
     .await
 }
 
+/// Global search across every accessible vault in every subscription
+/// Enumerates the vaults itself using the subscription and keyvault caches
+/// Emits the same progress events and is cancellable the same way as
+/// `global_search_secrets`
+#[tauri::command]
+pub async fn global_search_all(
+    window: tauri::WebviewWindow,
+    query: String,
+    search_type: String, // "key", "value", "both" or "tag"
+    mode: Option<SearchMode>, // "substring" (default) or "regex"
+    case_sensitive: Option<bool>,
+    operation_id: Option<String>,
+) -> Result<GlobalSearchResults, String> {
+    let (_registration, cancel) = register_search(operation_id.as_deref());
+    crate::azure::keyvault::secret::service::global_search_all(
+        &query,
+        &search_type,
+        mode.unwrap_or_default(),
+        case_sensitive.unwrap_or(false),
+        &cancel,
+        |batch, progress| emit_search_progress(&window, operation_id.as_deref(), batch, progress),
+    )
+    .await
+}
@@ -0,0 +1,9 @@
+//! Diagnostics-related Tauri commands
+
+use crate::azure::http::{request_metrics, RequestMetrics};
+
+/// Get the current request concurrency and how often Azure has throttled us
+#[tauri::command]
+pub fn get_request_metrics() -> RequestMetrics {
+    request_metrics()
+}
@@ -5,9 +5,15 @@
 //! between Tauri's requirements and the internal service layer.
 
 pub mod activity_log;
+pub mod audit_log;
 pub mod auth;
 pub mod cache;
 pub mod config;
+pub mod guard;
+pub mod history;
 pub mod keyvault;
+pub mod metrics;
+pub mod operations;
+pub mod rbac;
 pub mod resource_group;
 pub mod subscription;
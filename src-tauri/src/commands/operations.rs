@@ -0,0 +1,9 @@
+//! Operation-related Tauri commands
+
+/// Cancel a running operation by the id it was started with
+///
+/// Returns `false` if no operation with that id is running (e.g. it already finished).
+#[tauri::command]
+pub fn cancel_operation(id: String) -> bool {
+    crate::cancellation::cancel_operation(&id)
+}
@@ -0,0 +1,48 @@
+//! RBAC (role assignment) related Tauri commands
+
+use crate::azure::rbac::types::{KnownRole, RoleAssignmentResult, VaultRoleAssignment};
+use crate::commands::guard::ensure_writable;
+use std::collections::HashMap;
+
+/// Fetch the role assignments that apply to a Key Vault
+/// Role and principal names are resolved where possible
+#[tauri::command]
+pub async fn get_vault_role_assignments(
+    vault_resource_id: String,
+) -> Result<Vec<VaultRoleAssignment>, String> {
+    crate::azure::rbac::service::get_vault_role_assignments(&vault_resource_id).await
+}
+
+/// Assign an RBAC role to a principal on a Key Vault
+/// Returns the existing assignment if the principal already has the role
+#[tauri::command]
+pub async fn assign_vault_role(
+    vault_resource_id: String,
+    principal_id: String,
+    role_definition_id: String,
+) -> Result<RoleAssignmentResult, String> {
+    ensure_writable().await?;
+
+    crate::azure::rbac::service::assign_vault_role(
+        &vault_resource_id,
+        &principal_id,
+        &role_definition_id,
+    )
+    .await
+}
+
+/// Resolve role definition IDs or GUIDs to role names, falling back to the GUID
+/// Cached for a long time, as role definitions rarely change
+#[tauri::command]
+pub async fn resolve_role_definitions(
+    scope: String,
+    role_definition_ids: Vec<String>,
+) -> Result<HashMap<String, String>, String> {
+    crate::azure::rbac::service::resolve_role_definitions(&scope, role_definition_ids).await
+}
+
+/// Get the well-known built-in Key Vault roles (Secrets User/Officer, Administrator, ...)
+#[tauri::command]
+pub fn get_known_vault_roles() -> Vec<KnownRole> {
+    crate::azure::rbac::service::get_known_keyvault_roles()
+}
@@ -1,16 +1,23 @@
 ﻿//! Resource Group related Tauri commands
 
 use crate::azure::resource_group::types::ResourceGroup;
-use crate::cache::AZURE_CACHE;
+use crate::cache::azure_cache;
 
 /// Fetch all resource groups for a subscription
 /// Uses caching with automatic loading on cache miss
 #[tauri::command]
 pub async fn get_resource_groups(subscription_id: String) -> Result<Vec<ResourceGroup>, String> {
     let sub_id = subscription_id.clone();
-    AZURE_CACHE
+    azure_cache()
         .get_resource_groups_or_load(&subscription_id, || async move {
             crate::azure::resource_group::service::get_resource_groups(&sub_id).await
         })
         .await
 }
+
+/// Fetch resource groups for every accessible subscription
+/// Subscriptions that fail to load are skipped
+#[tauri::command]
+pub async fn fetch_all_resource_groups() -> Result<Vec<(String, Vec<ResourceGroup>)>, String> {
+    crate::azure::resource_group::service::fetch_all_resource_groups().await
+}
@@ -1,15 +1,25 @@
 //! Subscription-related Tauri commands
 
-use crate::azure::subscription::service::{get_subscriptions};
+use crate::azure::subscription::service::{filter_subscriptions, get_subscriptions};
 use crate::azure::subscription::types::Subscription;
-use crate::cache::AZURE_CACHE;
+use crate::cache::azure_cache;
 use anyhow::Result;
 
 /// Fetch all Azure subscriptions for the authenticated user
 /// Uses caching with automatic loading on cache miss
+/// Optionally filters by subscription state (e.g. "Enabled") and tenant ID
 #[tauri::command]
-pub async fn fetch_subscriptions() -> Result<Vec<Subscription>, String> {
-    AZURE_CACHE
+pub async fn fetch_subscriptions(
+    state: Option<String>,
+    tenant_id: Option<String>,
+) -> Result<Vec<Subscription>, String> {
+    let subscriptions = azure_cache()
         .get_subscriptions_or_load(|| async { get_subscriptions().await })
-        .await
+        .await?;
+
+    Ok(filter_subscriptions(
+        subscriptions,
+        state.as_deref(),
+        tenant_id.as_deref(),
+    ))
 }
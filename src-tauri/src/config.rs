@@ -6,30 +6,175 @@
 //! Note: Azure Client ID and Tenant ID are stored in user_config.rs
 //! and can be configured by the user at runtime.
 
+use serde::{Deserialize, Serialize};
+use std::sync::{PoisonError, RwLock};
+
+// ============================================================================
+// Cloud Environments
+// ============================================================================
+
+/// Azure cloud to sign in to and call; each has its own login, ARM, Key Vault
+/// and Graph hosts.
+///
+/// Azure Germany isn't offered: it was retired in 2021 and its tenants moved
+/// to the public cloud.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CloudEnvironment {
+    /// Azure public cloud
+    #[default]
+    Public,
+    /// Azure US Government
+    UsGov,
+    /// Azure China, operated by 21Vianet
+    China,
+}
+
+impl CloudEnvironment {
+    /// Azure AD authority host, without a trailing slash
+    pub fn authority_host(&self) -> &'static str {
+        match self {
+            CloudEnvironment::Public => "https://login.microsoftonline.com",
+            CloudEnvironment::UsGov => "https://login.microsoftonline.us",
+            CloudEnvironment::China => "https://login.chinacloudapi.cn",
+        }
+    }
+
+    /// Azure Resource Manager endpoint, without a trailing slash
+    pub fn arm_endpoint(&self) -> &'static str {
+        match self {
+            CloudEnvironment::Public => "https://management.azure.com",
+            CloudEnvironment::UsGov => "https://management.usgovcloudapi.net",
+            CloudEnvironment::China => "https://management.chinacloudapi.cn",
+        }
+    }
+
+    /// DNS suffix of Key Vault hosts (`{vault-name}.{suffix}`)
+    pub fn keyvault_dns_suffix(&self) -> &'static str {
+        match self {
+            CloudEnvironment::Public => "vault.azure.net",
+            CloudEnvironment::UsGov => "vault.usgovcloudapi.net",
+            CloudEnvironment::China => "vault.azure.cn",
+        }
+    }
+
+    /// Microsoft Graph endpoint, without a trailing slash
+    pub fn graph_endpoint(&self) -> &'static str {
+        match self {
+            CloudEnvironment::Public => "https://graph.microsoft.com",
+            CloudEnvironment::UsGov => "https://graph.microsoft.us",
+            CloudEnvironment::China => "https://microsoftgraph.chinacloudapi.cn",
+        }
+    }
+
+    /// Azure Resource Management API scope
+    pub fn management_scope(&self) -> &'static str {
+        match self {
+            CloudEnvironment::Public => "https://management.azure.com/.default",
+            CloudEnvironment::UsGov => "https://management.usgovcloudapi.net/.default",
+            CloudEnvironment::China => "https://management.chinacloudapi.cn/.default",
+        }
+    }
+
+    /// Azure Key Vault data plane API scope
+    pub fn keyvault_scope(&self) -> &'static str {
+        match self {
+            CloudEnvironment::Public => "https://vault.azure.net/.default",
+            CloudEnvironment::UsGov => "https://vault.usgovcloudapi.net/.default",
+            CloudEnvironment::China => "https://vault.azure.cn/.default",
+        }
+    }
+
+    /// Managed HSM data plane API scope
+    pub fn managed_hsm_scope(&self) -> &'static str {
+        match self {
+            CloudEnvironment::Public => "https://managedhsm.azure.net/.default",
+            CloudEnvironment::UsGov => "https://managedhsm.usgovcloudapi.net/.default",
+            CloudEnvironment::China => "https://managedhsm.azure.cn/.default",
+        }
+    }
+
+    /// Microsoft Graph API scope
+    pub fn graph_scope(&self) -> &'static str {
+        match self {
+            CloudEnvironment::Public => "https://graph.microsoft.com/.default",
+            CloudEnvironment::UsGov => "https://graph.microsoft.us/.default",
+            CloudEnvironment::China => "https://microsoftgraph.chinacloudapi.cn/.default",
+        }
+    }
+
+    /// Auth scopes for interactive login.
+    ///
+    /// OAuth 2.0 only allows one resource per token, so this requests the
+    /// management scope; Key Vault tokens are obtained separately via the
+    /// credential's get_token method.
+    pub fn auth_scopes(&self) -> &'static str {
+        match self {
+            CloudEnvironment::Public => {
+                "https://management.azure.com/.default offline_access openid profile"
+            }
+            CloudEnvironment::UsGov => {
+                "https://management.usgovcloudapi.net/.default offline_access openid profile"
+            }
+            CloudEnvironment::China => {
+                "https://management.chinacloudapi.cn/.default offline_access openid profile"
+            }
+        }
+    }
+}
+
+/// Cloud used for new requests, set from `UserConfig`
+static CLOUD_ENVIRONMENT: RwLock<CloudEnvironment> = RwLock::new(CloudEnvironment::Public);
+
+/// Switch the cloud used for new requests, e.g. from `UserConfig`
+pub fn set_cloud_environment(cloud: CloudEnvironment) {
+    *CLOUD_ENVIRONMENT
+        .write()
+        .unwrap_or_else(PoisonError::into_inner) = cloud;
+}
+
+/// Get the cloud used for new requests
+pub fn cloud_environment() -> CloudEnvironment {
+    *CLOUD_ENVIRONMENT
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+}
+
 // ============================================================================
 // OAuth2 Scopes
 // ============================================================================
 
-/// Azure Resource Management API scope
-pub const MANAGEMENT_SCOPE: &str = "https://management.azure.com/.default";
+/// Azure Resource Management API scope of the current cloud
+pub fn management_scope() -> &'static str {
+    cloud_environment().management_scope()
+}
 
-/// Azure Key Vault data plane API scope
-pub const KEYVAULT_SCOPE: &str = "https://vault.azure.net/.default";
+/// Azure Key Vault data plane API scope of the current cloud
+pub fn keyvault_scope() -> &'static str {
+    cloud_environment().keyvault_scope()
+}
 
-/// Auth scopes for interactive login - includes both Azure Management and Key Vault access
-/// Note: OAuth 2.0 only allows one resource per token, so we request Azure Management scope.
-/// Key Vault tokens will be obtained separately via the credential's get_token method.
-pub const AUTH_SCOPES: &str = "https://management.azure.com/.default offline_access openid profile";
+/// Managed HSM data plane API scope of the current cloud
+pub fn managed_hsm_scope() -> &'static str {
+    cloud_environment().managed_hsm_scope()
+}
+
+/// Microsoft Graph API scope of the current cloud
+pub fn graph_scope() -> &'static str {
+    cloud_environment().graph_scope()
+}
+
+/// Auth scopes for interactive login in the current cloud
+pub fn auth_scopes() -> &'static str {
+    cloud_environment().auth_scopes()
+}
 
 // ============================================================================
 // Azure AD Endpoints
 // ============================================================================
 
-/// Azure AD device code endpoint base URL
-pub const DEVICE_CODE_ENDPOINT: &str = "https://login.microsoftonline.com";
-
-/// Azure AD token endpoint base URL
-pub const TOKEN_ENDPOINT: &str = "https://login.microsoftonline.com";
+/// Standard Azure SDK environment variable overriding the authority host
+pub const AUTHORITY_HOST_ENV_VAR: &str = "AZURE_AUTHORITY_HOST";
 
 // ============================================================================
 // Polling Configuration
@@ -41,6 +186,68 @@ pub const MAX_POLL_ATTEMPTS: u32 = 60;
 /// Seconds to wait between poll attempts when rate limited
 pub const POLL_SLOWDOWN_SECONDS: u64 = 5;
 
+/// Seconds to wait for the browser to redirect back before assuming it was closed
+pub const AUTH_CODE_TIMEOUT_SECONDS: u64 = 300;
+
+/// Seconds to wait for a managed identity token before moving on in the login chain
+pub const MANAGED_IDENTITY_TIMEOUT_SECONDS: u64 = 5;
+
+/// Seconds to wait between status checks of a full vault restore
+pub const RESTORE_POLL_INTERVAL_SECONDS: u64 = 5;
+
+/// Maximum number of status checks before giving up on a full vault restore (30 minutes)
+pub const MAX_RESTORE_POLL_ATTEMPTS: u32 = 360;
+
+// ============================================================================
+// Throttling Configuration
+// ============================================================================
+
+/// Maximum number of secret values fetched concurrently from a single vault
+pub const MAX_CONCURRENT_SECRET_FETCHES: usize = 20;
+
+/// Maximum number of vaults processed concurrently in cross-vault operations
+pub const MAX_CONCURRENT_VAULTS: usize = 10;
+
+/// Maximum number of subscriptions queried concurrently in cross-subscription operations
+pub const MAX_CONCURRENT_SUBSCRIPTIONS: usize = 10;
+
+/// Default lower bound for the adaptive request concurrency, however much Azure throttles
+pub const ADAPTIVE_CONCURRENCY_MIN: usize = 2;
+
+/// Default upper bound (and starting value) for the adaptive request concurrency
+pub const ADAPTIVE_CONCURRENCY_MAX: usize = 64;
+
+/// Largest upper bound that can be configured for the adaptive request concurrency
+pub const MAX_ADAPTIVE_CONCURRENCY: usize = 256;
+
+/// Minimum time between two concurrency reductions, so one burst of 429s halves it only once
+pub const THROTTLE_DECREASE_COOLDOWN_MS: u64 = 2_000;
+
+/// Default timeout for a single Azure API request, including reading the response
+pub const DEFAULT_HTTP_TIMEOUT_SECS: u64 = 30;
+
+/// Largest HTTP timeout that can be configured
+pub const MAX_HTTP_TIMEOUT_SECS: u64 = 600;
+
+/// Largest cache TTL that can be configured (one day); Moka panics on huge TTLs
+pub const MAX_CACHE_TTL_SECS: u64 = 86_400;
+
+/// Default number of retries for requests throttled (429) or failed with a 5xx
+pub const DEFAULT_MAX_RETRIES: u32 = 3;
+
+/// Delay before the first retry; doubled for every further attempt
+pub const DEFAULT_RETRY_BASE_DELAY_MS: u64 = 500;
+
+/// Upper bound for a single retry delay, including delays asked for by `Retry-After`
+pub const MAX_RETRY_DELAY_SECS: u64 = 60;
+
+/// Retries for secret listings, which span many pages on large vaults
+pub const SECRET_LIST_MAX_RETRIES: u32 = 5;
+
+/// Maximum number of pages followed in a single paginated listing
+/// Guards against servers returning a self-referential `nextLink`
+pub const MAX_PAGINATION_PAGES: usize = 10_000;
+
 // ============================================================================
 // API Versions
 // ============================================================================
@@ -57,6 +264,9 @@ pub const KEYVAULT_DATA_API_VERSION: &str = "2025-07-01";
 /// Azure Resource Groups API version
 pub const RESOURCE_GROUPS_API_VERSION: &str = "2021-04-01";
 
+/// Azure Authorization (RBAC) API version
+pub const AUTHORIZATION_API_VERSION: &str = "2022-04-01";
+
 /// Azure Monitor Activity Logs API version
 pub const ACTIVITY_LOG_API_VERSION: &str = "2015-04-01";
 
@@ -70,48 +280,58 @@ pub mod urls {
   /// Get the URL to list all subscriptions
     pub fn subscriptions() -> String {
         format!(
-            "https://management.azure.com/subscriptions?api-version={}",
-            ARM_API_VERSION
+            "{}/subscriptions?api-version={}",
+            arm_endpoint(), ARM_API_VERSION
         )
     }
 
     /// Get the URL to list all Key Vaults in a subscription
     pub fn keyvaults(subscription_id: &str) -> String {
         format!(
-            "https://management.azure.com/subscriptions/{}/providers/Microsoft.KeyVault/vaults?api-version={}",
-            subscription_id, KEYVAULT_MGMT_API_VERSION
+            "{}/subscriptions/{}/providers/Microsoft.KeyVault/vaults?api-version={}",
+            arm_endpoint(), subscription_id, KEYVAULT_MGMT_API_VERSION
         )
     }
 
     /// Get the URL to create/update a Key Vault
     pub fn keyvault(subscription_id: &str, resource_group: &str, keyvault_name: &str) -> String {
         format!(
-            "https://management.azure.com/subscriptions/{}/resourceGroups/{}/providers/Microsoft.KeyVault/vaults/{}?api-version={}",
-            subscription_id, resource_group, keyvault_name, KEYVAULT_MGMT_API_VERSION
+            "{}/subscriptions/{}/resourceGroups/{}/providers/Microsoft.KeyVault/vaults/{}?api-version={}",
+            arm_endpoint(),
+            subscription_id,
+            resource_group,
+            keyvault_name,
+            KEYVAULT_MGMT_API_VERSION
+        )
+    }
+
+    /// Get the URL to check whether a Key Vault name is available
+    pub fn keyvault_name_availability(subscription_id: &str) -> String {
+        format!(
+            "{}/subscriptions/{}/providers/Microsoft.KeyVault/checkNameAvailability?api-version={}",
+            arm_endpoint(), subscription_id, KEYVAULT_MGMT_API_VERSION
         )
     }
 
     /// Get the URL to list all resource groups in a subscription
     pub fn resource_groups(subscription_id: &str) -> String {
         format!(
-            "https://management.azure.com/subscriptions/{}/resourcegroups?api-version={}",
-            subscription_id, RESOURCE_GROUPS_API_VERSION
+            "{}/subscriptions/{}/resourcegroups?api-version={}",
+            arm_endpoint(), subscription_id, RESOURCE_GROUPS_API_VERSION
         )
     }
 
     /// Get the URL to get a specific resource group
     pub fn resource_group(subscription_id: &str, resource_group_name: &str) -> String {
         format!(
-            "https://management.azure.com/subscriptions/{}/resourcegroups/{}?api-version={}",
-            subscription_id, resource_group_name, RESOURCE_GROUPS_API_VERSION
+            "{}/subscriptions/{}/resourcegroups/{}?api-version={}",
+            arm_endpoint(), subscription_id, resource_group_name, RESOURCE_GROUPS_API_VERSION
         )
     }
 
     /// Get the URL to list all secrets in a Key Vault
     pub fn secrets(keyvault_uri: &str) -> String {
-        let clean_uri = keyvault_uri
-            .trim_start_matches("https://")
-            .trim_end_matches('/');
+        let clean_uri = vault_host(keyvault_uri);
         format!(
             "https://{}/secrets?api-version={}",
             clean_uri, KEYVAULT_DATA_API_VERSION
@@ -120,9 +340,7 @@ pub mod urls {
 
     /// Get the URL to get a specific secret (optionally with version)
     pub fn secret(keyvault_uri: &str, secret_name: &str, secret_version: Option<&str>) -> String {
-        let clean_uri = keyvault_uri
-            .trim_start_matches("https://")
-            .trim_end_matches('/');
+        let clean_uri = vault_host(keyvault_uri);
 
         match secret_version {
             Some(version) => format!(
@@ -138,9 +356,7 @@ pub mod urls {
 
     /// Get the URL to create/update a secret
     pub fn create_secret(keyvault_uri: &str, secret_name: &str) -> String {
-        let clean_uri = keyvault_uri
-            .trim_start_matches("https://")
-            .trim_end_matches('/');
+        let clean_uri = vault_host(keyvault_uri);
         format!(
             "https://{}/secrets/{}?api-version={}",
             clean_uri, secret_name, KEYVAULT_DATA_API_VERSION
@@ -149,9 +365,7 @@ pub mod urls {
 
     /// Get the URL to list all versions of a secret
     pub fn secret_versions(keyvault_uri: &str, secret_name: &str) -> String {
-        let clean_uri = keyvault_uri
-            .trim_start_matches("https://")
-            .trim_end_matches('/');
+        let clean_uri = vault_host(keyvault_uri);
         format!(
             "https://{}/secrets/{}/versions?api-version={}",
             clean_uri, secret_name, KEYVAULT_DATA_API_VERSION
@@ -160,9 +374,7 @@ pub mod urls {
 
     /// Get the URL to delete a secret
     pub fn delete_secret(keyvault_uri: &str, secret_name: &str) -> String {
-        let clean_uri = keyvault_uri
-            .trim_start_matches("https://")
-            .trim_end_matches('/');
+        let clean_uri = vault_host(keyvault_uri);
         format!(
             "https://{}/secrets/{}?api-version={}",
             clean_uri, secret_name, KEYVAULT_DATA_API_VERSION
@@ -171,9 +383,7 @@ pub mod urls {
 
     /// Get the URL to list all deleted secrets in a Key Vault
     pub fn deleted_secrets(keyvault_uri: &str) -> String {
-        let clean_uri = keyvault_uri
-            .trim_start_matches("https://")
-            .trim_end_matches('/');
+        let clean_uri = vault_host(keyvault_uri);
         format!(
             "https://{}/deletedsecrets?api-version={}",
             clean_uri, KEYVAULT_DATA_API_VERSION
@@ -182,9 +392,7 @@ pub mod urls {
 
     /// Get the URL to recover a deleted secret
     pub fn recover_deleted_secret(keyvault_uri: &str, secret_name: &str) -> String {
-        let clean_uri = keyvault_uri
-            .trim_start_matches("https://")
-            .trim_end_matches('/');
+        let clean_uri = vault_host(keyvault_uri);
         format!(
             "https://{}/deletedsecrets/{}/recover?api-version={}",
             clean_uri, secret_name, KEYVAULT_DATA_API_VERSION
@@ -193,15 +401,126 @@ pub mod urls {
 
     /// Get the URL to purge a deleted secret (permanently delete)
     pub fn purge_deleted_secret(keyvault_uri: &str, secret_name: &str) -> String {
-        let clean_uri = keyvault_uri
-            .trim_start_matches("https://")
-            .trim_end_matches('/');
+        let clean_uri = vault_host(keyvault_uri);
         format!(
             "https://{}/deletedsecrets/{}?api-version={}",
             clean_uri, secret_name, KEYVAULT_DATA_API_VERSION
         )
     }
 
+    /// Get the URL to back up a secret with all its versions
+    pub fn backup_secret(keyvault_uri: &str, secret_name: &str) -> String {
+        let clean_uri = vault_host(keyvault_uri);
+        format!(
+            "https://{}/secrets/{}/backup?api-version={}",
+            clean_uri, secret_name, KEYVAULT_DATA_API_VERSION
+        )
+    }
+
+    /// Get the URL to restore a secret from a backup blob
+    pub fn restore_secret(keyvault_uri: &str) -> String {
+        let clean_uri = vault_host(keyvault_uri);
+        format!(
+            "https://{}/secrets/restore?api-version={}",
+            clean_uri, KEYVAULT_DATA_API_VERSION
+        )
+    }
+
+    /// Get the URL to list all keys in a Key Vault
+    pub fn keys(keyvault_uri: &str) -> String {
+        let clean_uri = vault_host(keyvault_uri);
+        format!(
+            "https://{}/keys?api-version={}",
+            clean_uri, KEYVAULT_DATA_API_VERSION
+        )
+    }
+
+    /// Get the URL to get a specific key (optionally with version)
+    pub fn key(keyvault_uri: &str, key_name: &str, key_version: Option<&str>) -> String {
+        let clean_uri = vault_host(keyvault_uri);
+
+        match key_version {
+            Some(version) => format!(
+                "https://{}/keys/{}/{}?api-version={}",
+                clean_uri, key_name, version, KEYVAULT_DATA_API_VERSION
+            ),
+            None => format!(
+                "https://{}/keys/{}?api-version={}",
+                clean_uri, key_name, KEYVAULT_DATA_API_VERSION
+            ),
+        }
+    }
+
+    /// Get the URL to list all certificates in a Key Vault
+    pub fn certificates(keyvault_uri: &str) -> String {
+        let clean_uri = vault_host(keyvault_uri);
+        format!(
+            "https://{}/certificates?api-version={}",
+            clean_uri, KEYVAULT_DATA_API_VERSION
+        )
+    }
+
+    /// Get the URL to start a full restore of a vault from a backup blob
+    pub fn vault_restore(keyvault_uri: &str) -> String {
+        let clean_uri = vault_host(keyvault_uri);
+        format!(
+            "https://{}/restore?api-version={}",
+            clean_uri, KEYVAULT_DATA_API_VERSION
+        )
+    }
+
+    /// Get the URL to check the status of a full vault restore
+    pub fn vault_restore_status(keyvault_uri: &str, job_id: &str) -> String {
+        let clean_uri = vault_host(keyvault_uri);
+        format!(
+            "https://{}/restore/{}/pending?api-version={}",
+            clean_uri, job_id, KEYVAULT_DATA_API_VERSION
+        )
+    }
+
+    /// Get the URL to list role assignments that apply to a scope.
+    ///
+    /// The `scope` is a full ARM resource ID (e.g. a Key Vault's ID). Assignments
+    /// inherited from parent scopes (resource group, subscription) are included.
+    pub fn role_assignments(scope: &str) -> String {
+        format!(
+            "{}{}/providers/Microsoft.Authorization/roleAssignments?api-version={}",
+            arm_endpoint(),
+            scope.trim_end_matches('/'),
+            AUTHORIZATION_API_VERSION
+        )
+    }
+
+    /// Get the URL to create a role assignment with the given name (a GUID) at a scope
+    pub fn role_assignment(scope: &str, assignment_name: &str) -> String {
+        format!(
+            "{}{}/providers/Microsoft.Authorization/roleAssignments/{}?api-version={}",
+            arm_endpoint(),
+            scope.trim_end_matches('/'),
+            assignment_name,
+            AUTHORIZATION_API_VERSION
+        )
+    }
+
+    /// Get the URL to list the caller's effective permissions on a scope
+    pub fn permissions(scope: &str) -> String {
+        format!(
+            "{}{}/providers/Microsoft.Authorization/permissions?api-version={}",
+            arm_endpoint(),
+            scope.trim_end_matches('/'),
+            AUTHORIZATION_API_VERSION
+        )
+    }
+
+    /// Get the URL of a role definition from its full ID
+    /// (`/subscriptions/{sub}/providers/Microsoft.Authorization/roleDefinitions/{guid}`)
+    pub fn role_definition(role_definition_id: &str) -> String {
+        format!(
+            "{}{}?api-version={}",
+            arm_endpoint(), role_definition_id, AUTHORIZATION_API_VERSION
+        )
+    }
+
     /// Get the URL to list activity logs for a specific resource.
     ///
     /// Uses the Azure Monitor Activity Log REST API.
@@ -220,15 +539,48 @@ pub mod urls {
         );
 
         format!(
-            "https://management.azure.com/subscriptions/{}/providers/Microsoft.Insights/eventtypes/management/values?api-version={}&$filter={}",
+            "{}/subscriptions/{}/providers/Microsoft.Insights/eventtypes/management/values?api-version={}&$filter={}",
+            arm_endpoint(),
             extract_subscription_id(resource_id),
             ACTIVITY_LOG_API_VERSION,
             urlencoding::encode(&filter)
         )
     }
 
+    /// Get the URL to resolve directory objects (users, service principals) by ID
+    pub fn graph_directory_objects_by_ids() -> String {
+        format!(
+            "{}/v1.0/directoryObjects/getByIds",
+            cloud_environment().graph_endpoint()
+        )
+    }
+
+    /// Resource Manager endpoint of the current cloud
+    fn arm_endpoint() -> &'static str {
+        cloud_environment().arm_endpoint()
+    }
+
+    /// Get a Key Vault's host from its URI, without scheme or trailing slash.
+    ///
+    /// A bare vault name gets the current cloud's Key Vault DNS suffix.
+    pub(crate) fn vault_host(keyvault_uri: &str) -> String {
+        vault_host_in(cloud_environment(), keyvault_uri)
+    }
+
+    /// Get a Key Vault's host from its URI, completing bare names for `cloud`
+    pub(crate) fn vault_host_in(cloud: CloudEnvironment, keyvault_uri: &str) -> String {
+        let host = keyvault_uri
+            .trim_start_matches("https://")
+            .trim_end_matches('/');
+        if host.contains('.') {
+            host.to_string()
+        } else {
+            format!("{}.{}", host, cloud.keyvault_dns_suffix())
+        }
+    }
+
     /// Extract subscription ID from a full ARM resource ID.
-    fn extract_subscription_id(resource_id: &str) -> &str {
+    pub(crate) fn extract_subscription_id(resource_id: &str) -> &str {
         // Resource ID format: /subscriptions/{sub-id}/resourceGroups/...
         let parts: Vec<&str> = resource_id.split('/').collect();
         if let Some(pos) = parts.iter().position(|&p| p.eq_ignore_ascii_case("subscriptions")) {
@@ -238,14 +590,35 @@ pub mod urls {
         }
         resource_id
     }
+
+    /// Extract the resource group name from a full ARM resource ID.
+    ///
+    /// Returns `None` if the ID isn't scoped to a resource group.
+    pub(crate) fn extract_resource_group(resource_id: &str) -> Option<&str> {
+        // Resource ID format: /subscriptions/{sub-id}/resourceGroups/{rg}/...
+        arm_id_segment(resource_id, "resourceGroups")
+    }
+
+    /// Get the value following a key segment in an ARM resource ID
+    /// (e.g. `subscriptions` -> `{sub-id}`), matching the key case-insensitively.
+    pub(crate) fn arm_id_segment<'a>(resource_id: &'a str, key: &str) -> Option<&'a str> {
+        let mut parts = resource_id.split('/');
+        parts.by_ref().find(|p| p.eq_ignore_ascii_case(key))?;
+        parts.next().filter(|value| !value.is_empty())
+    }
 }
 
 #[cfg(test)]
 mod tests {
   use super::*;
+    use std::sync::Mutex;
+
+    /// Serializes tests that read or switch the process-wide cloud
+    static CLOUD_LOCK: Mutex<()> = Mutex::new(());
 
   #[test]
     fn test_subscriptions_url() {
+        let _guard = CLOUD_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
         let url = urls::subscriptions();
         assert!(url.contains("management.azure.com"));
         assert!(url.contains("subscriptions"));
@@ -282,4 +655,92 @@ mod tests {
         assert!(url.contains("mysecret?api-version"));
         assert!(!url.contains("mysecret/"));
     }
+
+    #[test]
+    fn test_secret_backup_and_restore_urls() {
+        assert_eq!(
+            urls::backup_secret("https://myvault.vault.azure.net/", "mysecret"),
+            format!(
+                "https://myvault.vault.azure.net/secrets/mysecret/backup?api-version={}",
+                KEYVAULT_DATA_API_VERSION
+            )
+        );
+        assert_eq!(
+            urls::restore_secret("https://myvault.vault.azure.net"),
+            format!(
+                "https://myvault.vault.azure.net/secrets/restore?api-version={}",
+                KEYVAULT_DATA_API_VERSION
+            )
+        );
+    }
+
+    #[test]
+    fn test_key_urls() {
+        assert_eq!(
+            urls::keys("https://myvault.vault.azure.net/"),
+            format!(
+                "https://myvault.vault.azure.net/keys?api-version={}",
+                KEYVAULT_DATA_API_VERSION
+            )
+        );
+        assert!(urls::key("https://myvault.vault.azure.net", "mykey", Some("v1"))
+            .contains("/keys/mykey/v1?api-version"));
+        assert!(urls::key("https://myvault.vault.azure.net", "mykey", None)
+            .contains("/keys/mykey?api-version"));
+    }
+
+    #[test]
+    fn test_extract_resource_group() {
+        assert_eq!(
+            urls::extract_resource_group(
+                "/subscriptions/sub-123/resourceGroups/my-rg/providers/Microsoft.KeyVault/vaults/kv"
+            ),
+            Some("my-rg")
+        );
+        assert_eq!(
+            urls::extract_resource_group("/subscriptions/sub-123/resourcegroups/my-rg"),
+            Some("my-rg")
+        );
+        assert_eq!(urls::extract_resource_group("/subscriptions/sub-123"), None);
+        assert_eq!(urls::extract_resource_group("/subscriptions/sub-123/resourceGroups/"), None);
+    }
+
+    #[test]
+    fn test_vault_host_completes_bare_names() {
+        assert_eq!(
+            urls::vault_host_in(CloudEnvironment::UsGov, "myvault"),
+            "myvault.vault.usgovcloudapi.net"
+        );
+        assert_eq!(
+            urls::vault_host_in(CloudEnvironment::China, "https://myvault.vault.azure.cn/"),
+            "myvault.vault.azure.cn"
+        );
+    }
+
+    #[test]
+    fn test_urls_follow_cloud_environment() {
+        let _guard = CLOUD_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+        set_cloud_environment(CloudEnvironment::UsGov);
+        let secrets_url = urls::secrets("myvault");
+        let subscriptions_url = urls::subscriptions();
+        let scope = keyvault_scope();
+        set_cloud_environment(CloudEnvironment::Public);
+
+        assert_eq!(
+            secrets_url,
+            format!(
+                "https://myvault.vault.usgovcloudapi.net/secrets?api-version={}",
+                KEYVAULT_DATA_API_VERSION
+            )
+        );
+        assert!(subscriptions_url.starts_with("https://management.usgovcloudapi.net/"));
+        assert_eq!(scope, "https://vault.usgovcloudapi.net/.default");
+    }
+
+    #[test]
+    fn test_cloud_environment_names() {
+        let cloud: CloudEnvironment = serde_json::from_str(r#""us-gov""#).unwrap();
+        assert_eq!(cloud, CloudEnvironment::UsGov);
+        assert_eq!(CloudEnvironment::default(), CloudEnvironment::Public);
+    }
 }
@@ -0,0 +1,107 @@
+//! Recently viewed secrets history
+//!
+//! Keeps a small, in-memory, most-recent-first list of secrets the user has
+//! opened so the UI can offer quick navigation. Only identifiers are stored,
+//! never secret values.
+
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// Maximum number of entries kept in the history
+const MAX_RECENT_SECRETS: usize = 25;
+
+/// A single recently viewed secret
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecentSecret {
+    pub vault_uri: String,
+    pub secret_name: String,
+    /// Unix timestamp (seconds) of the last time the secret was opened
+    pub viewed_at: i64,
+}
+
+/// Bounded most-recent-first list of viewed secrets
+pub struct RecentSecrets {
+    entries: VecDeque<RecentSecret>,
+    capacity: usize,
+}
+
+impl RecentSecrets {
+    /// Create an empty history holding at most `capacity` entries
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// Record a view, moving an existing entry for the same secret to the front
+    pub fn record(&mut self, vault_uri: &str, secret_name: &str, viewed_at: i64) {
+        self.entries.retain(|e| {
+            !(e.vault_uri == vault_uri && e.secret_name.eq_ignore_ascii_case(secret_name))
+        });
+
+        self.entries.push_front(RecentSecret {
+            vault_uri: vault_uri.to_string(),
+            secret_name: secret_name.to_string(),
+            viewed_at,
+        });
+
+        self.entries.truncate(self.capacity);
+    }
+
+    /// Get all entries, most recent first
+    pub fn list(&self) -> Vec<RecentSecret> {
+        self.entries.iter().cloned().collect()
+    }
+
+    /// Remove all entries
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+lazy_static::lazy_static! {
+    /// Global recently viewed secrets history
+    pub static ref RECENT_SECRETS: Arc<Mutex<RecentSecrets>> =
+        Arc::new(Mutex::new(RecentSecrets::new(MAX_RECENT_SECRETS)));
+}
+
+/// Record that a secret was viewed
+pub async fn record_secret_view(vault_uri: &str, secret_name: &str) {
+    let mut history = RECENT_SECRETS.lock().await;
+    history.record(vault_uri, secret_name, chrono::Utc::now().timestamp());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_moves_duplicate_to_front() {
+        let mut history = RecentSecrets::new(5);
+        history.record("https://a.vault.azure.net", "one", 1);
+        history.record("https://a.vault.azure.net", "two", 2);
+        history.record("https://a.vault.azure.net", "one", 3);
+
+        let entries = history.list();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].secret_name, "one");
+        assert_eq!(entries[0].viewed_at, 3);
+    }
+
+    #[test]
+    fn test_history_is_bounded() {
+        let mut history = RecentSecrets::new(3);
+        for i in 0..10 {
+            history.record("https://a.vault.azure.net", &format!("secret-{}", i), i);
+        }
+
+        let entries = history.list();
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].secret_name, "secret-9");
+        assert_eq!(entries[2].secret_name, "secret-7");
+    }
+}
@@ -2,28 +2,56 @@
 //!
 //! This is the main library entry point for the Tauri application.
 
+mod audit_log;
 mod azure;
 mod cache;
+mod cancellation;
 mod commands;
 mod config;
+mod history;
 mod user_config;
+mod view_fingerprints;
 
-use commands::activity_log::{fetch_activity_logs, resolve_callers};
+use commands::activity_log::{fetch_activity_logs, fetch_activity_logs_resolved, resolve_callers};
+use commands::audit_log::{clear_local_audit_log, get_local_audit_log};
 use commands::auth::{
-  azure_login, azure_logout, check_auth, complete_browser_login,
-  get_current_user, start_browser_login,
+  auth_status_detail, azure_login, azure_logout, cancel_login, check_auth, complete_browser_login,
+  get_auth_status, get_current_user, get_openid_config, get_token_claims, reset_auth_flow,
+  start_browser_login, test_service_principal,
 };
 use commands::cache::{
   clear_cache, get_cache_stats, invalidate_keyvaults_cache, invalidate_resource_groups_cache,
-  invalidate_subscriptions_cache, invalidate_vault_cache,
+  invalidate_subscriptions_cache, invalidate_vault_cache, persist_cache, rebuild_cache_with_ttls,
+  reset_cache_stats,
 };
-use commands::config::{get_auto_login, get_azure_config, save_azure_config, set_auto_login};
+use commands::config::{
+  get_auto_login, get_azure_config, save_azure_config, set_authority_host, set_auto_login,
+  set_cache_refresh_ahead, set_cloud_environment, set_extra_scopes, set_http_timeout,
+  set_managed_identity_client_id, set_read_only, set_request_concurrency,
+};
+use commands::history::{clear_recent_secrets, get_recent_secrets};
 use commands::keyvault::{
-  check_keyvault_access, create_keyvault, create_secret, delete_keyvault, delete_secret, export_secrets,
-  fetch_keyvaults, get_deleted_secrets, get_secret, get_secret_versions, get_secrets,
-  global_search_secrets, parse_import_file, purge_deleted_secret, recover_deleted_secret, update_secret,
+  audit_secret_values, backup_secret, check_keyvault_access, clone_vault, compare_env_with_vault,
+  compare_secret_versions, create_keyvault, create_secret, create_secret_with_attributes,
+  create_secrets, delete_keyvault, delete_keyvault_by_id, delete_secret, delete_secret_with_info,
+  diff_secret_versions, enable_purge_protection, export_secrets, export_secrets_with_summary,
+  export_subscription, fetch_keyvaults, find_duplicate_values, find_vaults_without_soft_delete,
+  generate_secret_value, get_deleted_secrets, get_expiring_certificates, get_key, get_keys,
+  get_secret, get_secret_everywhere, get_secret_formatted, get_secret_names, get_secret_versions,
+  get_secrets, get_secrets_page, get_secrets_with_status, get_vault_access_report,
+  get_vault_capabilities, get_vault_stats, get_vault_tree, global_search_all, global_search_secrets,
+  import_secrets, parse_encrypted_import, parse_import_file, ping_vault, plan_import,
+  prefetch_secret_values, preview_create_keyvault, purge_deleted_secret, recover_deleted_secret,
+  restore_secret, restore_vault_backup, rollback_secret, rotate_secret, rotate_secret_random,
+  save_secret_to_file, set_secret_enabled, set_secret_from_file, set_secrets_enabled,
+  set_soft_delete_retention, set_vault_network_rules, update_secret, verify_read_access,
+};
+use commands::metrics::get_request_metrics;
+use commands::operations::cancel_operation;
+use commands::rbac::{
+  assign_vault_role, get_known_vault_roles, get_vault_role_assignments, resolve_role_definitions,
 };
-use commands::resource_group::get_resource_groups;
+use commands::resource_group::{fetch_all_resource_groups, get_resource_groups};
 use commands::subscription::fetch_subscriptions;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
@@ -42,48 +70,137 @@ pub fn run() {
             azure_login,
             start_browser_login,
             complete_browser_login,
+            reset_auth_flow,
+            cancel_login,
+            get_token_claims,
+            test_service_principal,
+            get_openid_config,
             check_auth,
             get_current_user,
+            get_auth_status,
+            auth_status_detail,
             azure_logout,
             // Config commands
             get_azure_config,
             save_azure_config,
             get_auto_login,
             set_auto_login,
+            set_extra_scopes,
+            set_authority_host,
+            set_managed_identity_client_id,
+            set_cache_refresh_ahead,
+            set_read_only,
+            set_http_timeout,
+            set_request_concurrency,
+            set_cloud_environment,
             // Subscription commands
             fetch_subscriptions,
             // Key Vault commands
             fetch_keyvaults,
+            get_vault_tree,
+            find_vaults_without_soft_delete,
             check_keyvault_access,
+            ping_vault,
+            get_vault_capabilities,
+            get_vault_access_report,
             create_keyvault,
+            preview_create_keyvault,
             delete_keyvault,
+            delete_keyvault_by_id,
+            clone_vault,
+            restore_vault_backup,
+            set_vault_network_rules,
+            enable_purge_protection,
+            set_soft_delete_retention,
             // Secret commands
             get_secrets,
+            get_secrets_page,
+            get_secrets_with_status,
+            get_secret_names,
             get_secret,
+            get_secret_formatted,
+            save_secret_to_file,
+            get_secret_everywhere,
             get_secret_versions,
+            get_keys,
+            get_key,
+            get_expiring_certificates,
+            prefetch_secret_values,
+            audit_secret_values,
+            find_duplicate_values,
+            verify_read_access,
+            get_vault_stats,
             delete_secret,
+            delete_secret_with_info,
             create_secret,
+            create_secret_with_attributes,
+            create_secrets,
             update_secret,
+            set_secret_enabled,
+            set_secrets_enabled,
+            set_secret_from_file,
+            rotate_secret_random,
+            rotate_secret,
+            generate_secret_value,
             export_secrets,
+            export_secrets_with_summary,
+            export_subscription,
             parse_import_file,
+            parse_encrypted_import,
+            plan_import,
+            import_secrets,
+            compare_env_with_vault,
+            compare_secret_versions,
+            diff_secret_versions,
+            rollback_secret,
             global_search_secrets,
+            global_search_all,
+            // Recently viewed secrets commands
+            get_recent_secrets,
+            clear_recent_secrets,
             // Deleted secret commands
             get_deleted_secrets,
             recover_deleted_secret,
             purge_deleted_secret,
+            backup_secret,
+            restore_secret,
+            // RBAC commands
+            get_vault_role_assignments,
+            assign_vault_role,
+            get_known_vault_roles,
+            resolve_role_definitions,
             // Resource Group commands
             get_resource_groups,
+            fetch_all_resource_groups,
             // Activity Log commands
             fetch_activity_logs,
+            fetch_activity_logs_resolved,
             resolve_callers,
+            // Local audit log commands
+            get_local_audit_log,
+            clear_local_audit_log,
+            // Diagnostics commands
+            get_request_metrics,
+            // Operation commands
+            cancel_operation,
             // Cache commands
             get_cache_stats,
+            reset_cache_stats,
+            rebuild_cache_with_ttls,
             clear_cache,
             invalidate_subscriptions_cache,
             invalidate_keyvaults_cache,
             invalidate_resource_groups_cache,
             invalidate_vault_cache,
+            persist_cache,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|_app, event| {
+            if let tauri::RunEvent::Exit = event {
+                if let Err(e) = cache::azure_cache().save_to_disk() {
+                    log::error!("Failed to save cache snapshot: {}", e);
+                }
+            }
+        });
 }
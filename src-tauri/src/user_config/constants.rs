@@ -14,6 +14,16 @@ pub const MULTI_TENANT_ENDPOINT: &str = "organizations";
 /// Configuration file name
 pub const CONFIG_FILE_NAME: &str = "config.json";
 
+/// Suffix appended to the config file name for the pre-migration backup
+pub const CONFIG_BACKUP_SUFFIX: &str = ".bak";
+
+/// Suffix (followed by a timestamp) for config files set aside because they couldn't be parsed
+pub const CONFIG_CORRUPT_SUFFIX: &str = ".corrupt-";
+
+/// Current config file schema version
+/// Bump this and add a step to `migrate_config` when making a breaking change
+pub const CONFIG_SCHEMA_VERSION: u32 = 1;
+
 /// Application name for config directory
 pub const APP_NAME: &str = "VaultRaider";
 
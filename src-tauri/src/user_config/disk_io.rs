@@ -1,7 +1,10 @@
-﻿use crate::user_config::constants::{APP_NAME, CONFIG_FILE_NAME};
+﻿use crate::user_config::constants::{
+  APP_NAME, CONFIG_BACKUP_SUFFIX, CONFIG_CORRUPT_SUFFIX, CONFIG_FILE_NAME, CONFIG_SCHEMA_VERSION,
+};
 use crate::user_config::types::UserConfig;
+use serde_json::Value;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// Get the configuration directory path
 fn get_config_dir() -> Option<PathBuf> {
@@ -15,37 +18,149 @@ fn get_config_file_path() -> Option<PathBuf> {
 
 /// Load configuration from disk
 pub fn load_config_from_disk() -> UserConfig {
-  let config_path = match get_config_file_path() {
-    Some(path) => path,
+  match get_config_file_path() {
+    Some(path) => load_config_from_path(&path),
     None => {
       log::warn!("Could not determine config directory, using defaults");
-      return UserConfig::default();
+      UserConfig::default()
     }
-  };
+  }
+}
 
+/// Load configuration from a specific file, migrating older schema versions
+fn load_config_from_path(config_path: &Path) -> UserConfig {
   if !config_path.exists() {
     log::info!("Config file not found, using defaults");
     return UserConfig::default();
   }
 
-  match fs::read_to_string(&config_path) {
-    Ok(content) => match serde_json::from_str(&content) {
-      Ok(config) => {
-        log::info!("Loaded configuration from {:?}", config_path);
-        config
-      }
-      Err(e) => {
-        log::error!("Failed to parse config file: {}", e);
-        UserConfig::default()
-      }
-    },
+  let content = match fs::read_to_string(config_path) {
+    Ok(content) => content,
     Err(e) => {
       log::error!("Failed to read config file: {}", e);
-      UserConfig::default()
+      return UserConfig::default();
     }
+  };
+
+  let mut raw: Value = match serde_json::from_str(&content) {
+    Ok(raw) => raw,
+    Err(e) => {
+      log::error!("Failed to parse config file: {}", e);
+      set_aside_corrupt_config(config_path);
+      return UserConfig::default();
+    }
+  };
+
+  let changed = migrate_config(&mut raw).unwrap_or_else(|e| {
+    // Loaded unmigrated and not saved back, so the file stays as it was
+    log::error!("{}, loading the config as-is", e);
+    false
+  });
+
+  let config: UserConfig = match serde_json::from_value(raw) {
+    Ok(config) => config,
+    Err(e) => {
+      log::error!("Failed to parse config file: {}", e);
+      set_aside_corrupt_config(config_path);
+      return UserConfig::default();
+    }
+  };
+
+  if changed {
+    persist_migrated_config(config_path, &config);
+  }
+
+  log::info!("Loaded configuration from {:?}", config_path);
+  config
+}
+
+/// Move an unparseable config file out of the way so the next save doesn't overwrite it.
+///
+/// The file is kept next to the original as `config.json.corrupt-<timestamp>`
+/// so the user can recover their settings by hand.
+fn set_aside_corrupt_config(config_path: &Path) {
+  let mut corrupt_path = config_path.as_os_str().to_owned();
+  corrupt_path.push(CONFIG_CORRUPT_SUFFIX);
+  corrupt_path.push(chrono::Utc::now().format("%Y%m%d%H%M%S").to_string());
+  let corrupt_path = PathBuf::from(corrupt_path);
+
+  match fs::rename(config_path, &corrupt_path) {
+    Ok(()) => log::warn!(
+      "Corrupt config file moved to {:?}, starting from defaults",
+      corrupt_path
+    ),
+    Err(e) => log::error!("Failed to move corrupt config file aside: {}", e),
+  }
+}
+
+/// Back up the original config file and write the migrated version in its place
+fn persist_migrated_config(config_path: &Path, config: &UserConfig) {
+  let mut backup_path = config_path.as_os_str().to_owned();
+  backup_path.push(CONFIG_BACKUP_SUFFIX);
+
+  if let Err(e) = fs::copy(config_path, &backup_path) {
+    // Don't overwrite the only copy of the user's settings
+    log::error!("Failed to back up config before migration, not saving: {}", e);
+    return;
+  }
+  log::info!("Backed up previous config to {:?}", backup_path);
+
+  match write_config(config_path, config) {
+    Ok(()) => log::info!(
+      "Migrated config to schema version {}",
+      config.schema_version
+    ),
+    Err(e) => log::error!("Failed to save migrated config: {}", e),
   }
 }
 
+/// Upgrade a raw config document in place to the current schema version.
+///
+/// Returns whether anything changed.
+fn migrate_config(raw: &mut Value) -> Result<bool, String> {
+  migrate_config_to(raw, CONFIG_SCHEMA_VERSION)
+}
+
+/// Upgrade a raw config document in place to schema version `target`.
+///
+/// Each step upgrades from one version to the next, so files of any age
+/// walk forward one version at a time. The document is only changed once
+/// every step is known, so on an error it is left as it was.
+fn migrate_config_to(raw: &mut Value, target: u32) -> Result<bool, String> {
+  let original_version = raw
+    .get("schema_version")
+    .and_then(Value::as_u64)
+    .unwrap_or(0) as u32;
+
+  if original_version > target {
+    log::warn!(
+      "Config schema version {} is newer than supported version {}, loading as-is",
+      original_version,
+      target
+    );
+    return Ok(false);
+  }
+
+  let mut version = original_version;
+  while version < target {
+    match version {
+      // v0 -> v1: introduce `schema_version`; all other fields are unchanged
+      0 => {}
+      _ => return Err(format!("No config migration from schema version {}", version)),
+    }
+    version += 1;
+  }
+
+  if version == original_version {
+    return Ok(false);
+  }
+
+  if let Some(obj) = raw.as_object_mut() {
+    obj.insert("schema_version".to_string(), Value::from(version));
+  }
+  Ok(true)
+}
+
 /// Save configuration to disk
 pub fn save_config_to_disk(config: &UserConfig) -> Result<(), String> {
   let config_dir = get_config_dir().ok_or("Could not determine config directory")?;
@@ -57,11 +172,105 @@ pub fn save_config_to_disk(config: &UserConfig) -> Result<(), String> {
   }
 
   let config_path = config_dir.join(CONFIG_FILE_NAME);
+  write_config(&config_path, config)?;
+
+  log::info!("Saved configuration to {:?}", config_path);
+  Ok(())
+}
+
+/// Serialize and write a configuration to the given path
+fn write_config(config_path: &Path, config: &UserConfig) -> Result<(), String> {
   let content =
     serde_json::to_string_pretty(config).map_err(|e| format!("Failed to serialize config: {}", e))?;
 
-  fs::write(&config_path, content).map_err(|e| format!("Failed to write config file: {}", e))?;
+  fs::write(config_path, content).map_err(|e| format!("Failed to write config file: {}", e))
+}
 
-  log::info!("Saved configuration to {:?}", config_path);
-  Ok(())
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// Create an empty, uniquely named directory for a test
+  fn test_dir() -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("vaultraider-test-{}", uuid::Uuid::new_v4()));
+    fs::create_dir_all(&dir).unwrap();
+    dir
+  }
+
+  #[test]
+  fn test_migrate_v0_config() {
+    let dir = test_dir();
+    let path = dir.join(CONFIG_FILE_NAME);
+    let v0 = r#"{"client_id":"my-client","tenant_id":null,"auto_login":true}"#;
+    fs::write(&path, v0).unwrap();
+
+    let config = load_config_from_path(&path);
+
+    assert_eq!(config.schema_version, CONFIG_SCHEMA_VERSION);
+    assert_eq!(config.client_id.as_deref(), Some("my-client"));
+    assert!(config.auto_login);
+
+    // Original is preserved as a backup, migrated version written in place
+    let backup_path = dir.join(format!("{}{}", CONFIG_FILE_NAME, CONFIG_BACKUP_SUFFIX));
+    let backup = fs::read_to_string(backup_path).unwrap();
+    assert_eq!(backup, v0);
+    let saved: Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(saved["schema_version"], CONFIG_SCHEMA_VERSION);
+
+    fs::remove_dir_all(dir).unwrap();
+  }
+
+  #[test]
+  fn test_corrupt_config_is_preserved() {
+    let dir = test_dir();
+    let path = dir.join(CONFIG_FILE_NAME);
+    let corrupt = r#"{"client_id":"my-client","tenant_id":"#;
+    fs::write(&path, corrupt).unwrap();
+
+    let config = load_config_from_path(&path);
+
+    assert!(config.client_id.is_none());
+    assert!(!path.exists());
+
+    let prefix = format!("{}{}", CONFIG_FILE_NAME, CONFIG_CORRUPT_SUFFIX);
+    let preserved: Vec<PathBuf> = fs::read_dir(&dir)
+      .unwrap()
+      .map(|entry| entry.unwrap().path())
+      .filter(|p| {
+        p.file_name()
+          .and_then(|name| name.to_str())
+          .is_some_and(|name| name.starts_with(&prefix))
+      })
+      .collect();
+    assert_eq!(preserved.len(), 1);
+    assert_eq!(fs::read_to_string(&preserved[0]).unwrap(), corrupt);
+
+    fs::remove_dir_all(dir).unwrap();
+  }
+
+  #[test]
+  fn test_current_config_is_not_migrated() {
+    let mut raw = serde_json::json!({
+      "schema_version": CONFIG_SCHEMA_VERSION,
+      "auto_login": false
+    });
+    assert_eq!(migrate_config(&mut raw), Ok(false));
+  }
+
+  #[test]
+  fn test_newer_config_is_left_alone() {
+    let raw = serde_json::json!({ "schema_version": CONFIG_SCHEMA_VERSION + 1 });
+    let mut migrated = raw.clone();
+    assert_eq!(migrate_config(&mut migrated), Ok(false));
+    assert_eq!(migrated, raw);
+  }
+
+  #[test]
+  fn test_missing_migration_step_is_an_error() {
+    let raw = serde_json::json!({ "auto_login": true });
+    let mut migrated = raw.clone();
+
+    assert!(migrate_config_to(&mut migrated, CONFIG_SCHEMA_VERSION + 1).is_err());
+    assert_eq!(migrated, raw);
+  }
 }
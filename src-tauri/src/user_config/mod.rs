@@ -11,23 +11,141 @@
 //! Users can optionally configure their own Client ID and/or Tenant ID for:
 //! - Using a custom app registration with specific permissions
 //! - Restricting authentication to a specific tenant
+//!
+//! # Authority Host
+//!
+//! The Azure AD login endpoint is resolved in this order:
+//! 1. `authority_host` in config.json
+//! 2. The `AZURE_AUTHORITY_HOST` environment variable (as used by the Azure SDKs)
+//! 3. The login host of the configured `cloud_environment` (public cloud by default)
+//!
+//! so the same binary can target a sovereign cloud in CI without editing config.json.
+//! Both overrides accept a bare host name or an `https://` URL. They only change
+//! where users log in; ARM and Key Vault endpoints follow `cloud_environment`.
 
 pub mod types;
 pub mod constants;
 mod disk_io;
 
+use crate::azure::http::{set_concurrency_bounds, set_default_timeout};
+use crate::cache::{azure_cache, replace_azure_cache, AzureCache, CacheTtls};
+use crate::config::{
+  auth_scopes, set_cloud_environment, ADAPTIVE_CONCURRENCY_MAX, ADAPTIVE_CONCURRENCY_MIN,
+  AUTHORITY_HOST_ENV_VAR, MAX_ADAPTIVE_CONCURRENCY, MAX_CACHE_TTL_SECS, MAX_HTTP_TIMEOUT_SECS,
+};
 use crate::user_config::constants::{MULTI_TENANT_ENDPOINT, USER_CONFIG, VAULTRAIDER_CLIENT_ID};
 use crate::user_config::disk_io::{load_config_from_disk, save_config_to_disk};
 use crate::user_config::types::UserConfig;
+use std::time::Duration;
 use tokio::sync::RwLock;
 
 
 /// Initialize the global configuration
 pub fn init_config() {
-  let config = load_config_from_disk();
+  let mut config = load_config_from_disk();
+  // A hand-edited config file mustn't keep the app from starting
+  if let Err(e) = validate_cache_ttls(&config) {
+    log::warn!("{}; using the default cache TTLs", e);
+    clear_cache_ttls(&mut config);
+  }
+  if let Err(e) = validate_concurrency_bounds(&config) {
+    log::warn!("{}; using the default request concurrency", e);
+    config.adaptive_concurrency_min = ADAPTIVE_CONCURRENCY_MIN;
+    config.adaptive_concurrency_max = ADAPTIVE_CONCURRENCY_MAX;
+  }
+  // Nothing is cached yet, so there is nothing for `rebuild_cache` to carry over
+  replace_azure_cache(AzureCache::with_ttls(cache_ttls(&config)));
+  apply_runtime_settings(&config);
   USER_CONFIG.get_or_init(|| RwLock::new(config));
 }
 
+/// Push settings that live outside the config lock to the components using them
+fn apply_runtime_settings(config: &UserConfig) {
+  azure_cache().set_refresh_ahead(config.cache_refresh_ahead);
+  set_default_timeout(Duration::from_secs(config.http_timeout_secs));
+  set_concurrency_bounds(config.adaptive_concurrency_min, config.adaptive_concurrency_max);
+  set_cloud_environment(config.cloud_environment);
+}
+
+/// Check that the request concurrency bounds satisfy 1 <= min <= max <= `MAX_ADAPTIVE_CONCURRENCY`
+fn validate_concurrency_bounds(config: &UserConfig) -> Result<(), String> {
+  let (min, max) = (config.adaptive_concurrency_min, config.adaptive_concurrency_max);
+  if 1 <= min && min <= max && max <= MAX_ADAPTIVE_CONCURRENCY {
+    Ok(())
+  } else {
+    Err(format!(
+      "Request concurrency must satisfy 1 <= min <= max <= {}",
+      MAX_ADAPTIVE_CONCURRENCY
+    ))
+  }
+}
+
+/// The configured cache TTLs in seconds, `None` where the default applies
+fn configured_ttls(config: &UserConfig) -> [Option<u64>; 6] {
+  [
+    config.subscription_ttl_secs,
+    config.resource_group_ttl_secs,
+    config.keyvault_ttl_secs,
+    config.secrets_list_ttl_secs,
+    config.keys_list_ttl_secs,
+    config.secret_value_ttl_secs,
+  ]
+}
+
+/// Check that every configured cache TTL is between 1 second and `MAX_CACHE_TTL_SECS`
+///
+/// Used both when the config is loaded and when it is updated.
+pub fn validate_cache_ttls(config: &UserConfig) -> Result<(), String> {
+  let valid = |secs: &Option<u64>| secs.is_none_or(|secs| (1..=MAX_CACHE_TTL_SECS).contains(&secs));
+  if configured_ttls(config).iter().all(valid) {
+    Ok(())
+  } else {
+    Err(format!(
+      "Cache TTLs must be between 1 and {} seconds",
+      MAX_CACHE_TTL_SECS
+    ))
+  }
+}
+
+fn clear_cache_ttls(config: &mut UserConfig) {
+  config.subscription_ttl_secs = None;
+  config.resource_group_ttl_secs = None;
+  config.keyvault_ttl_secs = None;
+  config.secrets_list_ttl_secs = None;
+  config.keys_list_ttl_secs = None;
+  config.secret_value_ttl_secs = None;
+}
+
+/// The cache TTLs from the config, with the built-in default for each one not set
+///
+/// Values outside the range `validate_cache_ttls` allows are clamped into it.
+pub fn cache_ttls(config: &UserConfig) -> CacheTtls {
+  let defaults = CacheTtls::default();
+  let ttl = |secs: Option<u64>, default: Duration| {
+    secs.map_or(default, |secs| Duration::from_secs(secs.clamp(1, MAX_CACHE_TTL_SECS)))
+  };
+
+  CacheTtls {
+    subscriptions: ttl(config.subscription_ttl_secs, defaults.subscriptions),
+    resource_groups: ttl(config.resource_group_ttl_secs, defaults.resource_groups),
+    keyvaults: ttl(config.keyvault_ttl_secs, defaults.keyvaults),
+    secrets_list: ttl(config.secrets_list_ttl_secs, defaults.secrets_list),
+    keys_list: ttl(config.keys_list_ttl_secs, defaults.keys_list),
+    secret_values: ttl(config.secret_value_ttl_secs, defaults.secret_values),
+  }
+}
+
+/// Replace the global cache with one built with the config's TTLs
+///
+/// Moka fixes a cache's TTL when it is built, so changed TTLs need a new cache.
+/// The cached lists and the refresh-ahead setting carry over, so the snapshot
+/// saved on exit still holds them; secret values are dropped.
+pub async fn rebuild_cache(config: &UserConfig) {
+  let cache = AzureCache::with_ttls(cache_ttls(config));
+  cache.carry_over(&azure_cache()).await;
+  replace_azure_cache(cache);
+}
+
 /// Get the current user configuration
 pub async fn get_config() -> UserConfig {
   let config_lock = USER_CONFIG.get_or_init(|| RwLock::new(load_config_from_disk()));
@@ -35,7 +153,7 @@ pub async fn get_config() -> UserConfig {
 }
 
 /// Update the user configuration
-pub async fn update_config(new_config: UserConfig) -> Result<(), String> {
+pub async fn update_config(mut new_config: UserConfig) -> Result<(), String> {
   // Validate: if client_id is provided, it must be a valid GUID
   if let Some(ref client_id) = new_config.client_id {
     if client_id.trim().is_empty() {
@@ -50,8 +168,27 @@ pub async fn update_config(new_config: UserConfig) -> Result<(), String> {
     }
   }
 
+  for scope in &new_config.extra_scopes {
+    validate_scope(scope)?;
+  }
+
+  if let Some(ref authority_host) = new_config.authority_host {
+    new_config.authority_host = normalize_authority_host(authority_host)?;
+  }
+
+  if !(1..=MAX_HTTP_TIMEOUT_SECS).contains(&new_config.http_timeout_secs) {
+    return Err(format!(
+      "HTTP timeout must be between 1 and {} seconds",
+      MAX_HTTP_TIMEOUT_SECS
+    ));
+  }
+
+  validate_cache_ttls(&new_config)?;
+  validate_concurrency_bounds(&new_config)?;
+
   // Save to disk first
   save_config_to_disk(&new_config)?;
+  apply_runtime_settings(&new_config);
 
   // Update in-memory config
   let config_lock = USER_CONFIG.get_or_init(|| RwLock::new(load_config_from_disk()));
@@ -79,9 +216,97 @@ pub async fn get_tenant_id() -> String {
     .unwrap_or_else(|| MULTI_TENANT_ENDPOINT.to_string())
 }
 
+/// Get the scopes to request during the device-code login
+///
+/// OAuth issues a token for a single resource, so extra scopes on other
+/// resources are only consented to up front here; tokens for them are
+/// still obtained per resource through the refresh-token flow.
+pub async fn get_auth_scopes() -> String {
+  let extra_scopes = get_config().await.extra_scopes;
+  std::iter::once(auth_scopes())
+    .chain(extra_scopes.iter().map(String::as_str))
+    .collect::<Vec<_>>()
+    .join(" ")
+}
+
+/// Get the effective Azure AD authority host, without a trailing slash
+///
+/// Precedence: explicit config > `AZURE_AUTHORITY_HOST` > the cloud environment's login host.
+/// Only used for login; other endpoints come from the cloud environment.
+pub async fn get_authority_host() -> String {
+  let config = get_config().await;
+  let from_env = std::env::var(AUTHORITY_HOST_ENV_VAR).ok();
+  resolve_authority_host(
+    config.authority_host.as_deref(),
+    from_env.as_deref(),
+    config.cloud_environment.authority_host(),
+  )
+}
+
+/// Pick the authority host by precedence, skipping blank and invalid values
+fn resolve_authority_host(
+  configured: Option<&str>,
+  from_env: Option<&str>,
+  cloud_default: &str,
+) -> String {
+  [configured, from_env]
+    .into_iter()
+    .flatten()
+    .find_map(|host| {
+      normalize_authority_host(host).unwrap_or_else(|e| {
+        log::warn!("Ignoring authority host '{}': {}", host, e);
+        None
+      })
+    })
+    .unwrap_or_else(|| cloud_default.to_string())
+}
+
+/// Normalize an authority host to an `https://` URL without a trailing slash
+///
+/// Accepts a bare host name, as the SDKs do for `AZURE_AUTHORITY_HOST`.
+/// Returns `None` for a blank value.
+fn normalize_authority_host(host: &str) -> Result<Option<String>, String> {
+  let host = host.trim().trim_end_matches('/');
+  if host.is_empty() {
+    return Ok(None);
+  }
+
+  let bare_host = match host.split_once("://") {
+    Some(("https", bare_host)) => bare_host,
+    Some(_) => return Err("Authority host must be an https:// URL or a host name".to_string()),
+    None => host,
+  };
+  if bare_host.is_empty() || bare_host.chars().any(char::is_whitespace) {
+    return Err(format!("Authority host '{}' is not a valid host name", host));
+  }
+
+  Ok(Some(format!("https://{}", bare_host)))
+}
+
+/// Validate that an OAuth scope is well-formed: a resource followed by a permission
+/// (e.g. `api://my-api/access` or `https://graph.microsoft.com/User.Read`)
+fn validate_scope(scope: &str) -> Result<(), String> {
+  if scope.is_empty() || scope.chars().any(char::is_whitespace) {
+    return Err(format!("Scope '{}' must be non-empty and contain no spaces", scope));
+  }
+
+  match scope.rsplit_once('/') {
+    Some((resource, permission))
+      if !permission.is_empty() && resource.contains(':') && !resource.ends_with('/') =>
+    {
+      Ok(())
+    }
+    _ => Err(format!(
+      "Scope '{}' must include a resource, e.g. api://my-api/access",
+      scope
+    )),
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
+  use crate::config::CloudEnvironment;
 
   #[test]
   fn test_default_config() {
@@ -90,10 +315,144 @@ mod tests {
     assert!(config.tenant_id.is_none());
   }
 
+  #[test]
+  fn test_cache_ttls_fall_back_to_defaults() {
+    let config = UserConfig {
+      secret_value_ttl_secs: Some(30),
+      ..UserConfig::default()
+    };
+
+    let ttls = cache_ttls(&config);
+    assert_eq!(ttls.secret_values, Duration::from_secs(30));
+    assert_eq!(ttls.secrets_list, CacheTtls::default().secrets_list);
+  }
+
+  #[test]
+  fn test_cache_ttls_are_bounded() {
+    let huge = UserConfig {
+      secret_value_ttl_secs: Some(40_000_000_000),
+      ..UserConfig::default()
+    };
+    let zero = UserConfig {
+      keyvault_ttl_secs: Some(0),
+      ..UserConfig::default()
+    };
+
+    assert!(validate_cache_ttls(&UserConfig::default()).is_ok());
+    assert!(validate_cache_ttls(&huge).is_err());
+    assert!(validate_cache_ttls(&zero).is_err());
+    assert_eq!(
+      cache_ttls(&huge).secret_values,
+      Duration::from_secs(MAX_CACHE_TTL_SECS)
+    );
+    assert_eq!(cache_ttls(&zero).keyvaults, Duration::from_secs(1));
+  }
+
   #[test]
   fn test_effective_values() {
     // When None, should use defaults
     assert_eq!(VAULTRAIDER_CLIENT_ID.len(), 36); // GUID length
     assert_eq!(MULTI_TENANT_ENDPOINT, "organizations");
   }
+
+  #[test]
+  fn test_concurrency_bounds_are_validated() {
+    let bounds = |min, max| UserConfig {
+      adaptive_concurrency_min: min,
+      adaptive_concurrency_max: max,
+      ..UserConfig::default()
+    };
+
+    assert!(validate_concurrency_bounds(&UserConfig::default()).is_ok());
+    assert!(validate_concurrency_bounds(&bounds(1, 1)).is_ok());
+    assert!(validate_concurrency_bounds(&bounds(0, 8)).is_err());
+    assert!(validate_concurrency_bounds(&bounds(16, 8)).is_err());
+    assert!(validate_concurrency_bounds(&bounds(1, MAX_ADAPTIVE_CONCURRENCY + 1)).is_err());
+  }
+
+  #[test]
+  fn test_concurrency_bounds_default_when_missing() {
+    let config: UserConfig = serde_json::from_str("{}").unwrap();
+    assert_eq!(config.adaptive_concurrency_min, ADAPTIVE_CONCURRENCY_MIN);
+    assert_eq!(config.adaptive_concurrency_max, ADAPTIVE_CONCURRENCY_MAX);
+  }
+
+  #[test]
+  fn test_resolve_authority_host_precedence() {
+    let gov = "https://login.microsoftonline.us";
+    let china = "https://login.chinacloudapi.cn";
+    let public = CloudEnvironment::Public.authority_host();
+
+    // Explicit config wins over the environment
+    assert_eq!(resolve_authority_host(Some(gov), Some(china), public), gov);
+    // Environment wins over the default
+    assert_eq!(resolve_authority_host(None, Some(china), public), china);
+    // Default when neither is set
+    assert_eq!(resolve_authority_host(None, None, public), public);
+  }
+
+  #[test]
+  fn test_resolve_authority_host_follows_cloud() {
+    let gov = CloudEnvironment::UsGov.authority_host();
+    assert_eq!(
+      resolve_authority_host(None, None, gov),
+      "https://login.microsoftonline.us"
+    );
+  }
+
+  #[test]
+  fn test_resolve_authority_host_normalizes() {
+    let public = CloudEnvironment::Public.authority_host();
+
+    // Blank values fall through to the next source
+    assert_eq!(
+      resolve_authority_host(Some("  "), Some("https://login.microsoftonline.us/"), public),
+      "https://login.microsoftonline.us"
+    );
+    assert_eq!(resolve_authority_host(None, Some(""), public), public);
+    assert_eq!(
+      resolve_authority_host(None, Some("login.chinacloudapi.cn"), public),
+      "https://login.chinacloudapi.cn"
+    );
+    // A bare host name in config.json is normalized the same way
+    assert_eq!(
+      resolve_authority_host(Some("login.microsoftonline.us/"), None, public),
+      "https://login.microsoftonline.us"
+    );
+    // Invalid values fall through too
+    assert_eq!(
+      resolve_authority_host(Some("http://login.microsoftonline.us"), None, public),
+      public
+    );
+  }
+
+  #[test]
+  fn test_normalize_authority_host() {
+    assert_eq!(
+      normalize_authority_host(" https://login.microsoftonline.us/ ").unwrap().as_deref(),
+      Some("https://login.microsoftonline.us")
+    );
+    assert_eq!(
+      normalize_authority_host("login.microsoftonline.us").unwrap().as_deref(),
+      Some("https://login.microsoftonline.us")
+    );
+    assert_eq!(normalize_authority_host("  ").unwrap(), None);
+
+    assert!(normalize_authority_host("http://login.microsoftonline.us").is_err());
+    assert!(normalize_authority_host("https://").is_err());
+    assert!(normalize_authority_host("login microsoftonline us").is_err());
+  }
+
+  #[test]
+  fn test_validate_scope() {
+    assert!(validate_scope("api://my-api/access").is_ok());
+    assert!(validate_scope("https://graph.microsoft.com/User.Read").is_ok());
+    assert!(validate_scope("api://a5b1c2d3-0000-0000-0000-000000000000/.default").is_ok());
+
+    assert!(validate_scope("").is_err());
+    assert!(validate_scope("User.Read").is_err());
+    assert!(validate_scope("api://my-api/").is_err());
+    assert!(validate_scope("https:///access").is_err());
+    assert!(validate_scope("api://my api/access").is_err());
+  }
 }
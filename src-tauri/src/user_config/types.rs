@@ -1,8 +1,15 @@
-﻿use serde::{Deserialize, Serialize};
+﻿use crate::config::{
+  CloudEnvironment, ADAPTIVE_CONCURRENCY_MAX, ADAPTIVE_CONCURRENCY_MIN, DEFAULT_HTTP_TIMEOUT_SECS,
+};
+use crate::user_config::constants::CONFIG_SCHEMA_VERSION;
+use serde::{Deserialize, Serialize};
 
 /// User configuration structure
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UserConfig {
+  /// Config file schema version (missing in files written before versioning, i.e. v0)
+  #[serde(default)]
+  pub schema_version: u32,
   /// Azure AD App Registration Client ID (optional - uses VaultRaider's app if not set)
   #[serde(default)]
   pub client_id: Option<String>,
@@ -12,14 +19,88 @@ pub struct UserConfig {
   /// Auto-login on app startup (default: false)
   #[serde(default)]
   pub auto_login: bool,
+  /// Additional OAuth scopes requested during the device-code login (advanced)
+  #[serde(default)]
+  pub extra_scopes: Vec<String>,
+  /// Azure AD login host, e.g. `https://login.microsoftonline.us` or `login.microsoftonline.us`
+  /// (optional - the cloud environment's login host if not set)
+  #[serde(default)]
+  pub authority_host: Option<String>,
+  /// Refresh near-expired vault and secret lists in the background instead of on the next access (default: false)
+  #[serde(default)]
+  pub cache_refresh_ahead: bool,
+  /// Block every command that changes anything in Azure, for read-only reviews (default: false)
+  #[serde(default, alias = "read_only_mode")]
+  pub read_only: bool,
+  /// Client ID of a user-assigned managed identity (optional - system-assigned identity if not set)
+  #[serde(default)]
+  pub managed_identity_client_id: Option<String>,
+  /// Seconds before an Azure API request times out (default: 30)
+  #[serde(default = "default_http_timeout_secs")]
+  pub http_timeout_secs: u64,
+  /// Fewest concurrent Azure requests when Azure throttles (default: 2)
+  #[serde(default = "default_adaptive_concurrency_min")]
+  pub adaptive_concurrency_min: usize,
+  /// Most concurrent Azure requests, and where the adaptive limit starts (default: 64)
+  #[serde(default = "default_adaptive_concurrency_max")]
+  pub adaptive_concurrency_max: usize,
+  /// Azure cloud to sign in to: public, US Government or China (default: public)
+  #[serde(default)]
+  pub cloud_environment: CloudEnvironment,
+  /// Seconds subscriptions stay cached (optional - built-in default if not set)
+  #[serde(default)]
+  pub subscription_ttl_secs: Option<u64>,
+  /// Seconds resource groups stay cached (optional - built-in default if not set)
+  #[serde(default)]
+  pub resource_group_ttl_secs: Option<u64>,
+  /// Seconds vault lists stay cached (optional - built-in default if not set)
+  #[serde(default)]
+  pub keyvault_ttl_secs: Option<u64>,
+  /// Seconds secret lists stay cached (optional - built-in default if not set)
+  #[serde(default)]
+  pub secrets_list_ttl_secs: Option<u64>,
+  /// Seconds key lists stay cached (optional - built-in default if not set)
+  #[serde(default)]
+  pub keys_list_ttl_secs: Option<u64>,
+  /// Seconds secret values stay cached (optional - built-in default if not set)
+  #[serde(default)]
+  pub secret_value_ttl_secs: Option<u64>,
+}
+
+fn default_http_timeout_secs() -> u64 {
+  DEFAULT_HTTP_TIMEOUT_SECS
+}
+
+fn default_adaptive_concurrency_min() -> usize {
+  ADAPTIVE_CONCURRENCY_MIN
+}
+
+fn default_adaptive_concurrency_max() -> usize {
+  ADAPTIVE_CONCURRENCY_MAX
 }
 
 impl Default for UserConfig {
   fn default() -> Self {
     Self {
+      schema_version: CONFIG_SCHEMA_VERSION,
       client_id: None,
       tenant_id: None,
       auto_login: false,
+      extra_scopes: Vec::new(),
+      authority_host: None,
+      cache_refresh_ahead: false,
+      read_only: false,
+      managed_identity_client_id: None,
+      http_timeout_secs: DEFAULT_HTTP_TIMEOUT_SECS,
+      adaptive_concurrency_min: ADAPTIVE_CONCURRENCY_MIN,
+      adaptive_concurrency_max: ADAPTIVE_CONCURRENCY_MAX,
+      cloud_environment: CloudEnvironment::Public,
+      subscription_ttl_secs: None,
+      resource_group_ttl_secs: None,
+      keyvault_ttl_secs: None,
+      secrets_list_ttl_secs: None,
+      keys_list_ttl_secs: None,
+      secret_value_ttl_secs: None,
     }
   }
 }
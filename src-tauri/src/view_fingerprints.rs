@@ -0,0 +1,258 @@
+//! Change detection for viewed secrets
+//!
+//! Remembers a fingerprint of each secret's value as it was last viewed, so
+//! the UI can flag secrets that changed since. Fingerprints are Argon2id
+//! digests with a per-install salt, persisted in the app data directory;
+//! secret values are never stored. Argon2id is deliberately slow, so short
+//! values can't be brute-forced from the file.
+
+use argon2::Argon2;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+use crate::user_config::constants::APP_NAME;
+
+/// Fingerprint store file name
+const FINGERPRINTS_FILE_NAME: &str = "viewed_fingerprints.json";
+
+/// Store format version; version 2 is Argon2id, older stores are discarded
+const FINGERPRINTS_VERSION: u32 = 2;
+
+/// Length of a fingerprint in bytes
+const FINGERPRINT_LENGTH: usize = 32;
+
+/// Last viewed value fingerprints, keyed by vault URI and secret name
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct FingerprintStore {
+    #[serde(default)]
+    version: u32,
+    /// Random per-install salt, so fingerprints can't be matched against
+    /// digests of guessed values computed elsewhere
+    salt: String,
+    fingerprints: HashMap<String, String>,
+    /// Bumped on every change, so an older save never overwrites a newer one
+    #[serde(skip)]
+    generation: u64,
+}
+
+impl FingerprintStore {
+    fn new() -> Self {
+        Self {
+            version: FINGERPRINTS_VERSION,
+            salt: uuid::Uuid::new_v4().to_string(),
+            fingerprints: HashMap::new(),
+            generation: 0,
+        }
+    }
+
+    /// Store `fingerprint` and report whether it differs from the previous one
+    ///
+    /// The first view of a secret is not reported as a change.
+    fn record(&mut self, key: String, fingerprint: String) -> bool {
+        self.generation += 1;
+        match self.fingerprints.insert(key, fingerprint.clone()) {
+            Some(previous) => previous != fingerprint,
+            None => false,
+        }
+    }
+}
+
+/// Fingerprint of `value`: Argon2id (default parameters) of the value with `salt`
+fn fingerprint(salt: &str, value: &str) -> Result<String, String> {
+    let mut digest = [0u8; FINGERPRINT_LENGTH];
+    Argon2::default()
+        .hash_password_into(value.as_bytes(), salt.as_bytes(), &mut digest)
+        .map_err(|e| format!("Failed to fingerprint value: {}", e))?;
+    Ok(digest.iter().map(|byte| format!("{:02x}", byte)).collect())
+}
+
+lazy_static::lazy_static! {
+    /// Loaded from disk on first use
+    static ref FINGERPRINTS: Arc<Mutex<Option<FingerprintStore>>> = Arc::new(Mutex::new(None));
+
+    /// Generation of the store last written to disk
+    static ref SAVED_GENERATION: std::sync::Mutex<u64> = std::sync::Mutex::new(0);
+}
+
+/// Get the fingerprint store file path
+fn get_fingerprints_path() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join(APP_NAME).join(FINGERPRINTS_FILE_NAME))
+}
+
+/// Build the store key for a secret, ignoring trailing slashes and name casing
+fn secret_key(vault_uri: &str, secret_name: &str) -> String {
+    format!("{}/{}", vault_uri.trim_end_matches('/'), secret_name.to_lowercase())
+}
+
+/// Record that a secret's value was viewed.
+///
+/// The fingerprint is computed and the store written on the blocking pool,
+/// without holding the store lock.
+///
+/// # Returns
+///
+/// Whether the value changed since the secret was last viewed.
+/// Storage failures are logged and never fail the view.
+pub async fn record_value_view(vault_uri: &str, secret_name: &str, value: &str) -> bool {
+    let path = get_fingerprints_path();
+
+    let salt = {
+        let mut guard = FINGERPRINTS.lock().await;
+        let store = guard.get_or_insert_with(|| {
+            path.as_deref()
+                .and_then(load_store)
+                .unwrap_or_else(FingerprintStore::new)
+        });
+        store.salt.clone()
+    };
+
+    let value = value.to_string();
+    let fingerprint = match tokio::task::spawn_blocking(move || fingerprint(&salt, &value)).await {
+        Ok(Ok(fingerprint)) => fingerprint,
+        Ok(Err(e)) => {
+            log::error!("{}", e);
+            return false;
+        }
+        Err(e) => {
+            log::error!("Fingerprint task failed: {}", e);
+            return false;
+        }
+    };
+
+    let (changed, generation, content) = {
+        let mut guard = FINGERPRINTS.lock().await;
+        let Some(store) = guard.as_mut() else {
+            return false;
+        };
+        let changed = store.record(secret_key(vault_uri, secret_name), fingerprint);
+        (changed, store.generation, serde_json::to_string(store))
+    };
+
+    let Some(path) = path else {
+        log::warn!("Could not determine app data directory, fingerprints not saved");
+        return changed;
+    };
+    let content = match content {
+        Ok(content) => content,
+        Err(e) => {
+            log::error!("Failed to serialize viewed secret fingerprints: {}", e);
+            return changed;
+        }
+    };
+
+    let saved = tokio::task::spawn_blocking(move || {
+        let mut saved_generation = SAVED_GENERATION.lock().unwrap_or_else(|e| e.into_inner());
+        // A later view already wrote a newer store
+        if generation <= *saved_generation {
+            return Ok(());
+        }
+        save_store(&path, &content)?;
+        *saved_generation = generation;
+        Ok::<(), String>(())
+    })
+    .await;
+    match saved {
+        Ok(Ok(())) => {}
+        Ok(Err(e)) => log::error!("Failed to save viewed secret fingerprints: {}", e),
+        Err(e) => log::error!("Failed to save viewed secret fingerprints: {}", e),
+    }
+
+    changed
+}
+
+/// Load the store, or `None` if it doesn't exist, can't be read or has an older format
+fn load_store(path: &Path) -> Option<FingerprintStore> {
+    let content = fs::read_to_string(path).ok()?;
+    match serde_json::from_str::<FingerprintStore>(&content) {
+        Ok(store) if store.version == FINGERPRINTS_VERSION => Some(store),
+        Ok(store) => {
+            log::info!(
+                "Discarding viewed secret fingerprints in old format version {}",
+                store.version
+            );
+            None
+        }
+        Err(e) => {
+            log::warn!("Ignoring unreadable viewed secret fingerprints: {}", e);
+            None
+        }
+    }
+}
+
+fn save_store(path: &Path, content: &str) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create directory: {}", e))?;
+    }
+    fs::write(path, content).map_err(|e| format!("Failed to write file: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sha2::{Digest, Sha256};
+
+    fn record(store: &mut FingerprintStore, key: String, value: &str) -> bool {
+        let fingerprint = fingerprint(&store.salt, value).unwrap();
+        store.record(key, fingerprint)
+    }
+
+    #[test]
+    fn test_record_detects_changes() {
+        let mut store = FingerprintStore::new();
+        let key = secret_key("https://kv.vault.azure.net/", "Db-Password");
+
+        assert!(!record(&mut store, key.clone(), "first"), "first view is not a change");
+        assert!(!record(&mut store, key.clone(), "first"));
+        assert!(record(&mut store, key.clone(), "second"));
+        let same_secret = secret_key("https://kv.vault.azure.net", "db-password");
+        assert!(!record(&mut store, same_secret, "second"));
+    }
+
+    #[test]
+    fn test_fingerprints_are_salted_and_never_plaintext() {
+        let mut store = FingerprintStore::new();
+        record(&mut store, secret_key("https://kv.vault.azure.net", "db-password"), "hunter2");
+
+        let unsalted = format!("{:x}", Sha256::digest(b"hunter2"));
+        let serialized = serde_json::to_string(&store).unwrap();
+        assert!(!serialized.contains("hunter2"));
+        assert!(!serialized.contains(&unsalted));
+        assert_ne!(
+            fingerprint(&FingerprintStore::new().salt, "hunter2").unwrap(),
+            fingerprint(&store.salt, "hunter2").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_store_roundtrip() {
+        let path = std::env::temp_dir()
+            .join(format!("vaultraider-test-{}", uuid::Uuid::new_v4()))
+            .join(FINGERPRINTS_FILE_NAME);
+
+        let mut store = FingerprintStore::new();
+        record(&mut store, secret_key("https://kv.vault.azure.net", "db-password"), "first");
+        save_store(&path, &serde_json::to_string(&store).unwrap()).unwrap();
+
+        let mut loaded = load_store(&path).unwrap();
+        let key = secret_key("https://kv.vault.azure.net", "db-password");
+        assert!(record(&mut loaded, key, "second"));
+
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_old_sha256_store_is_discarded() {
+        let path = std::env::temp_dir()
+            .join(format!("vaultraider-test-{}", uuid::Uuid::new_v4()))
+            .join(FINGERPRINTS_FILE_NAME);
+        save_store(&path, r#"{"salt":"s","fingerprints":{"https://kv/db":"abc"}}"#).unwrap();
+
+        assert!(load_store(&path).is_none());
+
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
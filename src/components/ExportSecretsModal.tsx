@@ -6,7 +6,17 @@ import { exportSecrets } from "../services/azureService";
 import type { Secret } from "../types/secrets";
 import { Button, FileSaveSelector, IconButton, Modal, ModalFooter } from "./common";
 
-type ExportFormat = "full" | "simple" | "keyValue" | "dotenv";
+type ExportFormat = "full" | "simple" | "keyValue" | "dotenv" | "yaml" | "csv" | "encrypted";
+
+const exportFormats: ExportFormat[] = [
+  "full",
+  "simple",
+  "keyValue",
+  "dotenv",
+  "yaml",
+  "csv",
+  "encrypted",
+];
 
 interface ExportOptions {
   includeName: boolean;
@@ -16,6 +26,7 @@ interface ExportOptions {
   includeCreated: boolean;
   includeUpdated: boolean;
   includeRecoveryLevel: boolean;
+  includeTags: boolean;
 }
 
 interface ExportSecretsModalProps {
@@ -34,6 +45,7 @@ const defaultOptions: ExportOptions = {
   includeCreated: false,
   includeUpdated: false,
   includeRecoveryLevel: false,
+  includeTags: false,
 };
 
 export function ExportSecretsModal({
@@ -47,6 +59,7 @@ export function ExportSecretsModal({
   const [options, setOptions] = useState<ExportOptions>(defaultOptions);
   const [isExporting, setIsExporting] = useState(false);
   const [savePath, setSavePath] = useState<string | null>(null);
+  const [passphrase, setPassphrase] = useState("");
   const { showSuccess, showError } = useToast();
 
   const handleOptionChange = (key: keyof ExportOptions) => {
@@ -58,6 +71,10 @@ export function ExportSecretsModal({
       showError("No location selected", "Please select a location to save the file");
       return;
     }
+    if (format === "encrypted" && !passphrase) {
+      showError("No passphrase", "Please enter a passphrase to encrypt the export with");
+      return;
+    }
 
     setIsExporting(true);
     try {
@@ -68,6 +85,8 @@ export function ExportSecretsModal({
         includeCreated: options.includeCreated,
         includeUpdated: options.includeUpdated,
         includeRecoveryLevel: options.includeRecoveryLevel,
+        includeTags: options.includeTags,
+        passphrase: format === "encrypted" ? passphrase : undefined,
       });
 
       await writeTextFile(savePath, exportContent);
@@ -85,6 +104,9 @@ export function ExportSecretsModal({
     simple: "Array of secrets with name and value only",
     keyValue: 'Simple key-value object { "secret-name": "value" }',
     dotenv: '.env file format (SECRET_NAME="value")',
+    yaml: "YAML mapping (secret-name: value), with metadata if selected",
+    csv: "Spreadsheet with name and value columns, plus selected attributes",
+    encrypted: "Full format encrypted with a passphrase (AES-256-GCM)",
   };
 
   const formatExamples: Record<ExportFormat, string> = {
@@ -107,6 +129,18 @@ export function ExportSecretsModal({
 }`,
     dotenv: `MY_SECRET="secret-value"
 ANOTHER_SECRET="another-value"`,
+    yaml: `my-secret: secret-value
+another-secret: another-value`,
+    csv: `name,value
+my-secret,secret-value
+another-secret,"value, with a comma"`,
+    encrypted: `{
+  "kind": "vaultraider-encrypted-export",
+  "version": 1,
+  "salt": "...",
+  "nonce": "...",
+  "ciphertext": "..."
+}`,
   };
 
   return (
@@ -136,7 +170,7 @@ ANOTHER_SECRET="another-value"`,
           Export Format
         </label>
         <div id="formatGrid" className="grid grid-cols-2 gap-3">
-          {(["full", "simple", "keyValue", "dotenv"] as ExportFormat[]).map((fmt) => (
+          {exportFormats.map((fmt) => (
             <button
               key={fmt}
               type="button"
@@ -148,7 +182,15 @@ ANOTHER_SECRET="another-value"`,
               }`}
             >
               <div className="font-medium text-sm text-gray-900 dark:text-gray-100 capitalize">
-                {fmt === "keyValue" ? "Key-Value" : fmt === "dotenv" ? ".env" : fmt}
+                {fmt === "keyValue"
+                  ? "Key-Value"
+                  : fmt === "dotenv"
+                    ? ".env"
+                    : fmt === "yaml"
+                      ? "YAML"
+                      : fmt === "csv"
+                        ? "CSV"
+                        : fmt}
               </div>
               <div className="text-xs text-gray-500 dark:text-gray-400 mt-1">
                 {formatDescriptions[fmt]}
@@ -174,8 +216,31 @@ ANOTHER_SECRET="another-value"`,
         </pre>
       </div>
 
+      {/* Passphrase for Encrypted Format */}
+      {format === "encrypted" && (
+        <div className="mb-6">
+          <label
+            htmlFor="exportPassphrase"
+            className="block text-sm font-medium text-gray-700 dark:text-gray-300 mb-2"
+          >
+            Passphrase
+          </label>
+          <input
+            id="exportPassphrase"
+            type="password"
+            value={passphrase}
+            onChange={(e) => setPassphrase(e.target.value)}
+            autoComplete="new-password"
+            className="w-full px-3 py-2 text-sm rounded-lg border border-gray-300 dark:border-gray-600 bg-white dark:bg-gray-700 text-gray-900 dark:text-gray-100"
+          />
+          <p className="text-xs text-gray-500 dark:text-gray-400 mt-1">
+            The passphrase can't be recovered; without it the export can't be imported.
+          </p>
+        </div>
+      )}
+
       {/* Options for Full Format */}
-      {format === "full" && (
+      {(format === "full" || format === "encrypted") && (
         <div className="mb-6">
           <label
             htmlFor="includeProperties"
@@ -239,6 +304,15 @@ ANOTHER_SECRET="another-value"`,
               />
               Recovery level
             </label>
+            <label className="flex items-center gap-2 text-sm text-gray-700 dark:text-gray-300">
+              <input
+                type="checkbox"
+                checked={options.includeTags}
+                onChange={() => handleOptionChange("includeTags")}
+                className="rounded border-gray-300 dark:border-gray-600"
+              />
+              Tags
+            </label>
           </div>
         </div>
       )}
@@ -257,6 +331,8 @@ ANOTHER_SECRET="another-value"`,
           defaultFileName={`${vaultName}-secrets-${new Date().toISOString().split("T")[0]}.json`}
           filters={[
             { name: "JSON", extensions: ["json"] },
+            { name: "YAML", extensions: ["yaml", "yml"] },
+            { name: "CSV", extensions: ["csv"] },
             { name: "Text", extensions: ["txt", "env"] },
           ]}
         />
@@ -279,7 +355,7 @@ ANOTHER_SECRET="another-value"`,
         <Button
           variant="primary"
           onClick={handleExport}
-          disabled={isExporting || !savePath}
+          disabled={isExporting || !savePath || (format === "encrypted" && !passphrase)}
           isLoading={isExporting}
           loadingText="Exporting..."
           leftIcon={<FileJsonIcon className="w-4 h-4" />}
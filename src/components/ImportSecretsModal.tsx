@@ -9,7 +9,12 @@ import {
 } from "lucide-react";
 import { useCallback, useState } from "react";
 import { useToast } from "../contexts/ToastContext";
-import { createSecret, parseImportFile, updateSecret } from "../services/azureService";
+import {
+  createSecret,
+  parseEncryptedImport,
+  parseImportFile,
+  updateSecret,
+} from "../services/azureService";
 import type { Secret } from "../types/secrets";
 import {
   ActionButton,
@@ -22,7 +27,28 @@ import {
   ProgressBar,
 } from "./common";
 
-type ImportFormat = "auto" | "full" | "simple" | "keyValue" | "dotenv";
+type ImportFormat =
+  | "auto"
+  | "full"
+  | "simple"
+  | "keyValue"
+  | "dotenv"
+  | "yaml"
+  | "csv"
+  | "k8s"
+  | "encrypted";
+
+const importFormats: ImportFormat[] = [
+  "auto",
+  "full",
+  "simple",
+  "keyValue",
+  "dotenv",
+  "yaml",
+  "csv",
+  "k8s",
+  "encrypted",
+];
 type ConflictResolution = "skip" | "override" | "ask";
 type SingleConflictAction = "skip" | "override";
 
@@ -60,6 +86,7 @@ export function ImportSecretsModal({
   const [conflictResolution, setConflictResolution] = useState<ConflictResolution>("ask");
   const [filePath, setFilePath] = useState<string | null>(null);
   const [fileContent, setFileContent] = useState<string | null>(null);
+  const [passphrase, setPassphrase] = useState("");
   const [conflicts, setConflicts] = useState<ConflictInfo[]>([]);
   const [newSecrets, setNewSecrets] = useState<ImportedSecret[]>([]);
   const [step, setStep] = useState<ImportStep>("select");
@@ -73,6 +100,7 @@ export function ImportSecretsModal({
     setConflictResolution("ask");
     setFilePath(null);
     setFileContent(null);
+    setPassphrase("");
     setConflicts([]);
     setNewSecrets([]);
     setStep("select");
@@ -107,7 +135,10 @@ export function ImportSecretsModal({
 
     setIsLoading(true);
     try {
-      const parsed = await parseImportFile(fileContent, format === "auto" ? undefined : format);
+      const parsed =
+        format === "encrypted"
+          ? await parseEncryptedImport(fileContent, passphrase)
+          : await parseImportFile(fileContent, format === "auto" ? undefined : format);
 
       // Check for conflicts
       const existingNames = new Set(
@@ -233,6 +264,10 @@ export function ImportSecretsModal({
     simple: "Array of secrets with name and value",
     keyValue: 'Simple key-value object { "secret-name": "value" }',
     dotenv: '.env file format (SECRET_NAME="value")',
+    yaml: "YAML mapping (secret-name: value) or secrets list",
+    csv: "CSV with a header row and name and value columns",
+    k8s: "Kubernetes Secret manifest (YAML with base64 data)",
+    encrypted: "Passphrase-protected export",
   };
 
   return (
@@ -280,7 +315,7 @@ export function ImportSecretsModal({
               Import Format
             </label>
             <div id="importFormat" className="grid grid-cols-2 gap-3">
-              {(["auto", "full", "simple", "keyValue", "dotenv"] as ImportFormat[]).map((fmt) => (
+              {importFormats.map((fmt) => (
                 <button
                   key={fmt}
                   type="button"
@@ -296,9 +331,15 @@ export function ImportSecretsModal({
                       ? "Key-Value"
                       : fmt === "dotenv"
                         ? ".env"
-                        : fmt === "auto"
-                          ? "Auto-detect"
-                          : fmt}
+                        : fmt === "yaml"
+                          ? "YAML"
+                          : fmt === "csv"
+                            ? "CSV"
+                            : fmt === "k8s"
+                              ? "Kubernetes Secret"
+                              : fmt === "auto"
+                                ? "Auto-detect"
+                                : fmt}
                   </div>
                   <div className="text-xs text-gray-500 dark:text-gray-400 mt-1">
                     {formatDescriptions[fmt]}
@@ -308,6 +349,26 @@ export function ImportSecretsModal({
             </div>
           </div>
 
+          {/* Passphrase for Encrypted Format */}
+          {format === "encrypted" && (
+            <div className="mb-6">
+              <label
+                htmlFor="importPassphrase"
+                className="block text-sm font-medium text-gray-700 dark:text-gray-300 mb-2"
+              >
+                Passphrase
+              </label>
+              <input
+                id="importPassphrase"
+                type="password"
+                value={passphrase}
+                onChange={(e) => setPassphrase(e.target.value)}
+                autoComplete="off"
+                className="w-full px-3 py-2 text-sm rounded-lg border border-gray-300 dark:border-gray-600 bg-white dark:bg-gray-700 text-gray-900 dark:text-gray-100"
+              />
+            </div>
+          )}
+
           {/* Conflict Resolution */}
           <div className="mb-6">
             <label
@@ -382,7 +443,7 @@ export function ImportSecretsModal({
             <Button
               variant="success"
               onClick={handleParseFile}
-              disabled={isLoading || !filePath}
+              disabled={isLoading || !filePath || (format === "encrypted" && !passphrase)}
               isLoading={isLoading}
               loadingText="Parsing..."
               leftIcon={<FileUpIcon className="w-4 h-4" />}
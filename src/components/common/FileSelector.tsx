@@ -31,6 +31,7 @@ export function FileOpenSelector({
         filters: [
           { name: "JSON Files", extensions: ["json"] },
           { name: "Environment Files", extensions: ["env"] },
+          { name: "CSV Files", extensions: ["csv"] },
           { name: "Text Files", extensions: ["txt"] },
           { name: "All Files", extensions: ["*"] },
         ],
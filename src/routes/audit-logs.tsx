@@ -362,11 +362,11 @@ const EventRow = memo(function EventRow({
 				</td>
 				<td className="px-4 py-3">
 					<div className="text-sm text-gray-600 dark:text-gray-400">
-						{formatTimestamp(event.eventTimestamp)}
+						{formatTimestamp(event.eventTime ?? event.eventTimestamp)}
 					</div>
-					{formatRelativeTime(event.eventTimestamp) && (
+					{formatRelativeTime(event.eventTime ?? event.eventTimestamp) && (
 						<div className="text-xs text-gray-400 dark:text-gray-500">
-							{formatRelativeTime(event.eventTimestamp)}
+							{formatRelativeTime(event.eventTime ?? event.eventTimestamp)}
 						</div>
 					)}
 				</td>
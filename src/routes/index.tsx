@@ -32,7 +32,9 @@ function Index() {
   const [isLoading, setIsLoading] = useState(false);
   const [message, setMessage] = useState("");
   const [deviceCodeInfo, setDeviceCodeInfo] = useState<DeviceCodeInfo | null>(null);
-  const [selectedMethod, setSelectedMethod] = useState<"cli" | "browser" | null>(null);
+  const [selectedMethod, setSelectedMethod] = useState<"cli" | "browser" | "redirect" | null>(
+    null,
+  );
 
   // Redirect to subscriptions if already authenticated
   useEffect(() => {
@@ -88,6 +90,23 @@ function Index() {
     }
   }
 
+  async function handleRedirectLogin() {
+    setSelectedMethod("redirect");
+    setIsLoading(true);
+    setMessage("Opening your browser...");
+
+    try {
+      // Opens the system browser and waits for the redirect to localhost
+      await invoke("start_browser_login", { useRedirect: true });
+      setMessage("Please complete the sign-in in your browser");
+      pollForDeviceCodeCompletion();
+    } catch (error) {
+      console.error("Error starting browser redirect login:", error);
+      setMessage(`Error: ${error}`);
+      setIsLoading(false);
+    }
+  }
+
   async function pollForDeviceCodeCompletion() {
     try {
       // This will poll automatically in the backend
@@ -130,6 +149,11 @@ function Index() {
         setMessage(
           "Still waiting for authentication. Please complete the sign-in in your browser.",
         );
+      } else if (errorMessage.includes("sign-in code expired")) {
+        // The backend already cleared the stale flow, so request a fresh code
+        // once this attempt has finished cleaning up
+        setMessage("Your sign-in code expired. Requesting a new one...");
+        setTimeout(handleBrowserLogin, 0);
       } else if (errorMessage.includes("expired_token") || errorMessage.includes("timed out")) {
         setMessage("Authentication timed out. Please try again.");
       } else {
@@ -308,6 +332,16 @@ function Index() {
                   >
                     Sign in with Browser
                   </Button>
+                  <Button
+                    variant="secondary"
+                    className="w-full mt-2"
+                    onClick={handleRedirectLogin}
+                    disabled={isLoading}
+                    isLoading={isLoading && selectedMethod === "redirect"}
+                    loadingText="Waiting for browser..."
+                  >
+                    Sign in with Browser Redirect
+                  </Button>
                 </div>
               </div>
             )}
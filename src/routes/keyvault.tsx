@@ -159,9 +159,9 @@ function Keyvaults() {
       if (!subscriptionId) throw new Error("Subscription ID is required");
       return deleteKeyvault(subscriptionId, resourceGroup, name);
     },
-    onSuccess: () => {
+    onSuccess: (deletion) => {
       setShowDeleteVaultModal(false);
-      showSuccess(`Key Vault "${name}" deleted successfully`);
+      showSuccess(`Key Vault "${name}" deleted successfully`, deletion.message, 8000);
       // Navigate back to subscriptions page
       navigate({
         to: "/subscriptions",
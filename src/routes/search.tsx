@@ -24,6 +24,8 @@ import {
   fetchSubscriptions,
   fetchSubscriptionsKey,
   globalSearchSecrets,
+  type SearchMode,
+  type SearchType,
 } from "../services/azureService";
 import { copyToClipboardWithToast } from "../utils/clipboard";
 import { requireAuth } from "../utils/routeGuards";
@@ -57,7 +59,7 @@ interface SearchResult {
   vaultUri: string;
   subscriptionId: string;
   subscriptionName: string;
-  matchType: "key" | "value" | "both";
+  matchType: SearchType;
   secretValue?: string;
   attributes: Secret["attributes"];
 }
@@ -70,7 +72,9 @@ function GlobalSearch() {
 
   // Search state
   const [searchQuery, setSearchQuery] = useState(urlQuery || "");
-  const [searchType, setSearchType] = useState<"key" | "value" | "both">("key");
+  const [searchType, setSearchType] = useState<SearchType>("key");
+  const [searchMode, setSearchMode] = useState<SearchMode>("substring");
+  const [caseSensitive, setCaseSensitive] = useState(false);
   const [selectedSubscriptions, setSelectedSubscriptions] = useState<Set<string>>(
     new Set(subscriptions.map((s) => s.subscriptionId)),
   );
@@ -255,11 +259,17 @@ function GlobalSearch() {
           try {
             // Call backend for single keyvault
             const results = await globalSearchSecrets({
-              vaultUris: [kv.properties.vaultUri],
-              vaultNames: [kv.name],
-              subscriptionIds: [kv.subscriptionId],
+              vaults: [
+                {
+                  uri: kv.properties.vaultUri,
+                  name: kv.name,
+                  subscriptionId: kv.subscriptionId,
+                },
+              ],
               query: searchQuery,
               searchType: searchType,
+              mode: searchMode,
+              caseSensitive,
             });
 
             // Convert backend results to frontend format
@@ -270,13 +280,17 @@ function GlobalSearch() {
               vaultUri: result.vaultUri,
               subscriptionId: result.subscriptionId,
               subscriptionName: kv.subscriptionName,
-              matchType: result.matchType as "key" | "value" | "both",
+              matchType: result.matchType as SearchType,
               secretValue: result.secretValue,
               attributes: result.attributes,
             }));
 
             return formattedResults;
           } catch (error) {
+            // An invalid pattern fails every vault the same way, so stop the search
+            if (String(error).startsWith("Invalid regular expression")) {
+              throw error;
+            }
             console.error(`Error searching vault ${kv.name}:`, error);
             return [];
           } finally {
@@ -306,7 +320,15 @@ function GlobalSearch() {
     } finally {
       setIsSearching(false);
     }
-  }, [searchQuery, searchType, filteredKeyvaults, selectedKeyvaults, showError]);
+  }, [
+    searchQuery,
+    searchType,
+    searchMode,
+    caseSensitive,
+    filteredKeyvaults,
+    selectedKeyvaults,
+    showError,
+  ]);
 
   // Handle enter key in search input
   const handleKeyPress = useCallback(
@@ -415,7 +437,7 @@ function GlobalSearch() {
                     name="searchType"
                     value="key"
                     checked={searchType === "key"}
-                    onChange={(e) => setSearchType(e.target.value as "key" | "value" | "both")}
+                    onChange={(e) => setSearchType(e.target.value as SearchType)}
                     className="w-4 h-4 text-primary-600 focus:ring-primary-500"
                   />
                   <span className="text-sm text-gray-700 dark:text-gray-300">Secret Names</span>
@@ -426,7 +448,7 @@ function GlobalSearch() {
                     name="searchType"
                     value="value"
                     checked={searchType === "value"}
-                    onChange={(e) => setSearchType(e.target.value as "key" | "value" | "both")}
+                    onChange={(e) => setSearchType(e.target.value as SearchType)}
                     className="w-4 h-4 text-primary-600 focus:ring-primary-500"
                   />
                   <span className="text-sm text-gray-700 dark:text-gray-300">Secret Values</span>
@@ -437,11 +459,40 @@ function GlobalSearch() {
                     name="searchType"
                     value="both"
                     checked={searchType === "both"}
-                    onChange={(e) => setSearchType(e.target.value as "key" | "value" | "both")}
+                    onChange={(e) => setSearchType(e.target.value as SearchType)}
                     className="w-4 h-4 text-primary-600 focus:ring-primary-500"
                   />
                   <span className="text-sm text-gray-700 dark:text-gray-300">Both</span>
                 </label>
+                <label className="flex items-center gap-2 cursor-pointer">
+                  <input
+                    type="radio"
+                    name="searchType"
+                    value="tag"
+                    checked={searchType === "tag"}
+                    onChange={(e) => setSearchType(e.target.value as SearchType)}
+                    className="w-4 h-4 text-primary-600 focus:ring-primary-500"
+                  />
+                  <span className="text-sm text-gray-700 dark:text-gray-300">Tags</span>
+                </label>
+                <label className="flex items-center gap-2 cursor-pointer">
+                  <input
+                    type="checkbox"
+                    checked={searchMode === "regex"}
+                    onChange={(e) => setSearchMode(e.target.checked ? "regex" : "substring")}
+                    className="w-4 h-4 text-primary-600 focus:ring-primary-500"
+                  />
+                  <span className="text-sm text-gray-700 dark:text-gray-300">Regex</span>
+                </label>
+                <label className="flex items-center gap-2 cursor-pointer">
+                  <input
+                    type="checkbox"
+                    checked={caseSensitive}
+                    onChange={(e) => setCaseSensitive(e.target.checked)}
+                    className="w-4 h-4 text-primary-600 focus:ring-primary-500"
+                  />
+                  <span className="text-sm text-gray-700 dark:text-gray-300">Match case</span>
+                </label>
               </div>
             </div>
           </div>
@@ -662,14 +713,18 @@ function GlobalSearch() {
                                 ? "bg-blue-100 text-blue-800 dark:bg-blue-900/30 dark:text-blue-400"
                                 : result.matchType === "value"
                                   ? "bg-green-100 text-green-800 dark:bg-green-900/30 dark:text-green-400"
-                                  : "bg-purple-100 text-purple-800 dark:bg-purple-900/30 dark:text-purple-400"
+                                  : result.matchType === "tag"
+                                    ? "bg-amber-100 text-amber-800 dark:bg-amber-900/30 dark:text-amber-400"
+                                    : "bg-purple-100 text-purple-800 dark:bg-purple-900/30 dark:text-purple-400"
                             }`}
                           >
                             {result.matchType === "key"
                               ? "Name match"
                               : result.matchType === "value"
                                 ? "Value match"
-                                : "Both match"}
+                                : result.matchType === "tag"
+                                  ? "Tag match"
+                                  : "Both match"}
                           </span>
                         </div>
 
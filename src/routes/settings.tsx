@@ -19,12 +19,20 @@ interface CacheStats {
   resourceGroupsCount: number;
   secretsListCount: number;
   secretValuesCount: number;
+  keysListCount: number;
+  secretValueHits: number;
+  secretValueMisses: number;
+  hitRatio: number | null;
 }
 
 async function getCacheStats(): Promise<CacheStats> {
   return await invoke("get_cache_stats");
 }
 
+async function resetCacheStats(): Promise<CacheStats> {
+  return await invoke("reset_cache_stats");
+}
+
 async function clearCache(): Promise<string> {
   return await invoke("clear_cache");
 }
@@ -86,6 +94,16 @@ function SettingsPage() {
     }
   };
 
+  const handleResetStats = async () => {
+    try {
+      await resetCacheStats();
+      await loadCacheStats();
+    } catch (error) {
+      console.error("Failed to reset cache stats:", error);
+      showError("Failed to reset cache statistics");
+    }
+  };
+
   const loadAutoLoginState = async () => {
     setIsLoadingAutoLogin(true);
     try {
@@ -134,21 +152,33 @@ function SettingsPage() {
                 <h3 className="text-sm font-medium text-gray-900 dark:text-gray-100">
                   Cache Statistics
                 </h3>
-                <Button
-                  variant="secondary"
-                  size="sm"
-                  onClick={loadCacheStats}
-                  disabled={isLoadingStats}
-                >
-                  {isLoadingStats ? (
-                    <>
-                      <Loader2 className="w-4 h-4 animate-spin" />
-                      Loading...
-                    </>
-                  ) : (
-                    "Load Stats"
+                <div className="flex items-center gap-2">
+                  {cacheStats && (
+                    <Button
+                      variant="secondary"
+                      size="sm"
+                      onClick={handleResetStats}
+                      disabled={isLoadingStats}
+                    >
+                      Reset Hit Counts
+                    </Button>
                   )}
-                </Button>
+                  <Button
+                    variant="secondary"
+                    size="sm"
+                    onClick={loadCacheStats}
+                    disabled={isLoadingStats}
+                  >
+                    {isLoadingStats ? (
+                      <>
+                        <Loader2 className="w-4 h-4 animate-spin" />
+                        Loading...
+                      </>
+                    ) : (
+                      "Load Stats"
+                    )}
+                  </Button>
+                </div>
               </div>
 
               {cacheStats && (
@@ -177,11 +207,22 @@ function SettingsPage() {
                       {cacheStats.secretsListCount}
                     </p>
                   </div>
-                  <div className="col-span-2 p-4 bg-gray-50 dark:bg-gray-900 rounded-lg">
+                  <div className="p-4 bg-gray-50 dark:bg-gray-900 rounded-lg">
                     <p className="text-xs text-gray-600 dark:text-gray-400">Secret Values</p>
                     <p className="text-2xl font-semibold text-gray-900 dark:text-gray-100 mt-1">
                       {cacheStats.secretValuesCount}
                     </p>
+                    <p className="text-xs text-gray-500 dark:text-gray-400 mt-1">
+                      {cacheStats.secretValueHits} hits, {cacheStats.secretValueMisses} misses
+                    </p>
+                  </div>
+                  <div className="p-4 bg-gray-50 dark:bg-gray-900 rounded-lg">
+                    <p className="text-xs text-gray-600 dark:text-gray-400">Hit Ratio</p>
+                    <p className="text-2xl font-semibold text-gray-900 dark:text-gray-100 mt-1">
+                      {cacheStats.hitRatio === null
+                        ? "–"
+                        : `${Math.round(cacheStats.hitRatio * 100)}%`}
+                    </p>
                   </div>
                 </div>
               )}
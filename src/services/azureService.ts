@@ -1,6 +1,8 @@
 import { invoke } from "@tauri-apps/api/core";
-import type { ActivityLogEvent } from "~/types/activityLog.ts";
-import type { KeyVault, KeyVaultAccess } from "~/types/keyvault.ts";
+import type { ActivityLogEvent, ActivityLogFilter } from "~/types/activityLog.ts";
+import type { ExpiringCertificates } from "~/types/certificates.ts";
+import type { Key, KeyBundle } from "~/types/keys.ts";
+import type { KeyVault, KeyVaultAccess, KeyVaultDeletion } from "~/types/keyvault.ts";
 import type { ResourceGroup } from "~/types/resourceGroups.ts";
 import type { DeletedSecretItem, Secret, SecretAttributes, SecretBundle } from "~/types/secrets.ts";
 import type { Subscription } from "~/types/subscriptions.ts";
@@ -69,9 +71,9 @@ export async function deleteKeyvault(
   subscriptionId: string,
   resourceGroup: string,
   keyvaultName: string,
-): Promise<void> {
+): Promise<KeyVaultDeletion> {
   try {
-    await invoke<void>("delete_keyvault", {
+    return await invoke<KeyVaultDeletion>("delete_keyvault", {
       subscriptionId,
       resourceGroup,
       keyvaultName,
@@ -106,6 +108,31 @@ export async function fetchSecrets(keyvaultUri: string): Promise<Secret[]> {
   }
 }
 
+export async function fetchKeys(keyvaultUri: string): Promise<Key[]> {
+  try {
+    return await invoke("get_keys", { keyvaultUri });
+  } catch (err) {
+    const errorMessage = err instanceof Error ? err.message : String(err);
+    console.error(`Failed to fetch keys for keyvault ${keyvaultUri}:`, errorMessage);
+    return [];
+  }
+}
+
+export async function getKey(
+  keyvaultUri: string,
+  keyName: string,
+  keyVersion?: string,
+): Promise<KeyBundle> {
+  return await invoke<KeyBundle>("get_key", { keyvaultUri, keyName, keyVersion });
+}
+
+export async function getExpiringCertificates(
+  vaultUris: string[],
+  days: number,
+): Promise<ExpiringCertificates> {
+  return await invoke<ExpiringCertificates>("get_expiring_certificates", { vaultUris, days });
+}
+
 // Global request queue for secret fetching (max 50 concurrent requests)
 const secretRequestQueue = new RequestQueue(50);
 
@@ -170,6 +197,89 @@ export async function fetchSecretVersions(
   }
 }
 
+/** What differs between two versions of a secret; values are never included */
+export interface SecretVersionDiff {
+  secretName: string;
+  versionA: string;
+  versionB: string;
+  valueChanged: boolean;
+  /** Differing fields, e.g. "enabled", "exp", "nbf", "contentType" or "tags" */
+  attributesChanged: string[];
+  aCreated: number;
+  bCreated: number;
+}
+
+export async function diffSecretVersions(
+  keyvaultUri: string,
+  secretName: string,
+  versionA: string,
+  versionB: string,
+): Promise<SecretVersionDiff> {
+  try {
+    return await invoke<SecretVersionDiff>("diff_secret_versions", {
+      keyvaultUri,
+      secretName,
+      versionA,
+      versionB,
+    });
+  } catch (err) {
+    const errorMessage = err instanceof Error ? err.message : String(err);
+    console.error(`Failed to diff versions of secret ${secretName}:`, errorMessage);
+    throw new Error(errorMessage);
+  }
+}
+
+export async function rollbackSecret(
+  keyvaultUri: string,
+  secretName: string,
+  targetVersion: string,
+): Promise<SecretBundle> {
+  try {
+    return await invoke<SecretBundle>("rollback_secret", {
+      keyvaultUri,
+      secretName,
+      targetVersion,
+    });
+  } catch (err) {
+    const errorMessage = err instanceof Error ? err.message : String(err);
+    console.error(`Failed to roll back secret ${secretName}:`, errorMessage);
+    throw new Error(errorMessage);
+  }
+}
+
+export interface GeneratorOptions {
+  length: number;
+  lowercase?: boolean;
+  uppercase?: boolean;
+  digits?: boolean;
+  symbols?: boolean;
+  excludeAmbiguous?: boolean;
+}
+
+export async function generateSecretValue(options: GeneratorOptions): Promise<string> {
+  try {
+    return await invoke<string>("generate_secret_value", { options });
+  } catch (err) {
+    const errorMessage = err instanceof Error ? err.message : String(err);
+    console.error("Failed to generate secret value:", errorMessage);
+    throw new Error(errorMessage);
+  }
+}
+
+export async function rotateSecret(
+  keyvaultUri: string,
+  secretName: string,
+  options: GeneratorOptions,
+): Promise<SecretBundle> {
+  try {
+    return await invoke<SecretBundle>("rotate_secret", { keyvaultUri, secretName, options });
+  } catch (err) {
+    const errorMessage = err instanceof Error ? err.message : String(err);
+    console.error(`Failed to rotate secret ${secretName}:`, errorMessage);
+    throw new Error(errorMessage);
+  }
+}
+
 export async function createSecret(
   keyvaultUri: string,
   secretName: string,
@@ -207,12 +317,15 @@ export async function updateSecret(
 }
 
 export interface ExportOptions {
-  format: "full" | "simple" | "keyValue" | "dotenv";
+  format: "full" | "simple" | "keyValue" | "dotenv" | "yaml" | "csv" | "encrypted";
   includeValue: boolean;
   includeEnabled: boolean;
   includeCreated: boolean;
   includeUpdated: boolean;
   includeRecoveryLevel: boolean;
+  includeTags?: boolean;
+  /** Required for the `encrypted` format */
+  passphrase?: string;
 }
 
 export async function exportSecrets(
@@ -226,12 +339,68 @@ export async function exportSecrets(
 export interface ImportedSecret {
   name: string;
   value: string;
+  /** Value wasn't valid UTF-8 and is kept base64-encoded */
+  binary?: boolean;
 }
 
 export async function parseImportFile(content: string, format?: string): Promise<ImportedSecret[]> {
   return await invoke<ImportedSecret[]>("parse_import_file", { content, format });
 }
 
+/** Decrypt an encrypted export; rejects with a clear message for a wrong passphrase */
+export async function parseEncryptedImport(
+  content: string,
+  passphrase: string,
+): Promise<ImportedSecret[]> {
+  return await invoke<ImportedSecret[]>("parse_encrypted_import", { content, passphrase });
+}
+
+export interface SecretImportResult {
+  name: string;
+  status: "created" | "updated" | "skipped" | "failed";
+  error: string | null;
+}
+
+/** What an import does with secrets that already exist in the vault */
+export type ConflictStrategy = "skip-existing" | "overwrite-existing" | "error-on-conflict";
+
+export interface PlannedImport {
+  name: string;
+  action: "create" | "update" | "skip";
+}
+
+export interface ImportPlan {
+  secrets: PlannedImport[];
+  creates: number;
+  updates: number;
+  skips: number;
+}
+
+/**
+ * Dry run of `importSecrets`: the action per secret, without writing anything.
+ * Rejects listing the conflicting names under "error-on-conflict".
+ */
+export async function planImport(
+  vaultUri: string,
+  secrets: ImportedSecret[],
+  strategy: ConflictStrategy,
+): Promise<ImportPlan> {
+  return await invoke<ImportPlan>("plan_import", { keyvaultUri: vaultUri, secrets, strategy });
+}
+
+/** Write parsed secrets concurrently, handling existing secrets per `strategy` */
+export async function importSecrets(
+  vaultUri: string,
+  secrets: ImportedSecret[],
+  strategy: ConflictStrategy,
+): Promise<SecretImportResult[]> {
+  return await invoke<SecretImportResult[]>("import_secrets", {
+    keyvaultUri: vaultUri,
+    secrets,
+    strategy,
+  });
+}
+
 // ============================================================================
 // Deleted Secret Operations
 // ============================================================================
@@ -275,16 +444,56 @@ export async function purgeDeletedSecret(keyvaultUri: string, secretName: string
   }
 }
 
+// ============================================================================
+// Backup and Restore Operations
+// ============================================================================
+
+/** Back up a secret with all its versions; returns an encrypted base64url blob */
+export async function backupSecret(keyvaultUri: string, secretName: string): Promise<string> {
+  try {
+    return await invoke<string>("backup_secret", { keyvaultUri, secretName });
+  } catch (err) {
+    const errorMessage = err instanceof Error ? err.message : String(err);
+    console.error(
+      `Failed to back up secret ${secretName} from keyvault ${keyvaultUri}:`,
+      errorMessage,
+    );
+    throw new Error(errorMessage);
+  }
+}
+
+export async function restoreSecret(keyvaultUri: string, blob: string): Promise<Secret> {
+  try {
+    return await invoke<Secret>("restore_secret", { keyvaultUri, blob });
+  } catch (err) {
+    const errorMessage = err instanceof Error ? err.message : String(err);
+    console.error(`Failed to restore secret into keyvault ${keyvaultUri}:`, errorMessage);
+    throw new Error(errorMessage);
+  }
+}
+
 // ============================================================================
 // Global Search Operations
 // ============================================================================
 
+export interface VaultRef {
+  uri: string;
+  name: string;
+  subscriptionId: string;
+}
+
+export type SearchType = "key" | "value" | "both" | "tag";
+
+export type SearchMode = "substring" | "regex";
+
 export interface GlobalSearchParams {
-  vaultUris: string[];
-  vaultNames: string[];
-  subscriptionIds: string[];
+  vaults: VaultRef[];
   query: string;
-  searchType: "key" | "value" | "both";
+  searchType: SearchType;
+  mode?: SearchMode;
+  caseSensitive?: boolean;
+  /** Lets the search be stopped with `cancelOperation`; it then returns the matches so far */
+  operationId?: string;
 }
 
 export interface GlobalSearchResult {
@@ -293,21 +502,41 @@ export interface GlobalSearchResult {
   vaultName: string;
   vaultUri: string;
   subscriptionId: string;
-  matchType: "key" | "value" | "both";
+  matchType: SearchType;
   secretValue?: string;
   attributes: SecretAttributes;
 }
 
+/** Payload of the `search-progress` event, emitted after each vault is searched */
+export interface SearchProgressEvent {
+  /** The `operationId` the search was started with */
+  operationId?: string;
+  vaultName: string;
+  processed: number;
+  total: number;
+  matchesSoFar: number;
+}
+
+/** Payload of the `search-result-batch` event: one vault's matches */
+export interface SearchResultBatchEvent {
+  /** The `operationId` the search was started with */
+  operationId?: string;
+  vaultName: string;
+  vaultUri: string;
+  results: GlobalSearchResult[];
+}
+
 export async function globalSearchSecrets(
   params: GlobalSearchParams,
 ): Promise<GlobalSearchResult[]> {
   try {
     return await invoke<GlobalSearchResult[]>("global_search_secrets", {
-      vaultUris: params.vaultUris,
-      vaultNames: params.vaultNames,
-      subscriptionIds: params.subscriptionIds,
+      vaults: params.vaults,
       query: params.query,
       searchType: params.searchType,
+      mode: params.mode,
+      caseSensitive: params.caseSensitive,
+      operationId: params.operationId,
     });
   } catch (err) {
     const errorMessage = err instanceof Error ? err.message : String(err);
@@ -316,6 +545,20 @@ export async function globalSearchSecrets(
   }
 }
 
+/** Operation id of the device code login, for `cancelOperation` */
+export const DEVICE_CODE_LOGIN_OPERATION = "device-code-login";
+
+/** Cancel a running operation; resolves to false if it already finished */
+export async function cancelOperation(id: string): Promise<boolean> {
+  try {
+    return await invoke<boolean>("cancel_operation", { id });
+  } catch (err) {
+    const errorMessage = err instanceof Error ? err.message : String(err);
+    console.error(`Failed to cancel operation ${id}:`, errorMessage);
+    return false;
+  }
+}
+
 // ============================================================================
 // Activity Log Operations
 // ============================================================================
@@ -323,9 +566,10 @@ export async function globalSearchSecrets(
 export async function fetchActivityLogs(
   vaultId: string,
   days?: number,
+  filter?: ActivityLogFilter,
 ): Promise<ActivityLogEvent[]> {
   try {
-    return await invoke<ActivityLogEvent[]>("fetch_activity_logs", { vaultId, days });
+    return await invoke<ActivityLogEvent[]>("fetch_activity_logs", { vaultId, days, filter });
   } catch (err) {
     const errorMessage = err instanceof Error ? err.message : String(err);
     console.error(`Failed to fetch activity logs for vault ${vaultId}:`, errorMessage);
@@ -353,3 +597,45 @@ export async function resolveCallers(
     return {};
   }
 }
+
+export interface ResolvedActivityLogEvent extends ActivityLogEvent {
+  /** The caller's identity; unresolved callers keep their raw value as display name */
+  resolvedCaller?: ResolvedCaller | null;
+}
+
+export async function fetchActivityLogsResolved(
+  vaultId: string,
+  days?: number,
+  filter?: ActivityLogFilter,
+): Promise<ResolvedActivityLogEvent[]> {
+  try {
+    return await invoke<ResolvedActivityLogEvent[]>("fetch_activity_logs_resolved", {
+      vaultId,
+      days,
+      filter,
+    });
+  } catch (err) {
+    const errorMessage = err instanceof Error ? err.message : String(err);
+    console.error(`Failed to fetch resolved activity logs for vault ${vaultId}:`, errorMessage);
+    throw new Error(errorMessage);
+  }
+}
+
+export interface CacheTtlSettings {
+  subscriptionTtlSecs?: number | null;
+  resourceGroupTtlSecs?: number | null;
+  keyvaultTtlSecs?: number | null;
+  secretsListTtlSecs?: number | null;
+  keysListTtlSecs?: number | null;
+  secretValueTtlSecs?: number | null;
+}
+
+export async function rebuildCacheWithTtls(ttls: CacheTtlSettings): Promise<string> {
+  try {
+    return await invoke<string>("rebuild_cache_with_ttls", { ttls });
+  } catch (err) {
+    const errorMessage = err instanceof Error ? err.message : String(err);
+    console.error("Failed to rebuild cache:", errorMessage);
+    throw new Error(errorMessage);
+  }
+}
@@ -1,3 +1,13 @@
+/** Conditions for the returned activity log events; unset fields match anything */
+export interface ActivityLogFilter {
+	/** Case-insensitive substring of the operation name, e.g. "secrets/write" */
+	operationNameContains?: string;
+	/** Outcome such as "Succeeded" or "Failed", matched against `status.value` */
+	resultType?: string;
+	/** Event level such as "Error" or "Informational" */
+	level?: string;
+}
+
 /** Activity Log event from Azure Monitor */
 export interface ActivityLogEvent {
 	authorization?: {
@@ -13,6 +23,8 @@ export interface ActivityLogEvent {
 	resultType?: string;
 	resultSignature?: string;
 	eventTimestamp?: string;
+	/** `eventTimestamp` normalized to UTC (RFC 3339) by the backend */
+	eventTime?: string;
 	submissionTimestamp?: string;
 	caller?: string;
 	correlationId?: string;
@@ -0,0 +1,24 @@
+export interface ExpiringCertificate {
+  vaultUri: string;
+  name: string;
+  id: string;
+  x5t?: string;
+  enabled: boolean;
+  /** Expiry time (unix seconds) */
+  expires: number;
+  /** The expiry time has already passed */
+  expired: boolean;
+  /** Whole days left until expiry, rounded down; negative once expired */
+  daysUntilExpiry: number;
+}
+
+/** A vault whose certificates couldn't be listed during an expiry check */
+export interface InaccessibleCertificateVault {
+  vaultUri: string;
+  error: string;
+}
+
+export interface ExpiringCertificates {
+  certificates: ExpiringCertificate[];
+  inaccessibleVaults: InaccessibleCertificateVault[];
+}
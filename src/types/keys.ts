@@ -0,0 +1,37 @@
+export interface KeyAttributes {
+  enabled: boolean;
+  created: number;
+  updated: number;
+  recoveryLevel: string;
+  recoverableDays: number;
+  exp?: number;
+  nbf?: number;
+  exportable: boolean;
+}
+
+export interface Key {
+  kid: string;
+  attributes: KeyAttributes;
+  keyOps: string[];
+  tags: Record<string, string>;
+  managed: boolean;
+}
+
+/** Public key material only; private fields are never returned */
+export interface PublicJsonWebKey {
+  kid: string;
+  kty: string;
+  keyOps: string[];
+  n?: string;
+  e?: string;
+  crv?: string;
+  x?: string;
+  y?: string;
+}
+
+export interface KeyBundle {
+  key: PublicJsonWebKey;
+  attributes: KeyAttributes;
+  tags: Record<string, string>;
+  managed: boolean;
+}
@@ -5,6 +5,13 @@ export interface KeyVaultAccess {
   errorMessage?: string;
 }
 
+export interface KeyVaultDeletion {
+  softDeleted: boolean;
+  retentionDays: number | null;
+  purgeProtected: boolean;
+  message: string;
+}
+
 export interface KeyVault {
   id: string;
   name: string;
@@ -1,6 +1,7 @@
 export interface Secret {
   id: string;
   attributes: SecretAttributes;
+  tags?: Record<string, string>;
 }
 
 export interface SecretBundle extends Secret {